@@ -0,0 +1,88 @@
+//! Compile-time decoding of encoded literals for [`base_xx`].
+//!
+//! `decode!("base58", "72k1xXWG59wUsYv7h2")` decodes the literal while the crate
+//! is compiled and expands to a `[u8; N]` array, so invalid input fails the build
+//! and no decoding or allocation happens at runtime. Codecs are enabled through
+//! the same cargo features as `base_xx`.
+#![deny(missing_docs)]
+
+use std::{str::FromStr, sync::Arc};
+
+use base_xx::{ByteVec, EncodedString, Encoding, SerialiseError, encoded_string::Decodable};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{LitStr, Token, parse::Parser, punctuated::Punctuated};
+
+/// Bytes decoded from a literal.
+struct Decoded(Vec<u8>);
+
+impl TryFrom<Arc<ByteVec>> for Decoded {
+    type Error = SerialiseError;
+
+    fn try_from(value: Arc<ByteVec>) -> Result<Self, SerialiseError> {
+        Ok(Self(value.get_bytes().to_vec()))
+    }
+}
+
+impl Decodable for Decoded {}
+
+/// Decodes `payload` in the encoding named by `encoding` into an array expression.
+fn expand(input: TokenStream) -> syn::Result<TokenStream> {
+    let args = Punctuated::<LitStr, Token![,]>::parse_terminated.parse2(input)?;
+    let [encoding, payload] = <[LitStr; 2]>::try_from(args.into_iter().collect::<Vec<_>>())
+        .map_err(|_| {
+            syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "expected an encoding name and an encoded string literal",
+            )
+        })?;
+
+    let name = Encoding::from_str(&encoding.value())
+        .map_err(|e| syn::Error::new(encoding.span(), e.to_string()))?;
+    let Decoded(bytes) = Decoded::try_decode(EncodedString::new(name, payload.value()))
+        .map_err(|e| syn::Error::new(payload.span(), e.to_string()))?;
+
+    Ok(quote! { [#(#bytes),*] })
+}
+
+/// Decodes an encoded string literal at compile time into a `[u8; N]`.
+///
+/// The first argument names the encoding as in `Encoding`'s `FromStr`
+/// implementation (`"base58"`, `"hex"`, `"intel-hex"`, ...), the second is the
+/// encoded text. An unknown encoding or invalid text is a compile error pointing
+/// at the offending literal.
+#[proc_macro]
+pub fn decode(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    expand(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "base58", feature = "hex"))]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_expand_to_array() {
+        let expanded = expand(quote! { "hex", "cafe01" }).unwrap_or_default();
+        assert_eq!(
+            expanded.to_string(),
+            quote! { [202u8, 254u8, 1u8] }.to_string()
+        );
+    }
+
+    #[test]
+    fn test_invalid_payload_is_err() {
+        assert!(expand(quote! { "base58", "0OIl" }).is_err());
+        assert!(expand(quote! { "base99", "abc" }).is_err());
+    }
+
+    #[test]
+    fn test_wrong_arguments_is_err() {
+        assert!(expand(quote! { "hex" }).is_err());
+        assert!(expand(quote! { "hex", "00", "11" }).is_err());
+        assert!(expand(quote! { hex, "00" }).is_err());
+    }
+}
@@ -0,0 +1,197 @@
+//! Throughput benchmarks for every algorithm at small, medium and large input sizes.
+//!
+//! Each size is its own criterion group so the report lists the algorithms side by
+//! side in bytes/second. To compare a change against a saved baseline:
+//!
+//! ```text
+//! cargo bench --bench codecs -- --save-baseline before
+//! # ...make changes...
+//! cargo bench --bench codecs -- --baseline before
+//! ```
+//!
+//! Setting `BASE_XX_BENCH_MAX_REGRESSION` to a percentage turns the comparison
+//! into a gate: after the run, any benchmark whose mean time rose by more than
+//! that much against the baseline is listed and the process exits with status 1.
+//!
+//! ```text
+//! BASE_XX_BENCH_MAX_REGRESSION=10 cargo bench --bench codecs -- --baseline before
+//! ```
+
+use std::{
+    fs,
+    hint::black_box,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+
+use base_xx::{
+    Base32, Base36, Base45, Base58, Base62, Base64, Base94, BinHex, EncodedString, Encoder,
+    Escaped, FilenameSafe, Hex, IntelHex, LineDump, Srec, Uuencode,
+};
+use criterion::{
+    BenchmarkGroup, BenchmarkId, Criterion, SamplingMode, Throughput, criterion_group,
+    measurement::WallTime,
+};
+
+const SIZES: [(&str, usize); 3] = [("64B", 64), ("4KiB", 4 * 1024), ("1MiB", 1024 * 1024)];
+
+/// The big-integer codecs are quadratic in the input length, so a 1 MiB run would
+/// take hours; they are measured at this size instead, which takes under a
+/// second per run and still shows a change in their growth.
+const QUADRATIC_LARGE: (&str, usize) = ("16KiB", 16 * 1024);
+
+/// Environment variable holding the largest allowed slowdown, in percent.
+const MAX_REGRESSION_VAR: &str = "BASE_XX_BENCH_MAX_REGRESSION";
+
+/// Deterministic, non-trivial input so codecs cannot take all-zero shortcuts.
+fn input(len: usize) -> Arc<Vec<u8>> {
+    let mut state: u32 = 0x9e37_79b9;
+    Arc::new(
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state.to_le_bytes()[0]
+            })
+            .collect(),
+    )
+}
+
+fn group(
+    c: &mut Criterion,
+    name: String,
+    len: usize,
+    quadratic: bool,
+) -> BenchmarkGroup<'_, WallTime> {
+    let mut group = c.benchmark_group(name);
+    group.throughput(Throughput::Bytes(len as u64));
+    if quadratic {
+        group.sample_size(10).sampling_mode(SamplingMode::Flat);
+    }
+    group
+}
+
+fn bench_codec<E: Encoder>(c: &mut Criterion, name: &str, quadratic: bool) {
+    for (label, len) in SIZES {
+        let (label, len) = if quadratic && len > QUADRATIC_LARGE.1 {
+            QUADRATIC_LARGE
+        } else {
+            (label, len)
+        };
+
+        let bytes = input(len);
+        let Ok(encoded) = E::try_encode(Arc::clone(&bytes)) else {
+            continue;
+        };
+
+        // Groups are finished when dropped at the end of each statement.
+        group(c, format!("encode/{label}"), len, quadratic).bench_with_input(
+            BenchmarkId::from_parameter(name),
+            &bytes,
+            |b, bytes| b.iter(|| E::try_encode(black_box(Arc::clone(bytes)))),
+        );
+        group(c, format!("decode/{label}"), len, quadratic).bench_with_input(
+            BenchmarkId::from_parameter(name),
+            &encoded,
+            |b, encoded: &EncodedString| b.iter(|| E::try_decode(black_box(encoded))),
+        );
+    }
+}
+
+fn codecs(c: &mut Criterion) {
+    bench_codec::<Base32>(c, "base32", false);
+    bench_codec::<Base36>(c, "base36", true);
+    bench_codec::<Base45>(c, "base45", false);
+    bench_codec::<Base58>(c, "base58", true);
+    bench_codec::<Base62>(c, "base62", true);
+    bench_codec::<Base64>(c, "base64", false);
+    bench_codec::<Hex>(c, "hex", false);
+    bench_codec::<Uuencode>(c, "uuencode", false);
+    bench_codec::<IntelHex>(c, "intel_hex", false);
+    bench_codec::<Srec>(c, "srec", false);
+    bench_codec::<BinHex>(c, "binhex", false);
+    bench_codec::<LineDump>(c, "line_dump", false);
+    bench_codec::<FilenameSafe>(c, "filename_safe", false);
+    bench_codec::<Escaped>(c, "escaped", false);
+    bench_codec::<Base94>(c, "base94", false);
+}
+
+/// Returns the directory criterion writes its results to.
+fn criterion_dir() -> PathBuf {
+    std::env::var_os("CRITERION_HOME").map_or_else(
+        || {
+            std::env::var_os("CARGO_TARGET_DIR")
+                .map_or_else(|| PathBuf::from("target"), PathBuf::from)
+                .join("criterion")
+        },
+        PathBuf::from,
+    )
+}
+
+/// Collects every `change/estimates.json` under `dir` written since `since`,
+/// which criterion only writes when comparing against a baseline.
+fn changes(dir: &Path, since: SystemTime, found: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            changes(&path, since, found);
+        } else if path.ends_with("change/estimates.json")
+            && entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .is_ok_and(|modified| modified >= since)
+        {
+            found.push(path);
+        }
+    }
+}
+
+/// Returns each benchmark whose mean time rose by more than `max_percent`
+/// since the baseline, with the rise in percent.
+fn regressions(since: SystemTime, max_percent: f64) -> Vec<(String, f64)> {
+    let root = criterion_dir();
+    let mut found = Vec::new();
+    changes(&root, since, &mut found);
+    let mut slower: Vec<(String, f64)> = found
+        .iter()
+        .filter_map(|path| {
+            let json: serde_json::Value =
+                serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+            let percent = json["mean"]["point_estimate"].as_f64()? * 100.0;
+            let name = path.parent()?.parent()?.strip_prefix(&root).ok()?;
+            (percent > max_percent).then(|| (name.display().to_string(), percent))
+        })
+        .collect();
+    slower.sort_by(|a, b| a.0.cmp(&b.0));
+    slower
+}
+
+criterion_group!(benches, codecs);
+
+fn main() {
+    let started = SystemTime::now();
+    benches();
+    Criterion::default().configure_from_args().final_summary();
+
+    let Some(max_percent) = std::env::var(MAX_REGRESSION_VAR)
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+    else {
+        return;
+    };
+    let slower = regressions(started, max_percent);
+    if slower.is_empty() {
+        println!("no benchmark regressed by more than {max_percent}%");
+        return;
+    }
+    for (name, percent) in &slower {
+        eprintln!("{name}: {percent:+.1}% (limit {max_percent}%)");
+    }
+    eprintln!("{} benchmark(s) regressed past the limit", slower.len());
+    std::process::exit(1);
+}
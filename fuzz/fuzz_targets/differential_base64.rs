@@ -0,0 +1,12 @@
+#![no_main]
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    base_xx::fuzz::differential_base64(
+        data,
+        |bytes| STANDARD.encode(bytes),
+        |text| STANDARD.decode(text).ok(),
+    );
+});
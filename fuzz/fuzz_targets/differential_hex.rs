@@ -0,0 +1,7 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    base_xx::fuzz::differential_hex(data, hex::encode, |text| hex::decode(text).ok());
+});
@@ -0,0 +1,270 @@
+use crate::{EncodedString, SerialiseError};
+
+/// Most candidates listed in the error for an ambiguous prefix.
+const MAX_LISTED: usize = 5;
+
+/// Returns `text` as compared by prefix lookups: lowercased if `folds_case`.
+fn key(text: &str, folds_case: bool) -> String {
+    if folds_case {
+        text.to_ascii_lowercase()
+    } else {
+        text.to_string()
+    }
+}
+
+/// Returns the number of leading bytes `a` and `b` share.
+fn common_len(a: &str, b: &str) -> usize {
+    a.bytes().zip(b.bytes()).take_while(|(x, y)| x == y).count()
+}
+
+/// Returns the first `len` bytes of `text`, extended to a character boundary.
+fn prefix_of(text: &str, len: usize) -> &str {
+    let end = (len.min(text.len())..=text.len())
+        .find(|&i| text.is_char_boundary(i))
+        .unwrap_or(text.len());
+    &text[..end]
+}
+
+/// Returns the shortest prefix of `target` that no other string in `values`
+/// starts with, as Git abbreviates commit hashes.
+///
+/// Strings equal to `target` are skipped, so `target` may be one of
+/// `values`. Case is ignored if `target`'s encoding is case-insensitive, such
+/// as hex, since users may type either case.
+///
+/// # Arguments
+/// * `values` - Every identifier the prefix must be told apart from
+/// * `target` - The identifier to abbreviate
+///
+/// # Returns
+/// The prefix, or the whole of `target` if another value starts with it
+#[must_use = "This returns the prefix but does nothing if unused"]
+pub fn shortest_unique_prefix<'a>(values: &[EncodedString], target: &'a EncodedString) -> &'a str {
+    let folds_case = !target.get_encoding().is_case_sensitive();
+    let text = target.get_string();
+    let target_key = key(text, folds_case);
+    let len = values
+        .iter()
+        .map(|value| key(value.get_string(), folds_case))
+        .filter(|other| *other != target_key)
+        .map(|other| common_len(&target_key, &other) + 1)
+        .max()
+        .unwrap_or(1);
+    prefix_of(text, len)
+}
+
+/// A set of identifiers that resolves abbreviated prefixes typed by users,
+/// and finds the shortest unambiguous prefix to display for each.
+///
+/// Lookups ignore case if every identifier's encoding is case-insensitive.
+#[derive(Debug, Clone, Default)]
+pub struct PrefixIndex {
+    /// The lookup keys and their identifiers, sorted by key
+    entries: Vec<(String, EncodedString)>,
+    /// True if keys are lowercased
+    folds_case: bool,
+    /// The fewest characters a displayed or typed prefix may have
+    min_len: usize,
+}
+
+impl PrefixIndex {
+    /// Builds an index of `values`. Duplicates are kept once.
+    ///
+    /// # Arguments
+    /// * `values` - The identifiers
+    ///
+    /// # Returns
+    /// The index
+    #[must_use = "This returns the index but does nothing if unused"]
+    pub fn new(values: impl IntoIterator<Item = EncodedString>) -> Self {
+        let values: Vec<EncodedString> = values.into_iter().collect();
+        let folds_case = !values.is_empty()
+            && values
+                .iter()
+                .all(|value| !value.get_encoding().is_case_sensitive());
+        let mut entries: Vec<(String, EncodedString)> = values
+            .into_iter()
+            .map(|value| (key(value.get_string(), folds_case), value))
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        entries.dedup_by(|a, b| a.0 == b.0);
+        Self {
+            entries,
+            folds_case,
+            min_len: 0,
+        }
+    }
+
+    /// Never shows or accepts prefixes shorter than `min_len` characters,
+    /// as Git shows at least seven, so abbreviations stay stable as the set
+    /// grows.
+    #[must_use = "This returns the index but does nothing if unused"]
+    pub const fn with_min_len(mut self, min_len: usize) -> Self {
+        self.min_len = min_len;
+        self
+    }
+
+    /// Returns the number of identifiers.
+    #[must_use = "This returns the number of identifiers but does nothing if unused"]
+    pub const fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the index holds no identifiers.
+    #[must_use = "This returns whether the index is empty but does nothing if unused"]
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the identifiers starting with `prefix`, in sorted order.
+    #[must_use = "This returns the matches but does nothing if unused"]
+    pub fn matches(&self, prefix: &str) -> impl Iterator<Item = &EncodedString> {
+        let prefix = key(prefix, self.folds_case);
+        let start = self
+            .entries
+            .partition_point(|(k, _)| k.as_str() < prefix.as_str());
+        self.entries[start..]
+            .iter()
+            .take_while(move |(k, _)| k.starts_with(&prefix))
+            .map(|(_, value)| value)
+    }
+
+    /// Resolves a prefix typed by a user to the one identifier it names.
+    ///
+    /// An exact match wins even if longer identifiers start with it.
+    ///
+    /// # Arguments
+    /// * `prefix` - The abbreviated identifier
+    ///
+    /// # Returns
+    /// The identifier
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `prefix` is shorter than the minimum length,
+    /// matches nothing, or matches several identifiers, listing a few of them.
+    pub fn resolve(&self, prefix: &str) -> Result<&EncodedString, SerialiseError> {
+        if prefix.len() < self.min_len {
+            return Err(SerialiseError::new(format!(
+                "prefix '{prefix}' is shorter than {} characters",
+                self.min_len
+            )));
+        }
+        let candidates: Vec<&EncodedString> = self.matches(prefix).collect();
+        let wanted = key(prefix, self.folds_case);
+        match candidates.as_slice() {
+            [] => Err(SerialiseError::new(format!(
+                "no identifier starts with '{prefix}'"
+            ))),
+            [only] => Ok(only),
+            [first, ..] if key(first.get_string(), self.folds_case) == wanted => Ok(first),
+            [..] => {
+                let listed: Vec<&str> = candidates
+                    .iter()
+                    .take(MAX_LISTED)
+                    .map(|value| value.get_string().as_str())
+                    .collect();
+                Err(SerialiseError::new(format!(
+                    "prefix '{prefix}' is ambiguous between {} identifiers: {}{}",
+                    candidates.len(),
+                    listed.join(", "),
+                    if candidates.len() > MAX_LISTED {
+                        ", ..."
+                    } else {
+                        ""
+                    }
+                )))
+            }
+        }
+    }
+
+    /// Returns the shortest prefix of `target` that [`PrefixIndex::resolve`]
+    /// resolves to it, and at least the minimum length.
+    ///
+    /// # Arguments
+    /// * `target` - An identifier, usually one in the index
+    ///
+    /// # Returns
+    /// The prefix, or the whole of `target` if an identifier starts with it
+    #[must_use = "This returns the prefix but does nothing if unused"]
+    pub fn shortest_prefix<'a>(&self, target: &'a EncodedString) -> &'a str {
+        let text = target.get_string();
+        let target_key = key(text, self.folds_case);
+        let at = self
+            .entries
+            .partition_point(|(k, _)| k.as_str() < target_key.as_str());
+        // Only the neighbours in sorted order can share the longest prefix.
+        let len = [at.checked_sub(1), Some(at), Some(at + 1)]
+            .into_iter()
+            .flatten()
+            .filter_map(|i| self.entries.get(i))
+            .filter(|(k, _)| *k != target_key)
+            .map(|(k, _)| common_len(&target_key, k) + 1)
+            .max()
+            .unwrap_or(1)
+            .max(self.min_len);
+        prefix_of(text, len)
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "base58", feature = "hex"))]
+mod tests {
+
+    use super::*;
+    use crate::Encoding;
+
+    fn hex(values: &[&str]) -> Vec<EncodedString> {
+        values
+            .iter()
+            .map(|v| EncodedString::new(Encoding::Hex, (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_shortest_unique_prefix() {
+        let values = hex(&["a1b2c3", "a1f000", "b00000", "a1b2"]);
+        assert_eq!(shortest_unique_prefix(&values, &values[0]), "a1b2c");
+        assert_eq!(shortest_unique_prefix(&values, &values[1]), "a1f");
+        assert_eq!(shortest_unique_prefix(&values, &values[2]), "b");
+        assert_eq!(shortest_unique_prefix(&values, &values[3]), "a1b2");
+        let upper = EncodedString::new(Encoding::Hex, "A1F000".to_string());
+        assert_eq!(shortest_unique_prefix(&values, &upper), "A1F");
+        let base58 = [
+            EncodedString::new(Encoding::Base58, "abc".to_string()),
+            EncodedString::new(Encoding::Base58, "aBc".to_string()),
+        ];
+        assert_eq!(shortest_unique_prefix(&base58, &base58[0]), "ab");
+    }
+
+    #[test]
+    fn test_resolve() {
+        let index = PrefixIndex::new(hex(&["a1b2c3", "a1f000", "b00000", "a1b2"]));
+        assert_eq!(index.len(), 4);
+        assert!(matches!(index.resolve("a1f"), Ok(v) if v.get_string() == "a1f000"));
+        assert!(matches!(index.resolve("B"), Ok(v) if v.get_string() == "b00000"));
+        assert!(matches!(index.resolve("a1b2"), Ok(v) if v.get_string() == "a1b2"));
+        assert!(matches!(index.resolve("a1"), Err(e) if e.get_message().contains("a1b2c3")));
+        assert!(index.resolve("c").is_err());
+        assert_eq!(index.matches("a1").count(), 3);
+
+        let strict = index.with_min_len(4);
+        assert!(strict.resolve("b").is_err());
+        assert!(strict.resolve("b000").is_ok());
+    }
+
+    #[test]
+    fn test_shortest_prefix_resolves() {
+        let values = hex(&["a1b2c3", "a1f000", "b00000", "a1b2", "ffff00", "fffe00"]);
+        let index = PrefixIndex::new(values.clone());
+        for value in &values {
+            let prefix = index.shortest_prefix(value);
+            assert_eq!(prefix, shortest_unique_prefix(&values, value));
+            assert!(
+                matches!(index.resolve(prefix), Ok(v) if v == value),
+                "{prefix}"
+            );
+        }
+        assert_eq!(index.with_min_len(3).shortest_prefix(&values[2]), "b00");
+        assert!(PrefixIndex::new([]).is_empty());
+    }
+}
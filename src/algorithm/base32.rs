@@ -0,0 +1,553 @@
+use std::{mem::MaybeUninit, sync::Arc};
+
+use crate::{
+    Decoder, EncodedString, Encoder, Encoding, SerialiseError, alphabet, decoder::BitDecoder,
+    serialise_error::check_ascii,
+};
+
+const ALPHABET: &[u8; 32] = alphabet::BASE32_STANDARD;
+const LOWER_DIGITS: [Option<u8>; 256] = alphabet::reverse_lookup(alphabet::BASE32_LOWER);
+const HEX_LOWER_DIGITS: [Option<u8>; 256] = alphabet::reverse_lookup(alphabet::BASE32_HEX_LOWER);
+const ZBASE32_DIGITS: [Option<u8>; 256] = alphabet::reverse_lookup(alphabet::ZBASE32);
+const PAD: u8 = b'=';
+
+/// The digits a [`Base32`] config writes and reads.
+///
+/// Decoders only accept digits from the configured alphabet, so the uppercase
+/// and lowercase RFC 4648 alphabets do not read each other.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Base32Alphabet {
+    /// RFC 4648 `A-Z2-7`
+    #[default]
+    Standard,
+    /// RFC 4648 in lowercase, `a-z2-7`, as used by onion service addresses
+    Lowercase,
+    /// z-base-32, as used by Tahoe-LAFS
+    ZBase32,
+    /// RFC 4648 "extended hex" in lowercase, `0-9a-v`, as used by DNSSEC NSEC3
+    HexLowercase,
+}
+
+impl Base32Alphabet {
+    /// Every alphabet, used to check padding characters against all of them.
+    const ALL: [Self; 4] = [
+        Self::Standard,
+        Self::Lowercase,
+        Self::ZBase32,
+        Self::HexLowercase,
+    ];
+
+    /// Returns the digits in value order.
+    #[must_use = "This returns the digits but does nothing if unused"]
+    pub const fn get_digits(self) -> &'static [u8; 32] {
+        match self {
+            Self::Standard => ALPHABET,
+            Self::Lowercase => alphabet::BASE32_LOWER,
+            Self::ZBase32 => alphabet::ZBASE32,
+            Self::HexLowercase => alphabet::BASE32_HEX_LOWER,
+        }
+    }
+
+    /// Returns the function that maps a character to its digit value.
+    const fn decoder(self) -> fn(u8) -> Option<u8> {
+        match self {
+            Self::Standard => Base32::decode_digit,
+            Self::Lowercase => |c| LOWER_DIGITS[c as usize],
+            Self::ZBase32 => |c| ZBASE32_DIGITS[c as usize],
+            Self::HexLowercase => |c| HEX_LOWER_DIGITS[c as usize],
+        }
+    }
+}
+
+/// Alphabet and padding options for [`Base32`].
+///
+/// The default pads with `=` as RFC 4648 requires. Some systems use a different
+/// padding character or none at all; decoders built from a config accept only
+/// the configured character.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Base32Config {
+    /// The padding character, or `None` for unpadded output
+    padding: Option<u8>,
+    /// The digits written and read
+    alphabet: Base32Alphabet,
+}
+
+impl Base32Config {
+    /// RFC 4648 output padded with `=`.
+    pub const STANDARD: Self = Self {
+        padding: Some(PAD),
+        alphabet: Base32Alphabet::Standard,
+    };
+
+    /// Unpadded output. Decoders reject padding characters.
+    pub const NO_PAD: Self = Self {
+        padding: None,
+        ..Self::STANDARD
+    };
+
+    /// Unpadded lowercase RFC 4648 output.
+    pub const LOWERCASE: Self = Self {
+        alphabet: Base32Alphabet::Lowercase,
+        ..Self::NO_PAD
+    };
+
+    /// Unpadded z-base-32 output.
+    pub const Z_BASE32: Self = Self {
+        alphabet: Base32Alphabet::ZBase32,
+        ..Self::NO_PAD
+    };
+
+    /// Unpadded lowercase "extended hex" output, exactly as DNSSEC NSEC3
+    /// hashed owner names are written (RFC 5155).
+    pub const NSEC3: Self = Self {
+        alphabet: Base32Alphabet::HexLowercase,
+        ..Self::NO_PAD
+    };
+
+    /// Pads with `padding` instead of `=`.
+    ///
+    /// # Arguments
+    /// * `padding` - The padding character
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `padding` is not a printable ASCII character or is
+    /// a digit of any of the base32 alphabets.
+    pub fn with_padding(padding: char) -> Result<Self, SerialiseError> {
+        match u8::try_from(padding) {
+            Ok(pad)
+                if pad.is_ascii_graphic()
+                    && !Base32Alphabet::ALL
+                        .iter()
+                        .any(|alphabet| alphabet.get_digits().contains(&pad)) =>
+            {
+                Ok(Self {
+                    padding: Some(pad),
+                    ..Self::STANDARD
+                })
+            }
+            _ => Err(SerialiseError::new(format!(
+                "'{padding}' cannot be used as base32 padding"
+            ))),
+        }
+    }
+
+    /// Writes and reads the digits of `alphabet`.
+    #[must_use = "This returns the updated config but does nothing if unused"]
+    pub const fn alphabet(mut self, alphabet: Base32Alphabet) -> Self {
+        self.alphabet = alphabet;
+        self
+    }
+
+    /// Returns the padding character, or `None` for unpadded output.
+    #[must_use = "This returns the padding character but does nothing if unused"]
+    pub fn get_padding(&self) -> Option<char> {
+        self.padding.map(char::from)
+    }
+
+    /// Returns the alphabet written and read.
+    #[must_use = "This returns the alphabet but does nothing if unused"]
+    pub const fn get_alphabet(&self) -> Base32Alphabet {
+        self.alphabet
+    }
+}
+
+impl Default for Base32Config {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+/// Base32 encoding implementation (RFC 4648).
+#[derive(Debug)]
+pub struct Base32 {}
+
+impl Base32 {
+    /// Returns the config for DNSSEC NSEC3 hashes: lowercase "extended hex"
+    /// without padding, the same as [`Base32Config::NSEC3`].
+    #[must_use = "This returns the config but does nothing if unused"]
+    pub const fn nsec3() -> Base32Config {
+        Base32Config::NSEC3
+    }
+
+    /// Convert bytes to a padded base32 string.
+    ///
+    /// # Errors
+    ///
+    /// This function currently does not return an error.
+    pub fn try_to_base32(bytes: &[u8]) -> Result<String, SerialiseError> {
+        Self::try_to_base32_with(bytes, Base32Config::STANDARD)
+    }
+
+    /// Convert bytes to a base32 string with the given alphabet and padding options.
+    ///
+    /// # Errors
+    ///
+    /// This function currently does not return an error.
+    pub fn try_to_base32_with(
+        bytes: &[u8],
+        config: Base32Config,
+    ) -> Result<String, SerialiseError> {
+        let mut buf = vec![0u8; bytes.len().div_ceil(5) * 8];
+        let len = Self::encode_into(bytes, &mut buf, config);
+        buf.truncate(len);
+        String::from_utf8(buf)
+            .map_err(|_| SerialiseError::new("base32 output is not ASCII".to_string()))
+    }
+
+    /// Writes the base32 digits of `bytes` to the start of `out`, which must hold
+    /// at least `8 * ceil(len / 5)` bytes, and returns the number written.
+    fn encode_into(bytes: &[u8], out: &mut [u8], config: Base32Config) -> usize {
+        let alphabet_digits = config.alphabet.get_digits();
+        let mut len = 0;
+        for chunk in bytes.chunks(5) {
+            let mut block = [0u8; 8];
+            block[3..3 + chunk.len()].copy_from_slice(chunk);
+            let n = u64::from_be_bytes(block);
+
+            // Each input byte contributes to ceil(8 * len / 5) output digits.
+            let digits = (chunk.len() * 8).div_ceil(5);
+            for i in 0..8 {
+                if i < digits {
+                    out[len] = alphabet_digits[((n >> (35 - 5 * i)) & 0x1f) as usize];
+                } else if let Some(pad) = config.padding {
+                    out[len] = pad;
+                } else {
+                    continue;
+                }
+                len += 1;
+            }
+        }
+        len
+    }
+
+    /// Writes padded base32 into a caller-provided buffer without allocating.
+    ///
+    /// # Arguments
+    /// * `input` - The bytes to encode
+    /// * `output` - The buffer to write to, at least
+    ///   [`base32_len(input.len())`](crate::const_encode::base32_len) bytes
+    ///
+    /// # Returns
+    /// The number of bytes written to the start of `output`
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `output` is too small. Building the error is
+    /// the only allocation.
+    pub fn encode_slice(input: &[u8], output: &mut [u8]) -> Result<usize, SerialiseError> {
+        let needed = input.len().div_ceil(5) * 8;
+        if output.len() < needed {
+            return Err(SerialiseError::new(format!(
+                "base32 output needs {needed} bytes, buffer has {}",
+                output.len()
+            )));
+        }
+        Ok(Self::encode_into(input, output, Base32Config::STANDARD))
+    }
+
+    const fn decode_digit(c: u8) -> Option<u8> {
+        match c {
+            b'A'..=b'Z' => Some(c - b'A'),
+            b'2'..=b'7' => Some(c - b'2' + 26),
+            _ => None,
+        }
+    }
+
+    /// Packs 5-bit digits into bytes, dropping any trailing bits that do not
+    /// make up a whole byte.
+    fn digits_to_bytes(digits: impl Iterator<Item = u8>) -> Vec<u8> {
+        let mut out: Vec<u8> = Vec::new();
+        let mut acc: u32 = 0;
+        let mut bits = 0;
+        for digit in digits {
+            acc = (acc << 5) | u32::from(digit);
+            bits += 5;
+            if bits >= 8 {
+                bits -= 8;
+                out.push(((acc >> bits) & 0xff) as u8);
+            }
+            acc &= (1 << bits) - 1;
+        }
+        out
+    }
+
+    /// Decodes a base32 string into bytes.
+    ///
+    /// Whitespace, including line breaks from wrapped output, is ignored and
+    /// trailing `=` padding is optional.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `base32` contains characters outside the base32 alphabet,
+    /// has misplaced padding, or has a length no encoder could have produced.
+    pub fn try_from_base32(base32: &str) -> Result<Vec<u8>, SerialiseError> {
+        Self::try_from_base32_with(base32, Base32Config::STANDARD)
+    }
+
+    /// Decodes a base32 string written with the given alphabet and padding options.
+    ///
+    /// Whitespace is ignored and trailing padding is optional; any other use of a
+    /// padding character, including `=` when a different one is configured, is an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `base32` contains characters outside the base32 alphabet,
+    /// has misplaced padding, or has a length no encoder could have produced.
+    pub fn try_from_base32_with(
+        base32: &str,
+        config: Base32Config,
+    ) -> Result<Vec<u8>, SerialiseError> {
+        check_ascii(base32, "base32")?;
+        let s = alphabet::strip_whitespace(base32);
+        let body = config
+            .padding
+            .map_or(&*s, |pad| s.trim_end_matches(pad as char));
+        let padding = s.len() - body.len();
+        if padding > 6 || (padding > 0 && !s.len().is_multiple_of(8)) {
+            return Err(SerialiseError::new("invalid base32 padding".to_string()));
+        }
+        if matches!(body.len() % 8, 1 | 3 | 6) {
+            return Err(SerialiseError::new("invalid base32 length".to_string()));
+        }
+
+        let decode_digit = config.alphabet.decoder();
+        let mut digits: Vec<u8> = Vec::with_capacity(body.len());
+        for c in body.bytes() {
+            let Some(digit) = decode_digit(c) else {
+                return Err(SerialiseError::new("invalid base32 character".to_string()));
+            };
+            digits.push(digit);
+        }
+
+        Ok(Self::digits_to_bytes(digits.into_iter()))
+    }
+
+    /// Decodes base32 leniently, skipping anything outside the alphabet.
+    ///
+    /// Lowercase letters are accepted, line breaks and other stray characters are
+    /// ignored, decoding stops at the first `=`, and trailing digits that do not
+    /// make up a whole byte are dropped.
+    ///
+    /// # Arguments
+    /// * `base32` - The base32 text to decode
+    ///
+    /// # Returns
+    /// The decoded bytes
+    #[must_use = "This returns the decoded bytes but does nothing if unused"]
+    pub fn from_base32_forgiving(base32: &str) -> Vec<u8> {
+        Self::digits_to_bytes(
+            base32
+                .bytes()
+                .take_while(|&c| c != PAD)
+                .filter_map(|c| Self::decode_digit(c.to_ascii_uppercase())),
+        )
+    }
+
+    /// Decodes base32 into a caller-provided buffer that need not be initialized.
+    ///
+    /// Accepts exactly the inputs [`Base32::try_from_base32`] accepts. Use this to skip
+    /// zeroing large output buffers; on error the buffer may be partly written.
+    ///
+    /// # Arguments
+    /// * `base32` - The base32 text to decode
+    /// * `output` - The buffer to write to, large enough for the decoded bytes
+    ///
+    /// # Returns
+    /// The decoded bytes, borrowed from the start of `output`
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `base32` is not valid base32 or the decoded
+    /// bytes do not fit in `output`.
+    pub fn decode_into_uninit<'a>(
+        base32: &str,
+        output: &'a mut [MaybeUninit<u8>],
+    ) -> Result<&'a [u8], SerialiseError> {
+        Base32Decoder::new()
+            .inner
+            .decode_into_uninit(base32, output)
+    }
+
+    /// Checks that `base32` is valid base32 without allocating the decoded bytes.
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` for exactly the inputs [`Base32::try_from_base32`] rejects.
+    pub fn validate(base32: &str) -> Result<(), SerialiseError> {
+        Base32Decoder::new().inner.validate(base32)
+    }
+}
+
+impl Encoder for Base32 {
+    fn try_encode(bytes: Arc<Vec<u8>>) -> Result<EncodedString, SerialiseError> {
+        Ok(EncodedString::new(
+            Encoding::Base32,
+            Self::try_to_base32(&bytes)?,
+        ))
+    }
+
+    fn try_decode(encoded: &EncodedString) -> Result<Arc<Vec<u8>>, SerialiseError> {
+        Ok(Arc::new(Self::try_from_base32(encoded.get_string())?))
+    }
+}
+
+/// Incremental base32 decoder for input that arrives in pieces.
+///
+/// Whitespace is skipped anywhere in the input.
+#[derive(Debug)]
+pub struct Base32Decoder {
+    pub(crate) inner: BitDecoder,
+}
+
+impl Base32Decoder {
+    /// Creates a decoder waiting for its first piece of input.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::with_config(Base32Config::STANDARD)
+    }
+
+    /// Creates a decoder that accepts the alphabet and padding character in `config`.
+    #[must_use]
+    pub const fn with_config(config: Base32Config) -> Self {
+        let pad = match config.padding {
+            Some(pad) => Some((pad, 8)),
+            None => None,
+        };
+        Self {
+            inner: BitDecoder::new("base32", 5, pad, config.alphabet.decoder()),
+        }
+    }
+}
+
+impl Default for Base32Decoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decoder for Base32Decoder {
+    fn feed(&mut self, input: &[u8]) -> Result<&[u8], SerialiseError> {
+        self.inner.feed(input)
+    }
+
+    fn finish(&mut self) -> Result<&[u8], SerialiseError> {
+        self.inner.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_alphabet_variants() {
+        assert!(matches!(
+            Base32::try_to_base32_with(b"hello", Base32Config::LOWERCASE).as_deref(),
+            Ok("nbswy3dp")
+        ));
+        // z-base-32 specification vector for 0xF0BFC7
+        assert!(matches!(
+            Base32::try_to_base32_with(&[0xf0, 0xbf, 0xc7], Base32Config::Z_BASE32).as_deref(),
+            Ok("6n9hq")
+        ));
+        assert!(matches!(
+            Base32::try_from_base32_with("c3zs6aubqe", Base32Config::Z_BASE32),
+            Ok(bytes) if bytes == b"foobar"
+        ));
+        assert!(Base32::try_from_base32_with("NBSWY3DP", Base32Config::LOWERCASE).is_err());
+        assert!(Base32::try_from_base32_with("nbswy3dp", Base32Config::NO_PAD).is_err());
+        let padded_z = Base32Config::STANDARD.alphabet(Base32Alphabet::ZBase32);
+        assert!(matches!(
+            Base32::try_to_base32_with(b"f", padded_z).as_deref(),
+            Ok("ca======")
+        ));
+        assert!(Base32Config::with_padding('y').is_err());
+    }
+
+    #[test]
+    fn test_nsec3() {
+        // RFC 4648 base32hex vector, lowercased and unpadded
+        assert!(matches!(
+            Base32::try_to_base32_with(b"foobar", Base32::nsec3()).as_deref(),
+            Ok("cpnmuoj1e8")
+        ));
+        // Hashed owner name of "example" from RFC 5155 appendix A
+        let hash = "0p9mhaveqvm6t7vbl5lop2u3t2rp3tom";
+        let bytes = Base32::try_from_base32_with(hash, Base32::nsec3()).unwrap_or_default();
+        assert_eq!(bytes.len(), 20);
+        assert!(matches!(
+            Base32::try_to_base32_with(&bytes, Base32::nsec3()).as_deref(),
+            Ok(s) if s == hash
+        ));
+        assert!(Base32::try_from_base32_with("CPNMUOJ1E8", Base32::nsec3()).is_err());
+        assert!(Base32Config::with_padding('v').is_err());
+    }
+
+    #[test]
+    fn test_encode_slice() {
+        let mut buf = [0u8; 16];
+        assert!(matches!(Base32::encode_slice(b"foobar", &mut buf), Ok(16)));
+        assert_eq!(&buf, b"MZXW6YTBOI======");
+        assert!(Base32::encode_slice(b"foobar12345", &mut buf).is_err());
+    }
+
+    #[test]
+    fn test_to_base32() {
+        let encoded: Vec<String> = [&b""[..], b"f", b"fo", b"foo", b"foob", b"fooba", b"foobar"]
+            .iter()
+            .map(|bytes| Base32::try_to_base32(bytes).unwrap_or_else(|_| String::new()))
+            .collect();
+        assert_eq!(
+            encoded,
+            [
+                "",
+                "MY======",
+                "MZXQ====",
+                "MZXW6===",
+                "MZXW6YQ=",
+                "MZXW6YTB",
+                "MZXW6YTBOI======"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_base32() {
+        assert!(matches!(
+            Base32::try_from_base32("MZXW6YTBOI======"),
+            Ok(bytes) if bytes == b"foobar"
+        ));
+        assert!(Base32::try_from_base32("MZXW6YTBO").is_err());
+        assert!(Base32::try_from_base32("mzxw6ytb").is_err());
+    }
+
+    #[test]
+    fn test_from_base32_forgiving_skips_invalid_characters() {
+        let text = "> mzxw\r\n> 6YT-BOI\t==";
+        assert_eq!(Base32::from_base32_forgiving(text), b"foobar");
+    }
+
+    #[test]
+    fn test_base32_decoder() {
+        let mut decoder = Base32Decoder::new();
+        let mut out: Vec<u8> = Vec::new();
+        for piece in ["MZXW", "6YTB", "OI====", "=="] {
+            out.extend_from_slice(decoder.feed(piece.as_bytes()).unwrap_or_default());
+        }
+        assert!(decoder.finish().is_ok());
+        assert_eq!(out, b"foobar");
+    }
+
+    #[test]
+    fn test_custom_padding() {
+        let tilde = Base32Config::with_padding('~').unwrap_or(Base32Config::STANDARD);
+        let encoded = Base32::try_to_base32_with(b"f", tilde).unwrap_or_default();
+        assert_eq!(encoded, "MY~~~~~~");
+        let mut decoder = Base32Decoder::with_config(tilde);
+        assert!(matches!(decoder.feed(encoded.as_bytes()), Ok(bytes) if bytes == b"f"));
+        assert!(decoder.finish().is_ok());
+        assert!(Base32::try_from_base32_with("MY======", tilde).is_err());
+        assert!(matches!(
+            Base32::try_to_base32_with(b"f", Base32Config::NO_PAD),
+            Ok(s) if s == "MY"
+        ));
+    }
+}
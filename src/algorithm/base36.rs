@@ -1,8 +1,28 @@
+#![cfg_attr(
+    all(feature = "no-panic", not(test)),
+    deny(
+        clippy::indexing_slicing,
+        clippy::panic,
+        clippy::unreachable,
+        clippy::unwrap_used,
+        clippy::expect_used
+    )
+)]
+
 use std::sync::Arc;
 
-use crate::{EncodedString, Encoder, Encoding, SerialiseError};
+#[cfg(feature = "num-bigint")]
+use num_bigint::BigUint;
+
+use crate::{
+    CheckScheme, EncodedString, Encoder, Encoding, SerialiseError, alphabet,
+    check::Luhn,
+    scratch::{self, Scratch},
+    serialise_error::check_ascii,
+};
 
-const ALPHABET: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+const ALPHABET: &[u8; 36] = alphabet::BASE36;
+const DIGITS: [Option<u8>; 256] = alphabet::reverse_lookup(ALPHABET);
 
 /// Base36 encoding implementation (0-9 and A-Z).
 ///
@@ -20,38 +40,52 @@ impl Base36 {
     /// # Returns
     /// The base36-encoded string
     #[must_use = "This returns the encoded string and does nothing if unused"]
-    #[allow(clippy::missing_panics_doc)]
     pub fn to_base36(bytes: &[u8]) -> String {
-        if bytes.is_empty() || bytes.iter().all(|&b| b == 0) {
-            return "0".to_string();
-        }
+        scratch::with_scratch(|scratch| Self::encode_with_scratch(bytes, scratch).to_string())
+    }
 
-        let mut n: Vec<u8> = bytes.to_vec();
-        let mut out = Vec::new();
+    /// Encodes a byte slice using base36 encoding, working in `scratch`
+    /// instead of allocating.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes to encode
+    /// * `scratch` - The buffers to work in, reused across calls
+    ///
+    /// # Returns
+    /// The base36-encoded string, borrowed from `scratch`; the same as
+    /// [`Base36::to_base36`]
+    #[must_use = "This returns the encoded string but does nothing if unused"]
+    pub fn encode_with_scratch<'s>(bytes: &[u8], scratch: &'s mut Scratch) -> &'s str {
+        let Scratch {
+            bytes: n,
+            text: out,
+            ..
+        } = scratch;
+        out.clear();
+        n.clear();
+        n.extend(bytes.iter().skip_while(|&&b| b == 0));
 
-        while !n.is_empty() {
+        let mut first = 0;
+        while first < n.len() {
             let mut rem = 0;
-            let mut i = 0;
-
-            while i < n.len() {
-                let v = u32::from(n[i]) + (rem * 256);
-                n[i] = u8::try_from(v / 36).unwrap_or_else(|_| unreachable!());
+            for b in n.iter_mut().skip(first) {
+                let v = u32::from(*b) + (rem * 256);
+                // `rem` is below 36, so the quotient always fits in a byte.
+                *b = u8::try_from(v / 36).unwrap_or_default();
                 rem = v % 36;
-                i += 1;
             }
 
-            out.push(u8::try_from(rem).unwrap_or_else(|_| unreachable!()));
+            out.extend(ALPHABET.get(rem as usize));
 
-            while n.first().copied() == Some(0) {
-                n.remove(0);
+            while n.get(first) == Some(&0) {
+                first += 1;
             }
         }
-
-        let mut result = String::with_capacity(out.len());
-        for byte in out.iter().rev() {
-            result.push(ALPHABET[*byte as usize] as char);
+        if out.is_empty() {
+            out.push(b'0');
         }
-        result
+        out.reverse();
+        scratch.get_text()
     }
 
     /// Converts a base36 string into its byte representation.
@@ -65,44 +99,58 @@ impl Base36 {
     /// # Errors
     /// Returns `SerialiseError` if the input contains invalid base36 characters
     pub fn base36_to_bytes(base36: &str) -> Result<Vec<u8>, SerialiseError> {
-        let s = base36.trim();
-        if s.is_empty() || s == "0" {
-            return Ok(vec![0]);
-        }
-
-        let mut acc = vec![0u8];
-        for c in s.chars() {
-            let Some(digit_usize) = ALPHABET
-                .iter()
-                .position(|x| *x == c.to_ascii_lowercase() as u8)
-            else {
-                return Err(SerialiseError::new("Invalid base36 character".to_string()));
-            };
-            let digit = u32::from(u8::try_from(digit_usize).unwrap_or_else(|_| unreachable!()));
-
-            let mut carry = digit;
-            for b in acc.iter_mut().rev() {
-                let v = u32::from(*b) * 36 + carry;
-                *b = u8::try_from(v & 0xff).unwrap_or_else(|_| unreachable!());
-                carry = v >> 8;
-            }
+        scratch::with_scratch(|scratch| {
+            Self::decode_with_scratch(base36, scratch).map(<[u8]>::to_vec)
+        })
+    }
 
-            while carry > 0 {
-                acc.insert(
-                    0,
-                    u8::try_from(carry & 0xff).unwrap_or_else(|_| unreachable!()),
-                );
-                carry >>= 8;
+    /// Converts a base36 string into its byte representation, working in
+    /// `scratch` instead of allocating.
+    ///
+    /// # Arguments
+    /// * `base36` - The base36-encoded string to convert
+    /// * `scratch` - The buffers to work in, reused across calls
+    ///
+    /// # Returns
+    /// The decoded bytes, borrowed from `scratch`; the same as
+    /// [`Base36::base36_to_bytes`]
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the input contains invalid base36 characters
+    pub fn decode_with_scratch<'s>(
+        base36: &str,
+        scratch: &'s mut Scratch,
+    ) -> Result<&'s [u8], SerialiseError> {
+        check_ascii(base36, "base36")?;
+        let acc = &mut scratch.bytes;
+        acc.clear();
+        acc.push(0);
+        let s = base36.trim();
+        if s != "0" {
+            let offset = base36.len() - base36.trim_start().len();
+            for (i, c) in s.bytes().enumerate() {
+                let digit = Self::decode_digit(offset + i, c)?;
+                scratch::mul_add(acc, 36, u32::from(digit));
             }
         }
-
-        while acc.len() > 1 && acc[0] == 0 {
-            acc.remove(0);
-        }
-
+        scratch::finish_be(acc);
         Ok(acc)
     }
 
+    /// Looks up the digit `c` at byte offset `position` of the input, in either case.
+    fn decode_digit(position: usize, c: u8) -> Result<u8, SerialiseError> {
+        DIGITS
+            .get(usize::from(c.to_ascii_lowercase()))
+            .copied()
+            .flatten()
+            .ok_or_else(|| {
+                SerialiseError::new(format!(
+                    "invalid base36 character '{}' at position {position}",
+                    char::from(c)
+                ))
+            })
+    }
+
     /// Decodes a base36 string into bytes, optionally left-padding to `size`.
     ///
     /// # Arguments
@@ -137,6 +185,164 @@ impl Base36 {
             }
         }
     }
+
+    /// Computes the Luhn mod 36 check character for base36 digits.
+    ///
+    /// The check character catches any single mistyped digit and most swaps of
+    /// adjacent digits, as in identifiers that are read out or typed by hand.
+    ///
+    /// # Arguments
+    /// * `digits` - The base36 digits, in either case
+    ///
+    /// # Returns
+    /// The lowercase check character to append
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `digits` contains characters outside the base36 alphabet.
+    pub fn check_char(digits: &str) -> Result<char, SerialiseError> {
+        let check = Luhn::BASE36.compute(digits)?;
+        Ok(check.chars().next().unwrap_or('0'))
+    }
+
+    /// Encodes bytes as base36 followed by a Luhn mod 36 check character.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes to encode
+    ///
+    /// # Returns
+    /// The base36-encoded string with its check character
+    #[must_use = "This returns the encoded string and does nothing if unused"]
+    pub fn to_base36_checked(bytes: &[u8]) -> String {
+        let mut encoded = Self::to_base36(bytes);
+        encoded.push(Self::check_char(&encoded).unwrap_or('0'));
+        encoded
+    }
+
+    /// Verifies the Luhn mod 36 check character at the end of `base36` and
+    /// decodes the digits before it, optionally left-padding to `size`.
+    ///
+    /// # Arguments
+    /// * `base36` - The base36-encoded string ending in its check character
+    /// * `size` - The expected size of the output in bytes, or 0 for no padding
+    ///
+    /// # Returns
+    /// The decoded bytes, without the check character
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` with [`ErrorKind::ChecksumMismatch`] if the check
+    /// character is wrong, or as [`Base36::from_base36`] does for the digits.
+    pub fn from_base36_checked(base36: &str, size: usize) -> Result<Arc<Vec<u8>>, SerialiseError> {
+        let digits = Luhn::BASE36.strip(base36.trim())?;
+        if digits.is_empty() {
+            return Err(SerialiseError::new(
+                "checked base36 needs at least one digit before the check character".to_string(),
+            ));
+        }
+        Self::from_base36(digits, size)
+    }
+
+    /// Encodes bytes as exactly `width` base36 digits, left-padded with `0`.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes to encode
+    /// * `width` - The number of digits to emit
+    ///
+    /// # Returns
+    /// The fixed-width base36-encoded string
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the value needs more than `width` digits.
+    pub fn encode_fixed(bytes: &[u8], width: usize) -> Result<String, SerialiseError> {
+        let digits = if bytes.iter().all(|&b| b == 0) {
+            String::new()
+        } else {
+            Self::to_base36(bytes)
+        };
+        if digits.len() > width {
+            return Err(SerialiseError::new(format!(
+                "base36 value needs {} digits, more than the fixed width {width}",
+                digits.len()
+            )));
+        }
+
+        let mut out = String::with_capacity(width);
+        out.extend(std::iter::repeat_n(
+            char::from(ALPHABET[0]),
+            width - digits.len(),
+        ));
+        out.push_str(&digits);
+        Ok(out)
+    }
+
+    /// Decodes a fixed-width base36 string, optionally left-padding to `size` bytes.
+    ///
+    /// # Arguments
+    /// * `encoded` - The base36-encoded string, exactly `width` digits long
+    /// * `width` - The expected number of digits
+    /// * `size` - The expected size of the output in bytes, or 0 for no padding
+    ///
+    /// # Returns
+    /// The decoded bytes
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `encoded` is not exactly `width` characters long,
+    /// contains characters outside the base36 alphabet, or needs more than `size` bytes.
+    pub fn decode_fixed(
+        encoded: &str,
+        width: usize,
+        size: usize,
+    ) -> Result<Vec<u8>, SerialiseError> {
+        check_ascii(encoded, "base36")?;
+        if encoded.len() != width {
+            return Err(SerialiseError::new(format!(
+                "base36 value has {} digits, expected the fixed width {width}",
+                encoded.len()
+            )));
+        }
+        Ok(Arc::unwrap_or_clone(Self::from_base36(encoded, size)?))
+    }
+
+    /// Checks that `base36` only contains base36 digits, without decoding it.
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `base36` contains characters outside the base36 alphabet.
+    pub fn validate(base36: &str) -> Result<(), SerialiseError> {
+        check_ascii(base36, "base36")?;
+        let offset = base36.len() - base36.trim_start().len();
+        base36
+            .trim()
+            .bytes()
+            .enumerate()
+            .try_for_each(|(i, c)| Self::decode_digit(offset + i, c).map(|_| ()))
+    }
+
+    /// Encodes a big integer using base36 encoding.
+    ///
+    /// # Arguments
+    /// * `n` - The value to encode
+    ///
+    /// # Returns
+    /// The base36-encoded string, the same as encoding the value's big-endian bytes
+    #[cfg(feature = "num-bigint")]
+    #[must_use = "This returns the encoded string and does nothing if unused"]
+    pub fn from_biguint(n: &BigUint) -> String {
+        Self::to_base36(&n.to_bytes_be())
+    }
+
+    /// Decodes a base36 string into a big integer.
+    ///
+    /// # Arguments
+    /// * `base36` - The base36-encoded string to decode
+    ///
+    /// # Returns
+    /// The decoded value
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the input contains characters outside the base36 alphabet
+    #[cfg(feature = "num-bigint")]
+    pub fn to_biguint(base36: &str) -> Result<BigUint, SerialiseError> {
+        Ok(BigUint::from_bytes_be(&Self::base36_to_bytes(base36)?))
+    }
 }
 
 impl Encoder for Base36 {
@@ -156,6 +362,7 @@ impl Encoder for Base36 {
 mod tests {
 
     use super::*;
+    use crate::ErrorKind;
 
     #[test]
     fn test_to_base36() {
@@ -186,4 +393,63 @@ mod tests {
 
         assert!(bytes.is_err());
     }
+
+    #[test]
+    fn test_case_folding_and_non_ascii() {
+        assert!(matches!(Base36::base36_to_bytes(" ZZ"), Ok(bytes) if bytes == [0x05, 0x0f]));
+        assert!(matches!(Base36::base36_to_bytes("zz"), Ok(bytes) if bytes == [0x05, 0x0f]));
+        assert!(matches!(
+            Base36::base36_to_bytes(" z!"),
+            Err(e) if e.get_message() == "invalid base36 character '!' at position 2"
+        ));
+        // U+212A KELVIN SIGN folds to 'k' under Unicode rules, but is not a digit
+        assert!(matches!(
+            Base36::base36_to_bytes("z\u{212a}"),
+            Err(e) if e.get_kind() == ErrorKind::NonAsciiInput { position: 1 }
+        ));
+        assert!(matches!(
+            Base36::decode_fixed("\u{43a}", 2, 0),
+            Err(e) if e.get_kind() == ErrorKind::NonAsciiInput { position: 0 }
+        ));
+    }
+
+    #[test]
+    fn test_luhn_check_char() {
+        assert!(matches!(Base36::check_char("a1b2c3"), Ok('r')));
+        assert!(matches!(Base36::check_char("A1B2C3"), Ok('r')));
+        let checked = Base36::to_base36_checked(b"part-0042");
+        assert!(matches!(
+            Base36::from_base36_checked(&checked.to_uppercase(), 0),
+            Ok(bytes) if bytes.as_slice() == b"part-0042"
+        ));
+        let typo = format!("{}x{}", &checked[..2], &checked[3..]);
+        assert!(matches!(
+            Base36::from_base36_checked(&typo, 0),
+            Err(e) if e.get_kind() == ErrorKind::ChecksumMismatch
+        ));
+        assert!(Base36::from_base36_checked("1ab2c3r", 0).is_err());
+    }
+
+    #[test]
+    fn test_fixed_width_round_trip() {
+        let encoded = Base36::encode_fixed(&[0xde, 0xad, 0xbe, 0xef], 12).unwrap_or_default();
+        assert_eq!(encoded.len(), 12);
+        assert!(encoded.starts_with("00000"));
+        assert!(matches!(
+            Base36::decode_fixed(&encoded, 12, 4),
+            Ok(bytes) if bytes == [0xde, 0xad, 0xbe, 0xef]
+        ));
+        assert!(matches!(Base36::encode_fixed(&[0, 0], 3), Ok(s) if s == "000"));
+        assert!(Base36::encode_fixed(&[0xde, 0xad, 0xbe, 0xef], 2).is_err());
+        assert!(Base36::decode_fixed(&encoded, 11, 4).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "num-bigint")]
+    fn test_biguint_round_trip() {
+        let n = BigUint::from(0xdead_beef_u32);
+        assert_eq!(Base36::from_biguint(&n), "1ps9wxb");
+        assert!(matches!(Base36::to_biguint("1ps9wxb"), Ok(m) if m == n));
+        assert!(matches!(Base36::to_biguint("0"), Ok(m) if m == BigUint::ZERO));
+    }
 }
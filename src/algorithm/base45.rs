@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+use crate::{
+    EncodedString, Encoder, Encoding, SerialiseError, alphabet, serialise_error::check_ascii,
+};
+
+const ALPHABET: &[u8; 45] = alphabet::BASE45;
+const DIGITS: [Option<u8>; 256] = alphabet::reverse_lookup(ALPHABET);
+
+/// Base45 encoding implementation (RFC 9285).
+///
+/// Every output character is in the QR code alphanumeric set, so a QR code can
+/// store Base45 text in alphanumeric mode at 5.5 bits per character.
+#[derive(Debug)]
+pub struct Base45 {}
+
+impl Base45 {
+    /// Encodes a byte slice using base45 encoding.
+    ///
+    /// Each pair of bytes becomes three characters and a final odd byte becomes two.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes to encode
+    ///
+    /// # Returns
+    /// The base45-encoded string
+    #[must_use = "This returns the encoded string and does nothing if unused"]
+    pub fn to_base45(bytes: &[u8]) -> String {
+        let mut out = String::with_capacity(bytes.len().div_ceil(2) * 3);
+        for chunk in bytes.chunks(2) {
+            let mut n = chunk.iter().fold(0usize, |n, &b| (n << 8) | usize::from(b));
+            for _ in 0..=chunk.len() {
+                out.push(char::from(ALPHABET[n % 45]));
+                n /= 45;
+            }
+        }
+        out
+    }
+
+    /// Returns the value of a group of two or three characters, checking that it
+    /// fits in the one or two bytes the group encodes.
+    fn group_value(group: &[u8]) -> Result<usize, SerialiseError> {
+        let mut n = 0usize;
+        for &c in group.iter().rev() {
+            let Some(digit) = DIGITS[usize::from(c)] else {
+                return Err(SerialiseError::new("invalid base45 character".to_string()));
+            };
+            n = n * 45 + usize::from(digit);
+        }
+
+        if n >> (8 * (group.len() - 1)) != 0 {
+            return Err(SerialiseError::new(format!(
+                "base45 group value {n} is out of range"
+            )));
+        }
+        Ok(n)
+    }
+
+    fn check_len(base45: &str) -> Result<(), SerialiseError> {
+        if base45.len() % 3 == 1 {
+            return Err(SerialiseError::new(format!(
+                "base45 input of {} characters is truncated",
+                base45.len()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Decodes a base45 string into bytes.
+    ///
+    /// # Arguments
+    /// * `base45` - The base45-encoded string to decode
+    ///
+    /// # Returns
+    /// The decoded bytes
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the input contains characters outside the base45
+    /// alphabet, has a length no encoder could have produced, or contains a group
+    /// whose value does not fit in the bytes it encodes.
+    pub fn try_from_base45(base45: &str) -> Result<Vec<u8>, SerialiseError> {
+        check_ascii(base45, "base45")?;
+        Self::check_len(base45)?;
+
+        let mut out = Vec::with_capacity(base45.len() / 3 * 2 + 1);
+        for group in base45.as_bytes().chunks(3) {
+            let [.., hi, lo] = Self::group_value(group)?.to_be_bytes();
+            if group.len() == 3 {
+                out.push(hi);
+            }
+            out.push(lo);
+        }
+
+        Ok(out)
+    }
+
+    /// Checks that `base45` is well-formed base45, without decoding it.
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` for any input [`Base45::try_from_base45`] would reject.
+    pub fn validate(base45: &str) -> Result<(), SerialiseError> {
+        check_ascii(base45, "base45")?;
+        Self::check_len(base45)?;
+        base45
+            .as_bytes()
+            .chunks(3)
+            .try_for_each(|group| Self::group_value(group).map(|_| ()))
+    }
+}
+
+impl Encoder for Base45 {
+    fn try_encode(bytes: Arc<Vec<u8>>) -> Result<EncodedString, SerialiseError> {
+        Ok(EncodedString::new(
+            Encoding::Base45,
+            Self::to_base45(&bytes),
+        ))
+    }
+
+    fn try_decode(encoded: &EncodedString) -> Result<Arc<Vec<u8>>, SerialiseError> {
+        Ok(Arc::new(Self::try_from_base45(encoded.get_string())?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_to_base45() {
+        assert_eq!(Base45::to_base45(b"AB"), "BB8");
+        assert_eq!(Base45::to_base45(b"Hello!!"), "%69 VD92EX0");
+        assert_eq!(Base45::to_base45(b"base-45"), "UJCLQE7W581");
+        assert_eq!(Base45::to_base45(b""), "");
+    }
+
+    #[test]
+    fn test_from_base45() {
+        assert!(matches!(
+            Base45::try_from_base45("QED8WEX0"),
+            Ok(bytes) if bytes == b"ietf!"
+        ));
+        assert!(matches!(
+            Base45::try_from_base45("%69 VD92EX0"),
+            Ok(bytes) if bytes == b"Hello!!"
+        ));
+    }
+
+    #[test]
+    fn test_from_invalid_base45_is_err() {
+        assert!(Base45::try_from_base45("GGW").is_err());
+        assert!(Base45::try_from_base45("BB8B").is_err());
+        assert!(Base45::try_from_base45("bb8").is_err());
+        assert!(Base45::try_from_base45("ZZ").is_err());
+    }
+}
@@ -1,8 +1,34 @@
+#![cfg_attr(
+    all(feature = "no-panic", not(test)),
+    deny(
+        clippy::indexing_slicing,
+        clippy::panic,
+        clippy::unreachable,
+        clippy::unwrap_used,
+        clippy::expect_used
+    )
+)]
+
 use std::sync::Arc;
 
-use crate::{EncodedString, Encoder, Encoding, SerialiseError};
+#[cfg(feature = "num-bigint")]
+use num_bigint::BigUint;
+
+use crate::{
+    EncodedString, Encoder, Encoding, SerialiseError, alphabet,
+    scratch::{self, Scratch},
+    serialise_error::check_ascii,
+};
+
+const ALPHABET: &[u8; 58] = alphabet::BASE58_BITCOIN;
+const DIGITS: [Option<u8>; 256] = alphabet::reverse_lookup(ALPHABET);
 
-const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+/// Number of base58 digits produced by each long division during encoding.
+const BLOCK_DIGITS: usize = 5;
+
+/// `58^5`, the largest power of 58 that fits in a `u32` limb, so each step of the
+/// division stays within native `u64` arithmetic.
+const BLOCK: u64 = 58u64.pow(5);
 
 /// Base58 encoding implementation (Bitcoin-style).
 ///
@@ -39,37 +65,74 @@ impl Base58 {
     /// # Returns
     /// The base58-encoded string
     #[must_use]
-    #[allow(clippy::missing_panics_doc)]
     pub fn to_base58(bytes: &[u8]) -> String {
-        if bytes.is_empty() {
-            return "0".to_string();
-        }
+        scratch::with_scratch(|scratch| Self::encode_with_scratch(bytes, scratch).to_string())
+    }
 
-        if bytes.iter().all(|&b| b == 0) {
-            return "0".to_string();
-        }
+    /// Encodes a byte slice using base58 encoding, working in `scratch`
+    /// instead of allocating.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes to encode
+    /// * `scratch` - The buffers to work in, reused across calls
+    ///
+    /// # Returns
+    /// The base58-encoded string, borrowed from `scratch`; the same as
+    /// [`Base58::to_base58`]
+    #[must_use = "This returns the encoded string but does nothing if unused"]
+    pub fn encode_with_scratch<'s>(bytes: &[u8], scratch: &'s mut Scratch) -> &'s str {
+        let Scratch {
+            words: limbs,
+            text: digits,
+            ..
+        } = scratch;
+        digits.clear();
+        let Some(bytes) = bytes
+            .iter()
+            .position(|&b| b != 0)
+            .and_then(|start| bytes.get(start..))
+        else {
+            digits.push(b'0');
+            return scratch.get_text();
+        };
 
-        let mut n = bytes.to_vec();
-        let mut out: Vec<u8> = Vec::new();
+        // Big-endian 32-bit limbs; the first takes the bytes left over from
+        // whole limbs.
+        limbs.clear();
+        limbs.extend(
+            bytes
+                .rchunks(4)
+                .rev()
+                .map(|chunk| chunk.iter().fold(0, |n, &b| (n << 8) | u64::from(b))),
+        );
 
-        while !n.is_empty() && n.iter().any(|&b| b != 0) {
-            let mut rem: u32 = 0;
-            for b in &mut n {
-                let v = (rem << 8) | u32::from(*b);
-                *b = u8::try_from(v / 58)
-                    .unwrap_or_else(|_| unreachable!("base58 division quotient must fit in u8"));
-                rem = v % 58;
+        // Dividing by 58^5 yields five digits per pass over the limbs, instead
+        // of one digit per pass over the bytes.
+        digits.reserve(bytes.len() * 138 / 100 + BLOCK_DIGITS);
+        let mut first = 0;
+        while first < limbs.len() {
+            let mut rem: u64 = 0;
+            for limb in limbs.iter_mut().skip(first) {
+                let v = (rem << 32) | *limb;
+                *limb = v / BLOCK;
+                rem = v % BLOCK;
+            }
+            while limbs.get(first) == Some(&0) {
+                first += 1;
             }
 
-            out.push(ALPHABET[rem as usize]);
-
-            while n.first().copied() == Some(0) {
-                n.remove(0);
+            for _ in 0..BLOCK_DIGITS {
+                digits.extend(ALPHABET.get(usize::try_from(rem % 58).unwrap_or_default()));
+                rem /= 58;
             }
         }
 
-        out.reverse();
-        out.into_iter().map(char::from).collect()
+        // The last block is padded with zero digits, which lead the number
+        while digits.len() > 1 && digits.last() == Some(&ALPHABET[0]) {
+            digits.pop();
+        }
+        digits.reverse();
+        scratch.get_text()
     }
 
     /// Converts a base58 string into its byte representation.
@@ -84,37 +147,42 @@ impl Base58 {
     /// Returns an error if the input contains characters outside the base58 alphabet.
     #[must_use = "This returns the decoded bytes but does nothing if unused"]
     pub fn base58_to_bytes(base58: &str) -> Result<Vec<u8>, SerialiseError> {
-        let s = base58.trim();
-        if s.is_empty() || s == "0" {
-            return Ok(vec![0]);
-        }
-
-        let mut bytes: Vec<u8> = vec![0];
-
-        for c in s.bytes() {
-            let Some(pos) = ALPHABET.iter().position(|&b| b == c) else {
-                return Err(SerialiseError::new("invalid base58 character".to_string()));
-            };
-            let digit = u32::try_from(pos).unwrap_or_else(|_| unreachable!());
-
-            let mut carry = digit;
-            for b in bytes.iter_mut().rev() {
-                let v = u32::from(*b) * 58 + carry;
-                *b = (v & 0xff) as u8;
-                carry = v >> 8;
-            }
+        scratch::with_scratch(|scratch| {
+            Self::decode_with_scratch(base58, scratch).map(<[u8]>::to_vec)
+        })
+    }
 
-            while carry > 0 {
-                bytes.insert(0, (carry & 0xff) as u8);
-                carry >>= 8;
+    /// Converts a base58 string into its byte representation, working in
+    /// `scratch` instead of allocating.
+    ///
+    /// # Arguments
+    /// * `base58` - The base58-encoded string to convert
+    /// * `scratch` - The buffers to work in, reused across calls
+    ///
+    /// # Returns
+    /// The decoded bytes, borrowed from `scratch`; the same as
+    /// [`Base58::base58_to_bytes`]
+    ///
+    /// # Errors
+    /// Returns an error if the input contains characters outside the base58 alphabet.
+    pub fn decode_with_scratch<'s>(
+        base58: &str,
+        scratch: &'s mut Scratch,
+    ) -> Result<&'s [u8], SerialiseError> {
+        let acc = &mut scratch.bytes;
+        acc.clear();
+        acc.push(0);
+        let s = base58.trim();
+        if s != "0" {
+            for c in s.bytes() {
+                let Some(digit) = DIGITS.get(usize::from(c)).copied().flatten() else {
+                    return Err(SerialiseError::new("invalid base58 character".to_string()));
+                };
+                scratch::mul_add(acc, 58, u32::from(digit));
             }
         }
-
-        while bytes.len() > 1 && bytes[0] == 0 {
-            bytes.remove(0);
-        }
-
-        Ok(bytes)
+        scratch::finish_be(acc);
+        Ok(acc)
     }
 
     /// Decodes a base58 string into bytes, optionally left-padding to `size`.
@@ -123,6 +191,7 @@ impl Base58 {
     ///
     /// Returns an error if the decoded value requires more than `size` bytes when `size > 0`.
     pub fn try_from_base58(base58: &str, size: usize) -> Result<Vec<u8>, SerialiseError> {
+        check_ascii(base58, "base58")?;
         match Self::base58_to_bytes(base58) {
             Ok(mut bytes) => {
                 if bytes.len() > size && size > 0 {
@@ -142,6 +211,111 @@ impl Base58 {
             Err(e) => Err(e),
         }
     }
+
+    /// Encodes bytes as exactly `width` base58 digits, left-padded with `1`.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes to encode
+    /// * `width` - The number of digits to emit
+    ///
+    /// # Returns
+    /// The fixed-width base58-encoded string
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the value needs more than `width` digits.
+    pub fn encode_fixed(bytes: &[u8], width: usize) -> Result<String, SerialiseError> {
+        let digits = if bytes.iter().all(|&b| b == 0) {
+            String::new()
+        } else {
+            Self::to_base58(bytes)
+        };
+        if digits.len() > width {
+            return Err(SerialiseError::new(format!(
+                "base58 value needs {} digits, more than the fixed width {width}",
+                digits.len()
+            )));
+        }
+
+        let mut out = String::with_capacity(width);
+        out.extend(std::iter::repeat_n(
+            char::from(ALPHABET[0]),
+            width - digits.len(),
+        ));
+        out.push_str(&digits);
+        Ok(out)
+    }
+
+    /// Decodes a fixed-width base58 string, optionally left-padding to `size` bytes.
+    ///
+    /// # Arguments
+    /// * `encoded` - The base58-encoded string, exactly `width` digits long
+    /// * `width` - The expected number of digits
+    /// * `size` - The expected size of the output in bytes, or 0 for no padding
+    ///
+    /// # Returns
+    /// The decoded bytes
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `encoded` is not exactly `width` characters long,
+    /// contains characters outside the base58 alphabet, or needs more than `size` bytes.
+    pub fn decode_fixed(
+        encoded: &str,
+        width: usize,
+        size: usize,
+    ) -> Result<Vec<u8>, SerialiseError> {
+        if encoded.len() != width {
+            return Err(SerialiseError::new(format!(
+                "base58 value has {} digits, expected the fixed width {width}",
+                encoded.len()
+            )));
+        }
+        Self::try_from_base58(encoded, size)
+    }
+
+    /// Checks that `base58` only contains base58 digits, without decoding it.
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `base58` contains characters outside the base58 alphabet.
+    pub fn validate(base58: &str) -> Result<(), SerialiseError> {
+        check_ascii(base58, "base58")?;
+        let s = base58.trim();
+        if s == "0"
+            || s.bytes()
+                .all(|c| DIGITS.get(usize::from(c)).copied().flatten().is_some())
+        {
+            Ok(())
+        } else {
+            Err(SerialiseError::new("invalid base58 character".to_string()))
+        }
+    }
+
+    /// Encodes a big integer using base58 encoding.
+    ///
+    /// # Arguments
+    /// * `n` - The value to encode
+    ///
+    /// # Returns
+    /// The base58-encoded string, the same as encoding the value's big-endian bytes
+    #[cfg(feature = "num-bigint")]
+    #[must_use = "This returns the encoded string and does nothing if unused"]
+    pub fn from_biguint(n: &BigUint) -> String {
+        Self::to_base58(&n.to_bytes_be())
+    }
+
+    /// Decodes a base58 string into a big integer.
+    ///
+    /// # Arguments
+    /// * `base58` - The base58-encoded string to decode
+    ///
+    /// # Returns
+    /// The decoded value
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the input contains characters outside the base58 alphabet
+    #[cfg(feature = "num-bigint")]
+    pub fn to_biguint(base58: &str) -> Result<BigUint, SerialiseError> {
+        Ok(BigUint::from_bytes_be(&Self::base58_to_bytes(base58)?))
+    }
 }
 
 impl Encoder for Base58 {
@@ -169,6 +343,42 @@ mod tests {
         assert_eq!(base58, "NE1FfXYqCHge2p4MZ56o8gdrDWMiHXPJLXk9ixxKgUebU7VqB",);
     }
 
+    /// Matches the one-digit-per-pass long division for every length up to a
+    /// few limbs, with and without leading zero bytes.
+    #[test]
+    fn test_block_encoding_matches_long_division() {
+        fn reference(bytes: &[u8]) -> String {
+            let mut n: Vec<u8> = bytes.iter().copied().skip_while(|&b| b == 0).collect();
+            let mut out = Vec::new();
+            while !n.is_empty() {
+                let mut rem = 0u32;
+                for b in &mut n {
+                    let v = (rem << 8) | u32::from(*b);
+                    *b = u8::try_from(v / 58).unwrap_or_default();
+                    rem = v % 58;
+                }
+                out.push(char::from(ALPHABET[rem as usize]));
+                n = n.into_iter().skip_while(|&b| b == 0).collect();
+            }
+            if out.is_empty() {
+                return "0".to_string();
+            }
+            out.iter().rev().collect()
+        }
+
+        for len in 0..=80 {
+            let bytes: Vec<u8> = (0..len)
+                .map(|i| u8::try_from((i * 97 + len * 31) % 256).unwrap_or_default())
+                .collect();
+            assert_eq!(Base58::to_base58(&bytes), reference(&bytes), "{len} bytes");
+            let mut padded = vec![0; 3];
+            padded.extend(&bytes);
+            assert_eq!(Base58::to_base58(&padded), reference(&padded));
+            let max = vec![0xff; len];
+            assert_eq!(Base58::to_base58(&max), reference(&max));
+        }
+    }
+
     #[test]
     fn test_from_base58() {
         let string = "NE1FfXYqCHge2p4MZ56o8gdrDWMiHXPJLXk9ixxKgUebU7VqB";
@@ -182,4 +392,27 @@ mod tests {
         let bytes = Base58::try_from_base58(string, 0);
         assert!(bytes.is_err());
     }
+
+    #[test]
+    fn test_fixed_width_round_trip() {
+        let encoded = Base58::encode_fixed(&[0xde, 0xad, 0xbe, 0xef], 12).unwrap_or_default();
+        assert_eq!(encoded.len(), 12);
+        assert!(encoded.starts_with("11111"));
+        assert!(matches!(
+            Base58::decode_fixed(&encoded, 12, 4),
+            Ok(bytes) if bytes == [0xde, 0xad, 0xbe, 0xef]
+        ));
+        assert!(matches!(Base58::encode_fixed(&[0, 0], 3), Ok(s) if s == "111"));
+        assert!(Base58::encode_fixed(&[0xde, 0xad, 0xbe, 0xef], 2).is_err());
+        assert!(Base58::decode_fixed(&encoded, 11, 4).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "num-bigint")]
+    fn test_biguint_round_trip() {
+        let n = BigUint::from(0xdead_beef_u32);
+        assert_eq!(Base58::from_biguint(&n), "6h8cQN");
+        assert!(matches!(Base58::to_biguint("6h8cQN"), Ok(m) if m == n));
+        assert!(matches!(Base58::to_biguint("0"), Ok(m) if m == BigUint::ZERO));
+    }
 }
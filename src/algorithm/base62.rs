@@ -0,0 +1,327 @@
+use std::sync::Arc;
+
+#[cfg(feature = "num-bigint")]
+use num_bigint::BigUint;
+
+use crate::{
+    EncodedString, Encoder, Encoding, SerialiseError, alphabet,
+    scratch::{self, Scratch},
+    serialise_error::check_ascii,
+};
+
+const ALPHABET: &[u8; 62] = alphabet::BASE62;
+
+/// Base62 encoding implementation (0-9, A-Z and a-z).
+///
+/// This type provides methods to encode and decode data using base62 encoding,
+/// which only uses alphanumeric characters and so is safe in URLs, filenames
+/// and identifiers.
+#[derive(Debug)]
+pub struct Base62 {}
+
+impl Base62 {
+    const fn digit_value(c: u8) -> Option<u32> {
+        match c {
+            b'0'..=b'9' => Some((c - b'0') as u32),
+            b'A'..=b'Z' => Some((c - b'A' + 10) as u32),
+            b'a'..=b'z' => Some((c - b'a' + 36) as u32),
+            _ => None,
+        }
+    }
+
+    /// Encodes a byte slice using base62 encoding.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes to encode
+    ///
+    /// # Returns
+    /// The base62-encoded string, `"0"` if the value is zero
+    #[must_use = "This returns the encoded string and does nothing if unused"]
+    pub fn to_base62(bytes: &[u8]) -> String {
+        scratch::with_scratch(|scratch| Self::encode_with_scratch(bytes, scratch).to_string())
+    }
+
+    /// Encodes a byte slice using base62 encoding, working in `scratch`
+    /// instead of allocating.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes to encode
+    /// * `scratch` - The buffers to work in, reused across calls
+    ///
+    /// # Returns
+    /// The base62-encoded string, borrowed from `scratch`; the same as
+    /// [`Base62::to_base62`]
+    #[must_use = "This returns the encoded string but does nothing if unused"]
+    pub fn encode_with_scratch<'s>(bytes: &[u8], scratch: &'s mut Scratch) -> &'s str {
+        let Scratch {
+            bytes: n,
+            text: out,
+            ..
+        } = scratch;
+        out.clear();
+        n.clear();
+        n.extend(bytes.iter().copied().skip_while(|&b| b == 0));
+
+        let mut first = 0;
+        while first < n.len() {
+            let mut rem: u32 = 0;
+            for b in &mut n[first..] {
+                let v = (rem << 8) | u32::from(*b);
+                *b = u8::try_from(v / 62).unwrap_or_else(|_| unreachable!());
+                rem = v % 62;
+            }
+
+            out.push(ALPHABET[rem as usize]);
+
+            first += n[first..].iter().take_while(|&&b| b == 0).count();
+        }
+        if out.is_empty() {
+            out.push(b'0');
+        }
+        out.reverse();
+        scratch.get_text()
+    }
+
+    /// Converts a base62 string into its byte representation.
+    ///
+    /// # Arguments
+    /// * `base62` - The base62-encoded string to convert
+    ///
+    /// # Returns
+    /// The decoded bytes, with leading zero bytes removed
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the input contains characters outside the base62 alphabet
+    pub fn base62_to_bytes(base62: &str) -> Result<Vec<u8>, SerialiseError> {
+        scratch::with_scratch(|scratch| {
+            Self::decode_with_scratch(base62, scratch).map(<[u8]>::to_vec)
+        })
+    }
+
+    /// Converts a base62 string into its byte representation, working in
+    /// `scratch` instead of allocating.
+    ///
+    /// # Arguments
+    /// * `base62` - The base62-encoded string to convert
+    /// * `scratch` - The buffers to work in, reused across calls
+    ///
+    /// # Returns
+    /// The decoded bytes, borrowed from `scratch`; the same as
+    /// [`Base62::base62_to_bytes`]
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the input contains characters outside the base62 alphabet
+    pub fn decode_with_scratch<'s>(
+        base62: &str,
+        scratch: &'s mut Scratch,
+    ) -> Result<&'s [u8], SerialiseError> {
+        let acc = &mut scratch.bytes;
+        acc.clear();
+        acc.push(0);
+        for c in base62.trim().bytes() {
+            let Some(digit) = Self::digit_value(c) else {
+                return Err(SerialiseError::new("invalid base62 character".to_string()));
+            };
+            scratch::mul_add(acc, 62, digit);
+        }
+        scratch::finish_be(acc);
+        Ok(acc)
+    }
+
+    /// Decodes a base62 string into bytes, optionally left-padding to `size`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `base62` contains characters outside the base62 alphabet.
+    ///
+    /// Returns `Err` if the decoded value requires more than `size` bytes when `size > 0`.
+    pub fn try_from_base62(base62: &str, size: usize) -> Result<Vec<u8>, SerialiseError> {
+        check_ascii(base62, "base62")?;
+        let mut bytes = Self::base62_to_bytes(base62)?;
+
+        if bytes.len() > size && size > 0 {
+            return Err(SerialiseError::new(format!(
+                "base62 value does not fit in {size} bytes"
+            )));
+        }
+
+        if bytes.len() < size && size > 0 {
+            let mut padded = vec![0u8; size - bytes.len()];
+            padded.append(&mut bytes);
+            return Ok(padded);
+        }
+
+        Ok(bytes)
+    }
+
+    /// Encodes bytes as exactly `width` base62 digits, left-padded with `0`.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes to encode
+    /// * `width` - The number of digits to emit
+    ///
+    /// # Returns
+    /// The fixed-width base62-encoded string
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the value needs more than `width` digits.
+    pub fn encode_fixed(bytes: &[u8], width: usize) -> Result<String, SerialiseError> {
+        let digits = if bytes.iter().all(|&b| b == 0) {
+            String::new()
+        } else {
+            Self::to_base62(bytes)
+        };
+        if digits.len() > width {
+            return Err(SerialiseError::new(format!(
+                "base62 value needs {} digits, more than the fixed width {width}",
+                digits.len()
+            )));
+        }
+
+        let mut out = String::with_capacity(width);
+        out.extend(std::iter::repeat_n(
+            char::from(ALPHABET[0]),
+            width - digits.len(),
+        ));
+        out.push_str(&digits);
+        Ok(out)
+    }
+
+    /// Decodes a fixed-width base62 string, optionally left-padding to `size` bytes.
+    ///
+    /// # Arguments
+    /// * `encoded` - The base62-encoded string, exactly `width` digits long
+    /// * `width` - The expected number of digits
+    /// * `size` - The expected size of the output in bytes, or 0 for no padding
+    ///
+    /// # Returns
+    /// The decoded bytes
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `encoded` is not exactly `width` characters long,
+    /// contains characters outside the base62 alphabet, or needs more than `size` bytes.
+    pub fn decode_fixed(
+        encoded: &str,
+        width: usize,
+        size: usize,
+    ) -> Result<Vec<u8>, SerialiseError> {
+        if encoded.len() != width {
+            return Err(SerialiseError::new(format!(
+                "base62 value has {} digits, expected the fixed width {width}",
+                encoded.len()
+            )));
+        }
+        Self::try_from_base62(encoded, size)
+    }
+
+    /// Checks that `base62` only contains base62 digits, without decoding it.
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `base62` contains characters outside the base62 alphabet.
+    pub fn validate(base62: &str) -> Result<(), SerialiseError> {
+        check_ascii(base62, "base62")?;
+        if base62
+            .trim()
+            .bytes()
+            .all(|c| Self::digit_value(c).is_some())
+        {
+            Ok(())
+        } else {
+            Err(SerialiseError::new("invalid base62 character".to_string()))
+        }
+    }
+
+    /// Encodes a big integer using base62 encoding.
+    ///
+    /// # Arguments
+    /// * `n` - The value to encode
+    ///
+    /// # Returns
+    /// The base62-encoded string, the same as encoding the value's big-endian bytes
+    #[cfg(feature = "num-bigint")]
+    #[must_use = "This returns the encoded string and does nothing if unused"]
+    pub fn from_biguint(n: &BigUint) -> String {
+        Self::to_base62(&n.to_bytes_be())
+    }
+
+    /// Decodes a base62 string into a big integer.
+    ///
+    /// # Arguments
+    /// * `base62` - The base62-encoded string to decode
+    ///
+    /// # Returns
+    /// The decoded value
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the input contains characters outside the base62 alphabet
+    #[cfg(feature = "num-bigint")]
+    pub fn to_biguint(base62: &str) -> Result<BigUint, SerialiseError> {
+        Ok(BigUint::from_bytes_be(&Self::base62_to_bytes(base62)?))
+    }
+}
+
+impl Encoder for Base62 {
+    fn try_encode(bytes: Arc<Vec<u8>>) -> Result<EncodedString, SerialiseError> {
+        Ok(EncodedString::new(
+            Encoding::Base62,
+            Self::to_base62(&bytes),
+        ))
+    }
+
+    fn try_decode(encoded: &EncodedString) -> Result<Arc<Vec<u8>>, SerialiseError> {
+        Ok(Arc::new(Self::try_from_base62(encoded.get_string(), 0)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_to_base62() {
+        assert_eq!(Base62::to_base62(&[0xde, 0xad, 0xbe, 0xef]), "44pZgF");
+        assert_eq!(Base62::to_base62(&[0, 0]), "0");
+    }
+
+    #[test]
+    fn test_from_base62() {
+        assert!(matches!(
+            Base62::try_from_base62("44pZgF", 0),
+            Ok(bytes) if bytes == [0xde, 0xad, 0xbe, 0xef]
+        ));
+        assert!(matches!(
+            Base62::try_from_base62("44pZgF", 6),
+            Ok(bytes) if bytes == [0, 0, 0xde, 0xad, 0xbe, 0xef]
+        ));
+    }
+
+    #[test]
+    fn test_from_invalid_base62() {
+        assert!(Base62::try_from_base62("44pZ-gF", 0).is_err());
+        assert!(Base62::try_from_base62("44pZgF", 3).is_err());
+    }
+
+    #[test]
+    fn test_fixed_width_round_trip() {
+        let encoded = Base62::encode_fixed(&[0xde, 0xad, 0xbe, 0xef], 12).unwrap_or_default();
+        assert_eq!(encoded.len(), 12);
+        assert!(encoded.starts_with("00000"));
+        assert!(matches!(
+            Base62::decode_fixed(&encoded, 12, 4),
+            Ok(bytes) if bytes == [0xde, 0xad, 0xbe, 0xef]
+        ));
+        assert!(matches!(Base62::encode_fixed(&[0, 0], 3), Ok(s) if s == "000"));
+        assert!(Base62::encode_fixed(&[0xde, 0xad, 0xbe, 0xef], 2).is_err());
+        assert!(Base62::decode_fixed(&encoded, 11, 4).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "num-bigint")]
+    fn test_biguint_round_trip() {
+        let n = BigUint::from(0xdead_beef_u32);
+        assert_eq!(Base62::from_biguint(&n), "44pZgF");
+        assert!(matches!(Base62::to_biguint("44pZgF"), Ok(m) if m == n));
+        assert!(matches!(Base62::to_biguint("0"), Ok(m) if m == BigUint::ZERO));
+    }
+}
@@ -1,8 +1,109 @@
-use std::sync::Arc;
+use std::{mem::MaybeUninit, sync::Arc};
 
-use crate::{EncodedString, Encoder, Encoding, SerialiseError};
+use crate::{
+    Decoder, EncodedString, Encoder, Encoding, LineEnding, SerialiseError, alphabet,
+    decoder::BitDecoder, serialise_error::check_ascii,
+};
 
-const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const ALPHABET: &[u8; 64] = alphabet::BASE64_STANDARD;
+const PAD: u8 = b'=';
+const DIGITS: [Option<u8>; 256] = alphabet::reverse_lookup(ALPHABET);
+
+/// Padding and line wrapping options for [`Base64`].
+///
+/// The default pads with `=` as RFC 4648 requires and writes a single line. Some
+/// systems use a different padding character or none at all; decoders built from
+/// a config accept only the configured character. Decoders accept either line
+/// ending whatever the config says.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Base64Config {
+    /// The padding character, or `None` for unpadded output
+    padding: Option<u8>,
+    /// Maximum line length, or `None` for a single line
+    wrap: Option<usize>,
+    /// Line ending between wrapped lines
+    line_ending: LineEnding,
+}
+
+impl Base64Config {
+    /// RFC 4648 output padded with `=`.
+    pub const STANDARD: Self = Self {
+        padding: Some(PAD),
+        wrap: None,
+        line_ending: LineEnding::Lf,
+    };
+
+    /// Unpadded output. Decoders reject padding characters.
+    pub const NO_PAD: Self = Self {
+        padding: None,
+        ..Self::STANDARD
+    };
+
+    /// MIME output (RFC 2045): padded, in lines of 76 characters ending in CRLF.
+    pub const MIME: Self = Self {
+        wrap: Some(76),
+        line_ending: LineEnding::CrLf,
+        ..Self::STANDARD
+    };
+
+    /// Pads with `padding` instead of `=`.
+    ///
+    /// # Arguments
+    /// * `padding` - The padding character
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `padding` is not a printable ASCII character or is
+    /// part of the base64 alphabet.
+    pub fn with_padding(padding: char) -> Result<Self, SerialiseError> {
+        match u8::try_from(padding) {
+            Ok(pad) if pad.is_ascii_graphic() && !ALPHABET.contains(&pad) => Ok(Self {
+                padding: Some(pad),
+                ..Self::STANDARD
+            }),
+            _ => Err(SerialiseError::new(format!(
+                "'{padding}' cannot be used as base64 padding"
+            ))),
+        }
+    }
+
+    /// Breaks the output into lines of at most `width` characters, or a single line if zero.
+    #[must_use = "This returns the updated config but does nothing if unused"]
+    pub const fn wrap(mut self, width: usize) -> Self {
+        self.wrap = if width == 0 { None } else { Some(width) };
+        self
+    }
+
+    /// Separates wrapped lines with `line_ending` instead of `\n`.
+    #[must_use = "This returns the updated config but does nothing if unused"]
+    pub const fn line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    /// Returns the padding character, or `None` for unpadded output.
+    #[must_use = "This returns the padding character but does nothing if unused"]
+    pub fn get_padding(&self) -> Option<char> {
+        self.padding.map(char::from)
+    }
+
+    /// Returns the maximum line length, or `None` for a single line.
+    #[must_use = "This returns the line length but does nothing if unused"]
+    pub const fn get_wrap(&self) -> Option<usize> {
+        self.wrap
+    }
+
+    /// Returns the line ending between wrapped lines.
+    #[must_use = "This returns the line ending but does nothing if unused"]
+    pub const fn get_line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+}
+
+impl Default for Base64Config {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
 
 /// Base64 encoding implementation (RFC 4648).
 #[derive(Debug)]
@@ -23,86 +124,146 @@ impl Base64 {
         self.serialised
     }
 
-    /// Convert bytes to base64 string.
+    /// Convert bytes to a padded base64 string.
     ///
     /// # Errors
     ///
     /// This function currently does not return an error.
     pub fn try_to_base64(bytes: &[u8]) -> Result<String, SerialiseError> {
-        if bytes.is_empty() {
-            return Ok("0".to_string());
-        }
+        Self::try_to_base64_with(bytes, Base64Config::STANDARD)
+    }
 
-        if bytes.iter().all(|&b| b == 0) {
-            return Ok("0".to_string());
+    /// Convert bytes to a base64 string with the given padding and wrapping options.
+    ///
+    /// # Errors
+    ///
+    /// This function currently does not return an error.
+    pub fn try_to_base64_with(
+        bytes: &[u8],
+        config: Base64Config,
+    ) -> Result<String, SerialiseError> {
+        let mut buf = vec![0u8; bytes.len().div_ceil(3) * 4];
+        let len = Self::encode_into(bytes, &mut buf, config.padding);
+        buf.truncate(len);
+        let mut out = String::from_utf8(buf)
+            .map_err(|_| SerialiseError::new("base64 output is not ASCII".to_string()))?;
+        if let Some(width) = config.wrap {
+            out = config.line_ending.wrap(&out, width);
         }
+        Ok(out)
+    }
 
-        let mut n = bytes.to_vec();
-        let mut out: Vec<u8> = Vec::new();
-
-        while !n.is_empty() && n.iter().any(|&b| b != 0) {
-            let mut rem: u32 = 0;
-            for b in &mut n {
-                let v = (rem << 8) | u32::from(*b);
-                *b = u8::try_from(v / 64).unwrap_or_else(|_| unreachable!());
-                rem = v % 64;
-            }
-
-            out.push(ALPHABET[rem as usize]);
+    /// Writes the base64 digits of `bytes` to the start of `out`, which must hold
+    /// at least `4 * ceil(len / 3)` bytes, and returns the number written.
+    fn encode_into(bytes: &[u8], out: &mut [u8], padding: Option<u8>) -> usize {
+        let mut len = 0;
+        for chunk in bytes.chunks(3) {
+            let b = [
+                chunk[0],
+                chunk.get(1).copied().unwrap_or(0),
+                chunk.get(2).copied().unwrap_or(0),
+            ];
+            let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
 
-            while n.first().copied() == Some(0) {
-                n.remove(0);
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    out[len] = ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize];
+                } else if let Some(pad) = padding {
+                    out[len] = pad;
+                } else {
+                    continue;
+                }
+                len += 1;
             }
         }
-
-        out.reverse();
-        Ok(out.into_iter().map(char::from).collect())
+        len
     }
 
-    fn base64_to_bytes(base64: &str) -> Result<Vec<u8>, SerialiseError> {
-        let s = base64.trim();
-        if s.is_empty() || s == "0" {
-            return Ok(vec![0]);
+    /// Writes padded base64 into a caller-provided buffer without allocating.
+    ///
+    /// # Arguments
+    /// * `input` - The bytes to encode
+    /// * `output` - The buffer to write to, at least
+    ///   [`base64_len(input.len())`](crate::const_encode::base64_len) bytes
+    ///
+    /// # Returns
+    /// The number of bytes written to the start of `output`
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `output` is too small. Building the error is
+    /// the only allocation.
+    pub fn encode_slice(input: &[u8], output: &mut [u8]) -> Result<usize, SerialiseError> {
+        let needed = input.len().div_ceil(3) * 4;
+        if output.len() < needed {
+            return Err(SerialiseError::new(format!(
+                "base64 output needs {needed} bytes, buffer has {}",
+                output.len()
+            )));
         }
+        Ok(Self::encode_into(input, output, Some(PAD)))
+    }
 
-        let mut bytes: Vec<u8> = vec![0];
-
-        for c in s.bytes() {
-            let Some(pos) = ALPHABET.iter().position(|&b| b == c) else {
-                return Err(SerialiseError::new("invalid base64 character".to_string()));
-            };
-            let digit = u32::try_from(pos).unwrap_or_else(|_| unreachable!());
+    const fn decode_digit(c: u8) -> Option<u8> {
+        DIGITS[c as usize]
+    }
 
-            let mut carry = digit;
-            for b in bytes.iter_mut().rev() {
-                let v = u32::from(*b) * 64 + carry;
-                *b = (v & 0xff) as u8;
-                carry = v >> 8;
+    /// Packs 6-bit digits into bytes, dropping any trailing bits that do not
+    /// make up a whole byte.
+    fn digits_to_bytes(digits: impl Iterator<Item = u8>) -> Vec<u8> {
+        let mut out: Vec<u8> = Vec::new();
+        let mut acc: u32 = 0;
+        let mut bits = 0;
+        for digit in digits {
+            acc = (acc << 6) | u32::from(digit);
+            bits += 6;
+            if bits >= 8 {
+                bits -= 8;
+                out.push(((acc >> bits) & 0xff) as u8);
             }
+            acc &= (1 << bits) - 1;
+        }
+        out
+    }
 
-            while carry > 0 {
-                bytes.insert(0, (carry & 0xff) as u8);
-                carry >>= 8;
-            }
+    fn base64_to_bytes(base64: &str, config: Base64Config) -> Result<Vec<u8>, SerialiseError> {
+        check_ascii(base64, "base64")?;
+        let s = alphabet::strip_whitespace(base64);
+        let body = config
+            .padding
+            .map_or(&*s, |pad| s.trim_end_matches(pad as char));
+        let padding = s.len() - body.len();
+        if padding > 2 || (padding > 0 && !s.len().is_multiple_of(4)) {
+            return Err(SerialiseError::new("invalid base64 padding".to_string()));
+        }
+        if body.len() % 4 == 1 {
+            return Err(SerialiseError::new("invalid base64 length".to_string()));
         }
 
-        while bytes.len() > 1 && bytes[0] == 0 {
-            bytes.remove(0);
+        let mut digits: Vec<u8> = Vec::with_capacity(body.len());
+        for c in body.bytes() {
+            let Some(digit) = Self::decode_digit(c) else {
+                return Err(SerialiseError::new("invalid base64 character".to_string()));
+            };
+            digits.push(digit);
         }
 
-        Ok(bytes)
+        Ok(Self::digits_to_bytes(digits.into_iter()))
     }
 
     /// Decodes a base64 string into bytes, optionally left-padding to `size`.
     ///
+    /// Whitespace, including line breaks from wrapped output, is ignored and
+    /// trailing `=` padding is optional.
+    ///
     /// # Errors
     ///
-    /// Returns `Err` if `base64` contains characters outside the base64 alphabet.
+    /// Returns `Err` if `base64` contains characters outside the base64 alphabet,
+    /// has misplaced padding, or has a length no encoder could have produced.
     ///
     /// Returns `Err` if the decoded value requires more than `size` bytes when `size > 0`.
     ///
     pub fn try_from_base64(base64: &str, size: usize) -> Result<Vec<u8>, SerialiseError> {
-        let mut bytes = Self::base64_to_bytes(base64)?;
+        let mut bytes = Self::base64_to_bytes(base64, Base64Config::STANDARD)?;
 
         if bytes.len() > size && size > 0 {
             return Err(SerialiseError::new(format!(
@@ -118,6 +279,132 @@ impl Base64 {
 
         Ok(bytes)
     }
+
+    /// Decodes a base64 string written with the given padding options.
+    ///
+    /// Whitespace is ignored and trailing padding is optional; any other use of a
+    /// padding character, including `=` when a different one is configured, is an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if `base64` contains characters outside the base64 alphabet,
+    /// has misplaced padding, or has a length no encoder could have produced.
+    pub fn try_from_base64_with(
+        base64: &str,
+        config: Base64Config,
+    ) -> Result<Vec<u8>, SerialiseError> {
+        Self::base64_to_bytes(base64, config)
+    }
+
+    /// Decodes a string written by `base_xx` 0.9 and earlier, whose `Base64`
+    /// wrote the bytes as one number in radix 64 rather than as RFC 4648.
+    ///
+    /// Use this to read back strings stored before the format change, then
+    /// re-encode them with [`try_to_base64`](Self::try_to_base64). As before,
+    /// `"0"` and the empty string decode to a single zero byte, leading zero
+    /// bytes are lost, and surrounding whitespace is ignored.
+    ///
+    /// # Arguments
+    /// * `base64` - The radix-64 string
+    /// * `size` - The length to left-pad the result to, or 0 for no padding
+    ///
+    /// # Returns
+    /// The decoded bytes
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `base64` contains characters outside the
+    /// base64 alphabet, or if the value requires more than `size` bytes when
+    /// `size > 0`.
+    pub fn try_from_legacy_base64(base64: &str, size: usize) -> Result<Vec<u8>, SerialiseError> {
+        let digits = base64.trim();
+        let mut bytes: Vec<u8> = vec![0];
+        if digits != "0" {
+            for (position, c) in digits.bytes().enumerate() {
+                let Some(digit) = Self::decode_digit(c) else {
+                    return Err(SerialiseError::new(format!(
+                        "invalid legacy base64 character '{}' at position {position}",
+                        char::from(c)
+                    )));
+                };
+                let mut carry = u32::from(digit);
+                for b in bytes.iter_mut().rev() {
+                    let [low, high, ..] = (u32::from(*b) * 64 + carry).to_le_bytes();
+                    *b = low;
+                    carry = u32::from(high);
+                }
+                if carry > 0 {
+                    bytes.insert(0, u8::try_from(carry).unwrap_or_default());
+                }
+            }
+        }
+        let leading = bytes.iter().take_while(|&&b| b == 0).count();
+        bytes.drain(..leading.min(bytes.len() - 1));
+
+        if size > 0 && bytes.len() > size {
+            return Err(SerialiseError::new(format!(
+                "legacy base64 value does not fit in {size} bytes"
+            )));
+        }
+        if bytes.len() < size {
+            let mut padded = vec![0u8; size - bytes.len()];
+            padded.append(&mut bytes);
+            return Ok(padded);
+        }
+        Ok(bytes)
+    }
+
+    /// Decodes base64 the way mail clients do, skipping anything outside the alphabet.
+    ///
+    /// Line breaks, tabs, `>` quoting and other stray characters are ignored,
+    /// decoding stops at the first `=`, and trailing digits that do not make up a
+    /// whole byte are dropped (RFC 2045 section 6.8, WHATWG forgiving-base64).
+    ///
+    /// # Arguments
+    /// * `base64` - The base64 text to decode
+    ///
+    /// # Returns
+    /// The decoded bytes
+    #[must_use = "This returns the decoded bytes but does nothing if unused"]
+    pub fn from_base64_forgiving(base64: &str) -> Vec<u8> {
+        Self::digits_to_bytes(
+            base64
+                .bytes()
+                .take_while(|&c| c != PAD)
+                .filter_map(Self::decode_digit),
+        )
+    }
+
+    /// Decodes base64 into a caller-provided buffer that need not be initialized.
+    ///
+    /// Accepts exactly the inputs [`Base64::try_from_base64`] accepts. Use this to skip
+    /// zeroing large output buffers; on error the buffer may be partly written.
+    ///
+    /// # Arguments
+    /// * `base64` - The base64 text to decode
+    /// * `output` - The buffer to write to, large enough for the decoded bytes
+    ///
+    /// # Returns
+    /// The decoded bytes, borrowed from the start of `output`
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `base64` is not valid base64 or the decoded
+    /// bytes do not fit in `output`.
+    pub fn decode_into_uninit<'a>(
+        base64: &str,
+        output: &'a mut [MaybeUninit<u8>],
+    ) -> Result<&'a [u8], SerialiseError> {
+        Base64Decoder::new()
+            .inner
+            .decode_into_uninit(base64, output)
+    }
+
+    /// Checks that `base64` is valid base64 without allocating the decoded bytes.
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` for exactly the inputs [`Base64::try_from_base64`] rejects.
+    pub fn validate(base64: &str) -> Result<(), SerialiseError> {
+        Base64Decoder::new().inner.validate(base64)
+    }
 }
 
 impl Encoder for Base64 {
@@ -133,11 +420,78 @@ impl Encoder for Base64 {
     }
 }
 
+/// Incremental base64 decoder for input that arrives in pieces.
+///
+/// Whitespace is skipped anywhere in the input.
+#[derive(Debug)]
+pub struct Base64Decoder {
+    pub(crate) inner: BitDecoder,
+}
+
+impl Base64Decoder {
+    /// Creates a decoder waiting for its first piece of input.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::with_config(Base64Config::STANDARD)
+    }
+
+    /// Creates a decoder that accepts the padding character in `config`.
+    #[must_use]
+    pub const fn with_config(config: Base64Config) -> Self {
+        let pad = match config.padding {
+            Some(pad) => Some((pad, 4)),
+            None => None,
+        };
+        Self {
+            inner: BitDecoder::new("base64", 6, pad, Base64::decode_digit),
+        }
+    }
+}
+
+impl Default for Base64Decoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decoder for Base64Decoder {
+    fn feed(&mut self, input: &[u8]) -> Result<&[u8], SerialiseError> {
+        self.inner.feed(input)
+    }
+
+    fn finish(&mut self) -> Result<&[u8], SerialiseError> {
+        self.inner.finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_decode_into_uninit() {
+        let mut buf = vec![MaybeUninit::uninit(); 16];
+        assert!(matches!(
+            Base64::decode_into_uninit("Zm9v\nYmE=", &mut buf),
+            Ok(bytes) if bytes == b"fooba"
+        ));
+        assert!(Base64::decode_into_uninit("Zm9vYmE=Zg", &mut buf).is_err());
+        assert!(matches!(
+            Base64::decode_into_uninit("Zm9vYmFyYmF6cXV4Lg==", &mut buf[..12]),
+            Err(e) if e.get_message() == "decoded base64 does not fit in 12 bytes"
+        ));
+    }
+
+    #[test]
+    fn test_encode_slice() {
+        let mut buf = [0u8; 8];
+        assert!(matches!(Base64::encode_slice(b"hello", &mut buf), Ok(8)));
+        assert_eq!(&buf, b"aGVsbG8=");
+        assert!(matches!(Base64::encode_slice(b"", &mut buf), Ok(0)));
+        assert!(Base64::encode_slice(b"hello!!", &mut buf).is_err());
+    }
+
     #[test]
     fn test_to_base64() {
         let string = Arc::new(b"0123456789abcdefghijklmnopqrstuvwxyz".to_vec());
@@ -158,4 +512,64 @@ mod tests {
         let bytes = Base64::try_from_base64(string, 0);
         assert!(bytes.is_err());
     }
+
+    #[test]
+    fn test_to_base64_pads_partial_blocks() {
+        let encoded: Vec<String> = [&b"f"[..], b"fo", b"foo", b"foob"]
+            .iter()
+            .map(|bytes| Base64::try_to_base64(bytes).unwrap_or_else(|_| String::new()))
+            .collect();
+        assert_eq!(encoded, ["Zg==", "Zm8=", "Zm9v", "Zm9vYg=="]);
+    }
+
+    #[test]
+    fn test_from_base64_bad_padding_is_err() {
+        assert!(Base64::try_from_base64("Zg=", 0).is_err());
+        assert!(Base64::try_from_base64("Z===", 0).is_err());
+        assert!(Base64::try_from_base64("Zg==Zg==", 0).is_err());
+    }
+
+    #[test]
+    fn test_from_base64_forgiving_skips_invalid_characters() {
+        let quoted = "> Zm9v\r\n> YmFy\tYm\r\n> F6\n=ignored";
+        assert_eq!(Base64::from_base64_forgiving(quoted), b"foobarbaz");
+    }
+
+    #[test]
+    fn test_from_legacy_base64() {
+        // Written by 0.9's radix-64 encoder for 0x01 0x00 0xff, the empty
+        // input, and 0x00 0x00 0x00 0x01.
+        assert!(
+            matches!(Base64::try_from_legacy_base64("QD/", 0), Ok(b) if b == [0x01, 0x00, 0xff])
+        );
+        assert!(matches!(Base64::try_from_legacy_base64("0", 0), Ok(b) if b == [0]));
+        assert!(matches!(Base64::try_from_legacy_base64("B", 4), Ok(b) if b == [0, 0, 0, 1]));
+        assert!(Base64::try_from_legacy_base64("QD/", 2).is_err());
+        assert!(Base64::try_from_legacy_base64("QA=", 0).is_err());
+    }
+
+    #[test]
+    fn test_custom_padding() {
+        let tilde = Base64Config::with_padding('~').unwrap_or(Base64Config::STANDARD);
+        let encoded = Base64::try_to_base64_with(b"f", tilde).unwrap_or_default();
+        assert_eq!(encoded, "Zg~~");
+        assert!(
+            matches!(Base64::try_from_base64_with(&encoded, tilde), Ok(bytes) if bytes == b"f")
+        );
+        assert!(Base64::try_from_base64_with("Zg==", tilde).is_err());
+        assert!(Base64Config::with_padding('+').is_err());
+
+        let encoded = Base64::try_to_base64_with(b"f", Base64Config::NO_PAD).unwrap_or_default();
+        assert_eq!(encoded, "Zg");
+        assert!(Base64::try_from_base64_with("Zg==", Base64Config::NO_PAD).is_err());
+    }
+
+    #[test]
+    fn test_mime_wrapping() {
+        let bytes = [0xa5u8; 60];
+        let encoded = Base64::try_to_base64_with(&bytes, Base64Config::MIME).unwrap_or_default();
+        let lines: Vec<&str> = encoded.split("\r\n").collect();
+        assert_eq!(lines.iter().map(|l| l.len()).collect::<Vec<_>>(), [76, 4]);
+        assert!(matches!(Base64::try_from_base64(&encoded, 0), Ok(decoded) if decoded == bytes));
+    }
 }
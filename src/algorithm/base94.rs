@@ -0,0 +1,201 @@
+use std::sync::Arc;
+
+use crate::{
+    EncodedString, Encoder, Encoding, SerialiseError, alphabet, serialise_error::check_ascii,
+};
+
+const ALPHABET: &[u8; 94] = alphabet::BASE94;
+const DIGITS: [Option<u8>; 256] = alphabet::reverse_lookup(ALPHABET);
+
+/// Bytes in a full block.
+const BLOCK_BYTES: usize = 9;
+
+/// Characters written for a block of `n` bytes: the fewest base-94 digits
+/// that can hold any `n`-byte value.
+pub(crate) const BLOCK_CHARS: [usize; BLOCK_BYTES + 1] = [0, 2, 3, 4, 5, 7, 8, 9, 10, 11];
+
+/// Base94 encoding, using every printable ASCII character except space.
+///
+/// Each block of nine bytes becomes eleven characters, the densest text for
+/// channels that allow any printable character, such as fields stuffed into
+/// log lines: about 6.55 bits per character against 6.4 for Base85. A final
+/// short block uses only as many characters as its bytes need, and decoding
+/// rejects any block whose value does not fit in its bytes, so every byte
+/// string has exactly one encoding.
+#[derive(Debug)]
+pub struct Base94 {}
+
+impl Base94 {
+    /// Encodes a byte slice using base94 encoding.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes to encode
+    ///
+    /// # Returns
+    /// The base94-encoded string
+    #[must_use = "This returns the encoded string and does nothing if unused"]
+    pub fn to_base94(bytes: &[u8]) -> String {
+        let mut out = String::with_capacity(bytes.len().div_ceil(BLOCK_BYTES) * 11);
+        let mut digits = [0u8; 11];
+        for block in bytes.chunks(BLOCK_BYTES) {
+            let mut n = block.iter().fold(0u128, |n, &b| (n << 8) | u128::from(b));
+            let len = BLOCK_CHARS[block.len()];
+            for digit in digits[..len].iter_mut().rev() {
+                *digit = ALPHABET[usize::try_from(n % 94).unwrap_or_default()];
+                n /= 94;
+            }
+            out.extend(digits[..len].iter().map(|&c| char::from(c)));
+        }
+        out
+    }
+
+    /// Returns the number of bytes in a final block of `len` characters, or
+    /// an error if no block encodes to that many.
+    fn block_bytes(len: usize) -> Result<usize, SerialiseError> {
+        BLOCK_CHARS
+            .iter()
+            .position(|&chars| chars == len)
+            .ok_or_else(|| {
+                SerialiseError::new(format!(
+                    "base94 input ends with a block of {len} characters, which no encoder writes"
+                ))
+            })
+    }
+
+    /// Returns the value of a block, checking that it fits in `bytes` bytes.
+    fn block_value(block: &[u8], bytes: usize, offset: usize) -> Result<u128, SerialiseError> {
+        let mut n = 0u128;
+        for (i, &c) in block.iter().enumerate() {
+            let Some(digit) = DIGITS[usize::from(c)] else {
+                return Err(SerialiseError::new(format!(
+                    "invalid base94 character '{}' at position {}",
+                    char::from(c),
+                    offset + i
+                )));
+            };
+            n = n * 94 + u128::from(digit);
+        }
+
+        if n >> (8 * bytes) != 0 {
+            return Err(SerialiseError::new(format!(
+                "base94 block at position {offset} is out of range for {bytes} bytes"
+            )));
+        }
+        Ok(n)
+    }
+
+    /// Splits `base94` into blocks and decodes each to its value and length.
+    fn blocks(
+        base94: &str,
+    ) -> Result<impl Iterator<Item = Result<(u128, usize), SerialiseError>>, SerialiseError> {
+        check_ascii(base94, "base94")?;
+        let full = BLOCK_CHARS[BLOCK_BYTES];
+        let last = Self::block_bytes(base94.len() % full)?;
+        Ok(base94
+            .as_bytes()
+            .chunks(full)
+            .enumerate()
+            .map(move |(i, block)| {
+                let bytes = if block.len() == full {
+                    BLOCK_BYTES
+                } else {
+                    last
+                };
+                Self::block_value(block, bytes, i * full).map(|n| (n, bytes))
+            }))
+    }
+
+    /// Decodes a base94 string into bytes.
+    ///
+    /// Decoding is strict: whitespace is rejected along with every other
+    /// character outside the alphabet.
+    ///
+    /// # Arguments
+    /// * `base94` - The base94-encoded string to decode
+    ///
+    /// # Returns
+    /// The decoded bytes
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the input contains characters outside the base94
+    /// alphabet, has a length no encoder could have produced, or contains a block
+    /// whose value does not fit in the bytes it encodes.
+    pub fn try_from_base94(base94: &str) -> Result<Vec<u8>, SerialiseError> {
+        let mut out = Vec::with_capacity(base94.len() / 11 * BLOCK_BYTES + BLOCK_BYTES);
+        for block in Self::blocks(base94)? {
+            let (n, bytes) = block?;
+            out.extend_from_slice(&n.to_be_bytes()[16 - bytes..]);
+        }
+        Ok(out)
+    }
+
+    /// Checks that `base94` is well-formed base94, without decoding it.
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` for any input [`Base94::try_from_base94`] would reject.
+    pub fn validate(base94: &str) -> Result<(), SerialiseError> {
+        Self::blocks(base94)?.try_for_each(|block| block.map(|_| ()))
+    }
+}
+
+impl Encoder for Base94 {
+    fn try_encode(bytes: Arc<Vec<u8>>) -> Result<EncodedString, SerialiseError> {
+        Ok(EncodedString::new(
+            Encoding::Base94,
+            Self::to_base94(&bytes),
+        ))
+    }
+
+    fn try_decode(encoded: &EncodedString) -> Result<Arc<Vec<u8>>, SerialiseError> {
+        Ok(Arc::new(Self::try_from_base94(encoded.get_string())?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_to_base94() {
+        assert_eq!(Base94::to_base94(b""), "");
+        assert_eq!(Base94::to_base94(&[0]), "!!");
+        assert_eq!(Base94::to_base94(&[0xff]), "#d");
+        assert_eq!(Base94::to_base94(&[0; 9]), "!".repeat(11));
+        assert_eq!(Base94::to_base94(&[0xff; 10]).len(), 13);
+        assert!(
+            Base94::to_base94(&[0xff; 9])
+                .bytes()
+                .all(|c| c.is_ascii_graphic())
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let bytes: Vec<u8> = (0..=255).rev().collect();
+        for len in 0..=bytes.len() {
+            let encoded = Base94::to_base94(&bytes[..len]);
+            assert_eq!(encoded.len(), len / 9 * 11 + BLOCK_CHARS[len % 9]);
+            assert!(matches!(Base94::try_from_base94(&encoded), Ok(b) if b == bytes[..len]));
+            assert!(Base94::validate(&encoded).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_from_invalid_base94_is_err() {
+        // Lengths no encoder writes, a space, a non-ASCII character, and
+        // blocks too large for their bytes.
+        for bad in [
+            "!",
+            "!!!!!!",
+            "!! ",
+            "!!\n!",
+            "!\u{e9}",
+            "$!",
+            "~~~~~~~~~~~",
+        ] {
+            assert!(Base94::try_from_base94(bad).is_err(), "{bad}");
+            assert!(Base94::validate(bad).is_err(), "{bad}");
+        }
+    }
+}
@@ -0,0 +1,436 @@
+use std::sync::Arc;
+
+use crate::{
+    EncodedString, Encoder, Encoding, ErrorKind, SerialiseError, checksum::Crc16,
+    serialise_error::check_ascii,
+};
+
+const ALPHABET: &[u8; 64] = b"!\"#$%&'()*+,-012345689@ABCDEFGHIJKLMNPQRSTUVXYZ[`abcdefhijklmpqr";
+
+pub(crate) const BANNER: &str = "(This file must be converted with BinHex 4.0)";
+
+const RUN_MARKER: u8 = 0x90;
+
+pub(crate) const LINE_LENGTH: usize = 64;
+
+/// Name used for the file header when encoding raw bytes through [`Encoder`].
+pub(crate) const DEFAULT_NAME: &[u8] = b"untitled";
+
+/// A Macintosh file carried by a `BinHex` 4.0 stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinHexFile {
+    /// The file name (Mac OS Roman, 1 to 63 bytes)
+    name: Vec<u8>,
+    /// The four character file type code
+    file_type: [u8; 4],
+    /// The four character creator code
+    creator: [u8; 4],
+    /// The Finder flags
+    flags: u16,
+    /// The data fork
+    data_fork: Vec<u8>,
+    /// The resource fork
+    resource_fork: Vec<u8>,
+}
+
+impl BinHexFile {
+    /// Creates a new `BinHexFile` instance.
+    ///
+    /// # Arguments
+    /// * `name` - The file name (Mac OS Roman, 1 to 63 bytes)
+    /// * `file_type` - The four character file type code
+    /// * `creator` - The four character creator code
+    /// * `flags` - The Finder flags
+    /// * `data_fork` - The data fork
+    /// * `resource_fork` - The resource fork
+    #[must_use = "This creates a new BinHexFile instance but does nothing if unused"]
+    pub const fn new(
+        name: Vec<u8>,
+        file_type: [u8; 4],
+        creator: [u8; 4],
+        flags: u16,
+        data_fork: Vec<u8>,
+        resource_fork: Vec<u8>,
+    ) -> Self {
+        Self {
+            name,
+            file_type,
+            creator,
+            flags,
+            data_fork,
+            resource_fork,
+        }
+    }
+
+    /// Returns the file name.
+    #[must_use = "This returns the file name but does nothing if unused"]
+    pub fn get_name(&self) -> &[u8] {
+        &self.name
+    }
+
+    /// Returns the four character file type code.
+    #[must_use = "This returns the file type but does nothing if unused"]
+    pub const fn get_file_type(&self) -> [u8; 4] {
+        self.file_type
+    }
+
+    /// Returns the four character creator code.
+    #[must_use = "This returns the creator but does nothing if unused"]
+    pub const fn get_creator(&self) -> [u8; 4] {
+        self.creator
+    }
+
+    /// Returns the Finder flags.
+    #[must_use = "This returns the Finder flags but does nothing if unused"]
+    pub const fn get_flags(&self) -> u16 {
+        self.flags
+    }
+
+    /// Returns the data fork.
+    #[must_use = "This returns the data fork but does nothing if unused"]
+    pub fn get_data_fork(&self) -> &[u8] {
+        &self.data_fork
+    }
+
+    /// Returns the resource fork.
+    #[must_use = "This returns the resource fork but does nothing if unused"]
+    pub fn get_resource_fork(&self) -> &[u8] {
+        &self.resource_fork
+    }
+}
+
+/// `BinHex` 4.0 (`.hqx`) implementation.
+///
+/// A `BinHex` stream is a Macintosh file header and both forks, each followed by a
+/// CRC, compressed with a simple run-length encoding and then written using a
+/// 64 character alphabet between `:` delimiters.
+#[derive(Debug)]
+pub struct BinHex {}
+
+impl BinHex {
+    fn push_literal(out: &mut Vec<u8>, byte: u8) {
+        out.push(byte);
+        if byte == RUN_MARKER {
+            out.push(0);
+        }
+    }
+
+    fn compress(bytes: &[u8]) -> Vec<u8> {
+        let mut out: Vec<u8> = Vec::with_capacity(bytes.len());
+        let mut rest = bytes;
+
+        while let Some(&byte) = rest.first() {
+            let run = rest.iter().take(255).take_while(|&&b| b == byte).count();
+
+            Self::push_literal(&mut out, byte);
+            if run >= 3 {
+                out.push(RUN_MARKER);
+                out.push(u8::try_from(run).unwrap_or(u8::MAX));
+            } else {
+                for _ in 1..run {
+                    Self::push_literal(&mut out, byte);
+                }
+            }
+
+            rest = &rest[run..];
+        }
+
+        out
+    }
+
+    fn expand(bytes: &[u8]) -> Result<Vec<u8>, SerialiseError> {
+        let mut out: Vec<u8> = Vec::with_capacity(bytes.len());
+        let mut last: Option<u8> = None;
+        let mut it = bytes.iter().copied();
+
+        while let Some(byte) = it.next() {
+            if byte != RUN_MARKER {
+                out.push(byte);
+                last = Some(byte);
+                continue;
+            }
+
+            let count = it.next().ok_or_else(|| {
+                SerialiseError::new("binhex run marker is missing its count".to_string())
+            })?;
+            if count == 0 {
+                out.push(RUN_MARKER);
+                last = Some(RUN_MARKER);
+                continue;
+            }
+
+            let repeated = last.ok_or_else(|| {
+                SerialiseError::new("binhex run marker has no byte to repeat".to_string())
+            })?;
+            for _ in 1..count {
+                out.push(repeated);
+            }
+        }
+
+        Ok(out)
+    }
+
+    fn push_fork(out: &mut Vec<u8>, fork: &[u8]) {
+        out.extend_from_slice(fork);
+        out.extend_from_slice(&Crc16::checksum(fork).to_be_bytes());
+    }
+
+    /// Encodes a file as a `BinHex` 4.0 stream.
+    ///
+    /// # Arguments
+    /// * `file` - The file to encode
+    ///
+    /// # Returns
+    /// The `BinHex` text, including the identification banner
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the file name is empty or longer than 63 bytes, or a
+    /// fork is larger than 4 GiB.
+    pub fn try_to_binhex(file: &BinHexFile) -> Result<String, SerialiseError> {
+        let name_len = u8::try_from(file.name.len())
+            .ok()
+            .filter(|len| (1..=63).contains(len))
+            .ok_or_else(|| {
+                SerialiseError::new("binhex file name must be 1 to 63 bytes".to_string())
+            })?;
+        let data_len = u32::try_from(file.data_fork.len())
+            .map_err(|_| SerialiseError::new("binhex data fork is too large".to_string()))?;
+        let resource_len = u32::try_from(file.resource_fork.len())
+            .map_err(|_| SerialiseError::new("binhex resource fork is too large".to_string()))?;
+
+        let mut header: Vec<u8> = vec![name_len];
+        header.extend_from_slice(&file.name);
+        header.push(0);
+        header.extend_from_slice(&file.file_type);
+        header.extend_from_slice(&file.creator);
+        header.extend_from_slice(&file.flags.to_be_bytes());
+        header.extend_from_slice(&data_len.to_be_bytes());
+        header.extend_from_slice(&resource_len.to_be_bytes());
+
+        let mut stream: Vec<u8> = Vec::new();
+        Self::push_fork(&mut stream, &header);
+        Self::push_fork(&mut stream, &file.data_fork);
+        Self::push_fork(&mut stream, &file.resource_fork);
+
+        let compressed = Self::compress(&stream);
+
+        let mut chars: Vec<u8> = vec![b':'];
+        for group in compressed.chunks(3) {
+            let b0 = group[0];
+            let b1 = *group.get(1).unwrap_or(&0);
+            let b2 = *group.get(2).unwrap_or(&0);
+
+            let sextets = [
+                b0 >> 2,
+                ((b0 << 4) | (b1 >> 4)) & 0x3f,
+                ((b1 << 2) | (b2 >> 6)) & 0x3f,
+                b2 & 0x3f,
+            ];
+            for &sextet in &sextets[..=group.len()] {
+                chars.push(ALPHABET[sextet as usize]);
+            }
+        }
+        chars.push(b':');
+
+        let mut out =
+            String::with_capacity(BANNER.len() + chars.len() + chars.len() / LINE_LENGTH + 3);
+        out.push_str(BANNER);
+        out.push_str("\n\n");
+        for line in chars.chunks(LINE_LENGTH) {
+            out.extend(line.iter().map(|&c| c as char));
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    fn sextet(c: u8) -> Option<u8> {
+        ALPHABET
+            .iter()
+            .position(|&a| a == c)
+            .and_then(|pos| u8::try_from(pos).ok())
+    }
+
+    fn take<'a>(stream: &mut &'a [u8], len: usize) -> Result<&'a [u8], SerialiseError> {
+        if stream.len() < len {
+            return Err(SerialiseError::new(
+                "binhex stream is truncated".to_string(),
+            ));
+        }
+        let (head, tail) = stream.split_at(len);
+        *stream = tail;
+        Ok(head)
+    }
+
+    fn take_fork(stream: &mut &[u8], len: usize, part: &str) -> Result<Vec<u8>, SerialiseError> {
+        let fork = Self::take(stream, len)?.to_vec();
+        let crc = Self::take(stream, 2)?;
+        if Crc16::checksum(&fork).to_be_bytes() != crc {
+            return Err(SerialiseError::new(format!("binhex {part} CRC mismatch"))
+                .with_kind(ErrorKind::ChecksumMismatch));
+        }
+        Ok(fork)
+    }
+
+    /// Decodes a `BinHex` 4.0 stream.
+    ///
+    /// Any text before the first `:` (such as the identification banner or mail
+    /// headers) is ignored, as is whitespace between encoded characters.
+    ///
+    /// # Arguments
+    /// * `binhex` - The `BinHex` text to decode
+    ///
+    /// # Returns
+    /// The decoded file
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the stream is not delimited by `:`, contains invalid
+    /// characters, is truncated, or any of the header or fork CRCs do not match.
+    pub fn from_binhex(binhex: &str) -> Result<BinHexFile, SerialiseError> {
+        check_ascii(binhex, "BinHex")?;
+        let start = binhex.find(BANNER).map_or(0, |pos| pos + BANNER.len());
+        let Some((_, body)) = binhex[start..].split_once(':') else {
+            return Err(SerialiseError::new(
+                "binhex data must start with ':'".to_string(),
+            ));
+        };
+        let Some((body, _)) = body.split_once(':') else {
+            return Err(SerialiseError::new(
+                "binhex data must end with ':'".to_string(),
+            ));
+        };
+
+        let mut sextets: Vec<u8> = Vec::with_capacity(body.len());
+        for c in body.bytes().filter(|c| !c.is_ascii_whitespace()) {
+            let sextet = Self::sextet(c)
+                .ok_or_else(|| SerialiseError::new("invalid binhex character".to_string()))?;
+            sextets.push(sextet);
+        }
+
+        let mut compressed: Vec<u8> = Vec::with_capacity(sextets.len() * 3 / 4);
+        for group in sextets.chunks(4) {
+            let s0 = group[0];
+            let s1 = *group.get(1).unwrap_or(&0);
+            let s2 = *group.get(2).unwrap_or(&0);
+            let s3 = *group.get(3).unwrap_or(&0);
+
+            let bytes = [(s0 << 2) | (s1 >> 4), (s1 << 4) | (s2 >> 2), (s2 << 6) | s3];
+            compressed.extend_from_slice(&bytes[..group.len() - 1]);
+        }
+
+        let expanded = Self::expand(&compressed)?;
+        let mut stream: &[u8] = &expanded;
+
+        let name_len = usize::from(
+            *stream
+                .first()
+                .ok_or_else(|| SerialiseError::new("binhex stream is truncated".to_string()))?,
+        );
+        let header = Self::take_fork(&mut stream, name_len + 20, "header")?;
+        let (name, info) = header[1..].split_at(name_len);
+        let field = |range: std::ops::Range<usize>| info.get(range).unwrap_or_default();
+
+        let file_type: [u8; 4] = field(1..5).try_into().unwrap_or_default();
+        let creator: [u8; 4] = field(5..9).try_into().unwrap_or_default();
+        let flags = u16::from_be_bytes(field(9..11).try_into().unwrap_or_default());
+        let data_len = u32::from_be_bytes(field(11..15).try_into().unwrap_or_default()) as usize;
+        let resource_len =
+            u32::from_be_bytes(field(15..19).try_into().unwrap_or_default()) as usize;
+
+        let data_fork = Self::take_fork(&mut stream, data_len, "data fork")?;
+        let resource_fork = Self::take_fork(&mut stream, resource_len, "resource fork")?;
+
+        Ok(BinHexFile::new(
+            name.to_vec(),
+            file_type,
+            creator,
+            flags,
+            data_fork,
+            resource_fork,
+        ))
+    }
+}
+
+impl Encoder for BinHex {
+    fn try_encode(bytes: Arc<Vec<u8>>) -> Result<EncodedString, SerialiseError> {
+        let file = BinHexFile::new(
+            DEFAULT_NAME.to_vec(),
+            [0; 4],
+            [0; 4],
+            0,
+            bytes.to_vec(),
+            vec![],
+        );
+        Ok(EncodedString::new(
+            Encoding::BinHex,
+            Self::try_to_binhex(&file)?,
+        ))
+    }
+
+    fn try_decode(encoded: &EncodedString) -> Result<Arc<Vec<u8>>, SerialiseError> {
+        Ok(Arc::new(Self::from_binhex(encoded.get_string())?.data_fork))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_to_binhex() {
+        let file = BinHexFile::new(
+            b"a".to_vec(),
+            *b"TEXT",
+            *b"ttxt",
+            0,
+            b"0123456789abcdefghijklmnopqrstuvwxyz".to_vec(),
+            vec![],
+        );
+        let binhex = BinHex::try_to_binhex(&file).unwrap_or_else(|_| String::new());
+        assert_eq!(
+            binhex,
+            concat!(
+                "(This file must be converted with BinHex 4.0)\n\n",
+                r#":!@%!9%9B9(4dH(3!N!8N!*!%"8d`-6)c0$8f0cJjB@*MC'9QCfKTDQYXE@j[F(&"#,
+                "\n",
+                r#"bFh4eGRGiHAV9'!!!:"#,
+                "\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_binhex_round_trip_with_runs() {
+        let mut data = vec![0x90; 300];
+        data.extend_from_slice(b"xyzzzzz\x90\x90\x90");
+        let file = BinHexFile::new(
+            b"runs".to_vec(),
+            *b"BINA",
+            *b"????",
+            0x0100,
+            data,
+            vec![7; 10],
+        );
+
+        let binhex = BinHex::try_to_binhex(&file).unwrap_or_else(|_| String::new());
+        assert!(matches!(BinHex::from_binhex(&binhex), Ok(decoded) if decoded == file));
+    }
+
+    #[test]
+    fn test_from_binhex_crc_mismatch_is_err() {
+        let file = BinHexFile::new(
+            b"a".to_vec(),
+            *b"TEXT",
+            *b"ttxt",
+            0,
+            b"abc".to_vec(),
+            vec![],
+        );
+        let binhex = BinHex::try_to_binhex(&file).unwrap_or_else(|_| String::new());
+        let mut corrupted = binhex.into_bytes();
+        let index = BANNER.len() + 12;
+        corrupted[index] = if corrupted[index] == b'a' { b'b' } else { b'a' };
+        let corrupted = String::from_utf8(corrupted).unwrap_or_default();
+        assert!(BinHex::from_binhex(&corrupted).is_err());
+    }
+}
@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use crate::{
+    EncodedString, Encoder, Encoding, SerialiseError, alphabet, serialise_error::check_ascii,
+};
+
+/// Printable ASCII with `\xNN` escapes, for readable logs of near-text data.
+///
+/// Bytes from space to `~` are written as they are, except `\`, which is
+/// written as `\\`. Every other byte is written as `\x` and two lowercase hex
+/// digits, so `b"GET /\r\n"` encodes to `GET /\x0d\x0a`. Unlike Base64, text
+/// stays readable, and unlike lossy escaping, decoding gives back exactly the
+/// original bytes.
+#[derive(Debug)]
+pub struct Escaped {}
+
+impl Escaped {
+    /// Escapes bytes as printable ASCII.
+    ///
+    /// # Errors
+    ///
+    /// This function never returns an error.
+    pub fn try_to_escaped(bytes: &[u8]) -> Result<String, SerialiseError> {
+        let mut out = String::with_capacity(bytes.len());
+        for &b in bytes {
+            match b {
+                b'\\' => out.push_str("\\\\"),
+                b' '..=b'~' => out.push(char::from(b)),
+                _ => {
+                    out.push_str("\\x");
+                    out.push(char::from(alphabet::HEX[usize::from(b >> 4)]));
+                    out.push(char::from(alphabet::HEX[usize::from(b & 0x0f)]));
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// Decodes text written by [`Escaped::try_to_escaped`].
+    ///
+    /// Hex digits in escapes may be in either case.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `text` contains a character other than printable
+    /// ASCII, such as a raw line break, or an escape other than `\\` and
+    /// `\xNN`.
+    pub fn try_from_escaped(text: &str) -> Result<Vec<u8>, SerialiseError> {
+        check_ascii(text, "escaped")?;
+        let bytes = text.as_bytes();
+        let mut out = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\\' if bytes.get(i + 1) == Some(&b'\\') => {
+                    out.push(b'\\');
+                    i += 2;
+                }
+                b'\\' if bytes.get(i + 1) == Some(&b'x') => {
+                    let value = text
+                        .get(i + 2..i + 4)
+                        .filter(|hex| hex.bytes().all(|c| c.is_ascii_hexdigit()))
+                        .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                        .ok_or_else(|| {
+                            SerialiseError::new(format!("invalid \\x escape at position {i}"))
+                        })?;
+                    out.push(value);
+                    i += 4;
+                }
+                b'\\' => {
+                    return Err(SerialiseError::new(format!(
+                        "invalid escape at position {i}, expected \\\\ or \\xNN"
+                    )));
+                }
+                c @ b' '..=b'~' => {
+                    out.push(c);
+                    i += 1;
+                }
+                c => {
+                    return Err(SerialiseError::new(format!(
+                        "unescaped control character 0x{c:02x} at position {i}"
+                    )));
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// Checks that `text` is valid escaped text.
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` for exactly the inputs
+    /// [`Escaped::try_from_escaped`] rejects.
+    pub fn validate(text: &str) -> Result<(), SerialiseError> {
+        Self::try_from_escaped(text).map(|_| ())
+    }
+}
+
+impl Encoder for Escaped {
+    fn try_encode(bytes: Arc<Vec<u8>>) -> Result<EncodedString, SerialiseError> {
+        Ok(EncodedString::new(
+            Encoding::Escaped,
+            Self::try_to_escaped(&bytes)?,
+        ))
+    }
+
+    fn try_decode(encoded: &EncodedString) -> Result<Arc<Vec<u8>>, SerialiseError> {
+        Ok(Arc::new(Self::try_from_escaped(encoded.get_string())?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_to_escaped() {
+        assert!(matches!(
+            Escaped::try_to_escaped(b"GET /a\\b\r\n\x00\xff").as_deref(),
+            Ok("GET /a\\\\b\\x0d\\x0a\\x00\\xff")
+        ));
+        assert!(matches!(Escaped::try_to_escaped(b"").as_deref(), Ok("")));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let escaped = Escaped::try_to_escaped(&bytes).unwrap_or_default();
+        assert!(escaped.bytes().all(|c| matches!(c, b' '..=b'~')));
+        assert!(matches!(Escaped::try_from_escaped(&escaped), Ok(b) if b == bytes));
+        assert!(matches!(Escaped::try_from_escaped("\\xFF\\x0a"), Ok(b) if b == [0xff, 0x0a]));
+    }
+
+    #[test]
+    fn test_rejects_invalid() {
+        for bad in ["\\", "\\n", "\\x1", "\\xg0", "\\x+1", "a\nb", "caf\u{e9}"] {
+            assert!(Escaped::try_from_escaped(bad).is_err(), "{bad}");
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use crate::{
+    Base32, Base32Config, EncodedString, Encoder, Encoding, SerialiseError,
+    serialise_error::check_ascii,
+};
+
+/// Longest file name accepted by common filesystems (NTFS, APFS, ext4).
+pub const MAX_LEN: usize = 255;
+
+/// Name of the empty input, since a file name cannot be empty.
+const EMPTY: &str = "_";
+
+/// File name encoding: unpadded, lowercase RFC 4648 Base32.
+///
+/// Output only uses `a-z` and `2-7`, so two different inputs never encode to
+/// names that differ only by case and collide on case-insensitive filesystems
+/// (Windows and macOS by default). Windows device names such as `con` or
+/// `com1` are never produced, as they have lengths or final digits Base32
+/// cannot end with, and empty input is written as `_`. Decoding accepts either
+/// case.
+#[derive(Debug)]
+pub struct FilenameSafe {}
+
+impl FilenameSafe {
+    /// Encodes bytes as a file name.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes to encode
+    ///
+    /// # Returns
+    /// The file name
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the name would be longer than [`MAX_LEN`]
+    /// characters, which happens above 159 bytes.
+    pub fn try_to_filename(bytes: &[u8]) -> Result<String, SerialiseError> {
+        if bytes.is_empty() {
+            return Ok(EMPTY.to_string());
+        }
+        let name = Base32::try_to_base32_with(bytes, Base32Config::LOWERCASE)?;
+        if name.len() > MAX_LEN {
+            return Err(SerialiseError::new(format!(
+                "{} bytes encode to a {} character file name, longer than {MAX_LEN}",
+                bytes.len(),
+                name.len()
+            )));
+        }
+        Ok(name)
+    }
+
+    /// Decodes a file name written by [`FilenameSafe::try_to_filename`].
+    ///
+    /// # Arguments
+    /// * `name` - The file name, in either case
+    ///
+    /// # Returns
+    /// The decoded bytes
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `name` contains characters other than Base32
+    /// digits or has an invalid length.
+    pub fn try_from_filename(name: &str) -> Result<Vec<u8>, SerialiseError> {
+        check_ascii(name, "file name")?;
+        if name == EMPTY {
+            return Ok(Vec::new());
+        }
+        if name.is_empty() {
+            return Err(SerialiseError::new("file name is empty".to_string()));
+        }
+        if let Some(c) = name
+            .chars()
+            .find(|c| !matches!(c, 'a'..='z' | 'A'..='Z' | '2'..='7'))
+        {
+            return Err(SerialiseError::new(format!(
+                "invalid file name character '{c}'"
+            )));
+        }
+        Base32::try_from_base32_with(&name.to_ascii_uppercase(), Base32Config::NO_PAD)
+    }
+
+    /// Checks that `name` is a valid encoded file name.
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` for exactly the inputs
+    /// [`FilenameSafe::try_from_filename`] rejects.
+    pub fn validate(name: &str) -> Result<(), SerialiseError> {
+        Self::try_from_filename(name).map(|_| ())
+    }
+}
+
+impl Encoder for FilenameSafe {
+    fn try_encode(bytes: Arc<Vec<u8>>) -> Result<EncodedString, SerialiseError> {
+        Ok(EncodedString::new(
+            Encoding::FilenameSafe,
+            Self::try_to_filename(&bytes)?,
+        ))
+    }
+
+    fn try_decode(encoded: &EncodedString) -> Result<Arc<Vec<u8>>, SerialiseError> {
+        Ok(Arc::new(Self::try_from_filename(encoded.get_string())?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_to_filename() {
+        assert!(matches!(
+            FilenameSafe::try_to_filename(b"foobar").as_deref(),
+            Ok("mzxw6ytboi")
+        ));
+        assert!(matches!(
+            FilenameSafe::try_to_filename(b"").as_deref(),
+            Ok("_")
+        ));
+        assert!(FilenameSafe::try_to_filename(&[0xff; 159]).is_ok());
+        assert!(FilenameSafe::try_to_filename(&[0xff; 160]).is_err());
+    }
+
+    #[test]
+    fn test_round_trip_any_case() {
+        let bytes: Vec<u8> = (0..=158).collect();
+        let name = FilenameSafe::try_to_filename(&bytes).unwrap_or_default();
+        assert!(
+            name.bytes()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        );
+        assert!(matches!(FilenameSafe::try_from_filename(&name), Ok(decoded) if decoded == bytes));
+        assert!(matches!(
+            FilenameSafe::try_from_filename(&name.to_uppercase()),
+            Ok(decoded) if decoded == bytes
+        ));
+        assert!(matches!(FilenameSafe::try_from_filename("_"), Ok(decoded) if decoded.is_empty()));
+    }
+
+    #[test]
+    fn test_invalid_is_err() {
+        assert!(FilenameSafe::try_from_filename("").is_err());
+        assert!(FilenameSafe::try_from_filename("mzxw_").is_err());
+        assert!(FilenameSafe::try_from_filename("mzxw 6ytboi").is_err());
+        assert!(FilenameSafe::try_from_filename("mzx").is_err());
+    }
+}
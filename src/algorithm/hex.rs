@@ -1,8 +1,20 @@
-use std::sync::Arc;
+#![cfg_attr(
+    all(feature = "no-panic", not(test)),
+    deny(
+        clippy::indexing_slicing,
+        clippy::panic,
+        clippy::unreachable,
+        clippy::unwrap_used,
+        clippy::expect_used
+    )
+)]
 
-use crate::{EncodedString, Encoder, Encoding, SerialiseError};
+use std::{mem::MaybeUninit, sync::Arc};
 
-const ALPHABET: &[u8; 16] = b"0123456789abcdef";
+use crate::{
+    Decoder, EncodedString, Encoder, Encoding, SerialiseError, alphabet, decoder::BitDecoder,
+    serialise_error::check_ascii,
+};
 
 /// Hex (base16) encoding implementation (RFC 4648).
 #[derive(Debug)]
@@ -15,14 +27,45 @@ impl Hex {
     ///
     /// This function never returns an error.
     pub fn try_to_hex(bytes: &[u8]) -> Result<String, SerialiseError> {
-        let mut out: Vec<u8> = Vec::with_capacity(bytes.len() * 2);
-        for &b in bytes {
-            out.push(ALPHABET[(b >> 4) as usize]);
-            out.push(ALPHABET[(b & 0x0f) as usize]);
+        let mut out = vec![0u8; bytes.len() * 2];
+        Self::encode_slice(bytes, &mut out)?;
+        String::from_utf8(out)
+            .map_err(|_| SerialiseError::new("hex output is not ASCII".to_string()))
+    }
+
+    /// Writes lowercase hex into a caller-provided buffer without allocating.
+    ///
+    /// # Arguments
+    /// * `input` - The bytes to encode
+    /// * `output` - The buffer to write to, at least
+    ///   [`hex_len(input.len())`](crate::const_encode::hex_len) bytes
+    ///
+    /// # Returns
+    /// The number of bytes written to the start of `output`
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `output` is too small. Building the error is
+    /// the only allocation.
+    pub fn encode_slice(input: &[u8], output: &mut [u8]) -> Result<usize, SerialiseError> {
+        let needed = input.len() * 2;
+        if output.len() < needed {
+            return Err(SerialiseError::new(format!(
+                "hex output needs {needed} bytes, buffer has {}",
+                output.len()
+            )));
         }
+        let (pairs, _) = output.as_chunks_mut::<2>();
+        for (&b, [hi, lo]) in input.iter().zip(pairs) {
+            *hi = Self::to_hex_digit(b >> 4);
+            *lo = Self::to_hex_digit(b & 0x0f);
+        }
+        Ok(needed)
+    }
 
-        // `out` is guaranteed to be ASCII.
-        unsafe { Ok(String::from_utf8_unchecked(out)) }
+    /// Returns the lowercase hex digit for the low four bits of `n`.
+    const fn to_hex_digit(n: u8) -> u8 {
+        let n = n & 0x0f;
+        if n < 10 { b'0' + n } else { b'a' + n - 10 }
     }
 
     const fn from_hex_digit(c: u8) -> Option<u8> {
@@ -36,13 +79,16 @@ impl Hex {
 
     /// Decodes a hex string into bytes.
     ///
+    /// Whitespace, including line breaks from wrapped output, is ignored.
+    ///
     /// # Errors
     ///
     /// Returns an error if `hex` contains a non-hex character.
     ///
     /// Returns an error if `hex` contains an odd number of characters.
     pub fn try_from_hex(hex: &str) -> Result<Vec<u8>, SerialiseError> {
-        let s = hex.trim();
+        check_ascii(hex, "hex")?;
+        let s = alphabet::strip_whitespace(hex);
         if s.is_empty() {
             return Ok(vec![]);
         }
@@ -54,18 +100,48 @@ impl Hex {
         }
 
         let mut out: Vec<u8> = Vec::with_capacity(s.len() / 2);
-        let bytes = s.as_bytes();
-        for i in (0..bytes.len()).step_by(2) {
-            let Some(hi) = Self::from_hex_digit(bytes[i]) else {
+        let (pairs, _) = s.as_bytes().as_chunks::<2>();
+        for &[hi, lo] in pairs {
+            let Some(hi) = Self::from_hex_digit(hi) else {
                 return Err(SerialiseError::new("invalid hex character".to_string()));
             };
-            let Some(lo) = Self::from_hex_digit(bytes[i + 1]) else {
+            let Some(lo) = Self::from_hex_digit(lo) else {
                 return Err(SerialiseError::new("invalid hex character".to_string()));
             };
             out.push((hi << 4) | lo);
         }
         Ok(out)
     }
+
+    /// Decodes hex into a caller-provided buffer that need not be initialized.
+    ///
+    /// Accepts exactly the inputs [`Hex::try_from_hex`] accepts. Use this to skip
+    /// zeroing large output buffers; on error the buffer may be partly written.
+    ///
+    /// # Arguments
+    /// * `hex` - The hex text to decode
+    /// * `output` - The buffer to write to, large enough for the decoded bytes
+    ///
+    /// # Returns
+    /// The decoded bytes, borrowed from the start of `output`
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `hex` is not valid hex or the decoded
+    /// bytes do not fit in `output`.
+    pub fn decode_into_uninit<'a>(
+        hex: &str,
+        output: &'a mut [MaybeUninit<u8>],
+    ) -> Result<&'a [u8], SerialiseError> {
+        HexDecoder::new().inner.decode_into_uninit(hex, output)
+    }
+
+    /// Checks that `hex` is valid hex without allocating the decoded bytes.
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` for exactly the inputs [`Hex::try_from_hex`] rejects.
+    pub fn validate(hex: &str) -> Result<(), SerialiseError> {
+        HexDecoder::new().inner.validate(hex)
+    }
 }
 
 impl Encoder for Hex {
@@ -81,11 +157,69 @@ impl Encoder for Hex {
     }
 }
 
+/// Incremental hex decoder for input that arrives in pieces.
+///
+/// Whitespace is skipped anywhere in the input.
+#[derive(Debug)]
+pub struct HexDecoder {
+    pub(crate) inner: BitDecoder,
+}
+
+impl HexDecoder {
+    /// Creates a decoder waiting for its first piece of input.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            inner: BitDecoder::new("hex", 4, None, Hex::from_hex_digit),
+        }
+    }
+}
+
+impl Default for HexDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decoder for HexDecoder {
+    fn feed(&mut self, input: &[u8]) -> Result<&[u8], SerialiseError> {
+        self.inner.feed(input)
+    }
+
+    fn finish(&mut self) -> Result<&[u8], SerialiseError> {
+        self.inner.finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_decode_into_uninit() {
+        let mut buf = [MaybeUninit::uninit(); 4];
+        assert!(matches!(
+            Hex::decode_into_uninit("de ad\nBE", &mut buf),
+            Ok(&[0xde, 0xad, 0xbe])
+        ));
+        assert!(Hex::decode_into_uninit("0102030405", &mut buf).is_err());
+        assert!(Hex::decode_into_uninit("abc", &mut buf).is_err());
+        assert!(matches!(Hex::decode_into_uninit("", &mut []), Ok(&[])));
+        assert!(matches!(
+            Hex::decode_into_uninit("0a0b0c0d", &mut buf),
+            Ok(&[0x0a, 0x0b, 0x0c, 0x0d])
+        ));
+    }
+
+    #[test]
+    fn test_encode_slice() {
+        let mut buf = [0u8; 8];
+        assert!(matches!(Hex::encode_slice(b"\x01\xab", &mut buf), Ok(4)));
+        assert_eq!(&buf[..4], b"01ab");
+        assert!(Hex::encode_slice(b"12345", &mut buf).is_err());
+    }
+
     #[test]
     fn test_to_hex() {
         let bytes = Arc::new(b"0123456789abcdefghijklmnopqrstuvwxyz".to_vec());
@@ -110,4 +244,13 @@ mod tests {
         let string = "gg";
         assert!(Hex::try_from_hex(string).is_err());
     }
+
+    #[test]
+    fn test_hex_decoder_splits_byte_across_pieces() {
+        let mut decoder = HexDecoder::new();
+        assert!(matches!(decoder.feed(b"0a0"), Ok(bytes) if bytes == [0x0a]));
+        assert!(matches!(decoder.feed(b"b\n"), Ok(bytes) if bytes == [0x0b]));
+        assert!(decoder.feed(b"f").is_ok());
+        assert!(decoder.finish().is_err());
+    }
 }
@@ -0,0 +1,284 @@
+use std::sync::Arc;
+
+use crate::{
+    EncodedString, Encoder, Encoding, ErrorKind, SerialiseError,
+    algorithm::{Hex, MemoryImage},
+    serialise_error::check_ascii,
+};
+
+const DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+const RECORD_DATA: u8 = 0x00;
+const RECORD_END_OF_FILE: u8 = 0x01;
+const RECORD_EXTENDED_SEGMENT_ADDRESS: u8 = 0x02;
+const RECORD_START_SEGMENT_ADDRESS: u8 = 0x03;
+const RECORD_EXTENDED_LINEAR_ADDRESS: u8 = 0x04;
+const RECORD_START_LINEAR_ADDRESS: u8 = 0x05;
+
+/// Default number of data bytes emitted per record.
+pub const DEFAULT_RECORD_LENGTH: u8 = 16;
+
+/// Default value used to fill gaps between non-contiguous records.
+pub const DEFAULT_FILL: u8 = 0xff;
+
+/// Intel HEX record format implementation.
+///
+/// Encodes bytes as `:LLAAAATT<data>CC` records, using extended linear address
+/// records for images above 64 KiB, and parses records back into a contiguous
+/// memory image.
+#[derive(Debug)]
+pub struct IntelHex {}
+
+impl IntelHex {
+    fn push_byte(out: &mut String, byte: u8) {
+        out.push(DIGITS[(byte >> 4) as usize] as char);
+        out.push(DIGITS[(byte & 0x0f) as usize] as char);
+    }
+
+    fn push_record(out: &mut String, address: u16, record_type: u8, data: &[u8]) {
+        let len = u8::try_from(data.len()).unwrap_or(u8::MAX);
+        let [hi, lo] = address.to_be_bytes();
+
+        let mut sum = len
+            .wrapping_add(hi)
+            .wrapping_add(lo)
+            .wrapping_add(record_type);
+        out.push(':');
+        Self::push_byte(out, len);
+        Self::push_byte(out, hi);
+        Self::push_byte(out, lo);
+        Self::push_byte(out, record_type);
+        for &b in data {
+            Self::push_byte(out, b);
+            sum = sum.wrapping_add(b);
+        }
+        Self::push_byte(out, sum.wrapping_neg());
+        out.push('\n');
+    }
+
+    /// Encodes bytes as Intel HEX records.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes to encode
+    /// * `base_address` - The address of the first byte
+    /// * `record_length` - The maximum number of data bytes per record
+    ///
+    /// # Returns
+    /// The Intel HEX text, terminated by an end-of-file record
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `record_length` is zero or the image does not fit
+    /// in the 32-bit address space.
+    pub fn try_to_intel_hex(
+        bytes: &[u8],
+        base_address: u32,
+        record_length: u8,
+    ) -> Result<String, SerialiseError> {
+        if record_length == 0 {
+            return Err(SerialiseError::new(
+                "intel hex record length must be greater than zero".to_string(),
+            ));
+        }
+
+        let end = u64::from(base_address) + bytes.len() as u64;
+        if end > 1 << 32 {
+            return Err(SerialiseError::new(
+                "intel hex image does not fit in the 32-bit address space".to_string(),
+            ));
+        }
+
+        let mut out = String::new();
+        let mut upper: u16 = 0;
+        let mut address = base_address;
+        let mut rest = bytes;
+
+        while !rest.is_empty() {
+            let [a3, a2, a1, a0] = address.to_be_bytes();
+            let record_upper = u16::from_be_bytes([a3, a2]);
+            let offset = u16::from_be_bytes([a1, a0]);
+
+            if record_upper != upper {
+                Self::push_record(
+                    &mut out,
+                    0,
+                    RECORD_EXTENDED_LINEAR_ADDRESS,
+                    &record_upper.to_be_bytes(),
+                );
+                upper = record_upper;
+            }
+
+            // A data record must not wrap around a 64 KiB segment boundary.
+            let to_boundary = 0x1_0000 - usize::from(offset);
+            let take = rest.len().min(usize::from(record_length)).min(to_boundary);
+            let (chunk, tail) = rest.split_at(take);
+
+            Self::push_record(&mut out, offset, RECORD_DATA, chunk);
+
+            rest = tail;
+            address = address.wrapping_add(u32::try_from(take).unwrap_or(u32::MAX));
+        }
+
+        Self::push_record(&mut out, 0, RECORD_END_OF_FILE, &[]);
+        Ok(out)
+    }
+
+    /// Parses Intel HEX records into a contiguous memory image.
+    ///
+    /// Gaps between data records are filled with `fill`. Start address records
+    /// are validated but otherwise ignored, and anything after the end-of-file
+    /// record is ignored.
+    ///
+    /// # Arguments
+    /// * `intel_hex` - The Intel HEX text to parse
+    /// * `fill` - The value used for addresses not covered by any data record
+    ///
+    /// # Returns
+    /// The parsed memory image
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if a record is malformed, has a bad checksum, overlaps
+    /// another record, lies more than [`MemoryImage::MAX_SPAN`] bytes above the
+    /// lowest record, or the end-of-file record is missing.
+    pub fn from_intel_hex(intel_hex: &str, fill: u8) -> Result<MemoryImage, SerialiseError> {
+        check_ascii(intel_hex, "Intel HEX")?;
+        let mut segments: Vec<(u32, Vec<u8>)> = Vec::new();
+        let mut upper: u32 = 0;
+        let mut terminated = false;
+
+        for line in intel_hex.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let Some(body) = line.strip_prefix(':') else {
+                return Err(SerialiseError::new(
+                    "intel hex record must start with ':'".to_string(),
+                ));
+            };
+
+            let record = Hex::try_from_hex(body)?;
+            if record.len() < 5 {
+                return Err(SerialiseError::new(
+                    "intel hex record is too short".to_string(),
+                ));
+            }
+            if record.iter().fold(0u8, |sum, &b| sum.wrapping_add(b)) != 0 {
+                return Err(
+                    SerialiseError::new("intel hex checksum mismatch".to_string())
+                        .with_kind(ErrorKind::ChecksumMismatch),
+                );
+            }
+
+            let len = usize::from(record[0]);
+            if record.len() != len + 5 {
+                return Err(SerialiseError::new(
+                    "intel hex record length does not match its data".to_string(),
+                ));
+            }
+            let offset = u32::from(u16::from_be_bytes([record[1], record[2]]));
+            let record_type = record[3];
+            let data = &record[4..4 + len];
+
+            match record_type {
+                RECORD_DATA => segments.push((upper + offset, data.to_vec())),
+                RECORD_END_OF_FILE => {
+                    terminated = true;
+                    break;
+                }
+                RECORD_EXTENDED_SEGMENT_ADDRESS | RECORD_EXTENDED_LINEAR_ADDRESS => {
+                    let [hi, lo] = data else {
+                        return Err(SerialiseError::new(
+                            "intel hex extended address record must have 2 data bytes".to_string(),
+                        ));
+                    };
+                    let value = u32::from(u16::from_be_bytes([*hi, *lo]));
+                    upper = if record_type == RECORD_EXTENDED_LINEAR_ADDRESS {
+                        value << 16
+                    } else {
+                        value << 4
+                    };
+                }
+                RECORD_START_SEGMENT_ADDRESS | RECORD_START_LINEAR_ADDRESS => {
+                    if len != 4 {
+                        return Err(SerialiseError::new(
+                            "intel hex start address record must have 4 data bytes".to_string(),
+                        ));
+                    }
+                }
+                _ => {
+                    return Err(SerialiseError::new(format!(
+                        "unsupported intel hex record type {record_type:02X}"
+                    )));
+                }
+            }
+        }
+
+        if !terminated {
+            return Err(SerialiseError::new(
+                "intel hex data is missing its end-of-file record".to_string(),
+            ));
+        }
+
+        MemoryImage::from_segments(segments, fill, "intel hex")
+    }
+}
+
+impl Encoder for IntelHex {
+    fn try_encode(bytes: Arc<Vec<u8>>) -> Result<EncodedString, SerialiseError> {
+        Ok(EncodedString::new(
+            Encoding::IntelHex,
+            Self::try_to_intel_hex(&bytes, 0, DEFAULT_RECORD_LENGTH)?,
+        ))
+    }
+
+    fn try_decode(encoded: &EncodedString) -> Result<Arc<Vec<u8>>, SerialiseError> {
+        Ok(Arc::new(
+            Self::from_intel_hex(encoded.get_string(), DEFAULT_FILL)?.into_bytes(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_to_intel_hex() {
+        let hex = IntelHex::try_to_intel_hex(b"0123456789abcdefghij", 0x0100, 16)
+            .unwrap_or_else(|_| String::new());
+        assert_eq!(
+            hex,
+            ":10010000303132333435363738396162636465668D\n\
+             :040110006768696A49\n\
+             :00000001FF\n"
+        );
+    }
+
+    #[test]
+    fn test_to_intel_hex_splits_at_segment_boundary() {
+        let hex = IntelHex::try_to_intel_hex(&[0xaa, 0xbb, 0xcc, 0xdd], 0xfffe, 16)
+            .unwrap_or_else(|_| String::new());
+        assert_eq!(
+            hex,
+            ":02FFFE00AABB9C\n:020000040001F9\n:02000000CCDD55\n:00000001FF\n"
+        );
+    }
+
+    #[test]
+    fn test_from_intel_hex_fills_gaps() {
+        let hex = ":020000000102FB\n:020004000304F3\n:00000001FF\n";
+        assert!(matches!(
+            IntelHex::from_intel_hex(hex, 0xff),
+            Ok(image) if image.get_base_address() == 0
+                && image.get_bytes() == [0x01, 0x02, 0xff, 0xff, 0x03, 0x04]
+        ));
+    }
+
+    #[test]
+    fn test_from_intel_hex_bad_checksum_is_err() {
+        let hex = ":020000000102FC\n:00000001FF\n";
+        assert!(IntelHex::from_intel_hex(hex, 0xff).is_err());
+    }
+}
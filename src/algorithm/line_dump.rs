@@ -0,0 +1,197 @@
+use std::sync::Arc;
+
+use crate::{
+    Base64, EncodedString, Encoder, Encoding, ErrorKind, SerialiseError, checksum::Crc32,
+    serialise_error::check_ascii,
+};
+
+/// Number of data bytes per line, which keeps lines under 80 characters.
+pub(crate) const BYTES_PER_LINE: usize = 48;
+
+/// Line-oriented, 7-bit safe dump format with a CRC on every line.
+///
+/// Each line is `<length> <data> <crc>`: the number of data bytes as two hex
+/// digits, the bytes in padded Base64 and the CRC-32 of the bytes as eight hex
+/// digits. The dump ends with `end <total length> <crc>`, where the CRC covers
+/// all the data, so truncation is detected too. Corruption on a noisy link only
+/// affects the lines it touches, and [`LineDump::decode_lines`] reports which
+/// lines those are so just they can be sent again.
+#[derive(Debug)]
+pub struct LineDump {}
+
+impl LineDump {
+    /// Writes `bytes` as a line dump.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes to write
+    ///
+    /// # Returns
+    /// The dump, one line per 48 bytes plus the `end` line, each ending in `\n`
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if Base64 encoding fails.
+    pub fn try_to_line_dump(bytes: &[u8]) -> Result<String, SerialiseError> {
+        let mut lines = bytes
+            .chunks(BYTES_PER_LINE)
+            .map(|chunk| {
+                Ok(format!(
+                    "{:02x} {} {:08x}\n",
+                    chunk.len(),
+                    Base64::try_to_base64(chunk)?,
+                    Crc32::checksum(chunk)
+                ))
+            })
+            .collect::<Result<Vec<String>, SerialiseError>>()?;
+        lines.push(format!(
+            "end {} {:08x}\n",
+            bytes.len(),
+            Crc32::checksum(bytes)
+        ));
+        Ok(lines.concat())
+    }
+
+    fn decode_line(line: &str) -> Result<Vec<u8>, SerialiseError> {
+        let fields: Vec<&str> = line.split(' ').collect();
+        let [len, data, crc] = fields[..] else {
+            return Err(SerialiseError::new(
+                "expected length, data and CRC fields".to_string(),
+            ));
+        };
+        let len = usize::from_str_radix(len, 16)
+            .map_err(|_| SerialiseError::new(format!("invalid length '{len}'")))?;
+        let crc = u32::from_str_radix(crc, 16)
+            .map_err(|_| SerialiseError::new(format!("invalid CRC '{crc}'")))?;
+        let bytes = Base64::try_from_base64(data, 0)?;
+
+        if bytes.len() != len {
+            return Err(SerialiseError::new(format!(
+                "length {len} does not match {} data bytes",
+                bytes.len()
+            )));
+        }
+        if Crc32::checksum(&bytes) != crc {
+            return Err(SerialiseError::new("CRC mismatch".to_string())
+                .with_kind(ErrorKind::ChecksumMismatch));
+        }
+        Ok(bytes)
+    }
+
+    /// Decodes each data line of a dump on its own.
+    ///
+    /// Blank lines and the `end` line are skipped, and a trailing `\r` is ignored.
+    ///
+    /// # Arguments
+    /// * `dump` - The dump to decode
+    ///
+    /// # Returns
+    /// For each data line, its 1-based line number in `dump` and either its bytes
+    /// or the reason it failed
+    #[must_use = "This returns the decoded lines but does nothing if unused"]
+    pub fn decode_lines(dump: &str) -> Vec<(usize, Result<Vec<u8>, SerialiseError>)> {
+        dump.lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim_end_matches('\r')))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with("end "))
+            .map(|(number, line)| (number, Self::decode_line(line)))
+            .collect()
+    }
+
+    /// Decodes a dump, checking every line and the `end` line.
+    ///
+    /// # Arguments
+    /// * `dump` - The dump to decode
+    ///
+    /// # Returns
+    /// The decoded bytes
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` listing the numbers of all lines that failed their
+    /// checks, or if the `end` line is missing or does not match the data.
+    pub fn try_from_line_dump(dump: &str) -> Result<Vec<u8>, SerialiseError> {
+        check_ascii(dump, "line dump")?;
+        let mut out: Vec<u8> = Vec::with_capacity(dump.len() / 80 * BYTES_PER_LINE);
+        let mut failed: Vec<String> = Vec::new();
+        for (number, line) in Self::decode_lines(dump) {
+            match line {
+                Ok(bytes) => out.extend_from_slice(&bytes),
+                Err(e) => failed.push(format!("line {number}: {e}")),
+            }
+        }
+        if !failed.is_empty() {
+            return Err(SerialiseError::new(format!(
+                "corrupt dump lines: {}",
+                failed.join("; ")
+            )));
+        }
+
+        let Some(end) = dump
+            .lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .find(|line| line.starts_with("end "))
+        else {
+            return Err(SerialiseError::new(
+                "dump is missing its end line".to_string(),
+            ));
+        };
+        let expected = format!("end {} {:08x}", out.len(), Crc32::checksum(&out));
+        if !end.eq_ignore_ascii_case(&expected) {
+            return Err(SerialiseError::new(format!(
+                "dump end line '{end}' does not match the data, expected '{expected}'"
+            )));
+        }
+
+        Ok(out)
+    }
+}
+
+impl Encoder for LineDump {
+    fn try_encode(bytes: Arc<Vec<u8>>) -> Result<EncodedString, SerialiseError> {
+        Ok(EncodedString::new(
+            Encoding::LineDump,
+            Self::try_to_line_dump(&bytes)?,
+        ))
+    }
+
+    fn try_decode(encoded: &EncodedString) -> Result<Arc<Vec<u8>>, SerialiseError> {
+        Ok(Arc::new(Self::try_from_line_dump(encoded.get_string())?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_to_line_dump() {
+        assert_eq!(Crc32::checksum(b"123456789"), 0xcbf4_3926);
+        assert_eq!(
+            LineDump::try_to_line_dump(b"foo").unwrap_or_default(),
+            "03 Zm9v 8c736521\nend 3 8c736521\n"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let dump = LineDump::try_to_line_dump(&bytes).unwrap_or_default();
+        assert_eq!(dump.lines().count(), 7);
+        assert!(dump.lines().all(|line| line.len() < 80 && line.is_ascii()));
+        assert!(matches!(LineDump::try_from_line_dump(&dump), Ok(decoded) if decoded == bytes));
+    }
+
+    #[test]
+    fn test_reports_corrupt_lines() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let dump = LineDump::try_to_line_dump(&bytes)
+            .unwrap_or_default()
+            .replacen("AAECAwQF", "AAECAwQG", 1);
+        let lines = LineDump::decode_lines(&dump);
+        assert!(matches!(lines[0], (1, Err(_))));
+        assert!(lines[1..].iter().all(|(_, line)| line.is_ok()));
+        assert!(LineDump::try_from_line_dump(&dump).is_err());
+
+        let truncated = dump.split_once('\n').map_or("", |(_, rest)| rest);
+        assert!(LineDump::try_from_line_dump(truncated).is_err());
+    }
+}
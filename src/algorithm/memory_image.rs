@@ -0,0 +1,125 @@
+use crate::SerialiseError;
+
+/// Contiguous memory image parsed from firmware record formats.
+///
+/// Produced by the Intel HEX and Motorola S-record decoders, which both describe
+/// data as address/bytes pairs that may leave gaps between records.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryImage {
+    /// The lowest address covered by a data record
+    base_address: u32,
+    /// The image bytes, starting at `base_address`
+    bytes: Vec<u8>,
+}
+
+impl MemoryImage {
+    /// The largest span, from the lowest to the highest address written, that a
+    /// decoder will fill in. A few records with far-apart addresses would
+    /// otherwise allocate up to 4 GiB.
+    pub const MAX_SPAN: usize = 64 * 1024 * 1024;
+
+    /// Assembles an image from `(address, data)` segments, filling gaps with `fill`.
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if two segments overlap, or if they span more
+    /// than [`MAX_SPAN`](Self::MAX_SPAN) bytes.
+    pub(crate) fn from_segments(
+        mut segments: Vec<(u32, Vec<u8>)>,
+        fill: u8,
+        format: &str,
+    ) -> Result<Self, SerialiseError> {
+        segments.sort_by_key(|(address, _)| *address);
+
+        let Some(base_address) = segments.first().map(|(address, _)| *address) else {
+            return Ok(Self {
+                base_address: 0,
+                bytes: vec![],
+            });
+        };
+
+        let mut bytes: Vec<u8> = Vec::new();
+        for (address, data) in segments {
+            let start = (address - base_address) as usize;
+            if start < bytes.len() {
+                return Err(SerialiseError::new(format!(
+                    "{format} record at {address:08X} overlaps a previous record"
+                )));
+            }
+            if start.saturating_add(data.len()) > Self::MAX_SPAN {
+                return Err(SerialiseError::new(format!(
+                    "{format} record at {address:08X} is more than {} bytes above {base_address:08X}",
+                    Self::MAX_SPAN
+                )));
+            }
+            bytes.resize(start, fill);
+            bytes.extend_from_slice(&data);
+        }
+
+        Ok(Self {
+            base_address,
+            bytes,
+        })
+    }
+
+    /// Returns the lowest address covered by the image.
+    #[must_use = "This returns the base address but does nothing if unused"]
+    pub const fn get_base_address(&self) -> u32 {
+        self.base_address
+    }
+
+    /// Returns the image bytes, starting at the base address.
+    #[must_use = "This returns the image bytes but does nothing if unused"]
+    pub fn get_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Consumes the image and returns its bytes.
+    #[must_use = "This returns the image bytes but does nothing if unused"]
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_from_segments_fills_gaps() {
+        let image =
+            MemoryImage::from_segments(vec![(0x14, vec![3]), (0x10, vec![1, 2])], 0, "test");
+        assert!(matches!(
+            image,
+            Ok(image) if image.get_base_address() == 0x10 && image.get_bytes() == [1, 2, 0, 0, 3]
+        ));
+    }
+
+    #[test]
+    fn test_from_overlapping_segments_is_err() {
+        let image = MemoryImage::from_segments(vec![(0, vec![1, 2]), (1, vec![3])], 0, "test");
+        assert!(image.is_err());
+    }
+
+    #[test]
+    fn test_from_far_apart_segments_is_err() {
+        let image = MemoryImage::from_segments(
+            vec![(0x0800_0000, vec![1]), (0x1fff_0000, vec![2])],
+            0,
+            "test",
+        );
+        assert!(matches!(image, Err(e) if e.get_message().contains("more than")));
+        let image = MemoryImage::from_segments(
+            vec![
+                (0, vec![1]),
+                (
+                    u32::try_from(MemoryImage::MAX_SPAN - 1).unwrap_or_default(),
+                    vec![2],
+                ),
+            ],
+            0,
+            "test",
+        );
+        assert!(matches!(image, Ok(image) if image.get_bytes().len() == MemoryImage::MAX_SPAN));
+    }
+}
@@ -1,20 +1,96 @@
+/// Base32 encoding implementation (RFC 4648).
+#[cfg(feature = "base32")]
+pub mod base32;
+
 /// Base36 encoding implementation (0-9 and A-Z).
+#[cfg(feature = "base36")]
 pub mod base36;
 
+/// Base45 encoding implementation (RFC 9285).
+#[cfg(feature = "base45")]
+pub mod base45;
+
 /// Base58 encoding implementation (Bitcoin-style).
+#[cfg(feature = "base58")]
 pub mod base58;
 
+/// Base62 encoding implementation (0-9, A-Z and a-z).
+#[cfg(feature = "base62")]
+pub mod base62;
+
 /// Base64 encoding implementation (RFC 4648).
+#[cfg(feature = "base64")]
 pub mod base64;
 
+/// `BinHex` 4.0 (`.hqx`) implementation.
+#[cfg(feature = "binhex")]
+pub mod binhex;
+
+/// Base94 encoding over every printable ASCII character except space.
+#[cfg(feature = "base94")]
+pub mod base94;
+
+/// Printable ASCII with `\xNN` escapes for everything else.
+#[cfg(feature = "escaped")]
+pub mod escaped;
+
+/// File name encoding safe on case-insensitive filesystems.
+#[cfg(feature = "filename-safe")]
+pub mod filename_safe;
+
 /// Hexadecimal encoding implementation (0-9 and A-F).
+#[cfg(feature = "hex")]
 pub mod hex;
 
+/// Intel HEX record format implementation.
+#[cfg(feature = "intel-hex")]
+pub mod intel_hex;
+
+/// Line dump format with a CRC-32 per line.
+#[cfg(feature = "line-dump")]
+pub mod line_dump;
+
+/// Contiguous memory images decoded from firmware record formats.
+#[cfg(any(feature = "intel-hex", feature = "srec"))]
+pub mod memory_image;
+
+/// Motorola S-record (SREC) format implementation.
+#[cfg(feature = "srec")]
+pub mod srec;
+
 /// `uuencode` implementation.
+#[cfg(feature = "uuencode")]
 pub mod uuencode;
 
+#[cfg(feature = "base32")]
+pub use base32::{Base32, Base32Alphabet, Base32Config, Base32Decoder};
+#[cfg(feature = "base36")]
 pub use base36::Base36;
+#[cfg(feature = "base45")]
+pub use base45::Base45;
+#[cfg(feature = "base58")]
 pub use base58::Base58;
-pub use base64::Base64;
-pub use hex::Hex;
-pub use uuencode::Uuencode;
+#[cfg(feature = "base62")]
+pub use base62::Base62;
+#[cfg(feature = "base64")]
+pub use base64::{Base64, Base64Config, Base64Decoder};
+#[cfg(feature = "base94")]
+pub use base94::Base94;
+#[cfg(feature = "binhex")]
+pub use binhex::BinHex;
+#[cfg(feature = "escaped")]
+pub use escaped::Escaped;
+#[cfg(feature = "filename-safe")]
+pub use filename_safe::FilenameSafe;
+#[cfg(feature = "hex")]
+pub use hex::{Hex, HexDecoder};
+#[cfg(feature = "intel-hex")]
+pub use intel_hex::IntelHex;
+#[cfg(feature = "line-dump")]
+pub use line_dump::LineDump;
+#[cfg(any(feature = "intel-hex", feature = "srec"))]
+pub use memory_image::MemoryImage;
+#[cfg(feature = "srec")]
+pub use srec::Srec;
+#[cfg(feature = "uuencode")]
+pub use uuencode::{FileMode, Uuencode, UuencodeConfig};
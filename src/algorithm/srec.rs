@@ -0,0 +1,350 @@
+use std::sync::Arc;
+
+use crate::{
+    EncodedString, Encoder, Encoding, ErrorKind, SerialiseError,
+    algorithm::{Hex, MemoryImage},
+    serialise_error::check_ascii,
+};
+
+const DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Default number of data bytes emitted per record.
+pub const DEFAULT_RECORD_LENGTH: u8 = 16;
+
+/// Default value used to fill gaps between non-contiguous records.
+pub const DEFAULT_FILL: u8 = 0xff;
+
+/// Address width used by S-record data and termination records.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, PartialOrd, Ord)]
+pub enum SrecAddressWidth {
+    /// 16-bit addresses (S1 data, S9 termination)
+    Bits16,
+    /// 24-bit addresses (S2 data, S8 termination)
+    Bits24,
+    /// 32-bit addresses (S3 data, S7 termination)
+    Bits32,
+}
+
+impl SrecAddressWidth {
+    /// Returns the narrowest address width that can address every byte below `end`.
+    #[must_use = "This returns the address width but does nothing if unused"]
+    pub const fn for_end_address(end: u64) -> Self {
+        if end <= 0x1_0000 {
+            Self::Bits16
+        } else if end <= 0x100_0000 {
+            Self::Bits24
+        } else {
+            Self::Bits32
+        }
+    }
+
+    const fn address_bytes(self) -> usize {
+        match self {
+            Self::Bits16 => 2,
+            Self::Bits24 => 3,
+            Self::Bits32 => 4,
+        }
+    }
+
+    const fn data_record(self) -> u8 {
+        match self {
+            Self::Bits16 => b'1',
+            Self::Bits24 => b'2',
+            Self::Bits32 => b'3',
+        }
+    }
+
+    const fn termination_record(self) -> u8 {
+        match self {
+            Self::Bits16 => b'9',
+            Self::Bits24 => b'8',
+            Self::Bits32 => b'7',
+        }
+    }
+}
+
+/// Motorola S-record (SREC) format implementation.
+///
+/// Encodes bytes as S1/S2/S3 data records with a header, record count and
+/// termination record, and parses records back into a contiguous memory image.
+#[derive(Debug)]
+pub struct Srec {}
+
+impl Srec {
+    fn push_byte(out: &mut String, byte: u8) {
+        out.push(DIGITS[(byte >> 4) as usize] as char);
+        out.push(DIGITS[(byte & 0x0f) as usize] as char);
+    }
+
+    fn push_record(out: &mut String, record_type: u8, address: &[u8], data: &[u8]) {
+        let count = u8::try_from(address.len() + data.len() + 1).unwrap_or(u8::MAX);
+
+        let mut sum = count;
+        out.push('S');
+        out.push(record_type as char);
+        Self::push_byte(out, count);
+        for &b in address.iter().chain(data) {
+            Self::push_byte(out, b);
+            sum = sum.wrapping_add(b);
+        }
+        Self::push_byte(out, !sum);
+        out.push('\n');
+    }
+
+    fn address_field(address: u32, width: SrecAddressWidth) -> Vec<u8> {
+        let bytes = address.to_be_bytes();
+        bytes[4 - width.address_bytes()..].to_vec()
+    }
+
+    /// Encodes bytes as S-records, choosing the narrowest address width for the image.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes to encode
+    /// * `base_address` - The address of the first byte
+    /// * `record_length` - The maximum number of data bytes per record
+    ///
+    /// # Returns
+    /// The S-record text: an S0 header, data records, a record count and a termination record
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the image does not fit in the 32-bit address space or
+    /// `record_length` is not valid for the chosen address width.
+    pub fn try_to_srec(
+        bytes: &[u8],
+        base_address: u32,
+        record_length: u8,
+    ) -> Result<String, SerialiseError> {
+        let end = u64::from(base_address) + bytes.len() as u64;
+        Self::try_to_srec_with_width(
+            bytes,
+            base_address,
+            record_length,
+            SrecAddressWidth::for_end_address(end),
+        )
+    }
+
+    /// Encodes bytes as S-records using an explicit address width.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes to encode
+    /// * `base_address` - The address of the first byte
+    /// * `record_length` - The maximum number of data bytes per record
+    /// * `width` - The address width of the data and termination records
+    ///
+    /// # Returns
+    /// The S-record text: an S0 header, data records, a record count and a termination record
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the image does not fit in `width`, or `record_length`
+    /// is zero or too long for a record with that address width.
+    pub fn try_to_srec_with_width(
+        bytes: &[u8],
+        base_address: u32,
+        record_length: u8,
+        width: SrecAddressWidth,
+    ) -> Result<String, SerialiseError> {
+        let max_length = 255 - width.address_bytes() - 1;
+        if record_length == 0 || usize::from(record_length) > max_length {
+            return Err(SerialiseError::new(format!(
+                "srec record length must be between 1 and {max_length}"
+            )));
+        }
+
+        let end = u64::from(base_address) + bytes.len() as u64;
+        if SrecAddressWidth::for_end_address(end) > width {
+            return Err(SerialiseError::new(
+                "srec image does not fit in the requested address width".to_string(),
+            ));
+        }
+
+        let mut out = String::new();
+        Self::push_record(&mut out, b'0', &[0, 0], &[]);
+
+        let mut count: u32 = 0;
+        let mut address = base_address;
+        for chunk in bytes.chunks(usize::from(record_length)) {
+            Self::push_record(
+                &mut out,
+                width.data_record(),
+                &Self::address_field(address, width),
+                chunk,
+            );
+            count += 1;
+            address = address.wrapping_add(u32::try_from(chunk.len()).unwrap_or(u32::MAX));
+        }
+
+        if count <= 0xffff {
+            Self::push_record(&mut out, b'5', &count.to_be_bytes()[2..], &[]);
+        } else if count <= 0xff_ffff {
+            Self::push_record(&mut out, b'6', &count.to_be_bytes()[1..], &[]);
+        }
+
+        Self::push_record(
+            &mut out,
+            width.termination_record(),
+            &Self::address_field(base_address, width),
+            &[],
+        );
+        Ok(out)
+    }
+
+    /// Parses S-records into a contiguous memory image.
+    ///
+    /// Gaps between data records are filled with `fill`. Header records are
+    /// ignored, record count records are checked against the number of data
+    /// records seen, and anything after the termination record is ignored.
+    ///
+    /// # Arguments
+    /// * `srec` - The S-record text to parse
+    /// * `fill` - The value used for addresses not covered by any data record
+    ///
+    /// # Returns
+    /// The parsed memory image
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if a record is malformed, has a bad checksum, overlaps
+    /// another record, lies more than [`MemoryImage::MAX_SPAN`] bytes above the
+    /// lowest record, disagrees with a record count, or the termination record is missing.
+    pub fn from_srec(srec: &str, fill: u8) -> Result<MemoryImage, SerialiseError> {
+        check_ascii(srec, "S-record")?;
+        let mut segments: Vec<(u32, Vec<u8>)> = Vec::new();
+        let mut terminated = false;
+
+        for line in srec.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut chars = line.chars();
+            if chars.next() != Some('S') {
+                return Err(SerialiseError::new(
+                    "srec record must start with 'S'".to_string(),
+                ));
+            }
+            let Some(record_type) = chars.next() else {
+                return Err(SerialiseError::new(
+                    "srec record is missing its type".to_string(),
+                ));
+            };
+
+            let record = Hex::try_from_hex(chars.as_str())?;
+            let Some((&count, rest)) = record.split_first() else {
+                return Err(SerialiseError::new("srec record is too short".to_string()));
+            };
+            if usize::from(count) != rest.len() || rest.is_empty() {
+                return Err(SerialiseError::new(
+                    "srec record count does not match its data".to_string(),
+                ));
+            }
+            if record.iter().fold(0u8, |sum, &b| sum.wrapping_add(b)) != 0xff {
+                return Err(SerialiseError::new("srec checksum mismatch".to_string())
+                    .with_kind(ErrorKind::ChecksumMismatch));
+            }
+            let fields = &rest[..rest.len() - 1];
+
+            let address_bytes = match record_type {
+                '0' | '1' | '5' | '9' => 2,
+                '2' | '6' | '8' => 3,
+                '3' | '7' => 4,
+                _ => {
+                    return Err(SerialiseError::new(format!(
+                        "unsupported srec record type S{record_type}"
+                    )));
+                }
+            };
+            if fields.len() < address_bytes {
+                return Err(SerialiseError::new(
+                    "srec record address is truncated".to_string(),
+                ));
+            }
+            let (address, data) = fields.split_at(address_bytes);
+            let address = address
+                .iter()
+                .fold(0u32, |acc, &b| (acc << 8) | u32::from(b));
+
+            match record_type {
+                '1' | '2' | '3' => segments.push((address, data.to_vec())),
+                '5' | '6' if address as usize != segments.len() => {
+                    return Err(SerialiseError::new(format!(
+                        "srec record count {address} does not match {} data records",
+                        segments.len()
+                    )));
+                }
+                '7' | '8' | '9' => {
+                    terminated = true;
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        if !terminated {
+            return Err(SerialiseError::new(
+                "srec data is missing its termination record".to_string(),
+            ));
+        }
+
+        MemoryImage::from_segments(segments, fill, "srec")
+    }
+}
+
+impl Encoder for Srec {
+    fn try_encode(bytes: Arc<Vec<u8>>) -> Result<EncodedString, SerialiseError> {
+        Ok(EncodedString::new(
+            Encoding::Srec,
+            Self::try_to_srec(&bytes, 0, DEFAULT_RECORD_LENGTH)?,
+        ))
+    }
+
+    fn try_decode(encoded: &EncodedString) -> Result<Arc<Vec<u8>>, SerialiseError> {
+        Ok(Arc::new(
+            Self::from_srec(encoded.get_string(), DEFAULT_FILL)?.into_bytes(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_to_srec() {
+        let srec = Srec::try_to_srec(b"0123456789abcdefghij", 0x0100, 16)
+            .unwrap_or_else(|_| String::new());
+        assert_eq!(
+            srec,
+            "S0030000FC\n\
+             S11301003031323334353637383961626364656689\n\
+             S10701106768696A45\n\
+             S5030002FA\n\
+             S9030100FB\n"
+        );
+    }
+
+    #[test]
+    fn test_to_srec_uses_wider_addresses() {
+        let srec = Srec::try_to_srec(&[0xaa], 0x0001_0000, 16).unwrap_or_else(|_| String::new());
+        assert_eq!(
+            srec,
+            "S0030000FC\nS205010000AA4F\nS5030001FB\nS804010000FA\n"
+        );
+    }
+
+    #[test]
+    fn test_from_srec_fills_gaps() {
+        let srec = "S0030000FC\nS10500000102F7\nS10500040304EF\nS9030000FC\n";
+        assert!(matches!(
+            Srec::from_srec(srec, 0xff),
+            Ok(image) if image.get_base_address() == 0
+                && image.get_bytes() == [0x01, 0x02, 0xff, 0xff, 0x03, 0x04]
+        ));
+    }
+
+    #[test]
+    fn test_from_srec_bad_checksum_is_err() {
+        let srec = "S10500000102F8\nS9030000FC\n";
+        assert!(Srec::from_srec(srec, 0xff).is_err());
+    }
+}
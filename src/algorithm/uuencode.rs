@@ -1,19 +1,156 @@
-use std::sync::Arc;
+use std::{fmt::Display, str::FromStr, sync::Arc};
 
-use crate::{EncodedString, Encoder, Encoding, SerialiseError};
+use crate::{
+    EncodedString, Encoder, Encoding, LineEnding, SerialiseError, serialise_error::check_ascii,
+};
+
+/// Options for the character [`Uuencode`] uses for a zero value and the line ending.
+///
+/// Modern encoders write a backtick for zero while historical ones write a space.
+/// Decoders accept either unless the config is strict, in which case only the
+/// configured character is accepted. Decoders accept either line ending.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct UuencodeConfig {
+    /// The character written for a zero value, `` ` `` or space
+    zero: u8,
+    /// Whether decoding rejects the other zero character
+    strict: bool,
+    /// Line ending written after each line
+    line_ending: LineEnding,
+}
+
+impl UuencodeConfig {
+    /// Writes a backtick for zero, as modern encoders do.
+    pub const BACKTICK: Self = Self {
+        zero: b'`',
+        strict: false,
+        line_ending: LineEnding::Lf,
+    };
+
+    /// Writes a space for zero, as historical encoders do.
+    pub const SPACE: Self = Self {
+        zero: b' ',
+        ..Self::BACKTICK
+    };
+
+    /// Makes decoding reject the zero character this config does not write.
+    #[must_use = "This returns the updated config but does nothing if unused"]
+    pub const fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Ends each line with `line_ending` instead of `\n`.
+    #[must_use = "This returns the updated config but does nothing if unused"]
+    pub const fn line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    /// Returns the character written for a zero value.
+    #[must_use = "This returns the zero character but does nothing if unused"]
+    pub const fn get_zero(&self) -> char {
+        self.zero as char
+    }
+
+    /// Returns true if decoding only accepts the configured zero character.
+    #[must_use = "This returns whether the config is strict but does nothing if unused"]
+    pub const fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Returns the line ending written after each line.
+    #[must_use = "This returns the line ending but does nothing if unused"]
+    pub const fn get_line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+}
+
+impl Default for UuencodeConfig {
+    fn default() -> Self {
+        Self::BACKTICK
+    }
+}
+
+/// Unix permission bits from the `begin <mode> <name>` line of a uuencoded file.
+///
+/// Holds the permission, setuid, setgid and sticky bits, `0` to `0o7777`.
+/// Parsed from and displayed as octal, with three digits unless any of the
+/// setuid, setgid or sticky bits are set.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct FileMode {
+    /// The mode bits
+    bits: u16,
+}
+
+impl FileMode {
+    /// `rw-r--r--`, the mode most encoders write for a regular file.
+    pub const DEFAULT: Self = Self { bits: 0o644 };
+
+    /// Creates a mode from its bits.
+    ///
+    /// # Arguments
+    /// * `bits` - The mode bits, e.g. `0o755`
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `bits` has bits above `0o7777` set, such as
+    /// the file type bits of `st_mode`.
+    pub fn new(bits: u16) -> Result<Self, SerialiseError> {
+        if bits > 0o7777 {
+            return Err(SerialiseError::new(format!(
+                "file mode {bits:o} has bits outside 7777"
+            )));
+        }
+        Ok(Self { bits })
+    }
+
+    /// Returns the mode bits.
+    #[must_use = "This returns the mode bits but does nothing if unused"]
+    pub const fn get_bits(&self) -> u16 {
+        self.bits
+    }
+}
+
+impl Default for FileMode {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Writes the mode as octal, e.g. `644` or `4755`.
+impl Display for FileMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:03o}", self.bits)
+    }
+}
+
+/// Parses one to four octal digits, e.g. `644` or `0755`.
+impl FromStr for FileMode {
+    type Err = SerialiseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || s.len() > 4 || !s.bytes().all(|c| matches!(c, b'0'..=b'7')) {
+            return Err(SerialiseError::new(format!(
+                "file mode '{s}' is not 1 to 4 octal digits"
+            )));
+        }
+        Self::new(u16::from_str_radix(s, 8).map_err(|e| SerialiseError::new(e.to_string()))?)
+    }
+}
 
 /// `uuencode` implementation.
 #[derive(Debug)]
 pub struct Uuencode {}
 
 impl Uuencode {
-    const fn enc6(v: u8) -> u8 {
+    const fn enc6(v: u8, config: UuencodeConfig) -> u8 {
         let v = v & 0x3f;
-        if v == 0 { b'`' } else { v + 0x20 }
+        if v == 0 { config.zero } else { v + 0x20 }
     }
 
-    const fn dec6(c: u8) -> Option<u8> {
+    const fn dec6(c: u8, config: UuencodeConfig) -> Option<u8> {
         match c {
+            b'`' | b' ' if config.strict && c != config.zero => None,
             b'`' | b' ' => Some(0),
             0x20..=0x5f => Some((c - 0x20) & 0x3f),
             _ => None,
@@ -21,13 +158,13 @@ impl Uuencode {
     }
 
     #[allow(clippy::missing_const_for_fn)]
-    fn enc_len(n: usize) -> u8 {
-        u8::try_from(n).map_or_else(|_| Self::enc6(0), Self::enc6)
+    fn enc_len(n: usize, config: UuencodeConfig) -> u8 {
+        u8::try_from(n).map_or_else(|_| Self::enc6(0, config), |n| Self::enc6(n, config))
     }
 
     #[allow(clippy::missing_const_for_fn)]
-    fn dec_len(c: u8) -> Option<usize> {
-        Self::dec6(c).map(usize::from)
+    fn dec_len(c: u8, config: UuencodeConfig) -> Option<usize> {
+        Self::dec6(c, config).map(usize::from)
     }
 
     /// Uuencode bytes using the traditional uuencode line format (45 bytes per line).
@@ -36,10 +173,19 @@ impl Uuencode {
     /// followed by encoded data, and ends with `\n`. The final line is "\`\n".
     #[must_use = "this returns the uuencoded string but does nothing if unused"]
     pub fn to_uuencode(bytes: &[u8]) -> String {
+        Self::to_uuencode_with(bytes, UuencodeConfig::BACKTICK)
+    }
+
+    /// Uuencode bytes, writing zero values with the character chosen in `config`.
+    ///
+    /// Use [`UuencodeConfig::SPACE`] to reproduce the output of historical encoders
+    /// byte for byte.
+    #[must_use = "this returns the uuencoded string but does nothing if unused"]
+    pub fn to_uuencode_with(bytes: &[u8], config: UuencodeConfig) -> String {
         let mut out: Vec<u8> = Vec::new();
 
         for chunk in bytes.chunks(45) {
-            out.push(Self::enc_len(chunk.len()));
+            out.push(Self::enc_len(chunk.len(), config));
 
             for triple in chunk.chunks(3) {
                 let b0 = triple[0];
@@ -51,17 +197,17 @@ impl Uuencode {
                 let c2 = ((b1 << 2) | (b2 >> 6)) & 0x3f;
                 let c3 = b2 & 0x3f;
 
-                out.push(Self::enc6(c0));
-                out.push(Self::enc6(c1));
-                out.push(Self::enc6(c2));
-                out.push(Self::enc6(c3));
+                out.push(Self::enc6(c0, config));
+                out.push(Self::enc6(c1, config));
+                out.push(Self::enc6(c2, config));
+                out.push(Self::enc6(c3, config));
             }
 
-            out.push(b'\n');
+            out.extend_from_slice(config.line_ending.as_str().as_bytes());
         }
 
-        out.push(b'`');
-        out.push(b'\n');
+        out.push(config.zero);
+        out.extend_from_slice(config.line_ending.as_str().as_bytes());
 
         let mut s = String::with_capacity(out.len());
         for b in out {
@@ -76,6 +222,20 @@ impl Uuencode {
     ///
     /// Returns an error if `uuencoded` contains invalid uuencode characters or malformed lines.
     pub fn from_uuencode(uuencoded: &str) -> Result<Vec<u8>, SerialiseError> {
+        Self::from_uuencode_with(uuencoded, UuencodeConfig::BACKTICK)
+    }
+
+    /// Decode a uuencoded string, accepting zero values as `config` allows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `uuencoded` contains invalid uuencode characters or malformed
+    /// lines, or uses the zero character `config` does not write when `config` is strict.
+    pub fn from_uuencode_with(
+        uuencoded: &str,
+        config: UuencodeConfig,
+    ) -> Result<Vec<u8>, SerialiseError> {
+        check_ascii(uuencoded, "uuencode")?;
         let mut out: Vec<u8> = Vec::new();
 
         for line in uuencoded.lines() {
@@ -87,7 +247,7 @@ impl Uuencode {
             let len_ch = it.next().ok_or_else(|| {
                 SerialiseError::new("uuencode line must have a length character".to_string())
             })?;
-            let line_len = Self::dec_len(len_ch).ok_or_else(|| {
+            let line_len = Self::dec_len(len_ch, config).ok_or_else(|| {
                 SerialiseError::new("invalid uuencode length character".to_string())
             })?;
             if line_len == 0 {
@@ -109,13 +269,13 @@ impl Uuencode {
                     .next()
                     .ok_or_else(|| SerialiseError::new("truncated uuencode data".to_string()))?;
 
-                let a = Self::dec6(a)
+                let a = Self::dec6(a, config)
                     .ok_or_else(|| SerialiseError::new("invalid uuencode character".to_string()))?;
-                let b = Self::dec6(b)
+                let b = Self::dec6(b, config)
                     .ok_or_else(|| SerialiseError::new("invalid uuencode character".to_string()))?;
-                let c = Self::dec6(c)
+                let c = Self::dec6(c, config)
                     .ok_or_else(|| SerialiseError::new("invalid uuencode character".to_string()))?;
-                let d = Self::dec6(d)
+                let d = Self::dec6(d, config)
                     .ok_or_else(|| SerialiseError::new("invalid uuencode character".to_string()))?;
 
                 let o0 = (a << 2) | (b >> 4);
@@ -153,6 +313,20 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_file_mode() {
+        assert!(matches!("644".parse::<FileMode>(), Ok(mode) if mode == FileMode::DEFAULT));
+        assert!(matches!("0755".parse::<FileMode>(), Ok(mode) if mode.get_bits() == 0o755));
+        assert!(matches!("4755".parse::<FileMode>(), Ok(mode) if mode.to_string() == "4755"));
+        assert_eq!(FileMode::new(0o600).unwrap_or_default().to_string(), "600");
+        assert_eq!(FileMode::new(0).unwrap_or_default().to_string(), "000");
+        assert!("".parse::<FileMode>().is_err());
+        assert!("648".parse::<FileMode>().is_err());
+        assert!("+644".parse::<FileMode>().is_err());
+        assert!("10644".parse::<FileMode>().is_err());
+        assert!(FileMode::new(0o100_644).is_err());
+    }
+
     #[test]
     fn test_to_uuencode() {
         let bytes = Arc::new(b"0123456789abcdefghijklmnopqrstuvwxyz".to_vec());
@@ -177,4 +351,26 @@ mod tests {
         let string = "gg";
         assert!(Uuencode::from_uuencode(string).is_err());
     }
+
+    #[test]
+    fn test_space_for_zero() {
+        let uuencode = Uuencode::to_uuencode_with(&[0, 0, 0], UuencodeConfig::SPACE);
+        assert_eq!(uuencode, "#    \n \n");
+        assert!(matches!(Uuencode::from_uuencode(&uuencode), Ok(bytes) if bytes == [0, 0, 0]));
+        assert!(
+            Uuencode::from_uuencode_with(&uuencode, UuencodeConfig::BACKTICK.strict()).is_err()
+        );
+        assert!(matches!(
+            Uuencode::from_uuencode_with(&uuencode, UuencodeConfig::SPACE.strict()),
+            Ok(bytes) if bytes == [0, 0, 0]
+        ));
+    }
+
+    #[test]
+    fn test_crlf_line_endings() {
+        let config = UuencodeConfig::BACKTICK.line_ending(LineEnding::CrLf);
+        let uuencode = Uuencode::to_uuencode_with(b"Cat", config);
+        assert_eq!(uuencode, "#0V%T\r\n`\r\n");
+        assert!(matches!(Uuencode::from_uuencode(&uuencode), Ok(bytes) if bytes == b"Cat"));
+    }
 }
@@ -0,0 +1,223 @@
+#[cfg(any(feature = "base32", feature = "base64", feature = "hex"))]
+use std::borrow::Cow;
+
+use crate::SerialiseError;
+
+/// Decimal digits.
+pub const DECIMAL: &[u8; 10] = b"0123456789";
+
+/// Lowercase hexadecimal digits.
+pub const HEX: &[u8; 16] = b"0123456789abcdef";
+
+/// RFC 4648 Base32 alphabet.
+pub const BASE32_STANDARD: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// RFC 4648 Base32 alphabet in lowercase.
+pub const BASE32_LOWER: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+/// z-base-32 alphabet, ordered so the most common digits are the easiest to
+/// read, write and say.
+pub const ZBASE32: &[u8; 32] = b"ybndrfg8ejkmcpqxot1uwisza345h769";
+
+/// RFC 4648 "extended hex" Base32 alphabet, which preserves sort order.
+pub const BASE32_HEX: &[u8; 32] = b"0123456789ABCDEFGHIJKLMNOPQRSTUV";
+
+/// RFC 4648 "extended hex" Base32 alphabet in lowercase, as used by DNSSEC
+/// NSEC3 hashed owner names.
+pub const BASE32_HEX_LOWER: &[u8; 32] = b"0123456789abcdefghijklmnopqrstuv";
+
+/// Base36 digits and lowercase letters.
+pub const BASE36: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// RFC 9285 Base45 alphabet, the QR code alphanumeric character set.
+pub const BASE45: &[u8; 45] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+/// Base62 digits, uppercase then lowercase letters (GMP ordering).
+pub const BASE62: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Bitcoin Base58 alphabet.
+pub const BASE58_BITCOIN: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Ripple Base58 alphabet.
+pub const BASE58_RIPPLE: &[u8; 58] = b"rpshnaf39wBUDNEGHJKLM4PQRST7VWXYZ2bcdeCg65jkm8oFqi1tuvAxyz";
+
+/// Flickr Base58 alphabet.
+pub const BASE58_FLICKR: &[u8; 58] = b"123456789abcdefghijkmnopqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ";
+
+/// RFC 4648 standard Base64 alphabet.
+pub const BASE64_STANDARD: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// RFC 4648 URL and filename safe Base64 alphabet.
+pub const BASE64_URL_SAFE: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Base64 alphabet in ASCII order, so encodings sort as their bytes do.
+pub const BASE64_ORDERED: &[u8; 64] =
+    b"-0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ_abcdefghijklmnopqrstuvwxyz";
+
+/// Every printable ASCII character except space, in ASCII order.
+pub const BASE94: &[u8; 94] = b"!\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~";
+
+/// Padding character used by the RFC 4648 encodings.
+const PAD: u8 = b'=';
+
+/// Builds a table mapping each byte to its position in `alphabet`, so decoders
+/// can look up a digit in O(1) rather than searching the alphabet.
+#[cfg(any(
+    feature = "base32",
+    feature = "base36",
+    feature = "base45",
+    feature = "base58",
+    feature = "base64",
+    feature = "base94"
+))]
+pub(crate) const fn reverse_lookup(alphabet: &[u8]) -> [Option<u8>; 256] {
+    let mut table = [None; 256];
+    let mut digit: u8 = 0;
+    while (digit as usize) < alphabet.len() {
+        table[alphabet[digit as usize] as usize] = Some(digit);
+        if digit == u8::MAX {
+            break;
+        }
+        digit += 1;
+    }
+    table
+}
+
+/// Removes ASCII whitespace, such as line breaks from wrapped output, borrowing
+/// `input` when there is none.
+#[cfg(any(feature = "base32", feature = "base64", feature = "hex"))]
+pub(crate) fn strip_whitespace(input: &str) -> Cow<'_, str> {
+    let input = input.trim();
+    if input.bytes().any(|b| b.is_ascii_whitespace()) {
+        Cow::Owned(input.split_ascii_whitespace().collect())
+    } else {
+        Cow::Borrowed(input)
+    }
+}
+
+/// Rewrites `input` from one alphabet to another of the same radix.
+///
+/// Each character is replaced by the character at the same position in
+/// `to_alphabet`, so this runs in O(n) without decoding. Because digit values
+/// are unchanged, the result is exactly what decoding with `from_alphabet` and
+/// re-encoding with `to_alphabet` would produce. `=` padding is copied through
+/// unless it is part of either alphabet.
+///
+/// # Arguments
+/// * `input` - The encoded text to translate
+/// * `from_alphabet` - The alphabet `input` is written in
+/// * `to_alphabet` - The alphabet to translate into
+///
+/// # Returns
+/// The translated text
+///
+/// # Errors
+/// Returns `SerialiseError` if the alphabets differ in length, are not ASCII,
+/// contain repeated characters, or `input` contains a character outside
+/// `from_alphabet`.
+pub fn transcode_alphabet(
+    input: &str,
+    from_alphabet: &[u8],
+    to_alphabet: &[u8],
+) -> Result<String, SerialiseError> {
+    if from_alphabet.len() != to_alphabet.len() {
+        return Err(SerialiseError::new(format!(
+            "cannot transcode between alphabets of {} and {} characters",
+            from_alphabet.len(),
+            to_alphabet.len()
+        )));
+    }
+    if !from_alphabet.is_ascii() || !to_alphabet.is_ascii() {
+        return Err(SerialiseError::new(
+            "alphabets must only contain ASCII characters".to_string(),
+        ));
+    }
+
+    let mut map: [Option<u8>; 128] = [None; 128];
+    let mut targets = [false; 128];
+    for (&from, &to) in from_alphabet.iter().zip(to_alphabet) {
+        if map[usize::from(from)].replace(to).is_some() {
+            return Err(SerialiseError::new(format!(
+                "source alphabet repeats the character '{}'",
+                char::from(from)
+            )));
+        }
+        if std::mem::replace(&mut targets[usize::from(to)], true) {
+            return Err(SerialiseError::new(format!(
+                "target alphabet repeats the character '{}'",
+                char::from(to)
+            )));
+        }
+    }
+    if map[usize::from(PAD)].is_none() && !targets[usize::from(PAD)] {
+        map[usize::from(PAD)] = Some(PAD);
+    }
+
+    input
+        .chars()
+        .map(|c| {
+            u8::try_from(c)
+                .ok()
+                .and_then(|b| map.get(usize::from(b)).copied().flatten())
+                .map(char::from)
+                .ok_or_else(|| {
+                    SerialiseError::new(format!("character '{c}' is not in the source alphabet"))
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_transcode_base64_to_url_safe() {
+        assert!(matches!(
+            transcode_alphabet("+/+/ab==", BASE64_STANDARD, BASE64_URL_SAFE),
+            Ok(s) if s == "-_-_ab=="
+        ));
+    }
+
+    #[test]
+    fn test_transcode_bitcoin_to_ripple_round_trips() {
+        let bitcoin = "1BoatSLRHtKNngkdXEeobR76b53LETtpyT";
+        let ripple = transcode_alphabet(bitcoin, BASE58_BITCOIN, BASE58_RIPPLE)
+            .unwrap_or_else(|_| String::new());
+        assert_eq!(ripple, "rBo2tSLRHtK48gkdXNeobRfabnsLNTtFyT");
+        assert!(matches!(
+            transcode_alphabet(&ripple, BASE58_RIPPLE, BASE58_BITCOIN),
+            Ok(s) if s == bitcoin
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "base58")]
+    fn test_reverse_lookup() {
+        let table = reverse_lookup(BASE58_BITCOIN);
+        for (i, &c) in BASE58_BITCOIN.iter().enumerate() {
+            assert_eq!(table[usize::from(c)].map(usize::from), Some(i));
+        }
+        assert_eq!(table.iter().flatten().count(), 58);
+        assert!(table[usize::from(b'0')].is_none());
+    }
+
+    #[test]
+    fn test_transcode_invalid_input_is_err() {
+        assert!(transcode_alphabet("0OIl", BASE58_BITCOIN, BASE58_RIPPLE).is_err());
+        assert!(transcode_alphabet("ab", BASE36, HEX).is_err());
+        assert!(transcode_alphabet("ab", b"aab", b"xyz").is_err());
+    }
+
+    #[test]
+    fn test_transcode_to_repeated_alphabet_is_err() {
+        assert!(matches!(
+            transcode_alphabet("ab", b"abc", b"xxz"),
+            Err(e) if e.get_message() == "target alphabet repeats the character 'x'"
+        ));
+        assert!(transcode_alphabet("a=", b"ab", b"x=").is_err());
+    }
+}
@@ -0,0 +1,230 @@
+use std::sync::Arc;
+use std::thread;
+
+use crate::{ByteVec, EncodedString, Encoding, SerialiseError};
+
+/// Applies `f` to every input, splitting the inputs across up to `threads`
+/// scoped threads and keeping the results in input order.
+fn map_parallel<T, R, F>(inputs: &[T], threads: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let chunk_size = inputs.len().div_ceil(threads.max(1)).max(1);
+    if chunk_size >= inputs.len() {
+        return inputs.iter().map(f).collect();
+    }
+
+    let f = &f;
+    thread::scope(|scope| {
+        let handles: Vec<_> = inputs
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter().map(f).collect::<Vec<R>>()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|payload| std::panic::resume_unwind(payload))
+            })
+            .collect()
+    })
+}
+
+/// Encodes one input exactly as [`ByteVec::try_encode`] does, so batches record
+/// the same integrity metadata as single calls.
+fn encode_one(bytes: &[u8], encoding: Encoding) -> Result<EncodedString, SerialiseError> {
+    ByteVec::new(Arc::new(bytes.to_vec())).try_encode(encoding)
+}
+
+fn decode_one(encoded: &str, encoding: Encoding) -> Result<Arc<ByteVec>, SerialiseError> {
+    EncodedString::new(encoding, encoded.to_string()).try_decode()
+}
+
+/// Encodes each input with the same encoding.
+///
+/// This is a convenience over a loop, not a faster path: each input costs what
+/// [`ByteVec::try_encode`] does, as the codecs have no per-call setup to share.
+/// Use [`encode_batch_parallel`] to save time on large batches.
+///
+/// # Arguments
+/// * `inputs` - The byte strings to encode
+/// * `encoding` - The encoding to use for all of them
+///
+/// # Returns
+/// One result per input, in input order. A failure does not stop the batch.
+#[must_use = "This returns the encoded strings but does nothing if unused"]
+pub fn encode_batch(
+    inputs: &[&[u8]],
+    encoding: Encoding,
+) -> Vec<Result<EncodedString, SerialiseError>> {
+    inputs
+        .iter()
+        .map(|bytes| encode_one(bytes, encoding))
+        .collect()
+}
+
+/// Encodes each input with the same encoding, spread across `threads` threads.
+///
+/// # Arguments
+/// * `inputs` - The byte strings to encode
+/// * `encoding` - The encoding to use for all of them
+/// * `threads` - The maximum number of threads to use
+///
+/// # Returns
+/// One result per input, in input order, as for [`encode_batch`].
+#[must_use = "This returns the encoded strings but does nothing if unused"]
+pub fn encode_batch_parallel(
+    inputs: &[&[u8]],
+    encoding: Encoding,
+    threads: usize,
+) -> Vec<Result<EncodedString, SerialiseError>> {
+    map_parallel(inputs, threads, |bytes| encode_one(bytes, encoding))
+}
+
+/// Decodes each input from the same encoding.
+///
+/// As with [`encode_batch`], each input costs what decoding one
+/// [`EncodedString`] does; use [`decode_batch_parallel`] to save time on large
+/// batches.
+///
+/// # Arguments
+/// * `inputs` - The encoded strings to decode
+/// * `encoding` - The encoding all of them are in
+///
+/// # Returns
+/// One result per input, in input order. A failure does not stop the batch.
+#[must_use = "This returns the decoded bytes but does nothing if unused"]
+pub fn decode_batch(
+    inputs: &[&str],
+    encoding: Encoding,
+) -> Vec<Result<Arc<ByteVec>, SerialiseError>> {
+    inputs
+        .iter()
+        .map(|encoded| decode_one(encoded, encoding))
+        .collect()
+}
+
+/// Decodes each input from the same encoding, spread across `threads` threads.
+///
+/// # Arguments
+/// * `inputs` - The encoded strings to decode
+/// * `encoding` - The encoding all of them are in
+/// * `threads` - The maximum number of threads to use
+///
+/// # Returns
+/// One result per input, in input order, as for [`decode_batch`].
+#[must_use = "This returns the decoded bytes but does nothing if unused"]
+pub fn decode_batch_parallel(
+    inputs: &[&str],
+    encoding: Encoding,
+    threads: usize,
+) -> Vec<Result<Arc<ByteVec>, SerialiseError>> {
+    map_parallel(inputs, threads, |encoded| decode_one(encoded, encoding))
+}
+
+/// Iterator adapter returned by [`EncodeIter::encoded`].
+#[derive(Debug, Clone)]
+pub struct Encoded<I> {
+    /// The byte strings still to encode
+    iter: I,
+    /// The encoding to use
+    encoding: Encoding,
+}
+
+impl<I> Iterator for Encoded<I>
+where
+    I: Iterator,
+    I::Item: AsRef<[u8]>,
+{
+    type Item = Result<EncodedString, SerialiseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next()
+            .map(|bytes| encode_one(bytes.as_ref(), self.encoding))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// Adds [`EncodeIter::encoded`] to iterators of byte strings.
+pub trait EncodeIter: Iterator + Sized
+where
+    Self::Item: AsRef<[u8]>,
+{
+    /// Lazily encodes each item with `encoding`.
+    ///
+    /// # Arguments
+    /// * `encoding` - The encoding to use for every item
+    ///
+    /// # Returns
+    /// An iterator of encode results, one per item
+    #[must_use = "iterator adapters are lazy and do nothing unless consumed"]
+    fn encoded(self, encoding: Encoding) -> Encoded<Self> {
+        Encoded {
+            iter: self,
+            encoding,
+        }
+    }
+}
+
+impl<I> EncodeIter for I
+where
+    I: Iterator,
+    I::Item: AsRef<[u8]>,
+{
+}
+
+#[cfg(test)]
+#[cfg(feature = "hex")]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_batch_round_trip() {
+        let inputs: [&[u8]; 3] = [b"", b"\x01", b"\xca\xfe"];
+        let encoded: Vec<String> = encode_batch(&inputs, Encoding::Hex)
+            .into_iter()
+            .map(|encoded| encoded.map(|e| e.get_string().clone()).unwrap_or_default())
+            .collect();
+        assert_eq!(encoded, ["", "01", "cafe"]);
+
+        let strs: Vec<&str> = encoded.iter().map(String::as_str).collect();
+        let decoded = decode_batch(&strs, Encoding::Hex);
+        assert!(
+            decoded
+                .iter()
+                .zip(inputs)
+                .all(|(d, i)| matches!(d, Ok(d) if d.get_bytes() == i))
+        );
+    }
+
+    #[test]
+    fn test_parallel_keeps_order_and_errors() {
+        let strs: Vec<String> = (0..100u8).map(|i| format!("{i:02x}")).collect();
+        let mut strs: Vec<&str> = strs.iter().map(String::as_str).collect();
+        strs[42] = "zz";
+        let decoded = decode_batch_parallel(&strs, Encoding::Hex, 4);
+        assert_eq!(decoded.len(), 100);
+        assert!(decoded[42].is_err());
+        assert!(matches!(&decoded[99], Ok(d) if d.get_bytes() == [99]));
+        assert_eq!(decoded.iter().filter(|d| d.is_ok()).count(), 99);
+    }
+
+    #[test]
+    fn test_encoded_adapter() {
+        let rows = [vec![0xde, 0xad], vec![0xbe, 0xef]];
+        let encoded: Vec<String> = rows
+            .iter()
+            .encoded(Encoding::Hex)
+            .map(|e| e.map(|e| e.get_string().clone()).unwrap_or_default())
+            .collect();
+        assert_eq!(encoded, ["dead", "beef"]);
+    }
+}
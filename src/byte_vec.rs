@@ -1,16 +1,52 @@
-use std::{fmt::Debug, sync::Arc};
+use std::{
+    fmt::Debug,
+    hint::black_box,
+    ops::{Bound, RangeBounds},
+    sync::Arc,
+};
 
 use crate::{
-    Base36, EncodedString, Encoder, Encoding, SerialiseError,
-    algorithm::{Base58, Base64, Hex, Uuencode},
+    ByteDiff, ByteReader, EncodeBuilder, EncodedString, Encoder, Encoding, Renderings,
+    SerialiseError, instrument,
 };
 
+#[cfg(feature = "base32")]
+use crate::algorithm::Base32;
+#[cfg(feature = "base36")]
+use crate::algorithm::Base36;
+#[cfg(feature = "base45")]
+use crate::algorithm::Base45;
+#[cfg(feature = "base58")]
+use crate::algorithm::Base58;
+#[cfg(feature = "base62")]
+use crate::algorithm::Base62;
+#[cfg(feature = "base64")]
+use crate::algorithm::Base64;
+#[cfg(feature = "base94")]
+use crate::algorithm::Base94;
+#[cfg(feature = "binhex")]
+use crate::algorithm::BinHex;
+#[cfg(feature = "escaped")]
+use crate::algorithm::Escaped;
+#[cfg(feature = "filename-safe")]
+use crate::algorithm::FilenameSafe;
+#[cfg(feature = "hex")]
+use crate::algorithm::Hex;
+#[cfg(feature = "intel-hex")]
+use crate::algorithm::IntelHex;
+#[cfg(feature = "line-dump")]
+use crate::algorithm::LineDump;
+#[cfg(feature = "srec")]
+use crate::algorithm::Srec;
+#[cfg(feature = "uuencode")]
+use crate::algorithm::Uuencode;
+
 /// Raw byte representation of serializable data.
 ///
 /// This type represents the raw bytes of a serializable structure along with
 /// its type information. It serves as an intermediate format between the
 /// original data and its string representation.
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ByteVec {
     bytes: Arc<Vec<u8>>,
 }
@@ -31,8 +67,165 @@ impl ByteVec {
         &self.bytes
     }
 
+    /// Returns the bytes, copying them only if they are shared.
+    #[must_use = "This returns the byte data but does nothing if unused"]
+    pub fn into_vec(self) -> Vec<u8> {
+        Arc::unwrap_or_clone(self.bytes)
+    }
+
+    /// Compares with `other` in time that depends only on the lengths, not on
+    /// where the bytes first differ.
+    ///
+    /// Use this instead of `==` to check secrets such as API keys, where an
+    /// early exit would let an attacker find the value byte by byte from
+    /// response times. Lengths are not hidden.
+    ///
+    /// # Arguments
+    /// * `other` - The bytes to compare with
+    ///
+    /// # Returns
+    /// `true` if both hold the same bytes
+    #[must_use = "This returns whether the bytes are equal but does nothing if unused"]
+    pub fn ct_eq(&self, other: &Self) -> bool {
+        ct_eq(&self.bytes, &other.bytes)
+    }
+
+    /// Returns the number of bytes.
+    #[must_use = "This returns the length but does nothing if unused"]
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns true if there are no bytes.
+    #[must_use = "This returns whether the bytes are empty but does nothing if unused"]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Copies a range of the bytes into a new `ByteVec`.
+    ///
+    /// # Arguments
+    /// * `range` - The byte offsets to copy, e.g. `4..` or `..len`
+    ///
+    /// # Returns
+    /// The bytes in `range`
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `range` is out of bounds or ends before it starts.
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> Result<Self, SerialiseError> {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.saturating_add(1),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.bytes.len(),
+        };
+        self.bytes.get(start..end).map_or_else(
+            || {
+                Err(SerialiseError::new(format!(
+                    "range {start}..{end} is out of bounds for {} bytes",
+                    self.bytes.len()
+                )))
+            },
+            |bytes| Ok(Self::new(Arc::new(bytes.to_vec()))),
+        )
+    }
+
+    /// Splits the bytes in two at `mid`, e.g. into a header and a body.
+    ///
+    /// # Arguments
+    /// * `mid` - The offset of the first byte of the second part
+    ///
+    /// # Returns
+    /// The bytes before `mid` and the bytes from `mid` on
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `mid` is past the end of the bytes.
+    pub fn split_at(&self, mid: usize) -> Result<(Self, Self), SerialiseError> {
+        Ok((self.slice(..mid)?, self.slice(mid..)?))
+    }
+
+    /// Joins `parts` into one `ByteVec`, in order.
+    ///
+    /// # Arguments
+    /// * `parts` - The parts to join, e.g. a header and a body
+    ///
+    /// # Returns
+    /// The concatenated bytes
+    #[must_use = "This returns the concatenated bytes but does nothing if unused"]
+    pub fn concat(parts: &[Self]) -> Self {
+        Self::new(Arc::new(
+            parts.iter().flat_map(Self::get_bytes).copied().collect(),
+        ))
+    }
+
+    /// Decodes `encoded` and appends the bytes.
+    ///
+    /// The bytes are only copied if this `ByteVec` shares them with another.
+    ///
+    /// # Arguments
+    /// * `encoded` - The encoded bytes to append
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `encoded` does not decode, in which case
+    /// nothing is appended.
+    pub fn extend_from_encoded(&mut self, encoded: &EncodedString) -> Result<(), SerialiseError> {
+        let decoded = encoded.try_decode()?;
+        Arc::make_mut(&mut self.bytes).extend_from_slice(decoded.get_bytes());
+        Ok(())
+    }
+
+    /// Compares with `other` offset by offset, for readable test failures on
+    /// binary payloads.
+    ///
+    /// # Arguments
+    /// * `other` - The bytes to compare with, shown on the right
+    ///
+    /// # Returns
+    /// The differences, which display as an annotated hexdump
+    #[must_use = "This returns the diff but does nothing if unused"]
+    pub fn diff<'a>(&'a self, other: &'a Self) -> ByteDiff<'a> {
+        crate::diff::diff(&self.bytes, &other.bytes)
+    }
+
+    /// Writes the bytes in hex, Base64, Base58, Base36 and escaped ASCII at
+    /// once, as debugging usually needs several of them.
+    ///
+    /// Encodings whose features are disabled are left out.
+    ///
+    /// # Returns
+    /// The renderings, which display as one aligned line per encoding
+    #[must_use = "This returns the renderings but does nothing if unused"]
+    pub fn render_all(&self) -> Renderings {
+        Renderings::new(self)
+    }
+
+    /// Returns a cursor for reading integers and byte runs out of the bytes.
+    #[must_use = "This creates a reader but does nothing if unused"]
+    pub fn reader(&self) -> ByteReader<'_> {
+        ByteReader::new(&self.bytes)
+    }
+
+    /// Starts an encode with options, e.g. line wrapping or uppercase output.
+    ///
+    /// # Arguments
+    /// * `encoding` - The encoding to use
+    ///
+    /// # Returns
+    /// A builder; call [`EncodeBuilder::finish`] to encode
+    #[must_use = "This creates a builder but does nothing until finished"]
+    pub const fn encode_with(&self, encoding: Encoding) -> EncodeBuilder<'_> {
+        EncodeBuilder::new(self, encoding)
+    }
+
     /// Encodes this type using the specified `Encoding`.
     ///
+    /// The result records the length and checksum of the bytes so decoding can
+    /// detect a truncated or corrupted copy.
+    ///
     /// # Parameters
     /// * `value` - The value to encode
     /// * `encoding` - The encoding to use when encoding this type.
@@ -44,40 +237,103 @@ impl ByteVec {
     /// * `SerialiseError` - If the specified encoding is unsupported or an error occurs during serialisation.
     #[must_use = "The result of this function is a `Result` containing the encoded string if successful, or a `SerialiseError` if an error occurs."]
     pub fn try_encode(&self, encoding: Encoding) -> Result<EncodedString, SerialiseError> {
-        match encoding {
+        let encoded = instrument::record("encode", encoding, self.bytes.len(), || match encoding {
+            #[cfg(feature = "base36")]
             Encoding::Base36 => match Base36::try_encode(Arc::clone(&self.bytes)) {
                 Ok(encoded) => Ok(encoded),
                 Err(error) => Err(error),
             },
+            #[cfg(feature = "base58")]
             Encoding::Base58 => match Base58::try_encode(Arc::clone(&self.bytes)) {
                 Ok(encoded) => Ok(encoded),
                 Err(error) => Err(error),
             },
+            #[cfg(feature = "base64")]
             Encoding::Base64 => match Base64::try_encode(Arc::clone(&self.bytes)) {
                 Ok(encoded) => Ok(encoded),
                 Err(error) => Err(error),
             },
+            #[cfg(feature = "hex")]
             Encoding::Hex => match Hex::try_encode(Arc::clone(&self.bytes)) {
                 Ok(encoded) => Ok(encoded),
                 Err(error) => Err(error),
             },
+            #[cfg(feature = "uuencode")]
             Encoding::Uuencode => match Uuencode::try_encode(Arc::clone(&self.bytes)) {
                 Ok(encoded) => Ok(encoded),
                 Err(error) => Err(error),
             },
-        }
+            #[cfg(feature = "intel-hex")]
+            Encoding::IntelHex => match IntelHex::try_encode(Arc::clone(&self.bytes)) {
+                Ok(encoded) => Ok(encoded),
+                Err(error) => Err(error),
+            },
+            #[cfg(feature = "srec")]
+            Encoding::Srec => match Srec::try_encode(Arc::clone(&self.bytes)) {
+                Ok(encoded) => Ok(encoded),
+                Err(error) => Err(error),
+            },
+            #[cfg(feature = "binhex")]
+            Encoding::BinHex => match BinHex::try_encode(Arc::clone(&self.bytes)) {
+                Ok(encoded) => Ok(encoded),
+                Err(error) => Err(error),
+            },
+            #[cfg(feature = "base32")]
+            Encoding::Base32 => match Base32::try_encode(Arc::clone(&self.bytes)) {
+                Ok(encoded) => Ok(encoded),
+                Err(error) => Err(error),
+            },
+            #[cfg(feature = "base62")]
+            Encoding::Base62 => match Base62::try_encode(Arc::clone(&self.bytes)) {
+                Ok(encoded) => Ok(encoded),
+                Err(error) => Err(error),
+            },
+            #[cfg(feature = "base45")]
+            Encoding::Base45 => match Base45::try_encode(Arc::clone(&self.bytes)) {
+                Ok(encoded) => Ok(encoded),
+                Err(error) => Err(error),
+            },
+            #[cfg(feature = "line-dump")]
+            Encoding::LineDump => match LineDump::try_encode(Arc::clone(&self.bytes)) {
+                Ok(encoded) => Ok(encoded),
+                Err(error) => Err(error),
+            },
+            #[cfg(feature = "filename-safe")]
+            Encoding::FilenameSafe => match FilenameSafe::try_encode(Arc::clone(&self.bytes)) {
+                Ok(encoded) => Ok(encoded),
+                Err(error) => Err(error),
+            },
+            #[cfg(feature = "escaped")]
+            Encoding::Escaped => match Escaped::try_encode(Arc::clone(&self.bytes)) {
+                Ok(encoded) => Ok(encoded),
+                Err(error) => Err(error),
+            },
+            #[cfg(feature = "base94")]
+            Encoding::Base94 => match Base94::try_encode(Arc::clone(&self.bytes)) {
+                Ok(encoded) => Ok(encoded),
+                Err(error) => Err(error),
+            },
+        });
+        encoded.map(|encoded| encoded.with_integrity(&self.bytes))
     }
 }
 
 impl Debug for ByteVec {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let bytes_as_string = self.try_encode(Encoding::Base58).map_or_else(
-            |_| "<base58 encoding failed>".to_string(),
-            |encoded| encoded.get_string().clone(),
-        );
-
         let mut debug = f.debug_struct("ByteVec");
-        debug.field("bytes", &bytes_as_string);
+
+        #[cfg(feature = "base58")]
+        {
+            let bytes_as_string = self.try_encode(Encoding::Base58).map_or_else(
+                |_| "<base58 encoding failed>".to_string(),
+                |encoded| encoded.get_string().clone(),
+            );
+            debug.field("bytes", &bytes_as_string);
+        }
+
+        #[cfg(not(feature = "base58"))]
+        debug.field("bytes", &self.bytes);
+
         debug.finish()
     }
 }
@@ -141,11 +397,71 @@ where
     }
 }
 
+/// Compares two byte slices without exiting at the first difference.
+///
+/// `black_box` keeps the compiler from turning the loop back into an early exit.
+pub(crate) fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a
+        .iter()
+        .zip(b)
+        .fold(0u8, |diff, (x, y)| black_box(diff | (x ^ y)));
+    black_box(diff) == 0
+}
+
 #[cfg(test)]
+#[cfg(any(
+    feature = "base36",
+    feature = "base58",
+    feature = "base64",
+    feature = "hex",
+    feature = "uuencode"
+))]
 mod tests {
     use super::*;
 
     #[test]
+    fn test_slice_and_split() {
+        let bytes = ByteVec::new(Arc::new(b"headbody".to_vec()));
+        assert!(matches!(bytes.slice(4..), Ok(body) if body.get_bytes() == b"body"));
+        assert!(matches!(bytes.slice(1..=2), Ok(part) if part.get_bytes() == b"ea"));
+        assert!(matches!(
+            bytes.slice(6..9),
+            Err(e) if e.get_message() == "range 6..9 is out of bounds for 8 bytes"
+        ));
+        let (start, end) = (5, 4);
+        assert!(bytes.slice(start..end).is_err());
+        assert!(matches!(
+            bytes.split_at(4),
+            Ok((head, body)) if head.get_bytes() == b"head" && body.get_bytes() == b"body"
+        ));
+        assert!(bytes.split_at(9).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "hex")]
+    fn test_concat_and_extend() {
+        let head = ByteVec::new(Arc::new(vec![0x01]));
+        let body = ByteVec::new(Arc::new(vec![0x02, 0x03]));
+        let mut joined = ByteVec::concat(&[head.clone(), body]);
+        assert_eq!(joined.get_bytes(), [0x01, 0x02, 0x03]);
+        assert!(ByteVec::concat(&[]).is_empty());
+
+        let shared = joined.clone();
+        let trailer = EncodedString::new(Encoding::Hex, "0405".to_string());
+        assert!(joined.extend_from_encoded(&trailer).is_ok());
+        assert_eq!(joined.len(), 5);
+        assert_eq!(shared.len(), 3);
+        let bad = EncodedString::new(Encoding::Hex, "zz".to_string());
+        assert!(joined.extend_from_encoded(&bad).is_err());
+        assert_eq!(joined.len(), 5);
+        assert_eq!(head.len(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "base36")]
     fn test_encodable_encoding_base36() {
         struct Test {
             bytes: Arc<Vec<u8>>,
@@ -181,6 +497,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "base58")]
     fn test_encodable_encoding_base58() {
         struct Test {
             bytes: Arc<Vec<u8>>,
@@ -216,6 +533,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "base64")]
     fn test_encodable_encoding_base64() {
         struct Test {
             bytes: Arc<Vec<u8>>,
@@ -251,6 +569,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "hex")]
     fn test_encodable_encoding_hex() {
         struct Test {
             bytes: Arc<Vec<u8>>,
@@ -286,6 +605,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "uuencode")]
     fn test_encodable_encoding_uuencode() {
         struct Test {
             bytes: Arc<Vec<u8>>,
@@ -319,4 +639,13 @@ mod tests {
             "D,#$R,S0U-C<X.6%B8V1E9F=H:6IK;&UN;W!Q<G-T=79W>'EZ\n`\n"
         );
     }
+
+    #[test]
+    fn test_ct_eq() {
+        let key = ByteVec::new(Arc::new(b"secret".to_vec()));
+        assert!(key.ct_eq(&ByteVec::new(Arc::new(b"secret".to_vec()))));
+        assert!(!key.ct_eq(&ByteVec::new(Arc::new(b"secreT".to_vec()))));
+        assert!(!key.ct_eq(&ByteVec::new(Arc::new(b"secrets".to_vec()))));
+        assert!(ct_eq(b"", b""));
+    }
 }
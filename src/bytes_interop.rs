@@ -0,0 +1,121 @@
+use std::sync::Arc;
+
+use bytes::{Bytes, BytesMut};
+
+use crate::{ByteVec, EncodedString, Encoding, SerialiseError};
+
+impl From<Bytes> for ByteVec {
+    /// Takes over the buffer of `bytes` without copying if nothing else shares
+    /// it and it was built from a `Vec`.
+    fn from(bytes: Bytes) -> Self {
+        Self::new(Arc::new(Vec::from(bytes)))
+    }
+}
+
+impl From<BytesMut> for ByteVec {
+    /// Takes over the buffer of `bytes` without copying if nothing else shares it.
+    fn from(bytes: BytesMut) -> Self {
+        Self::new(Arc::new(Vec::from(bytes)))
+    }
+}
+
+impl From<ByteVec> for Bytes {
+    /// Takes over the buffer of `byte_vec` without copying if nothing else shares it.
+    fn from(byte_vec: ByteVec) -> Self {
+        Self::from(byte_vec.into_vec())
+    }
+}
+
+/// Encodes the contents of a `Bytes` buffer.
+///
+/// The buffer is handed to the encoder without copying if nothing else shares
+/// it, as for a frozen `BytesMut`.
+///
+/// # Arguments
+/// * `bytes` - The bytes to encode
+/// * `encoding` - The encoding to use
+///
+/// # Returns
+/// The encoded string
+///
+/// # Errors
+/// Returns `SerialiseError` if encoding fails.
+pub fn encode(bytes: Bytes, encoding: Encoding) -> Result<EncodedString, SerialiseError> {
+    ByteVec::from(bytes).try_encode(encoding)
+}
+
+/// Decodes an encoded string into a `Bytes` buffer, without copying the
+/// decoded bytes again.
+///
+/// # Arguments
+/// * `encoded` - The encoded string
+///
+/// # Returns
+/// The decoded bytes
+///
+/// # Errors
+/// Returns `SerialiseError` if `encoded` does not decode.
+pub fn decode(encoded: &EncodedString) -> Result<Bytes, SerialiseError> {
+    let decoded = encoded.try_decode()?;
+    Ok(Bytes::from(Arc::unwrap_or_clone(decoded)))
+}
+
+/// Decodes an encoded string into a `BytesMut` buffer, ready to be parsed or
+/// extended in place.
+///
+/// # Arguments
+/// * `encoded` - The encoded string
+///
+/// # Returns
+/// The decoded bytes
+///
+/// # Errors
+/// Returns `SerialiseError` if `encoded` does not decode.
+pub fn decode_mut(encoded: &EncodedString) -> Result<BytesMut, SerialiseError> {
+    decode(encoded).map(|bytes| {
+        bytes
+            .try_into_mut()
+            .unwrap_or_else(|shared| BytesMut::from(&shared[..]))
+    })
+}
+
+#[cfg(test)]
+#[cfg(feature = "base64")]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let encoded = encode(Bytes::from_static(b"hello"), Encoding::Base64);
+        assert!(matches!(&encoded, Ok(e) if e.get_string() == "aGVsbG8="));
+        assert!(matches!(
+            encoded.and_then(|encoded| decode(&encoded)),
+            Ok(bytes) if bytes == "hello"
+        ));
+    }
+
+    #[test]
+    fn test_conversions_keep_the_buffer() {
+        let mut buf = BytesMut::with_capacity(64);
+        buf.extend_from_slice(b"payload");
+        let ptr = buf.as_ptr();
+        let byte_vec = ByteVec::from(buf.freeze());
+        assert_eq!(byte_vec.get_bytes().as_ptr(), ptr);
+        let bytes = Bytes::from(byte_vec);
+        assert_eq!(bytes.as_ptr(), ptr);
+        assert_eq!(bytes, "payload");
+    }
+
+    #[test]
+    fn test_decode_mut() {
+        let encoded = EncodedString::new(Encoding::Base64, "aGk=".to_string());
+        let decoded = decode_mut(&encoded);
+        assert!(matches!(&decoded, Ok(bytes) if bytes == "hi"));
+        let mut decoded = decoded.unwrap_or_default();
+        decoded.extend_from_slice(b"!");
+        assert_eq!(decoded, "hi!");
+        let invalid = EncodedString::new(Encoding::Base64, "a".to_string());
+        assert!(decode_mut(&invalid).is_err());
+    }
+}
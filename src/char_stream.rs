@@ -0,0 +1,250 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use crate::{ByteVec, EncodedString, Encoding, SerialiseError};
+#[cfg(any(feature = "base32", feature = "base64", feature = "hex"))]
+use crate::{Decoder, decoder::BitDecoder};
+
+/// Returns an incremental decoder for `encoding`, or `None` if it has to be
+/// decoded in one piece.
+#[cfg(any(feature = "base32", feature = "base64", feature = "hex"))]
+pub(crate) fn streaming_decoder(encoding: Encoding) -> Option<BitDecoder> {
+    match encoding {
+        #[cfg(feature = "hex")]
+        Encoding::Hex => Some(crate::HexDecoder::new().inner),
+        #[cfg(feature = "base32")]
+        Encoding::Base32 => Some(crate::Base32Decoder::new().inner),
+        #[cfg(feature = "base64")]
+        Encoding::Base64 => Some(crate::Base64Decoder::new().inner),
+        #[allow(unreachable_patterns)]
+        _ => None,
+    }
+}
+
+/// Returns the number of bytes that encode to a whole number of characters
+/// with no padding, or `None` if `encoding` has to be encoded in one piece.
+pub(crate) const fn block_len(encoding: Encoding) -> Option<usize> {
+    match encoding {
+        #[cfg(feature = "hex")]
+        Encoding::Hex => Some(1),
+        #[cfg(feature = "base32")]
+        Encoding::Base32 => Some(5),
+        #[cfg(feature = "base64")]
+        Encoding::Base64 => Some(3),
+        #[allow(unreachable_patterns)]
+        _ => None,
+    }
+}
+
+/// Iterator adapter returned by [`DecodeChars::chars_to_bytes`].
+#[derive(Debug)]
+pub struct CharsToBytes<I> {
+    /// The encoded characters still to decode
+    iter: I,
+    /// The encoding of the characters
+    encoding: Encoding,
+    /// Decoder for encodings that can be decoded a character at a time
+    #[cfg(any(feature = "base32", feature = "base64", feature = "hex"))]
+    decoder: Option<BitDecoder>,
+    /// Bytes decoded but not yet returned
+    pending: VecDeque<u8>,
+    /// True once the input is used up or an error has been returned
+    done: bool,
+}
+
+impl<I: Iterator<Item = char>> CharsToBytes<I> {
+    /// Pulls input until some bytes are decoded, the input ends or it fails.
+    fn fill(&mut self) -> Result<(), SerialiseError> {
+        #[cfg(any(feature = "base32", feature = "base64", feature = "hex"))]
+        if let Some(decoder) = &mut self.decoder {
+            let mut buf = [0u8; 4];
+            while self.pending.is_empty() {
+                let Some(c) = self.iter.next() else {
+                    self.done = true;
+                    self.pending.extend(decoder.finish()?);
+                    break;
+                };
+                self.pending
+                    .extend(decoder.feed(c.encode_utf8(&mut buf).as_bytes())?);
+            }
+            return Ok(());
+        }
+
+        self.done = true;
+        let encoded: String = self.iter.by_ref().collect();
+        let decoded = EncodedString::new(self.encoding, encoded).try_decode()?;
+        self.pending.extend(decoded.get_bytes());
+        Ok(())
+    }
+}
+
+impl<I: Iterator<Item = char>> Iterator for CharsToBytes<I> {
+    type Item = Result<u8, SerialiseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pending.is_empty()
+            && !self.done
+            && let Err(e) = self.fill()
+        {
+            self.done = true;
+            self.pending.clear();
+            return Some(Err(e));
+        }
+        self.pending.pop_front().map(Ok)
+    }
+}
+
+/// Iterator adapter returned by [`EncodeBytes::bytes_to_chars`].
+#[derive(Debug, Clone)]
+pub struct BytesToChars<I> {
+    /// The bytes still to encode
+    iter: I,
+    /// The encoding to use
+    encoding: Encoding,
+    /// Characters encoded but not yet returned
+    pending: VecDeque<char>,
+    /// True once the input is used up or an error has been returned
+    done: bool,
+}
+
+impl<I: Iterator<Item = u8>> BytesToChars<I> {
+    /// Encodes the next block of input, or all of it for encodings that cannot
+    /// be encoded a block at a time.
+    fn fill(&mut self) -> Result<(), SerialiseError> {
+        let bytes: Vec<u8> = match block_len(self.encoding) {
+            Some(len) => self.iter.by_ref().take(len).collect(),
+            None => self.iter.by_ref().collect(),
+        };
+        if block_len(self.encoding).is_none_or(|len| bytes.len() < len) {
+            self.done = true;
+        }
+        let encoded = ByteVec::new(Arc::new(bytes)).try_encode(self.encoding)?;
+        self.pending.extend(encoded.get_string().chars());
+        Ok(())
+    }
+}
+
+impl<I: Iterator<Item = u8>> Iterator for BytesToChars<I> {
+    type Item = Result<char, SerialiseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() && !self.done {
+            if let Err(e) = self.fill() {
+                self.done = true;
+                return Some(Err(e));
+            }
+        }
+        self.pending.pop_front().map(Ok)
+    }
+}
+
+/// Adds [`DecodeChars::chars_to_bytes`] to iterators of characters, such as
+/// the input of a tokenizer or parser combinator.
+pub trait DecodeChars: Iterator<Item = char> + Sized {
+    /// Lazily decodes the characters from `encoding`.
+    ///
+    /// Hex, Base32 and Base64 are decoded as characters arrive, skipping
+    /// whitespace. Other encodings read the whole input before the first byte
+    /// is returned. To decode a field in the middle of a larger stream, adapt
+    /// only the field, e.g. with `by_ref().take_while(..)`.
+    ///
+    /// # Arguments
+    /// * `encoding` - The encoding of the characters
+    ///
+    /// # Returns
+    /// An iterator of decoded bytes, which ends after the first error
+    #[must_use = "iterator adapters are lazy and do nothing unless consumed"]
+    fn chars_to_bytes(self, encoding: Encoding) -> CharsToBytes<Self> {
+        CharsToBytes {
+            iter: self,
+            encoding,
+            #[cfg(any(feature = "base32", feature = "base64", feature = "hex"))]
+            decoder: streaming_decoder(encoding),
+            pending: VecDeque::new(),
+            done: false,
+        }
+    }
+}
+
+impl<I: Iterator<Item = char>> DecodeChars for I {}
+
+/// Adds [`EncodeBytes::bytes_to_chars`] to iterators of bytes.
+pub trait EncodeBytes: Iterator<Item = u8> + Sized {
+    /// Lazily encodes the bytes with `encoding`.
+    ///
+    /// Hex, Base32 and Base64 are encoded a block at a time, giving the same
+    /// characters as encoding all of the bytes at once. Other encodings read the
+    /// whole input before the first character is returned.
+    ///
+    /// # Arguments
+    /// * `encoding` - The encoding to use
+    ///
+    /// # Returns
+    /// An iterator of encoded characters, which ends after the first error
+    #[must_use = "iterator adapters are lazy and do nothing unless consumed"]
+    fn bytes_to_chars(self, encoding: Encoding) -> BytesToChars<Self> {
+        BytesToChars {
+            iter: self,
+            encoding,
+            pending: VecDeque::new(),
+            done: false,
+        }
+    }
+}
+
+impl<I: Iterator<Item = u8>> EncodeBytes for I {}
+
+#[cfg(test)]
+#[cfg(all(
+    feature = "base32",
+    feature = "base58",
+    feature = "base64",
+    feature = "hex"
+))]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let bytes: Vec<u8> = (1..=40).collect();
+        for encoding in [
+            Encoding::Hex,
+            Encoding::Base32,
+            Encoding::Base64,
+            Encoding::Base58,
+        ] {
+            let expected = ByteVec::new(Arc::new(bytes.clone()))
+                .try_encode(encoding)
+                .map(|e| e.get_string().clone())
+                .unwrap_or_default();
+            let encoded: Result<String, _> =
+                bytes.iter().copied().bytes_to_chars(encoding).collect();
+            assert!(matches!(&encoded, Ok(s) if *s == expected), "{encoding}");
+            let decoded: Result<Vec<u8>, _> = expected.chars().chars_to_bytes(encoding).collect();
+            assert!(matches!(decoded, Ok(d) if d == bytes), "{encoding}");
+        }
+    }
+
+    #[test]
+    fn test_field_mid_stream() {
+        let mut input = "key=Zm9vYmFy; rest".chars();
+        let key: String = input.by_ref().take_while(|&c| c != '=').collect();
+        let value: Result<Vec<u8>, _> = input
+            .by_ref()
+            .take_while(|&c| c != ';')
+            .chars_to_bytes(Encoding::Base64)
+            .collect();
+        assert_eq!(key, "key");
+        assert!(matches!(value, Ok(v) if v == b"foobar"));
+        assert_eq!(input.collect::<String>(), " rest");
+    }
+
+    #[test]
+    fn test_decode_is_lazy_and_stops_at_error() {
+        let mut bytes = "cafe!!".chars().chars_to_bytes(Encoding::Hex);
+        assert!(matches!(bytes.next(), Some(Ok(0xca))));
+        assert!(matches!(bytes.next(), Some(Ok(0xfe))));
+        assert!(matches!(bytes.next(), Some(Err(_))));
+        assert!(bytes.next().is_none());
+    }
+}
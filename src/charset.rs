@@ -0,0 +1,307 @@
+use std::{ops::RangeInclusive, sync::Arc};
+
+use crate::{ByteVec, Encoding};
+
+#[cfg(any(
+    feature = "base32",
+    feature = "base36",
+    feature = "base45",
+    feature = "base58",
+    feature = "base62",
+    feature = "base64",
+    feature = "hex"
+))]
+use crate::alphabet;
+
+/// Number of sample bytes encoded to compare the density of encodings.
+const SAMPLE_LEN: usize = 1024;
+
+/// Character restrictions of a channel encoded text is sent through.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CharsetPolicy {
+    /// A DNS label: letters, digits and `-`, where case is not preserved
+    DnsLabel,
+    /// The POSIX portable filename character set: letters, digits, `.`, `_` and `-`
+    FilenameSafe,
+    /// Characters a POSIX shell passes through without quoting
+    ShellSafe,
+    /// Characters that need no escaping inside a JSON string
+    JsonString,
+    /// The QR code alphanumeric set: digits, uppercase letters and ` $%*+-./:`
+    QrAlphanumeric,
+}
+
+/// A set of ASCII bytes a channel allows, such as the characters legal in a
+/// tenant's transport.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct CharSet {
+    /// One bit per byte value, set if the byte is allowed
+    bits: [u64; 4],
+    /// Whether the channel may change the case of letters
+    folds_case: bool,
+}
+
+impl CharSet {
+    /// Creates a set of the characters in `chars`; non-ASCII characters are
+    /// ignored, as no encoding writes them.
+    #[must_use = "This returns the character set but does nothing if unused"]
+    pub fn new(chars: &str) -> Self {
+        chars
+            .bytes()
+            .filter(u8::is_ascii)
+            .fold(Self::default(), Self::with)
+    }
+
+    /// Adds a byte to the set.
+    #[must_use = "This returns the updated character set but does nothing if unused"]
+    pub const fn with(mut self, c: u8) -> Self {
+        self.bits[(c / 64) as usize] |= 1 << (c % 64);
+        self
+    }
+
+    /// Adds every byte in `range` to the set, e.g. `b' '..=b'~'`.
+    #[must_use = "This returns the updated character set but does nothing if unused"]
+    pub fn with_range(self, range: RangeInclusive<u8>) -> Self {
+        range.fold(self, Self::with)
+    }
+
+    /// Removes the characters in `chars` from the set.
+    #[must_use = "This returns the updated character set but does nothing if unused"]
+    pub fn without(mut self, chars: &str) -> Self {
+        for c in chars.bytes() {
+            self.bits[usize::from(c / 64)] &= !(1 << (c % 64));
+        }
+        self
+    }
+
+    /// Marks the channel as one that may change the case of letters, so only
+    /// case-insensitive encodings fit it.
+    #[must_use = "This returns the updated character set but does nothing if unused"]
+    pub const fn case_folding(mut self) -> Self {
+        self.folds_case = true;
+        self
+    }
+
+    /// Returns true if `c` is in the set.
+    #[must_use = "This returns whether the byte is allowed but does nothing if unused"]
+    pub const fn contains(&self, c: u8) -> bool {
+        self.bits[(c / 64) as usize] & (1 << (c % 64)) != 0
+    }
+
+    /// Returns true if the channel may change the case of letters.
+    #[must_use = "This returns whether the channel folds case but does nothing if unused"]
+    pub const fn folds_case(&self) -> bool {
+        self.folds_case
+    }
+}
+
+impl From<CharsetPolicy> for CharSet {
+    fn from(policy: CharsetPolicy) -> Self {
+        let alphanumeric = Self::default()
+            .with_range(b'0'..=b'9')
+            .with_range(b'A'..=b'Z')
+            .with_range(b'a'..=b'z');
+        match policy {
+            CharsetPolicy::DnsLabel => alphanumeric.with(b'-').case_folding(),
+            CharsetPolicy::FilenameSafe => alphanumeric.with(b'.').with(b'_').with(b'-'),
+            CharsetPolicy::ShellSafe => Self::new("%+,-./:=@_")
+                .with_range(b'0'..=b'9')
+                .with_range(b'A'..=b'Z')
+                .with_range(b'a'..=b'z'),
+            CharsetPolicy::JsonString => Self::default().with_range(b' '..=b'~').without("\"\\"),
+            CharsetPolicy::QrAlphanumeric => Self::new(" $%*+-./:")
+                .with_range(b'0'..=b'9')
+                .with_range(b'A'..=b'Z'),
+        }
+    }
+}
+
+/// Returns true if the encoder for `encoding` can write `c`, including padding,
+/// separators and line breaks.
+fn may_write(encoding: Encoding, c: u8) -> bool {
+    match encoding {
+        #[cfg(feature = "base36")]
+        Encoding::Base36 => alphabet::BASE36.contains(&c),
+        #[cfg(feature = "base58")]
+        Encoding::Base58 => alphabet::BASE58_BITCOIN.contains(&c),
+        #[cfg(feature = "base64")]
+        Encoding::Base64 => alphabet::BASE64_STANDARD.contains(&c) || c == b'=',
+        #[cfg(feature = "uuencode")]
+        Encoding::Uuencode => matches!(c, b' '..=b'`' | b'\n'),
+        #[cfg(feature = "hex")]
+        Encoding::Hex => alphabet::HEX.contains(&c),
+        #[cfg(feature = "intel-hex")]
+        Encoding::IntelHex => matches!(c, b':' | b'0'..=b'9' | b'A'..=b'F' | b'\n'),
+        #[cfg(feature = "srec")]
+        Encoding::Srec => matches!(c, b'S' | b'0'..=b'9' | b'A'..=b'F' | b'\n'),
+        #[cfg(feature = "binhex")]
+        Encoding::BinHex => matches!(c, b' '..=b'~' | b'\n'),
+        #[cfg(feature = "base32")]
+        Encoding::Base32 => alphabet::BASE32_STANDARD.contains(&c) || c == b'=',
+        #[cfg(feature = "base62")]
+        Encoding::Base62 => alphabet::BASE62.contains(&c),
+        #[cfg(feature = "base45")]
+        Encoding::Base45 => alphabet::BASE45.contains(&c),
+        #[cfg(feature = "line-dump")]
+        Encoding::LineDump => {
+            alphabet::BASE64_STANDARD.contains(&c) || matches!(c, b'=' | b' ' | b'\n')
+        }
+        #[cfg(feature = "filename-safe")]
+        Encoding::FilenameSafe => matches!(c, b'a'..=b'z' | b'2'..=b'7' | b'_'),
+        #[cfg(feature = "escaped")]
+        Encoding::Escaped => matches!(c, b' '..=b'~'),
+        #[cfg(feature = "base94")]
+        Encoding::Base94 => c.is_ascii_graphic(),
+    }
+}
+
+/// Returns true if everything `encoding` writes can pass through a channel with
+/// the restrictions of `policy` unchanged, or with only its case changed for
+/// channels that fold case.
+///
+/// Length limits, such as the 63 characters of a DNS label, are not checked.
+///
+/// # Arguments
+/// * `encoding` - The encoding to check
+/// * `policy` - The restrictions of the channel
+///
+/// # Returns
+/// True if the encoding is safe for the channel
+#[must_use = "This returns whether the encoding fits but does nothing if unused"]
+pub fn fits_charset(encoding: Encoding, policy: CharsetPolicy) -> bool {
+    fits(encoding, &policy.into())
+}
+
+/// Returns true if everything `encoding` writes is in `allowed`, and the
+/// encoding is case-insensitive if `allowed` folds case.
+pub(crate) fn fits(encoding: Encoding, allowed: &CharSet) -> bool {
+    (0..=u8::MAX).all(|c| !may_write(encoding, c) || allowed.contains(c))
+        && (!allowed.folds_case() || !encoding.is_case_sensitive())
+}
+
+/// Returns `len` sample bytes, spread over every byte value, to compare the
+/// output lengths of encodings.
+pub(crate) fn sample(len: usize) -> ByteVec {
+    ByteVec::new(Arc::new(
+        (0..len)
+            .map(|i| i.to_le_bytes()[0].wrapping_mul(167))
+            .collect(),
+    ))
+}
+
+/// Returns the enabled encoding with the shortest output that fits `policy`.
+///
+/// Density is measured by encoding a fixed 1 KiB sample, so headers, padding
+/// and line breaks are taken into account.
+///
+/// # Arguments
+/// * `policy` - The restrictions of the channel
+///
+/// # Returns
+/// The densest compliant encoding, or `None` if no enabled encoding fits
+#[must_use = "This returns the suggested encoding but does nothing if unused"]
+pub fn densest_encoding(policy: CharsetPolicy) -> Option<Encoding> {
+    Encoding::best_for(&policy.into(), SAMPLE_LEN)
+}
+
+#[cfg(test)]
+#[cfg(all(
+    feature = "base36",
+    feature = "base45",
+    feature = "base62",
+    feature = "base64",
+    feature = "base94",
+    feature = "uuencode"
+))]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_fits_charset() {
+        assert!(fits_charset(Encoding::Base36, CharsetPolicy::DnsLabel));
+        assert!(!fits_charset(Encoding::Base62, CharsetPolicy::DnsLabel));
+        assert!(fits_charset(Encoding::Base62, CharsetPolicy::FilenameSafe));
+        assert!(!fits_charset(Encoding::Base64, CharsetPolicy::FilenameSafe));
+        assert!(fits_charset(Encoding::Base64, CharsetPolicy::ShellSafe));
+        assert!(!fits_charset(Encoding::Base45, CharsetPolicy::ShellSafe));
+        assert!(fits_charset(Encoding::Base45, CharsetPolicy::JsonString));
+        assert!(!fits_charset(Encoding::Uuencode, CharsetPolicy::JsonString));
+        assert!(fits_charset(
+            Encoding::Base45,
+            CharsetPolicy::QrAlphanumeric
+        ));
+        assert!(!fits_charset(
+            Encoding::Base36,
+            CharsetPolicy::QrAlphanumeric
+        ));
+    }
+
+    #[test]
+    fn test_densest_encoding() {
+        assert_eq!(
+            densest_encoding(CharsetPolicy::DnsLabel),
+            Some(Encoding::Base36)
+        );
+        assert_eq!(
+            densest_encoding(CharsetPolicy::FilenameSafe),
+            Some(Encoding::Base62)
+        );
+        assert_eq!(
+            densest_encoding(CharsetPolicy::ShellSafe),
+            Some(Encoding::Base64)
+        );
+        assert_eq!(
+            densest_encoding(CharsetPolicy::JsonString),
+            Some(Encoding::Base64)
+        );
+        assert_eq!(
+            densest_encoding(CharsetPolicy::QrAlphanumeric),
+            Some(Encoding::Base45)
+        );
+    }
+
+    #[test]
+    fn test_char_set() {
+        let set = CharSet::new("abc").with_range(b'0'..=b'9').without("b5");
+        assert!(set.contains(b'a') && set.contains(b'9'));
+        assert!(!set.contains(b'b') && !set.contains(b'5') && !set.contains(b'A'));
+        assert!(!set.folds_case());
+        assert!(CharSet::from(CharsetPolicy::DnsLabel).folds_case());
+        assert!(!CharSet::from(CharsetPolicy::JsonString).contains(b'"'));
+    }
+
+    #[test]
+    fn test_char_set_ignores_non_ascii() {
+        // 'é' is 0xc3 0xa9 in UTF-8; neither byte may end up in the set.
+        let set = CharSet::new("aé");
+        assert_eq!(set, CharSet::new("a"));
+        assert!(!set.contains(0xc3) && !set.contains(0xa9));
+    }
+
+    #[test]
+    fn test_best_for() {
+        let printable = CharSet::default().with_range(b' '..=b'~');
+        assert_eq!(Encoding::best_for(&printable, 1024), Some(Encoding::Base94));
+        assert_eq!(
+            Encoding::best_for(&printable.without("\\"), 1024),
+            Some(Encoding::Base64)
+        );
+        let no_symbols = CharSet::default()
+            .with_range(b'0'..=b'9')
+            .with_range(b'A'..=b'Z')
+            .with_range(b'a'..=b'z');
+        assert_eq!(Encoding::best_for(&no_symbols, 32), Some(Encoding::Base62));
+        let upper = no_symbols.without("abcdefghijklmnopqrstuvwxyz");
+        assert_eq!(Encoding::best_for(&upper, 32), None);
+        assert_eq!(
+            Encoding::best_for(&upper.with(b'='), 32),
+            Some(Encoding::Base32)
+        );
+        assert_eq!(
+            Encoding::best_for(&no_symbols.case_folding(), 32),
+            Some(Encoding::Base36)
+        );
+        assert_eq!(Encoding::best_for(&CharSet::new("01"), 32), None);
+    }
+}
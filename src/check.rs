@@ -0,0 +1,359 @@
+use crate::{ErrorKind, SerialiseError, alphabet, serialise_error::check_ascii};
+
+/// A check-digit scheme that appends characters to a code so that typing
+/// mistakes are caught before the code is used.
+///
+/// Schemes work on the text of a code, so they can be added on top of any
+/// encoding whose output people read out or type, such as voucher codes.
+pub trait CheckScheme {
+    /// Returns the name of the scheme, used in error messages.
+    fn get_name(&self) -> &'static str;
+
+    /// Returns the number of check characters the scheme appends.
+    fn get_check_len(&self) -> usize;
+
+    /// Returns false if the check characters may be typed in either case.
+    fn is_case_sensitive(&self) -> bool {
+        true
+    }
+
+    /// Computes the check characters for `digits`.
+    ///
+    /// # Arguments
+    /// * `digits` - The code without check characters
+    ///
+    /// # Returns
+    /// The check characters to append
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `digits` has a character the scheme does not cover.
+    fn compute(&self, digits: &str) -> Result<String, SerialiseError>;
+
+    /// Appends the check characters to `digits`.
+    ///
+    /// # Arguments
+    /// * `digits` - The code without check characters
+    ///
+    /// # Returns
+    /// The code followed by its check characters
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `digits` has a character the scheme does not cover.
+    fn append(&self, digits: &str) -> Result<String, SerialiseError> {
+        Ok(format!("{digits}{}", self.compute(digits)?))
+    }
+
+    /// Verifies the check characters at the end of `code` and removes them.
+    ///
+    /// # Arguments
+    /// * `code` - The code followed by its check characters
+    ///
+    /// # Returns
+    /// The code without check characters
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` with [`ErrorKind::ChecksumMismatch`] if the check
+    /// characters are wrong, or if `code` is too short or has a character the
+    /// scheme does not cover.
+    fn strip<'a>(&self, code: &'a str) -> Result<&'a str, SerialiseError> {
+        check_ascii(code, self.get_name())?;
+        let Some(split) = code.len().checked_sub(self.get_check_len()) else {
+            return Err(SerialiseError::new(format!(
+                "{} code is shorter than its {} check characters",
+                self.get_name(),
+                self.get_check_len()
+            )));
+        };
+        let (digits, actual) = code.split_at(split);
+        let expected = self.compute(digits)?;
+        if expected == actual
+            || (!self.is_case_sensitive() && expected.eq_ignore_ascii_case(actual))
+        {
+            Ok(digits)
+        } else {
+            Err(SerialiseError::new(format!(
+                "{} check characters '{actual}' are wrong, expected '{expected}'",
+                self.get_name()
+            ))
+            .with_kind(ErrorKind::ChecksumMismatch))
+        }
+    }
+}
+
+/// Looks up the value of each character of `digits`, failing on the first one
+/// `value` does not cover.
+pub(crate) fn digit_values(
+    name: &str,
+    digits: &str,
+    value: impl Fn(u8) -> Option<u32>,
+) -> Result<Vec<u32>, SerialiseError> {
+    check_ascii(digits, name)?;
+    digits
+        .bytes()
+        .enumerate()
+        .map(|(position, c)| {
+            value(c).ok_or_else(|| {
+                SerialiseError::new(format!(
+                    "invalid {name} character '{}' at position {position}",
+                    char::from(c)
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Returns the value of a decimal digit.
+fn decimal(c: u8) -> Option<u32> {
+    c.is_ascii_digit().then(|| u32::from(c - b'0'))
+}
+
+/// The Luhn mod N algorithm over any alphabet, appending one check character.
+///
+/// Catches every single-character error and most swaps of adjacent characters.
+/// Over the decimal digits this is the Luhn algorithm used on payment cards.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Luhn {
+    /// The characters of the code, in order of value
+    alphabet: &'static [u8],
+    /// Whether letters may be typed in either case
+    ignore_case: bool,
+}
+
+impl Luhn {
+    /// Luhn over the decimal digits.
+    pub const DECIMAL: Self = Self::new(alphabet::DECIMAL);
+
+    /// Luhn mod 36 over `0-9a-z`, in either case.
+    pub const BASE36: Self = Self::new(alphabet::BASE36).ignore_case();
+
+    /// Creates a Luhn mod N scheme, where N is the length of `alphabet`.
+    ///
+    /// # Arguments
+    /// * `alphabet` - The characters of the code, in order of value
+    #[must_use]
+    pub const fn new(alphabet: &'static [u8]) -> Self {
+        Self {
+            alphabet,
+            ignore_case: false,
+        }
+    }
+
+    /// Accepts letters in either case, for alphabets with letters in one case.
+    #[must_use = "This returns the updated scheme but does nothing if unused"]
+    pub const fn ignore_case(mut self) -> Self {
+        self.ignore_case = true;
+        self
+    }
+
+    fn value(&self, c: u8) -> Option<u32> {
+        let find = |c: u8| self.alphabet.iter().position(|&a| a == c);
+        let position = find(c).or_else(|| {
+            self.ignore_case
+                .then(|| find(c.to_ascii_lowercase()).or_else(|| find(c.to_ascii_uppercase())))
+                .flatten()
+        })?;
+        u32::try_from(position).ok()
+    }
+}
+
+impl CheckScheme for Luhn {
+    fn get_name(&self) -> &'static str {
+        "luhn"
+    }
+
+    fn get_check_len(&self) -> usize {
+        1
+    }
+
+    fn is_case_sensitive(&self) -> bool {
+        !self.ignore_case
+    }
+
+    fn compute(&self, digits: &str) -> Result<String, SerialiseError> {
+        let n = u32::try_from(self.alphabet.len()).unwrap_or(u32::MAX);
+        let values = digit_values(self.get_name(), digits, |c| self.value(c))?;
+        let sum = values
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, &value)| {
+                let addend = if i % 2 == 0 { value * 2 } else { value };
+                addend / n + addend % n
+            })
+            .sum::<u32>();
+        let check = (n - sum % n) % n;
+        Ok(char::from(self.alphabet[check as usize]).to_string())
+    }
+}
+
+/// ISO 7064 MOD 97-10, appending two decimal check digits.
+///
+/// Letters count as 10 to 35 in either case, as in IBANs, so alphanumeric codes
+/// are covered. Catches every single-character error and every swap of two
+/// adjacent characters.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Mod97;
+
+impl CheckScheme for Mod97 {
+    fn get_name(&self) -> &'static str {
+        "mod 97-10"
+    }
+
+    fn get_check_len(&self) -> usize {
+        2
+    }
+
+    fn compute(&self, digits: &str) -> Result<String, SerialiseError> {
+        let values = digit_values(self.get_name(), digits, |c| char::from(c).to_digit(36))?;
+        let remainder = values.iter().fold(0, |acc, &value| {
+            if value < 10 {
+                (acc * 10 + value) % 97
+            } else {
+                (acc * 100 + value) % 97
+            }
+        });
+        Ok(format!("{:02}", 98 - remainder * 100 % 97))
+    }
+}
+
+/// The Verhoeff algorithm, appending one decimal check digit.
+///
+/// Catches every single-digit error and every swap of adjacent digits.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Verhoeff;
+
+impl Verhoeff {
+    /// Multiplication table of the dihedral group D5
+    const D: [[u8; 10]; 10] = [
+        [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+        [1, 2, 3, 4, 0, 6, 7, 8, 9, 5],
+        [2, 3, 4, 0, 1, 7, 8, 9, 5, 6],
+        [3, 4, 0, 1, 2, 8, 9, 5, 6, 7],
+        [4, 0, 1, 2, 3, 9, 5, 6, 7, 8],
+        [5, 9, 8, 7, 6, 0, 4, 3, 2, 1],
+        [6, 5, 9, 8, 7, 1, 0, 4, 3, 2],
+        [7, 6, 5, 9, 8, 2, 1, 0, 4, 3],
+        [8, 7, 6, 5, 9, 3, 2, 1, 0, 4],
+        [9, 8, 7, 6, 5, 4, 3, 2, 1, 0],
+    ];
+    /// Permutation applied to a digit according to its position
+    const P: [[u8; 10]; 8] = [
+        [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+        [1, 5, 7, 6, 2, 8, 3, 0, 9, 4],
+        [5, 8, 0, 3, 7, 9, 6, 1, 4, 2],
+        [8, 9, 1, 6, 0, 4, 3, 5, 2, 7],
+        [9, 4, 5, 3, 1, 2, 6, 8, 7, 0],
+        [4, 2, 8, 6, 5, 7, 3, 9, 0, 1],
+        [2, 7, 9, 3, 8, 0, 6, 4, 1, 5],
+        [7, 0, 4, 6, 9, 1, 3, 2, 5, 8],
+    ];
+    /// Inverses in D5
+    const INV: [u8; 10] = [0, 4, 3, 2, 1, 5, 6, 7, 8, 9];
+}
+
+impl CheckScheme for Verhoeff {
+    fn get_name(&self) -> &'static str {
+        "verhoeff"
+    }
+
+    fn get_check_len(&self) -> usize {
+        1
+    }
+
+    fn compute(&self, digits: &str) -> Result<String, SerialiseError> {
+        let values = digit_values(self.get_name(), digits, decimal)?;
+        let c = values.iter().rev().enumerate().fold(0, |c, (i, &value)| {
+            Self::D[usize::from(c)][usize::from(Self::P[(i + 1) % 8][value as usize])]
+        });
+        Ok(Self::INV[usize::from(c)].to_string())
+    }
+}
+
+/// The Damm algorithm, appending one decimal check digit.
+///
+/// Catches every single-digit error and every swap of adjacent digits, with a
+/// single lookup table.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Damm;
+
+impl Damm {
+    /// A totally anti-symmetric quasigroup of order 10
+    const TABLE: [[u8; 10]; 10] = [
+        [0, 3, 1, 7, 5, 9, 8, 6, 4, 2],
+        [7, 0, 9, 2, 1, 5, 4, 8, 6, 3],
+        [4, 2, 0, 6, 8, 7, 1, 3, 5, 9],
+        [1, 7, 5, 0, 9, 8, 3, 4, 2, 6],
+        [6, 1, 2, 3, 0, 4, 5, 9, 7, 8],
+        [3, 6, 7, 4, 2, 0, 9, 5, 8, 1],
+        [5, 8, 6, 9, 7, 2, 0, 1, 3, 4],
+        [8, 9, 4, 5, 3, 6, 2, 0, 1, 7],
+        [9, 4, 3, 8, 6, 1, 7, 2, 0, 5],
+        [2, 5, 8, 1, 4, 3, 6, 7, 9, 0],
+    ];
+}
+
+impl CheckScheme for Damm {
+    fn get_name(&self) -> &'static str {
+        "damm"
+    }
+
+    fn get_check_len(&self) -> usize {
+        1
+    }
+
+    fn compute(&self, digits: &str) -> Result<String, SerialiseError> {
+        let values = digit_values(self.get_name(), digits, decimal)?;
+        let interim = values
+            .iter()
+            .fold(0, |c, &value| Self::TABLE[usize::from(c)][value as usize]);
+        Ok(interim.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_known_check_digits() {
+        assert!(matches!(Luhn::DECIMAL.compute("7992739871"), Ok(s) if s == "3"));
+        assert!(matches!(Mod97.compute("WEST12345698765432GB"), Ok(s) if s == "82"));
+        assert!(matches!(Verhoeff.compute("236"), Ok(s) if s == "3"));
+        assert!(matches!(Damm.compute("572"), Ok(s) if s == "4"));
+    }
+
+    #[test]
+    fn test_adjacent_swaps_are_caught() {
+        let schemes: [&dyn CheckScheme; 3] = [&Mod97, &Verhoeff, &Damm];
+        for scheme in schemes {
+            let code = scheme.append("4902715836").unwrap_or_default();
+            assert!(matches!(scheme.strip(&code), Ok("4902715836")));
+            let bytes = code.as_bytes();
+            for i in 0..bytes.len() - 1 {
+                if bytes[i] == bytes[i + 1] {
+                    continue;
+                }
+                let mut swapped = bytes.to_vec();
+                swapped.swap(i, i + 1);
+                let swapped = String::from_utf8(swapped).unwrap_or_default();
+                assert!(
+                    matches!(scheme.strip(&swapped), Err(e) if e.get_kind() == ErrorKind::ChecksumMismatch),
+                    "{} missed a swap in {swapped}",
+                    scheme.get_name()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_input_is_err() {
+        assert!(matches!(
+            Verhoeff.compute("12a4"),
+            Err(e) if e.get_message() == "invalid verhoeff character 'a' at position 2"
+        ));
+        assert!(Mod97.strip("1").is_err());
+        assert!(matches!(Luhn::BASE36.strip("A1B2C3R"), Ok("A1B2C3")));
+        assert!(Luhn::new(alphabet::BASE36).strip("a1b2c3R").is_err());
+    }
+}
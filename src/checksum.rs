@@ -0,0 +1,270 @@
+/// Builds the lookup table for a reflected (least significant bit first)
+/// 32-bit CRC with the reversed polynomial `poly`.
+const fn reflected_table(poly: u32) -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i: u32 = 0;
+    while i < 256 {
+        let mut crc = i;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 0 {
+                crc >> 1
+            } else {
+                (crc >> 1) ^ poly
+            };
+            bit += 1;
+        }
+        table[i as usize] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// CRC-32 (IEEE 802.3) lookup table.
+const CRC32_TABLE: [u32; 256] = reflected_table(0xedb8_8320);
+
+/// CRC-32C (Castagnoli) lookup table.
+const CRC32C_TABLE: [u32; 256] = reflected_table(0x82f6_3b78);
+
+/// CRC-16/XMODEM lookup table, most significant bit first.
+const CRC16_TABLE: [u16; 256] = {
+    let mut table = [0u16; 256];
+    let mut i: u16 = 0;
+    while i < 256 {
+        let mut crc = i << 8;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x8000 == 0 {
+                crc << 1
+            } else {
+                (crc << 1) ^ 0x1021
+            };
+            bit += 1;
+        }
+        table[i as usize] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Runs a reflected 32-bit CRC over `bytes`, starting from the raw register
+/// value `crc`.
+fn update_reflected(table: &[u32; 256], crc: u32, bytes: &[u8]) -> u32 {
+    bytes.iter().fold(crc, |crc, &b| {
+        table[usize::from(crc.to_le_bytes()[0] ^ b)] ^ (crc >> 8)
+    })
+}
+
+/// CRC-16/XMODEM: polynomial `0x1021`, initial value 0, no reflection.
+///
+/// This is the CRC used by XMODEM and, over the data followed by two zero
+/// bytes, by `BinHex`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Crc16 {
+    /// The CRC of the bytes so far
+    crc: u16,
+}
+
+impl Crc16 {
+    /// Creates a CRC of no bytes.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { crc: 0 }
+    }
+
+    /// Adds `bytes` to the CRC.
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.crc = bytes.iter().fold(self.crc, |crc, &b| {
+            CRC16_TABLE[usize::from(crc.to_be_bytes()[0] ^ b)] ^ (crc << 8)
+        });
+    }
+
+    /// Returns the CRC of the bytes added so far.
+    #[must_use = "This returns the CRC but does nothing if unused"]
+    pub const fn get_value(&self) -> u16 {
+        self.crc
+    }
+
+    /// Returns the CRC of `bytes`.
+    #[must_use = "This returns the CRC but does nothing if unused"]
+    pub fn checksum(bytes: &[u8]) -> u16 {
+        let mut crc = Self::new();
+        crc.update(bytes);
+        crc.get_value()
+    }
+}
+
+/// CRC-32 as used by Ethernet, zip, gzip and PNG (IEEE 802.3).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Crc32 {
+    /// The CRC register, which is the complement of the CRC so far
+    register: u32,
+}
+
+impl Crc32 {
+    /// Creates a CRC of no bytes.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { register: !0 }
+    }
+
+    /// Adds `bytes` to the CRC.
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.register = update_reflected(&CRC32_TABLE, self.register, bytes);
+    }
+
+    /// Returns the CRC of the bytes added so far.
+    #[must_use = "This returns the CRC but does nothing if unused"]
+    pub const fn get_value(&self) -> u32 {
+        !self.register
+    }
+
+    /// Returns the CRC of `bytes`.
+    #[must_use = "This returns the CRC but does nothing if unused"]
+    pub fn checksum(bytes: &[u8]) -> u32 {
+        let mut crc = Self::new();
+        crc.update(bytes);
+        crc.get_value()
+    }
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// CRC-32C (Castagnoli) as used by iSCSI, SCTP, ext4 and many storage formats.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Crc32c {
+    /// The CRC register, which is the complement of the CRC so far
+    register: u32,
+}
+
+impl Crc32c {
+    /// Creates a CRC of no bytes.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { register: !0 }
+    }
+
+    /// Adds `bytes` to the CRC.
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.register = update_reflected(&CRC32C_TABLE, self.register, bytes);
+    }
+
+    /// Returns the CRC of the bytes added so far.
+    #[must_use = "This returns the CRC but does nothing if unused"]
+    pub const fn get_value(&self) -> u32 {
+        !self.register
+    }
+
+    /// Returns the CRC of `bytes`.
+    #[must_use = "This returns the CRC but does nothing if unused"]
+    pub fn checksum(bytes: &[u8]) -> u32 {
+        let mut crc = Self::new();
+        crc.update(bytes);
+        crc.get_value()
+    }
+}
+
+impl Default for Crc32c {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Adler-32 (RFC 1950), as used by zlib and by [`EncodedString`](crate::EncodedString)
+/// to verify decoded bytes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Adler32 {
+    /// Sum of the bytes plus one, modulo 65521
+    a: u32,
+    /// Sum of the values of `a`, modulo 65521
+    b: u32,
+}
+
+impl Adler32 {
+    /// The modulus of both sums.
+    const MOD: u32 = 65_521;
+
+    /// Largest block that cannot overflow the sums before reducing.
+    const BLOCK: usize = 5552;
+
+    /// Creates a checksum of no bytes.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { a: 1, b: 0 }
+    }
+
+    /// Adds `bytes` to the checksum.
+    pub fn update(&mut self, bytes: &[u8]) {
+        for block in bytes.chunks(Self::BLOCK) {
+            for &byte in block {
+                self.a += u32::from(byte);
+                self.b += self.a;
+            }
+            self.a %= Self::MOD;
+            self.b %= Self::MOD;
+        }
+    }
+
+    /// Returns the checksum of the bytes added so far.
+    #[must_use = "This returns the checksum but does nothing if unused"]
+    pub const fn get_value(&self) -> u32 {
+        (self.b << 16) | self.a
+    }
+
+    /// Returns the checksum of `bytes`.
+    #[must_use = "This returns the checksum but does nothing if unused"]
+    pub fn checksum(bytes: &[u8]) -> u32 {
+        let mut adler = Self::new();
+        adler.update(bytes);
+        adler.get_value()
+    }
+}
+
+impl Default for Adler32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_check_values() {
+        assert_eq!(Crc16::checksum(b"123456789"), 0x31c3);
+        assert_eq!(Crc32::checksum(b"123456789"), 0xcbf4_3926);
+        assert_eq!(Crc32c::checksum(b"123456789"), 0xe306_9283);
+        assert_eq!(Adler32::checksum(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(Crc16::checksum(b""), 0);
+        assert_eq!(Crc32::checksum(b""), 0);
+        assert_eq!(Crc32c::checksum(b""), 0);
+        assert_eq!(Adler32::checksum(b""), 1);
+    }
+
+    #[test]
+    fn test_streaming_matches_one_shot() {
+        let bytes: Vec<u8> = (0..20_000u32).map(|i| i.to_le_bytes()[0]).collect();
+        let (mut crc16, mut crc32, mut crc32c, mut adler) =
+            (Crc16::new(), Crc32::new(), Crc32c::new(), Adler32::new());
+        for piece in bytes.chunks(777) {
+            crc16.update(piece);
+            crc32.update(piece);
+            crc32c.update(piece);
+            adler.update(piece);
+        }
+        assert_eq!(crc16.get_value(), Crc16::checksum(&bytes));
+        assert_eq!(crc32.get_value(), Crc32::checksum(&bytes));
+        assert_eq!(crc32c.get_value(), Crc32c::checksum(&bytes));
+        assert_eq!(adler.get_value(), Adler32::checksum(&bytes));
+    }
+}
@@ -0,0 +1,190 @@
+use crate::{SerialiseError, alphabet, serialise_error::check_ascii};
+
+/// Writes `bytes` as a CSS hex color, `#rrggbb` for three bytes or
+/// `#rrggbbaa` for four, in lowercase.
+///
+/// # Arguments
+/// * `bytes` - The red, green and blue channels, then optionally alpha
+///
+/// # Returns
+/// The color with its leading `#`
+///
+/// # Errors
+/// Returns `SerialiseError` if `bytes` is not three or four bytes long.
+pub fn encode(bytes: &[u8]) -> Result<String, SerialiseError> {
+    check_len(bytes)?;
+    let mut out = String::with_capacity(1 + bytes.len() * 2);
+    out.push('#');
+    for &b in bytes {
+        out.push(char::from(alphabet::HEX[usize::from(b >> 4)]));
+        out.push(char::from(alphabet::HEX[usize::from(b & 0x0f)]));
+    }
+    Ok(out)
+}
+
+/// Writes `bytes` as a CSS hex color, using the three or four digit short
+/// form, such as `#f80`, when every channel repeats its digit.
+///
+/// # Arguments
+/// * `bytes` - The red, green and blue channels, then optionally alpha
+///
+/// # Returns
+/// The shortest color with its leading `#`
+///
+/// # Errors
+/// Returns `SerialiseError` if `bytes` is not three or four bytes long.
+pub fn encode_short(bytes: &[u8]) -> Result<String, SerialiseError> {
+    check_len(bytes)?;
+    if !bytes.iter().all(|&b| b >> 4 == b & 0x0f) {
+        return encode(bytes);
+    }
+    let mut out = String::with_capacity(1 + bytes.len());
+    out.push('#');
+    for &b in bytes {
+        out.push(char::from(alphabet::HEX[usize::from(b & 0x0f)]));
+    }
+    Ok(out)
+}
+
+/// Parses a CSS hex color of 3, 4, 6 or 8 digits, with or without the
+/// leading `#`, in either case.
+///
+/// Short forms repeat each digit, so `#f80` is `#ff8800`.
+///
+/// # Arguments
+/// * `text` - The color
+///
+/// # Returns
+/// The red, green and blue channels, then alpha if the color has one
+///
+/// # Errors
+/// Returns `SerialiseError` if `text` has a number of digits other than 3,
+/// 4, 6 or 8, or a character that is not a hex digit.
+pub fn decode(text: &str) -> Result<Vec<u8>, SerialiseError> {
+    check_ascii(text, "color")?;
+    let digits = text.strip_prefix('#').unwrap_or(text);
+    let nibbles = digits
+        .bytes()
+        .enumerate()
+        .map(|(i, c)| {
+            char::from(c)
+                .to_digit(16)
+                .and_then(|d| u8::try_from(d).ok())
+                .ok_or_else(|| {
+                    SerialiseError::new(format!(
+                        "invalid hex digit '{}' at position {} in color '{text}'",
+                        char::from(c),
+                        text.len() - digits.len() + i
+                    ))
+                })
+        })
+        .collect::<Result<Vec<u8>, _>>()?;
+    match nibbles.len() {
+        3 | 4 => Ok(nibbles.iter().map(|&n| n << 4 | n).collect()),
+        6 | 8 => Ok(nibbles
+            .chunks(2)
+            .map(|pair| pair[0] << 4 | pair[1])
+            .collect()),
+        len => Err(SerialiseError::new(format!(
+            "color '{text}' has {len} digits, expected 3, 4, 6 or 8"
+        ))),
+    }
+}
+
+/// Parses a CSS hex color as [`decode`] does, filling in an opaque alpha of
+/// `0xff` for colors without one.
+///
+/// # Arguments
+/// * `text` - The color
+///
+/// # Returns
+/// The red, green, blue and alpha channels
+///
+/// # Errors
+/// Returns `SerialiseError` for exactly the inputs [`decode`] rejects.
+pub fn decode_rgba(text: &str) -> Result<[u8; 4], SerialiseError> {
+    let bytes = decode(text)?;
+    let mut rgba = [0xff; 4];
+    rgba[..bytes.len()].copy_from_slice(&bytes);
+    Ok(rgba)
+}
+
+/// Checks that `text` is a valid CSS hex color.
+///
+/// # Errors
+/// Returns `SerialiseError` for exactly the inputs [`decode`] rejects.
+pub fn validate(text: &str) -> Result<(), SerialiseError> {
+    decode(text).map(|_| ())
+}
+
+/// Checks that `bytes` holds three or four channels.
+fn check_len(bytes: &[u8]) -> Result<(), SerialiseError> {
+    if matches!(bytes.len(), 3 | 4) {
+        Ok(())
+    } else {
+        Err(SerialiseError::new(format!(
+            "a color must be 3 or 4 bytes, got {}",
+            bytes.len()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        assert!(matches!(
+            encode(&[0xff, 0x88, 0x00]).as_deref(),
+            Ok("#ff8800")
+        ));
+        assert!(matches!(
+            encode(&[0x12, 0x34, 0x56, 0x78]).as_deref(),
+            Ok("#12345678")
+        ));
+        assert!(matches!(
+            encode_short(&[0xff, 0x88, 0x00]).as_deref(),
+            Ok("#f80")
+        ));
+        assert!(matches!(
+            encode_short(&[0xff, 0x88, 0x00, 0xaa]).as_deref(),
+            Ok("#f80a")
+        ));
+        assert!(matches!(
+            encode_short(&[0xff, 0x88, 0x01]).as_deref(),
+            Ok("#ff8801")
+        ));
+        assert!(encode(&[0; 2]).is_err());
+        assert!(encode_short(&[0; 5]).is_err());
+    }
+
+    #[test]
+    fn test_decode() {
+        assert!(matches!(decode("#F80"), Ok(b) if b == [0xff, 0x88, 0x00]));
+        assert!(matches!(decode("f80a"), Ok(b) if b == [0xff, 0x88, 0x00, 0xaa]));
+        assert!(matches!(decode("#ff8801"), Ok(b) if b == [0xff, 0x88, 0x01]));
+        assert!(matches!(decode("#12345678"), Ok(b) if b == [0x12, 0x34, 0x56, 0x78]));
+        assert!(matches!(decode_rgba("#000"), Ok([0, 0, 0, 0xff])));
+        assert!(matches!(decode_rgba("#0000"), Ok([0, 0, 0, 0])));
+    }
+
+    #[test]
+    fn test_invalid_is_err() {
+        for bad in [
+            "",
+            "#",
+            "#ff",
+            "#fffff",
+            "#fffffffff",
+            "##fff",
+            "#ggg",
+            "# fff",
+            "#f\u{e9}f",
+        ] {
+            assert!(decode(bad).is_err(), "{bad}");
+            assert!(validate(bad).is_err(), "{bad}");
+        }
+    }
+}
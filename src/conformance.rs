@@ -0,0 +1,306 @@
+use std::sync::Arc;
+
+use crate::{
+    Base32, Base58, Base64, EncodedString, Encoder, Hex, SerialiseError,
+    alphabet::{self, transcode_alphabet},
+};
+
+/// A known-answer test: some bytes and the text they must encode to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Vector {
+    /// The input bytes
+    bytes: &'static [u8],
+    /// The expected encoding of `bytes`
+    encoded: &'static str,
+}
+
+impl Vector {
+    /// Creates a test vector.
+    ///
+    /// # Arguments
+    /// * `bytes` - The input bytes
+    /// * `encoded` - The expected encoding of `bytes`
+    #[must_use]
+    pub const fn new(bytes: &'static [u8], encoded: &'static str) -> Self {
+        Self { bytes, encoded }
+    }
+
+    /// Returns the input bytes.
+    #[must_use]
+    pub const fn get_bytes(&self) -> &'static [u8] {
+        self.bytes
+    }
+
+    /// Returns the expected encoding.
+    #[must_use]
+    pub const fn get_encoded(&self) -> &'static str {
+        self.encoded
+    }
+}
+
+/// RFC 4648 section 10 Base16 vectors.
+pub const BASE16: &[Vector] = &[
+    Vector::new(b"", ""),
+    Vector::new(b"f", "66"),
+    Vector::new(b"fo", "666F"),
+    Vector::new(b"foo", "666F6F"),
+    Vector::new(b"foob", "666F6F62"),
+    Vector::new(b"fooba", "666F6F6261"),
+    Vector::new(b"foobar", "666F6F626172"),
+];
+
+/// RFC 4648 section 10 Base32 vectors.
+pub const BASE32: &[Vector] = &[
+    Vector::new(b"", ""),
+    Vector::new(b"f", "MY======"),
+    Vector::new(b"fo", "MZXQ===="),
+    Vector::new(b"foo", "MZXW6==="),
+    Vector::new(b"foob", "MZXW6YQ="),
+    Vector::new(b"fooba", "MZXW6YTB"),
+    Vector::new(b"foobar", "MZXW6YTBOI======"),
+];
+
+/// RFC 4648 section 10 Base32 vectors with the extended hex alphabet.
+pub const BASE32_HEX: &[Vector] = &[
+    Vector::new(b"", ""),
+    Vector::new(b"f", "CO======"),
+    Vector::new(b"fo", "CPNG===="),
+    Vector::new(b"foo", "CPNMU==="),
+    Vector::new(b"foob", "CPNMUOG="),
+    Vector::new(b"fooba", "CPNMUOJ1"),
+    Vector::new(b"foobar", "CPNMUOJ1E8======"),
+];
+
+/// RFC 4648 section 10 Base64 vectors, plus one using the last two digits.
+pub const BASE64: &[Vector] = &[
+    Vector::new(b"", ""),
+    Vector::new(b"f", "Zg=="),
+    Vector::new(b"fo", "Zm8="),
+    Vector::new(b"foo", "Zm9v"),
+    Vector::new(b"foob", "Zm9vYg=="),
+    Vector::new(b"fooba", "Zm9vYmE="),
+    Vector::new(b"foobar", "Zm9vYmFy"),
+    Vector::new(b"\xfb\xff", "+/8="),
+];
+
+/// RFC 4648 section 5 URL-safe Base64 vectors.
+pub const BASE64_URL: &[Vector] = &[
+    Vector::new(b"foobar", "Zm9vYmFy"),
+    Vector::new(b"\xfb\xff", "-_8="),
+    Vector::new(b"\x03\xe0\xff\xbf", "A-D_vw=="),
+];
+
+/// Bitcoin Base58 vectors from the Bitcoin Core test suite that this crate's
+/// Base58 reproduces; the rest are in [`BASE58_BITCOIN_LEADING_ZEROS`].
+pub const BASE58_BITCOIN: &[Vector] = &[
+    Vector::new(b"\x61", "2g"),
+    Vector::new(b"\x62\x62\x62", "a3gV"),
+    Vector::new(b"\x63\x63\x63", "aPEr"),
+    Vector::new(b"simply a long string", "2cFupjhnEsSn59qHXstmK2ffpLv2"),
+    Vector::new(b"\x51\x6b\x6f\xcd\x0f", "ABnLTmg"),
+    Vector::new(b"\xbf\x4f\x89\x00\x1e\x67\x02\x74\xdd", "3SEo3LWLoPntC"),
+    Vector::new(b"\x57\x2e\x47\x94", "3EFU7m"),
+    Vector::new(
+        b"\xec\xac\x89\xca\xd9\x39\x23\xc0\x23\x21",
+        "EJDM8drfXA6uyA",
+    ),
+    Vector::new(b"\x10\xc8\x51\x1e", "Rt5zm"),
+];
+
+/// The bytes 0 to 255 in order.
+const EVERY_BYTE: [u8; 256] = {
+    let mut bytes = [0; 256];
+    let mut byte: u8 = 0;
+    loop {
+        bytes[byte as usize] = byte;
+        if byte == u8::MAX {
+            break;
+        }
+        byte += 1;
+    }
+    bytes
+};
+
+/// The Bitcoin Core Base58 vectors with leading zero bytes, and the empty
+/// input, which this crate's Base58 is known to fail.
+///
+/// Bitcoin writes each leading zero byte as a `1`. This crate's Base58 encodes
+/// the value of the bytes instead, so it drops leading zero bytes, writes `0`
+/// for a value of zero, and decodes leading `1`s to nothing. [`run_all`] does
+/// not check these; they are kept so the difference is tested rather than
+/// hidden.
+pub const BASE58_BITCOIN_LEADING_ZEROS: &[Vector] = &[
+    Vector::new(b"", ""),
+    Vector::new(
+        b"\x00\xeb\x15\x23\x1d\xfc\xeb\x60\x92\x58\x86\xb6\x7d\x06\x52\x99\x92\x59\x15\xae\xb1\x72\xc0\x66\x47",
+        "1NS17iag9jJgTHD1VXjvLCEnZuQ3rJDE9L",
+    ),
+    Vector::new(b"\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00", "1111111111"),
+    Vector::new(
+        b"\x00\x01\x11\xd3\x8e\x5f\xc9\x07\x1f\xfc\xd2\x0b\x4a\x76\x3c\xc9\xae\x4f\x25\x2b\xb4\xe4\x8f\xd6\x6a\x83\x5e\x25\x2a\xda\x93\xff\x48\x0d\x6d\xd4\x3d\xc6\x2a\x64\x11\x55\xa5",
+        "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz",
+    ),
+    Vector::new(
+        &EVERY_BYTE,
+        "1cWB5HCBdLjAuqGGReWE3R3CguuwSjw6RHn39s2yuDRTS5NsBgNiFpWgAnEx6VQi8csexkgYw3mdYrMHr8x9i7aEwP8kZ7vccXWqKDvGv3u1GxFKPuAkn8JCPPGDMf3vMMnbzm6Nh9zh1gcNsMvH3ZNLmP5fSG6DGbbi2tuwMWPthr4boWwCxf7ewSgNQeacyozhKDDQQ1qL5fQFUW52QKUZDZ5fw3KXNQJMcNTcaB723LchjeKun7MuGW5qyCBZYzA1KjofN1gYBV3NqyhQJ3Ns746GNuf9N2pQPmHz4xpnSrrfCvy6TVVz5d4PdrjeshsWQwpZsZGzvbdAdN8MKV5QsBDY",
+    ),
+];
+
+/// Checks an encoder against known-answer vectors, in both directions.
+///
+/// Each vector's bytes must encode to exactly its text, ignoring case if the
+/// encoder reports a case-insensitive [`Encoding`](crate::Encoding), and the
+/// text must decode back to the bytes. Use this to certify a custom or forked
+/// [`Encoder`] before relying on it.
+///
+/// # Arguments
+/// * `vectors` - The vectors to check, e.g. [`BASE64`]
+///
+/// # Errors
+/// Returns `SerialiseError` listing every vector that failed, or if the encoder
+/// cannot encode empty input, which is used to find its encoding.
+pub fn certify<E: Encoder>(vectors: &[Vector]) -> Result<(), SerialiseError> {
+    let encoding = E::try_encode(Arc::new(Vec::new()))?.get_encoding();
+    check(
+        vectors,
+        |bytes| {
+            let encoded = E::try_encode(Arc::new(bytes.to_vec()))?;
+            Ok((encoded.get_string().clone(), encoding.is_case_sensitive()))
+        },
+        |text| Ok(E::try_decode(&EncodedString::new(encoding, text.to_string()))?.to_vec()),
+    )
+}
+
+/// Runs every built-in vector set against this crate's codecs.
+///
+/// URL-safe Base64 and Base32 with the hex alphabet are checked by translating
+/// the standard output with [`transcode_alphabet`].
+///
+/// # Errors
+/// Returns `SerialiseError` listing every vector that failed, grouped by set.
+pub fn run_all() -> Result<(), SerialiseError> {
+    let (base32, base32_hex) = (alphabet::BASE32_STANDARD, alphabet::BASE32_HEX);
+    let (base64, base64_url) = (alphabet::BASE64_STANDARD, alphabet::BASE64_URL_SAFE);
+    let results = [
+        ("base16", certify::<Hex>(BASE16)),
+        ("base32", certify::<Base32>(BASE32)),
+        ("base64", certify::<Base64>(BASE64)),
+        ("base58", certify::<Base58>(BASE58_BITCOIN)),
+        (
+            "base32hex",
+            check(
+                BASE32_HEX,
+                |bytes| {
+                    let text = Base32::try_to_base32(bytes)?;
+                    Ok((transcode_alphabet(&text, base32, base32_hex)?, true))
+                },
+                |text| Base32::try_from_base32(&transcode_alphabet(text, base32_hex, base32)?),
+            ),
+        ),
+        (
+            "base64url",
+            check(
+                BASE64_URL,
+                |bytes| {
+                    let text = Base64::try_to_base64(bytes)?;
+                    Ok((transcode_alphabet(&text, base64, base64_url)?, true))
+                },
+                |text| Base64::try_from_base64(&transcode_alphabet(text, base64_url, base64)?, 0),
+            ),
+        ),
+    ];
+    let failures: Vec<String> = results
+        .into_iter()
+        .filter_map(|(name, result)| result.err().map(|e| format!("{name}: {e}")))
+        .collect();
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(SerialiseError::new(failures.join("; ")))
+    }
+}
+
+/// Runs `encode` and `decode` over `vectors`, collecting every mismatch.
+///
+/// `encode` returns the text and whether it must match the vector's case.
+fn check(
+    vectors: &[Vector],
+    encode: impl Fn(&[u8]) -> Result<(String, bool), SerialiseError>,
+    decode: impl Fn(&str) -> Result<Vec<u8>, SerialiseError>,
+) -> Result<(), SerialiseError> {
+    let mut failures = Vec::new();
+    for vector in vectors {
+        match encode(vector.bytes) {
+            Ok((text, true)) if text == vector.encoded => {}
+            Ok((text, false)) if text.eq_ignore_ascii_case(vector.encoded) => {}
+            Ok((text, _)) => failures.push(format!(
+                "{:02x?} encoded to '{text}', expected '{}'",
+                vector.bytes, vector.encoded
+            )),
+            Err(e) => failures.push(format!("{:02x?} failed to encode: {e}", vector.bytes)),
+        }
+        match decode(vector.encoded) {
+            Ok(bytes) if bytes == vector.bytes => {}
+            Ok(bytes) => failures.push(format!(
+                "'{}' decoded to {bytes:02x?}, expected {:02x?}",
+                vector.encoded, vector.bytes
+            )),
+            Err(e) => failures.push(format!("'{}' failed to decode: {e}", vector.encoded)),
+        }
+    }
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(SerialiseError::new(failures.join(", ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_run_all() {
+        assert!(matches!(run_all(), Ok(())), "{:?}", run_all());
+    }
+
+    #[test]
+    fn test_base58_leading_zero_vectors_fail_as_expected() {
+        for vector in BASE58_BITCOIN_LEADING_ZEROS {
+            assert!(certify::<Base58>(&[*vector]).is_err());
+            // Today's output: the value of the bytes, without leading zeros.
+            let digits = vector.encoded.trim_start_matches('1');
+            let start = vector.bytes.iter().position(|&b| b != 0);
+            let value = start.map_or(&[0][..], |start| &vector.bytes[start..]);
+            assert_eq!(
+                Base58::to_base58(vector.bytes),
+                if digits.is_empty() { "0" } else { digits }
+            );
+            assert!(
+                matches!(Base58::base58_to_bytes(vector.encoded), Ok(bytes) if bytes == value),
+                "{}",
+                vector.encoded
+            );
+        }
+    }
+
+    #[test]
+    fn test_certify_reports_failures() {
+        struct Broken {}
+        impl Encoder for Broken {
+            fn try_encode(bytes: Arc<Vec<u8>>) -> Result<EncodedString, SerialiseError> {
+                let mut encoded = Base64::try_to_base64(&bytes)?;
+                encoded.make_ascii_lowercase();
+                Ok(EncodedString::new(crate::Encoding::Base64, encoded))
+            }
+            fn try_decode(encoded: &EncodedString) -> Result<Arc<Vec<u8>>, SerialiseError> {
+                Base64::try_decode(encoded)
+            }
+        }
+        assert!(matches!(
+            certify::<Broken>(&BASE64[..3]),
+            Err(e) if e.get_message() == "[66] encoded to 'zg==', expected 'Zg==', [66, 6f] encoded to 'zm8=', expected 'Zm8='"
+        ));
+    }
+}
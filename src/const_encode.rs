@@ -0,0 +1,219 @@
+use crate::{Encoding, alphabet};
+
+/// Returns the most characters `N` bytes encode to in `encoding`, for sizing
+/// stack buffers at compile time, e.g.
+/// `[u8; max_encoded_len::<32>(Encoding::Base58)]`.
+///
+/// See [`Encoding::get_max_encoded_len`] for how tight the bound is.
+#[must_use]
+pub const fn max_encoded_len<const N: usize>(encoding: Encoding) -> usize {
+    encoding.get_max_encoded_len(N)
+}
+
+/// Returns the length of the hex encoding of `len` bytes.
+#[cfg(feature = "hex")]
+#[must_use]
+pub const fn hex_len(len: usize) -> usize {
+    len * 2
+}
+
+/// Encodes `bytes` as lowercase hex at compile time.
+///
+/// Stable Rust cannot compute an array length from an input length, so the
+/// output length `N` must be passed in; [`encode_const!`](crate::encode_const)
+/// does this for you.
+///
+/// # Panics
+/// Fails to compile, or panics at runtime, if `N` is not `hex_len(bytes.len())`.
+#[cfg(feature = "hex")]
+#[must_use]
+pub const fn hex<const N: usize>(bytes: &[u8]) -> [u8; N] {
+    assert!(
+        N == hex_len(bytes.len()),
+        "output length must be hex_len(input)"
+    );
+
+    let mut out = [0u8; N];
+    let mut i = 0;
+    while i < bytes.len() {
+        out[2 * i] = alphabet::HEX[(bytes[i] >> 4) as usize];
+        out[2 * i + 1] = alphabet::HEX[(bytes[i] & 0x0f) as usize];
+        i += 1;
+    }
+    out
+}
+
+/// Returns the length of the padded Base32 encoding of `len` bytes.
+#[cfg(feature = "base32")]
+#[must_use]
+pub const fn base32_len(len: usize) -> usize {
+    len.div_ceil(5) * 8
+}
+
+/// Encodes `bytes` as padded RFC 4648 Base32 at compile time.
+///
+/// `N` must be `base32_len(bytes.len())`, as for [`hex`].
+///
+/// # Panics
+/// Fails to compile, or panics at runtime, if `N` is not `base32_len(bytes.len())`.
+#[cfg(feature = "base32")]
+#[must_use]
+pub const fn base32<const N: usize>(bytes: &[u8]) -> [u8; N] {
+    assert!(
+        N == base32_len(bytes.len()),
+        "output length must be base32_len(input)"
+    );
+
+    let mut out = [b'='; N];
+    let mut block = 0;
+    while block * 5 < bytes.len() {
+        let start = block * 5;
+        let mut n: u64 = 0;
+        let mut j = 0;
+        while j < 5 {
+            let byte = if start + j < bytes.len() {
+                bytes[start + j]
+            } else {
+                0
+            };
+            n = (n << 8) | byte as u64;
+            j += 1;
+        }
+
+        let remaining = bytes.len() - start;
+        let digits = if remaining >= 5 {
+            8
+        } else {
+            (remaining * 8).div_ceil(5)
+        };
+        let mut k = 0;
+        while k < digits {
+            out[block * 8 + k] = alphabet::BASE32_STANDARD[((n >> (35 - 5 * k)) & 0x1f) as usize];
+            k += 1;
+        }
+        block += 1;
+    }
+    out
+}
+
+/// Returns the length of the padded Base64 encoding of `len` bytes.
+#[cfg(feature = "base64")]
+#[must_use]
+pub const fn base64_len(len: usize) -> usize {
+    len.div_ceil(3) * 4
+}
+
+/// Encodes `bytes` as padded RFC 4648 Base64 at compile time.
+///
+/// `N` must be `base64_len(bytes.len())`, as for [`hex`].
+///
+/// # Panics
+/// Fails to compile, or panics at runtime, if `N` is not `base64_len(bytes.len())`.
+#[cfg(feature = "base64")]
+#[must_use]
+pub const fn base64<const N: usize>(bytes: &[u8]) -> [u8; N] {
+    assert!(
+        N == base64_len(bytes.len()),
+        "output length must be base64_len(input)"
+    );
+
+    let mut out = [b'='; N];
+    let mut block = 0;
+    while block * 3 < bytes.len() {
+        let start = block * 3;
+        let mut n: u32 = 0;
+        let mut j = 0;
+        while j < 3 {
+            let byte = if start + j < bytes.len() {
+                bytes[start + j]
+            } else {
+                0
+            };
+            n = (n << 8) | byte as u32;
+            j += 1;
+        }
+
+        let remaining = bytes.len() - start;
+        let digits = if remaining >= 3 { 4 } else { remaining + 1 };
+        let mut k = 0;
+        while k < digits {
+            out[block * 4 + k] = alphabet::BASE64_STANDARD[((n >> (18 - 6 * k)) & 0x3f) as usize];
+            k += 1;
+        }
+        block += 1;
+    }
+    out
+}
+
+/// Encodes a constant byte string at compile time, producing a `&'static str`.
+///
+/// The first argument is `hex`, `base32` or `base64`, the second any constant
+/// expression of type `&[u8]`. The output matches the runtime encoders.
+#[macro_export]
+macro_rules! encode_const {
+    ($encoding:ident, $bytes:expr) => {{
+        const __ENCODE_CONST_INPUT: &[u8] = $bytes;
+        const __ENCODE_CONST_OUTPUT: [u8; $crate::__encode_const_len!(
+            $encoding,
+            __ENCODE_CONST_INPUT.len()
+        )] = $crate::const_encode::$encoding(__ENCODE_CONST_INPUT);
+        const __ENCODE_CONST_STR: &str = match ::core::str::from_utf8(&__ENCODE_CONST_OUTPUT) {
+            Ok(s) => s,
+            Err(_) => unreachable!(),
+        };
+        __ENCODE_CONST_STR
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __encode_const_len {
+    (hex, $len:expr) => {
+        $crate::const_encode::hex_len($len)
+    };
+    (base32, $len:expr) => {
+        $crate::const_encode::base32_len($len)
+    };
+    (base64, $len:expr) => {
+        $crate::const_encode::base64_len($len)
+    };
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "base32", feature = "base64", feature = "hex"))]
+mod tests {
+
+    use super::*;
+    use crate::{Base32, Base64, Hex};
+
+    const SAMPLE: &[u8] = b"\x00\xffforeign key!";
+
+    #[test]
+    fn test_matches_runtime_encoders() {
+        assert_eq!(
+            crate::encode_const!(hex, SAMPLE),
+            Hex::try_to_hex(SAMPLE).unwrap_or_default()
+        );
+        assert_eq!(
+            crate::encode_const!(base32, SAMPLE),
+            Base32::try_to_base32(SAMPLE).unwrap_or_default()
+        );
+        assert_eq!(
+            crate::encode_const!(base64, SAMPLE),
+            Base64::try_to_base64(SAMPLE).unwrap_or_default()
+        );
+        assert_eq!(crate::encode_const!(base64, b""), "");
+    }
+
+    #[test]
+    fn test_encode_const_macro() {
+        const HEX: &str = crate::encode_const!(hex, b"\xca\xfe");
+        const BASE32: &str = crate::encode_const!(base32, b"foob");
+        const BASE64: &str = crate::encode_const!(base64, b"fo");
+        const OUT: [u8; base32_len(1)] = base32(b"f");
+        assert_eq!(HEX, "cafe");
+        assert_eq!(BASE32, "MZXW6YQ=");
+        assert_eq!(BASE64, "Zm8=");
+        assert_eq!(&OUT, b"MY======");
+    }
+}
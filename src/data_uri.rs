@@ -0,0 +1,270 @@
+use std::{fmt::Display, sync::Arc};
+
+use crate::{Base64, ByteVec, SerialiseError};
+
+/// The media type of a data URI when none is given (RFC 2397 section 2).
+const DEFAULT_MEDIA_TYPE: &str = "text/plain;charset=US-ASCII";
+
+/// A media type such as `image/png` or `text/html;charset=utf-8`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaType {
+    /// The type and subtype, in lowercase
+    essence: String,
+    /// The parameters, with names in lowercase, in the order given
+    parameters: Vec<(String, String)>,
+}
+
+impl MediaType {
+    /// Parses a media type, such as the part of a data URI before the comma.
+    ///
+    /// Parameters without a value, such as `base64`, are skipped. A missing
+    /// type, as in `;charset=utf-8`, is `text/plain`.
+    ///
+    /// # Arguments
+    /// * `text` - The media type
+    ///
+    /// # Returns
+    /// The media type
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the type is not of the form `type/subtype`.
+    pub fn parse(text: &str) -> Result<Self, SerialiseError> {
+        let mut parts = text.split(';');
+        let essence = parts.next().unwrap_or_default().trim().to_ascii_lowercase();
+        let essence = if essence.is_empty() {
+            "text/plain".to_string()
+        } else {
+            essence
+        };
+        let is_token = |s: &str| {
+            !s.is_empty()
+                && s.bytes()
+                    .all(|c| c.is_ascii_alphanumeric() || b"!#$&-^_.+".contains(&c))
+        };
+        if !essence
+            .split_once('/')
+            .is_some_and(|(kind, subtype)| is_token(kind) && is_token(subtype))
+        {
+            return Err(SerialiseError::new(format!(
+                "invalid media type '{essence}'"
+            )));
+        }
+        let parameters = parts
+            .filter_map(|parameter| parameter.split_once('='))
+            .map(|(name, value)| {
+                (
+                    name.trim().to_ascii_lowercase(),
+                    value.trim().trim_matches('"').to_string(),
+                )
+            })
+            .collect();
+        Ok(Self {
+            essence,
+            parameters,
+        })
+    }
+
+    /// Returns the type and subtype in lowercase, e.g. `"image/png"`.
+    #[must_use = "This returns the essence but does nothing if unused"]
+    pub fn get_essence(&self) -> &str {
+        &self.essence
+    }
+
+    /// Returns the value of the parameter called `name`, ignoring case.
+    #[must_use = "This returns the parameter value but does nothing if unused"]
+    pub fn get_parameter(&self, name: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the parameters in the order given.
+    #[must_use = "This returns the parameters but does nothing if unused"]
+    pub fn get_parameters(&self) -> &[(String, String)] {
+        &self.parameters
+    }
+}
+
+impl Display for MediaType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.essence)?;
+        for (name, value) in &self.parameters {
+            write!(f, ";{name}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Decodes `%XX` escapes, leaving other characters as they are.
+fn percent_decode(text: &str) -> Result<Vec<u8>, SerialiseError> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let value = text
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| {
+                    SerialiseError::new(format!("invalid percent escape at position {i}"))
+                })?;
+            out.push(value);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    Ok(out)
+}
+
+/// Parses and decodes a data URI (RFC 2397), such as
+/// `data:image/png;base64,iVBORw0KGgo=`.
+///
+/// Base64 data may be percent-encoded, wrapped or unpadded; other data is
+/// percent-decoded.
+///
+/// # Arguments
+/// * `uri` - The data URI
+///
+/// # Returns
+/// The media type and the decoded bytes
+///
+/// # Errors
+/// Returns `SerialiseError` if `uri` does not start with `data:`, has no
+/// comma, has an invalid media type, or its data does not decode.
+pub fn parse_data_uri(uri: &str) -> Result<(MediaType, ByteVec), SerialiseError> {
+    let rest = uri
+        .get(..5)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("data:"))
+        .map(|_| &uri[5..])
+        .ok_or_else(|| SerialiseError::new("data URI must start with 'data:'".to_string()))?;
+    let Some((header, data)) = rest.split_once(',') else {
+        return Err(SerialiseError::new(
+            "data URI has no ',' before its data".to_string(),
+        ));
+    };
+    let (header, is_base64) = match header.rsplit_once(';') {
+        Some((media_type, flag)) if flag.trim().eq_ignore_ascii_case("base64") => {
+            (media_type, true)
+        }
+        _ => (header, false),
+    };
+    let media_type = MediaType::parse(if header.is_empty() {
+        DEFAULT_MEDIA_TYPE
+    } else {
+        header
+    })?;
+
+    let data = percent_decode(data)?;
+    let bytes = if is_base64 {
+        let text = std::str::from_utf8(&data)
+            .map_err(|_| SerialiseError::new("data URI base64 is not ASCII".to_string()))?;
+        Base64::try_from_base64(text, 0)?
+    } else {
+        data
+    };
+    Ok((media_type, ByteVec::new(Arc::new(bytes))))
+}
+
+/// Returns true if `c` ends a data URI embedded in HTML, CSS or JavaScript.
+const fn ends_uri(c: u8) -> bool {
+    matches!(c, b'"' | b'\'' | b'`' | b')' | b'<' | b'>') || c.is_ascii_whitespace()
+}
+
+/// Finds and decodes every data URI in a document, such as an HTML page, a
+/// stylesheet or a script.
+///
+/// A data URI runs from `data:` to the first quote, backtick, `)`, `<`, `>`
+/// or whitespace, which covers HTML attributes, CSS `url(...)` values and
+/// JavaScript strings. Data URIs that do not decode are skipped.
+///
+/// # Arguments
+/// * `document` - The document to search
+///
+/// # Returns
+/// The media type and decoded bytes of each data URI, in document order
+#[must_use = "This returns the decoded data URIs but does nothing if unused"]
+pub fn extract_data_uris(document: &str) -> Vec<(MediaType, ByteVec)> {
+    let bytes = document.as_bytes();
+    let mut found = Vec::new();
+    let mut start = 0;
+    while let Some(offset) = bytes[start..]
+        .windows(5)
+        .position(|w| w.eq_ignore_ascii_case(b"data:"))
+    {
+        let uri_start = start + offset;
+        let len = bytes[uri_start..]
+            .iter()
+            .position(|&c| ends_uri(c))
+            .unwrap_or(bytes.len() - uri_start);
+        let uri = &document[uri_start..uri_start + len];
+        if let Ok(decoded) = parse_data_uri(uri) {
+            found.push(decoded);
+        }
+        start = uri_start + len.max(5);
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_parse_data_uri() {
+        assert!(parse_data_uri("data:text/html;charset=UTF-8;base64,PGI+aGk8L2I+").is_ok());
+        let Ok((media_type, bytes)) =
+            parse_data_uri("data:text/html;charset=UTF-8;base64,PGI+aGk8L2I+")
+        else {
+            return;
+        };
+        assert_eq!(media_type.get_essence(), "text/html");
+        assert_eq!(media_type.get_parameter("Charset"), Some("UTF-8"));
+        assert_eq!(bytes.get_bytes(), b"<b>hi</b>");
+
+        assert!(matches!(
+            parse_data_uri("data:,A%20brief%20note"),
+            Ok((m, b)) if m.to_string() == "text/plain;charset=US-ASCII" && b.get_bytes() == b"A brief note"
+        ));
+        assert!(parse_data_uri("data:text/plain").is_err());
+        assert!(parse_data_uri("data:image;base64,AAAA").is_err());
+        assert!(parse_data_uri("http://example.com").is_err());
+    }
+
+    #[test]
+    fn test_extract_from_html_and_css() {
+        let document = r#"<img src="data:image/gif;base64,R0lGODlhAQABAAAAACw=" alt=x>
+<style>.a { background: url(data:image/png;base64,iVBORw0K) }
+.b { background: url('DATA:text/plain,hi') }</style>
+<script>const broken = "data:image/png;base64,!!!"; const s = `data:;base64,aGk=`;</script>"#;
+        let found = extract_data_uris(document);
+        let summary: Vec<(&str, usize)> = found
+            .iter()
+            .map(|(m, b)| (m.get_essence(), b.get_bytes().len()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("image/gif", 14),
+                ("image/png", 6),
+                ("text/plain", 2),
+                ("text/plain", 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_media_type() {
+        assert!(MediaType::parse("Image/SVG+XML; Charset=\"utf-8\"").is_ok());
+        let Ok(media_type) = MediaType::parse("Image/SVG+XML; Charset=\"utf-8\"") else {
+            return;
+        };
+        assert_eq!(media_type.get_essence(), "image/svg+xml");
+        assert_eq!(media_type.get_parameter("charset"), Some("utf-8"));
+        assert_eq!(media_type.to_string(), "image/svg+xml;charset=utf-8");
+        assert!(MediaType::parse("not a type").is_err());
+    }
+}
@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use crate::{ByteVec, EncodedString, Encoding, SerialiseError};
+
+/// Decodes input that may be in any of several encodings, such as IDs during
+/// a migration from hex to Base58.
+///
+/// Encodings are tried in order and the first one that decodes the input
+/// wins, so put the strictest first: `"cafe"` is valid hex, Base58 and
+/// Base64, and decodes to different bytes in each.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodePolicy {
+    /// The encodings to try, in order
+    encodings: Vec<Encoding>,
+}
+
+impl DecodePolicy {
+    /// Creates a policy that tries `encodings` in order.
+    ///
+    /// # Arguments
+    /// * `encodings` - The encodings to try, strictest first
+    ///
+    /// # Returns
+    /// The policy
+    #[must_use = "This returns the policy but does nothing if unused"]
+    pub fn chain(encodings: impl IntoIterator<Item = Encoding>) -> Self {
+        Self {
+            encodings: encodings.into_iter().collect(),
+        }
+    }
+
+    /// Returns the encodings tried, in order.
+    #[must_use = "This returns the encodings but does nothing if unused"]
+    pub fn get_encodings(&self) -> &[Encoding] {
+        &self.encodings
+    }
+
+    /// Decodes `input` with the first encoding in the chain that accepts it.
+    ///
+    /// # Arguments
+    /// * `input` - The encoded text
+    ///
+    /// # Returns
+    /// The encoding that matched and the decoded bytes
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the chain is empty or no encoding in it
+    /// decodes `input`, listing why each one failed.
+    pub fn decode(&self, input: &str) -> Result<(Encoding, Arc<ByteVec>), SerialiseError> {
+        if self.encodings.is_empty() {
+            return Err(SerialiseError::new(
+                "decode policy has no encodings to try".to_string(),
+            ));
+        }
+        let mut failures: Vec<String> = Vec::with_capacity(self.encodings.len());
+        for &encoding in &self.encodings {
+            match EncodedString::new(encoding, input.to_string()).try_decode() {
+                Ok(bytes) => return Ok((encoding, bytes)),
+                Err(e) => failures.push(format!("{encoding}: {}", e.get_message())),
+            }
+        }
+        Err(SerialiseError::new(format!(
+            "input is not valid in any encoding of the policy ({})",
+            failures.join("; ")
+        )))
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "base58", feature = "hex"))]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_first_match_wins() {
+        let policy = DecodePolicy::chain([Encoding::Hex, Encoding::Base58]);
+        assert!(matches!(
+            policy.decode("cafe"),
+            Ok((Encoding::Hex, bytes)) if bytes.get_bytes() == [0xca, 0xfe]
+        ));
+        let base58 = ByteVec::new(Arc::new(b"new id".to_vec()))
+            .try_encode(Encoding::Base58)
+            .map(|e| e.get_string().clone())
+            .unwrap_or_default();
+        assert!(matches!(
+            policy.decode(&base58),
+            Ok((Encoding::Base58, bytes)) if bytes.get_bytes() == b"new id"
+        ));
+    }
+
+    #[test]
+    fn test_order_matters() {
+        let policy = DecodePolicy::chain([Encoding::Base58, Encoding::Hex]);
+        assert!(matches!(policy.decode("cafe"), Ok((Encoding::Base58, _))));
+        assert_eq!(policy.get_encodings(), [Encoding::Base58, Encoding::Hex]);
+    }
+
+    #[test]
+    fn test_no_match_is_err() {
+        let policy = DecodePolicy::chain([Encoding::Hex, Encoding::Base58]);
+        assert!(matches!(
+            policy.decode("0OIl"),
+            Err(e) if e.get_message().contains("hex: ") && e.get_message().contains("base58: ")
+        ));
+        assert!(DecodePolicy::chain([]).decode("cafe").is_err());
+    }
+}
@@ -0,0 +1,277 @@
+#![cfg_attr(
+    all(feature = "no-panic", not(test)),
+    deny(
+        clippy::indexing_slicing,
+        clippy::panic,
+        clippy::unreachable,
+        clippy::unwrap_used,
+        clippy::expect_used
+    )
+)]
+
+#[cfg(any(feature = "base32", feature = "base64", feature = "hex"))]
+use std::mem::MaybeUninit;
+
+use crate::SerialiseError;
+
+/// Push-style incremental decoder.
+///
+/// Input can be fed in arbitrary pieces, for example as packets arrive, and
+/// each call returns the bytes that could be decoded so far. Characters that
+/// straddle a piece boundary are held until the next call.
+pub trait Decoder {
+    /// Feeds the next piece of encoded input.
+    ///
+    /// # Arguments
+    /// * `input` - The next piece of encoded text
+    ///
+    /// # Returns
+    /// The bytes decoded from this piece, valid until the next call
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the input is not valid for the encoding
+    fn feed(&mut self, input: &[u8]) -> Result<&[u8], SerialiseError>;
+
+    /// Signals the end of the input and resets the decoder for reuse.
+    ///
+    /// # Returns
+    /// Any bytes still held by the decoder
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the input ended part way through a value
+    fn finish(&mut self) -> Result<&[u8], SerialiseError>;
+}
+
+/// Shared state machine for encodings that map each character to a fixed
+/// number of bits (hex, Base32, Base64).
+///
+/// ASCII whitespace is skipped anywhere in the input.
+#[cfg(any(feature = "base32", feature = "base64", feature = "hex"))]
+#[derive(Debug)]
+pub(crate) struct BitDecoder {
+    /// Name of the encoding, used in error messages
+    name: &'static str,
+    /// Number of bits each character carries
+    bits_per_digit: u32,
+    /// Padding character and padded block length, or `None` if padding is not allowed
+    pad: Option<(u8, usize)>,
+    decode_digit: fn(u8) -> Option<u8>,
+    acc: u32,
+    bits: u32,
+    digits: usize,
+    padding: usize,
+    /// Number of bytes consumed since the last reset
+    position: usize,
+    out: Vec<u8>,
+}
+
+#[cfg(any(feature = "base32", feature = "base64", feature = "hex"))]
+impl BitDecoder {
+    pub(crate) const fn new(
+        name: &'static str,
+        bits_per_digit: u32,
+        pad: Option<(u8, usize)>,
+        decode_digit: fn(u8) -> Option<u8>,
+    ) -> Self {
+        Self {
+            name,
+            bits_per_digit,
+            pad,
+            decode_digit,
+            acc: 0,
+            bits: 0,
+            digits: 0,
+            padding: 0,
+            position: 0,
+            out: Vec::new(),
+        }
+    }
+
+    /// Returns true if `digits` characters carry a whole number of bytes with no
+    /// more characters than needed.
+    const fn is_complete(&self, digits: usize) -> bool {
+        let bits = digits * self.bits_per_digit as usize;
+        (bits / 8 * 8).div_ceil(self.bits_per_digit as usize) == digits
+    }
+
+    /// Consumes one character, returning a byte once enough bits have arrived.
+    fn push(&mut self, c: u8) -> Result<Option<u8>, SerialiseError> {
+        let position = self.position;
+        self.position += 1;
+        if !c.is_ascii() {
+            return Err(SerialiseError::new(format!(
+                "non-ASCII byte at position {position} in {} input",
+                self.name
+            ))
+            .with_kind(crate::ErrorKind::NonAsciiInput { position }));
+        }
+        if c.is_ascii_whitespace() {
+            return Ok(None);
+        }
+
+        if let Some((pad, block)) = self.pad
+            && c == pad
+        {
+            self.padding += 1;
+            if self.padding >= block {
+                return Err(SerialiseError::new(format!(
+                    "invalid {} padding",
+                    self.name
+                )));
+            }
+            return Ok(None);
+        }
+
+        let Some(digit) = (self.decode_digit)(c) else {
+            return Err(SerialiseError::new(format!(
+                "invalid {} character",
+                self.name
+            )));
+        };
+        if self.padding > 0 {
+            return Err(SerialiseError::new(format!(
+                "unexpected {} data after padding",
+                self.name
+            )));
+        }
+
+        self.acc = (self.acc << self.bits_per_digit) | u32::from(digit);
+        self.bits += self.bits_per_digit;
+        self.digits += 1;
+        let byte = if self.bits >= 8 {
+            self.bits -= 8;
+            Some(((self.acc >> self.bits) & 0xff) as u8)
+        } else {
+            None
+        };
+        self.acc &= (1 << self.bits) - 1;
+        Ok(byte)
+    }
+
+    /// Checks that `input` is complete and valid without keeping the decoded bytes.
+    pub(crate) fn validate(mut self, input: &str) -> Result<(), SerialiseError> {
+        for c in input.bytes() {
+            self.push(c)?;
+        }
+        self.finish().map(|_| ())
+    }
+
+    /// Decodes `input` into the start of `output` without initializing the
+    /// rest of the buffer first.
+    ///
+    /// This is the only unsafe code in the crate: stable Rust has no safe way
+    /// to view initialized `MaybeUninit` elements as a slice without copying
+    /// them, which is the point of this function.
+    #[allow(unsafe_code)]
+    pub(crate) fn decode_into_uninit<'a>(
+        mut self,
+        input: &str,
+        output: &'a mut [MaybeUninit<u8>],
+    ) -> Result<&'a [u8], SerialiseError> {
+        let mut len = 0;
+        for c in input.bytes() {
+            if let Some(byte) = self.push(c)? {
+                let Some(slot) = output.get_mut(len) else {
+                    return Err(SerialiseError::new(format!(
+                        "decoded {} does not fit in {} bytes",
+                        self.name,
+                        output.len()
+                    )));
+                };
+                slot.write(byte);
+                len += 1;
+            }
+        }
+        self.finish()?;
+        let written = output.get(..len).unwrap_or_default();
+        // SAFETY: `len` only grows right after `slot.write`, so each of the
+        // first `len` elements was initialized above, and `written` borrows
+        // `output` for `'a` so nothing can uninitialize them meanwhile.
+        Ok(unsafe { written.assume_init_ref() })
+    }
+
+    const fn reset(&mut self) {
+        self.acc = 0;
+        self.bits = 0;
+        self.digits = 0;
+        self.padding = 0;
+        self.position = 0;
+    }
+}
+
+#[cfg(any(feature = "base32", feature = "base64", feature = "hex"))]
+impl Decoder for BitDecoder {
+    fn feed(&mut self, input: &[u8]) -> Result<&[u8], SerialiseError> {
+        self.out.clear();
+        self.out
+            .reserve(input.len() * self.bits_per_digit as usize / 8);
+
+        for &c in input {
+            if let Some(byte) = self.push(c)? {
+                self.out.push(byte);
+            }
+        }
+
+        Ok(&self.out)
+    }
+
+    fn finish(&mut self) -> Result<&[u8], SerialiseError> {
+        self.out.clear();
+        let digits = self.digits;
+        let padding = self.padding;
+        self.reset();
+
+        if !self.is_complete(digits) {
+            return Err(SerialiseError::new(format!(
+                "{} input ended part way through a value",
+                self.name
+            )));
+        }
+        if let Some((_, block)) = self.pad
+            && padding > 0
+            && !(digits + padding).is_multiple_of(block)
+        {
+            return Err(SerialiseError::new(format!(
+                "invalid {} padding",
+                self.name
+            )));
+        }
+
+        Ok(&self.out)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "base64")]
+mod tests {
+
+    use super::*;
+    use crate::algorithm::Base64Decoder;
+
+    #[test]
+    fn test_feed_across_boundaries() {
+        let mut decoder = Base64Decoder::new();
+        let mut out: Vec<u8> = Vec::new();
+        for piece in ["Zm", "9vYm", "FyYm", "F6"] {
+            out.extend_from_slice(decoder.feed(piece.as_bytes()).unwrap_or_default());
+        }
+        assert!(decoder.finish().is_ok());
+        assert_eq!(out, b"foobarbaz");
+    }
+
+    #[test]
+    fn test_data_after_padding_is_err() {
+        let mut decoder = Base64Decoder::new();
+        assert!(decoder.feed(b"Zg==").is_ok());
+        assert!(decoder.feed(b"Zg").is_err());
+    }
+
+    #[test]
+    fn test_truncated_input_is_err_and_resets() {
+        let mut decoder = Base64Decoder::new();
+        assert!(decoder.feed(b"Zm9vY").is_ok());
+        assert!(decoder.finish().is_err());
+        assert!(matches!(decoder.feed(b"Zg=="), Ok(bytes) if bytes == b"f"));
+        assert!(decoder.finish().is_ok());
+    }
+}
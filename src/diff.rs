@@ -0,0 +1,181 @@
+use std::{fmt::Display, ops::Range};
+
+use crate::hexdump::{self, ASCII_COLUMN, BYTES_PER_ROW, hex_column, row_bytes};
+
+/// The differences between two byte strings, compared offset by offset.
+///
+/// Created by [`diff`] or [`ByteVec::diff`](crate::ByteVec::diff). The changes
+/// are the ranges of offsets where the bytes differ, including any bytes past
+/// the end of the shorter string. Displaying the diff, or iterating
+/// [`ByteDiff::lines`], renders each row with a change as a pair of hexdump
+/// lines, `-` for the left bytes and `+` for the right, with `^` marking the
+/// changed bytes underneath. Unchanged rows between them are shown as `*`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ByteDiff<'a> {
+    /// The bytes on the left, usually the expected value
+    left: &'a [u8],
+    /// The bytes on the right, usually the actual value
+    right: &'a [u8],
+    /// Ranges of offsets where the bytes differ, in order
+    changes: Vec<Range<usize>>,
+}
+
+impl ByteDiff<'_> {
+    /// Returns the ranges of offsets where the bytes differ, in order.
+    #[must_use = "This returns the changes but does nothing if unused"]
+    pub fn get_changes(&self) -> &[Range<usize>] {
+        &self.changes
+    }
+
+    /// Returns the number of offsets where the bytes differ.
+    #[must_use = "This returns the changed length but does nothing if unused"]
+    pub fn get_changed_len(&self) -> usize {
+        self.changes.iter().map(ExactSizeIterator::len).sum()
+    }
+
+    /// Returns true if the byte strings are the same.
+    #[must_use = "This returns whether the bytes are identical but does nothing if unused"]
+    pub const fn is_identical(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Returns true if the byte at `offset` differs or exists on one side only.
+    fn is_changed(&self, offset: usize) -> bool {
+        self.left.get(offset) != self.right.get(offset)
+    }
+
+    /// Renders the diff one line at a time, without building the whole text.
+    ///
+    /// # Returns
+    /// The lines of the rendered diff, without line endings
+    pub fn lines(&self) -> impl Iterator<Item = String> + '_ {
+        let rows = self
+            .left
+            .len()
+            .max(self.right.len())
+            .div_ceil(BYTES_PER_ROW);
+        let mut last_row: Option<usize> = None;
+        (0..rows)
+            .filter(|&row| {
+                let start = row * BYTES_PER_ROW;
+                (start..start + BYTES_PER_ROW).any(|offset| self.is_changed(offset))
+            })
+            .flat_map(move |row| {
+                let skipped = last_row.map_or(row > 0, |last| row > last + 1);
+                last_row = Some(row);
+                let start = row * BYTES_PER_ROW;
+                let changed: Vec<bool> = (start..start + BYTES_PER_ROW)
+                    .map(|offset| self.is_changed(offset))
+                    .collect();
+                skipped.then(|| "*".to_string()).into_iter().chain([
+                    render_row('-', start, row_bytes(self.left, start)),
+                    render_row('+', start, row_bytes(self.right, start)),
+                    render_markers(&changed),
+                ])
+            })
+    }
+}
+
+impl Display for ByteDiff<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in self.lines() {
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders one row of bytes as a hexdump row prefixed with `sign`.
+fn render_row(sign: char, start: usize, bytes: &[u8]) -> String {
+    let row = hexdump::render_row(start, bytes, |_| None, &mut |cell| {
+        cell.get_text().to_string()
+    });
+    format!("{sign}{row}")
+}
+
+/// Renders `^` under the hex and ASCII columns of each changed byte.
+fn render_markers(changed: &[bool]) -> String {
+    // Rows are shifted one column right by the sign.
+    let mut line = vec![b' '; 1 + ASCII_COLUMN + BYTES_PER_ROW];
+    for (i, _) in changed.iter().enumerate().filter(|&(_, &changed)| changed) {
+        line[1 + hex_column(i)] = b'^';
+        line[2 + hex_column(i)] = b'^';
+        line[1 + ASCII_COLUMN + i] = b'^';
+    }
+    String::from_utf8_lossy(&line).trim_end().to_string()
+}
+
+/// Compares two byte strings offset by offset.
+///
+/// Bytes are compared at the same offset, not aligned by content, so an
+/// inserted byte shows every later byte as changed; this suits fixed-layout
+/// payloads such as headers and records.
+///
+/// # Arguments
+/// * `left` - The bytes on the left, usually the expected value
+/// * `right` - The bytes on the right, usually the actual value
+///
+/// # Returns
+/// The differences, which display as an annotated hexdump
+#[must_use = "This returns the diff but does nothing if unused"]
+pub fn diff<'a>(left: &'a [u8], right: &'a [u8]) -> ByteDiff<'a> {
+    let mut changes: Vec<Range<usize>> = Vec::new();
+    for offset in 0..left.len().max(right.len()) {
+        if left.get(offset) == right.get(offset) {
+            continue;
+        }
+        match changes.last_mut() {
+            Some(last) if last.end == offset => last.end += 1,
+            _ => changes.push(offset..offset + 1),
+        }
+    }
+    ByteDiff {
+        left,
+        right,
+        changes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_changes() {
+        let result = diff(b"hello world", b"hellO wOrld!!");
+        assert_eq!(result.get_changes(), [4..5, 7..8, 11..13]);
+        assert_eq!(result.get_changed_len(), 4);
+        assert!(!result.is_identical());
+        assert!(diff(b"same", b"same").is_identical());
+        assert_eq!(diff(b"same", b"same").to_string(), "");
+    }
+
+    #[test]
+    fn test_render() {
+        let result = diff(b"Hello world!", b"Hello World");
+        let lines: Vec<String> = result.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "-00000000  48 65 6c 6c 6f 20 77 6f  72 6c 64 21              |Hello world!|",
+                "+00000000  48 65 6c 6c 6f 20 57 6f  72 6c 64                 |Hello World|",
+                "                             ^^              ^^                     ^    ^",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unchanged_rows_are_skipped() {
+        let left = [0u8; 64];
+        let mut right = left;
+        right[1] = 1;
+        right[50] = 2;
+        let lines: Vec<String> = diff(&left, &right).lines().collect();
+        assert_eq!(lines.len(), 7);
+        assert!(lines[0].starts_with("-00000000"));
+        assert_eq!(lines[3], "*");
+        assert!(lines[4].starts_with("-00000030"));
+        assert!(lines[5].starts_with("+00000030  00 00 02 00"));
+    }
+}
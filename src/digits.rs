@@ -0,0 +1,101 @@
+use std::borrow::Cow;
+
+/// The zero of every run of ten decimal digits in Unicode (general category
+/// `Nd`), in code point order. Each run holds the digits 0 to 9 in order.
+const DIGIT_ZEROS: &[u32] = &[
+    0x0030, 0x0660, 0x06F0, 0x07C0, 0x0966, 0x09E6, 0x0A66, 0x0AE6, 0x0B66, 0x0BE6, 0x0C66, 0x0CE6,
+    0x0D66, 0x0DE6, 0x0E50, 0x0ED0, 0x0F20, 0x1040, 0x1090, 0x17E0, 0x1810, 0x1946, 0x19D0, 0x1A80,
+    0x1A90, 0x1B50, 0x1BB0, 0x1C40, 0x1C50, 0xA620, 0xA8D0, 0xA900, 0xA9D0, 0xA9F0, 0xAA50, 0xABF0,
+    0xFF10, 0x104A0, 0x10D30, 0x11066, 0x110F0, 0x11136, 0x111D0, 0x112F0, 0x11450, 0x114D0,
+    0x11650, 0x116C0, 0x11730, 0x118E0, 0x11950, 0x11C50, 0x11D50, 0x11DA0, 0x11F50, 0x16A60,
+    0x16AC0, 0x16B50, 0x1D7CE, 0x1D7D8, 0x1D7E2, 0x1D7EC, 0x1D7F6, 0x1E140, 0x1E2F0, 0x1E4F0,
+    0x1E950, 0x1FBF0,
+];
+
+/// Returns the value of a decimal digit in any script, such as `3` for the
+/// Arabic-Indic digit `'٣'` or the fullwidth digit `'３'`.
+///
+/// # Arguments
+/// * `c` - The character
+///
+/// # Returns
+/// The digit's value from 0 to 9, or `None` if `c` is not a decimal digit
+#[must_use = "This returns the digit value but does nothing if unused"]
+pub fn unicode_digit_value(c: char) -> Option<u8> {
+    let code = u32::from(c);
+    let at = DIGIT_ZEROS.partition_point(|&zero| zero <= code);
+    let zero = DIGIT_ZEROS.get(at.checked_sub(1)?)?;
+    u8::try_from(code - zero).ok().filter(|&value| value < 10)
+}
+
+/// Replaces decimal digits from other scripts with ASCII digits, for input
+/// typed on mobile keyboards set to a locale with its own digits.
+///
+/// Decoders only accept ASCII digits and reject others with
+/// [`ErrorKind::NonAsciiInput`](crate::ErrorKind::NonAsciiInput), so call
+/// this first to accept them. Other characters are left as they are.
+///
+/// # Arguments
+/// * `input` - The text as entered
+///
+/// # Returns
+/// The text with every decimal digit in ASCII, borrowed if nothing changed
+#[must_use = "This returns the normalized text but does nothing if unused"]
+pub fn normalize_digits(input: &str) -> Cow<'_, str> {
+    if input.is_ascii() {
+        return Cow::Borrowed(input);
+    }
+    Cow::Owned(
+        input
+            .chars()
+            .map(|c| match unicode_digit_value(c) {
+                Some(value) if !c.is_ascii() => char::from(b'0' + value),
+                _ => c,
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_unicode_digit_value() {
+        assert_eq!(unicode_digit_value('7'), Some(7));
+        assert_eq!(unicode_digit_value('\u{0663}'), Some(3));
+        assert_eq!(unicode_digit_value('\u{06F9}'), Some(9));
+        assert_eq!(unicode_digit_value('\u{0966}'), Some(0));
+        assert_eq!(unicode_digit_value('\u{FF15}'), Some(5));
+        assert_eq!(unicode_digit_value('\u{1D7FF}'), Some(9));
+        for c in ['a', '/', ':', '\u{066A}', '\u{00B2}', '\u{2167}'] {
+            assert_eq!(unicode_digit_value(c), None, "{c}");
+        }
+    }
+
+    #[test]
+    fn test_normalize_digits() {
+        assert!(matches!(normalize_digits("12ab"), Cow::Borrowed("12ab")));
+        assert_eq!(normalize_digits("\u{0661}\u{0662}\u{0663}"), "123");
+        assert_eq!(normalize_digits("A\u{FF10}-\u{0967}é"), "A0-1é");
+    }
+
+    #[test]
+    #[cfg(feature = "base36")]
+    fn test_decoders_name_unicode_digits() {
+        use crate::{EncodedString, Encoding, ErrorKind};
+
+        let typed = EncodedString::new(Encoding::Base36, "ab\u{0663}".to_string());
+        assert!(matches!(
+            typed.try_decode(),
+            Err(e) if e.get_kind() == ErrorKind::NonAsciiInput { position: 2 }
+                && e.get_message().contains("digit 3")
+        ));
+        let normalized = EncodedString::new(
+            Encoding::Base36,
+            normalize_digits(typed.get_string()).into_owned(),
+        );
+        assert!(normalized.try_decode().is_ok());
+    }
+}
@@ -0,0 +1,171 @@
+use crate::{Base32, Base32Config, SerialiseError};
+
+/// Longest label DNS allows (RFC 1035 section 2.3.4).
+pub const MAX_LABEL_LEN: usize = 63;
+
+/// Longest domain name DNS allows in text form, without the trailing dot.
+pub const MAX_NAME_LEN: usize = 253;
+
+/// Encodes `bytes` as DNS labels of at most 63 characters.
+///
+/// The bytes are written as unpadded lowercase Base32, which only uses letters
+/// and digits, so labels never contain or start or end with a hyphen, and
+/// survive resolvers that change the case of names.
+///
+/// # Arguments
+/// * `bytes` - The bytes to encode
+///
+/// # Returns
+/// The labels in order, none for empty input
+///
+/// # Errors
+/// Returns `SerialiseError` if Base32 encoding fails.
+pub fn encode_labels(bytes: &[u8]) -> Result<Vec<String>, SerialiseError> {
+    let encoded = Base32::try_to_base32_with(bytes, Base32Config::LOWERCASE)?;
+    Ok(encoded
+        .as_bytes()
+        .chunks(MAX_LABEL_LEN)
+        .map(|label| String::from_utf8_lossy(label).into_owned())
+        .collect())
+}
+
+/// Decodes labels written by [`encode_labels`], in either case.
+///
+/// # Arguments
+/// * `labels` - The labels in order
+///
+/// # Returns
+/// The decoded bytes
+///
+/// # Errors
+/// Returns `SerialiseError` if a label is empty, longer than 63 characters or
+/// contains characters outside the Base32 alphabet, or the joined labels are not
+/// valid Base32.
+pub fn decode_labels<S: AsRef<str>>(labels: &[S]) -> Result<Vec<u8>, SerialiseError> {
+    let mut encoded = String::with_capacity(labels.len() * MAX_LABEL_LEN);
+    for label in labels {
+        let label = label.as_ref();
+        if label.is_empty() || label.len() > MAX_LABEL_LEN {
+            return Err(SerialiseError::new(format!(
+                "DNS label '{label}' must be 1 to {MAX_LABEL_LEN} characters"
+            )));
+        }
+        if label.contains(|c: char| c.is_whitespace()) {
+            return Err(SerialiseError::new(format!(
+                "DNS label '{label}' contains whitespace"
+            )));
+        }
+        encoded.push_str(&label.to_ascii_uppercase());
+    }
+    Base32::try_from_base32_with(&encoded, Base32Config::NO_PAD)
+}
+
+/// Encodes `bytes` as labels under `zone`, e.g. `<labels>.t.example.com`.
+///
+/// # Arguments
+/// * `bytes` - The bytes to encode
+/// * `zone` - The domain to append, without leading or trailing dots
+///
+/// # Returns
+/// The domain name, without a trailing dot
+///
+/// # Errors
+/// Returns `SerialiseError` if the name would be longer than 253 characters.
+pub fn encode_name(bytes: &[u8], zone: &str) -> Result<String, SerialiseError> {
+    let mut labels = encode_labels(bytes)?;
+    if !zone.is_empty() {
+        labels.push(zone.to_string());
+    }
+    let name = labels.join(".");
+    if name.len() > MAX_NAME_LEN {
+        return Err(SerialiseError::new(format!(
+            "{} bytes under '{zone}' need a {} character name, longer than {MAX_NAME_LEN}",
+            bytes.len(),
+            name.len()
+        )));
+    }
+    Ok(name)
+}
+
+/// Decodes a domain name written by [`encode_name`], ignoring case and a
+/// trailing dot.
+///
+/// # Arguments
+/// * `name` - The domain name
+/// * `zone` - The domain the data labels are under
+///
+/// # Returns
+/// The decoded bytes
+///
+/// # Errors
+/// Returns `SerialiseError` if `name` is not under `zone` or its data labels
+/// are invalid.
+pub fn decode_name(name: &str, zone: &str) -> Result<Vec<u8>, SerialiseError> {
+    let name = name.strip_suffix('.').unwrap_or(name);
+    let data = if zone.is_empty() {
+        Some(name)
+    } else if name.eq_ignore_ascii_case(zone) {
+        Some("")
+    } else if name.len() > zone.len() && name.is_char_boundary(name.len() - zone.len()) {
+        let (data, suffix) = name.split_at(name.len() - zone.len());
+        data.strip_suffix('.')
+            .filter(|_| suffix.eq_ignore_ascii_case(zone))
+    } else {
+        None
+    };
+    let Some(data) = data else {
+        return Err(SerialiseError::new(format!(
+            "'{name}' is not a name under '{zone}'"
+        )));
+    };
+    if data.is_empty() {
+        return Ok(Vec::new());
+    }
+    decode_labels(&data.split('.').collect::<Vec<&str>>())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_encode_labels() {
+        let bytes = [0xa5; 100];
+        let labels = encode_labels(&bytes).unwrap_or_default();
+        assert_eq!(
+            labels.iter().map(String::len).collect::<Vec<_>>(),
+            [63, 63, 34]
+        );
+        assert!(labels.iter().all(|label| {
+            label
+                .bytes()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        }));
+        assert!(matches!(decode_labels(&labels), Ok(decoded) if decoded == bytes));
+        assert!(matches!(encode_labels(b""), Ok(labels) if labels.is_empty()));
+    }
+
+    #[test]
+    fn test_name_round_trip() {
+        let name = encode_name(b"hello, world", "t.example.com").unwrap_or_default();
+        assert_eq!(name, "nbswy3dpfqqho33snrsa.t.example.com");
+        assert!(matches!(
+            decode_name(&format!("{}.", name.to_uppercase()), "t.example.com"),
+            Ok(decoded) if decoded == b"hello, world"
+        ));
+        assert!(
+            matches!(decode_name("t.example.com", "t.example.com"), Ok(decoded) if decoded.is_empty())
+        );
+    }
+
+    #[test]
+    fn test_invalid_is_err() {
+        assert!(encode_name(&[0; 200], "example.com").is_err());
+        assert!(decode_name("nbswy3dp.example.org", "example.com").is_err());
+        assert!(decode_name("nbswy3dp.xexample.com", "example.com").is_err());
+        assert!(decode_labels(&["nbsw", ""]).is_err());
+        assert!(decode_labels(&["nbsw-y3dp"]).is_err());
+        assert!(decode_labels(&["a".repeat(64)]).is_err());
+    }
+}
@@ -0,0 +1,228 @@
+use crate::{ByteVec, EncodedString, Encoding, LineEnding, SerialiseError};
+
+/// Builder for encodes that need options beyond the default output.
+///
+/// Created by [`ByteVec::encode_with`]. Each option is checked against the
+/// encoding when [`EncodeBuilder::finish`] runs, so asking for an option that
+/// would make the output undecodable is an error rather than silently ignored.
+#[derive(Debug, Clone)]
+pub struct EncodeBuilder<'a> {
+    /// The bytes to encode
+    byte_vec: &'a ByteVec,
+    /// The encoding to use
+    encoding: Encoding,
+    /// Maximum line length, or `None` for a single line
+    wrap: Option<usize>,
+    /// Line ending between wrapped lines
+    line_ending: LineEnding,
+    /// Whether to write letters in uppercase
+    upper: bool,
+    /// Whether to keep trailing `=` padding
+    pad: bool,
+}
+
+impl<'a> EncodeBuilder<'a> {
+    /// Creates a builder with the default options: one line, the encoding's
+    /// natural case and padding where the encoding has it.
+    ///
+    /// # Arguments
+    /// * `byte_vec` - The bytes to encode
+    /// * `encoding` - The encoding to use
+    #[must_use = "This creates a builder but does nothing until finished"]
+    pub const fn new(byte_vec: &'a ByteVec, encoding: Encoding) -> Self {
+        Self {
+            byte_vec,
+            encoding,
+            wrap: None,
+            line_ending: LineEnding::Lf,
+            upper: false,
+            pad: true,
+        }
+    }
+
+    /// Breaks the output into lines of at most `width` characters, or a single line if zero.
+    ///
+    /// Applies to Base32, Base64 and hex.
+    #[must_use = "This returns the updated builder but does nothing until finished"]
+    pub const fn wrap(mut self, width: usize) -> Self {
+        self.wrap = if width == 0 { None } else { Some(width) };
+        self
+    }
+
+    /// Separates wrapped lines with `line_ending` instead of `\n`.
+    ///
+    /// Only affects output that is wrapped with [`EncodeBuilder::wrap`].
+    #[must_use = "This returns the updated builder but does nothing until finished"]
+    pub const fn line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    /// Writes letters in uppercase.
+    ///
+    /// Applies to case-insensitive encodings: Base32, Base36 and hex.
+    #[must_use = "This returns the updated builder but does nothing until finished"]
+    pub const fn upper(mut self) -> Self {
+        self.upper = true;
+        self
+    }
+
+    /// Keeps or drops trailing `=` padding.
+    ///
+    /// Dropping padding applies to Base32 and Base64, and is a no-op for the
+    /// encodings that never pad (Base36, Base45, Base58, Base62, hex and file names).
+    #[must_use = "This returns the updated builder but does nothing until finished"]
+    pub const fn pad(mut self, pad: bool) -> Self {
+        self.pad = pad;
+        self
+    }
+
+    const fn supports_wrap(encoding: Encoding) -> bool {
+        match encoding {
+            #[cfg(feature = "base32")]
+            Encoding::Base32 => true,
+            #[cfg(feature = "base64")]
+            Encoding::Base64 => true,
+            #[cfg(feature = "hex")]
+            Encoding::Hex => true,
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+
+    const fn supports_upper(encoding: Encoding) -> bool {
+        match encoding {
+            #[cfg(feature = "base32")]
+            Encoding::Base32 => true,
+            #[cfg(feature = "base36")]
+            Encoding::Base36 => true,
+            #[cfg(feature = "hex")]
+            Encoding::Hex => true,
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+
+    const fn supports_unpadded(encoding: Encoding) -> bool {
+        match encoding {
+            #[cfg(feature = "base32")]
+            Encoding::Base32 => true,
+            #[cfg(feature = "base36")]
+            Encoding::Base36 => true,
+            #[cfg(feature = "base45")]
+            Encoding::Base45 => true,
+            #[cfg(feature = "filename-safe")]
+            Encoding::FilenameSafe => true,
+            #[cfg(feature = "base58")]
+            Encoding::Base58 => true,
+            #[cfg(feature = "base62")]
+            Encoding::Base62 => true,
+            #[cfg(feature = "base64")]
+            Encoding::Base64 => true,
+            #[cfg(feature = "hex")]
+            Encoding::Hex => true,
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+
+    fn unsupported(&self, option: &str) -> SerialiseError {
+        SerialiseError::new(format!("{option} is not supported for {}", self.encoding))
+    }
+
+    /// Encodes the bytes with the chosen options.
+    ///
+    /// # Returns
+    /// The encoded string, which decodes back to the original bytes
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if encoding fails or an option does not apply to the encoding.
+    pub fn finish(self) -> Result<EncodedString, SerialiseError> {
+        if self.wrap.is_some() && !Self::supports_wrap(self.encoding) {
+            return Err(self.unsupported("line wrapping"));
+        }
+        if self.upper && !Self::supports_upper(self.encoding) {
+            return Err(self.unsupported("uppercase output"));
+        }
+        if !self.pad && !Self::supports_unpadded(self.encoding) {
+            return Err(self.unsupported("unpadded output"));
+        }
+
+        let encoded = self.byte_vec.try_encode(self.encoding)?;
+        let mut string = encoded.get_string().clone();
+
+        if !self.pad {
+            string.truncate(string.trim_end_matches('=').len());
+        }
+        if self.upper {
+            string.make_ascii_uppercase();
+        }
+        if let Some(width) = self.wrap {
+            string = self.line_ending.wrap(&string, width);
+        }
+
+        Ok(EncodedString::new(self.encoding, string).with_integrity(self.byte_vec.get_bytes()))
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "base58", feature = "base64", feature = "hex"))]
+mod tests {
+
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn test_wrap_without_padding() {
+        let bytes = ByteVec::new(Arc::new(b"foobarbazq".to_vec()));
+        let encoded = bytes
+            .encode_with(Encoding::Base64)
+            .wrap(4)
+            .pad(false)
+            .finish();
+        assert!(matches!(
+            &encoded,
+            Ok(encoded) if encoded.get_string() == "Zm9v\nYmFy\nYmF6\ncQ"
+        ));
+        assert!(matches!(
+            encoded.and_then(|encoded| encoded.canonicalize()),
+            Ok(canonical) if canonical.get_string() == "Zm9vYmFyYmF6cQ=="
+        ));
+    }
+
+    #[test]
+    fn test_upper_hex() {
+        let bytes = ByteVec::new(Arc::new(vec![0xca, 0xfe]));
+        assert!(matches!(
+            bytes.encode_with(Encoding::Hex).upper().finish(),
+            Ok(encoded) if encoded.get_string() == "CAFE"
+        ));
+    }
+
+    #[test]
+    fn test_unsupported_option_is_err() {
+        let bytes = ByteVec::new(Arc::new(vec![0xca, 0xfe]));
+        assert!(
+            bytes
+                .encode_with(Encoding::Base58)
+                .upper()
+                .finish()
+                .is_err()
+        );
+        assert!(
+            bytes
+                .encode_with(Encoding::Base58)
+                .wrap(4)
+                .finish()
+                .is_err()
+        );
+        assert!(
+            bytes
+                .encode_with(Encoding::Base58)
+                .pad(false)
+                .finish()
+                .is_ok()
+        );
+    }
+}
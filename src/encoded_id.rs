@@ -0,0 +1,281 @@
+use std::sync::Arc;
+
+use crate::{ByteVec, EncodedString, Encoding, SerialiseError};
+
+/// Encodes the bytes of an ID generated by [`encoded_id!`](crate::encoded_id).
+///
+/// # Arguments
+/// * `encoding` - The ID type's encoding
+/// * `bytes` - The ID's bytes
+///
+/// # Returns
+/// The encoded ID
+///
+/// # Errors
+/// Returns `SerialiseError` if `encoding` fails to encode the bytes.
+#[doc(hidden)]
+pub fn encode(encoding: Encoding, bytes: &[u8]) -> Result<String, SerialiseError> {
+    Ok(ByteVec::new(Arc::new(bytes.to_vec()))
+        .try_encode(encoding)?
+        .into_string())
+}
+
+/// Decodes and validates the text of an ID generated by
+/// [`encoded_id!`](crate::encoded_id).
+///
+/// # Arguments
+/// * `name` - The ID type's name, for error messages
+/// * `encoding` - The ID type's encoding
+/// * `text` - The encoded ID
+///
+/// # Returns
+/// The ID's bytes
+///
+/// # Errors
+/// Returns `SerialiseError` if `text` is not valid in `encoding` or does not
+/// decode to exactly `N` bytes.
+#[doc(hidden)]
+pub fn decode<const N: usize>(
+    name: &str,
+    encoding: Encoding,
+    text: &str,
+) -> Result<[u8; N], SerialiseError> {
+    let decoded = EncodedString::new(encoding, text.to_string())
+        .try_decode()
+        .map_err(|e| e.with_context(format!("parsing {name}")))?;
+    let bytes = decoded.get_bytes();
+    let mut id = [0u8; N];
+    // Number encodings drop leading zero bytes, which a fixed length restores.
+    let pad = if encoding.is_numeric() {
+        N.saturating_sub(bytes.len())
+    } else {
+        0
+    };
+    if pad + bytes.len() != N {
+        return Err(SerialiseError::new(format!(
+            "{name} must decode to {N} bytes, but \"{text}\" decodes to {}",
+            bytes.len()
+        )));
+    }
+    id[pad..].copy_from_slice(bytes);
+    Ok(id)
+}
+
+/// Deserializes an ID generated by [`encoded_id!`](crate::encoded_id) from
+/// its encoded string.
+///
+/// # Errors
+/// Returns the deserializer's error if the input is not a string or does not
+/// parse as `T`.
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: std::str::FromStr<Err = SerialiseError>,
+{
+    /// Parses a borrowed or owned string as `T`.
+    struct Visitor<T>(std::marker::PhantomData<T>);
+
+    impl<T: std::str::FromStr<Err = SerialiseError>> serde::de::Visitor<'_> for Visitor<T> {
+        type Value = T;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("an encoded ID string")
+        }
+
+        fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<T, E> {
+            v.parse().map_err(E::custom)
+        }
+    }
+
+    deserializer.deserialize_str(Visitor(std::marker::PhantomData))
+}
+
+/// Generates a newtype for IDs of a fixed number of bytes that are written in
+/// one encoding.
+///
+/// `encoded_id!(pub OrderId, Encoding::Base58, 16)` defines `OrderId`
+/// wrapping `[u8; 16]`, with `Display` writing the Base58 text and `FromStr`
+/// and `TryFrom<&str>` accepting only text that decodes to exactly 16 bytes.
+/// It also derives `Clone`, `Copy`, `Eq`, `Ord` and `Hash`, converts from and
+/// to the byte array, and formats with `Debug` as `OrderId("...")`. With the
+/// `serde` feature, it serializes as its encoded string.
+///
+/// Base36, Base58 and Base62 write the bytes as a number without its leading
+/// zero bytes, so their IDs are shorter when they start with zeros; parsing
+/// restores them.
+///
+/// Attributes such as doc comments can be written before the visibility. The
+/// encoding is any constant expression of type [`Encoding`](crate::Encoding).
+#[macro_export]
+macro_rules! encoded_id {
+    ($(#[$meta:meta])* $vis:vis $name:ident, $encoding:expr, $len:expr) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        $vis struct $name([u8; $len]);
+
+        #[allow(dead_code)]
+        impl $name {
+            /// The encoding IDs are written in
+            pub const ENCODING: $crate::Encoding = $encoding;
+            /// The number of bytes in an ID
+            pub const LEN: usize = $len;
+
+            /// Creates an ID from its bytes.
+            #[must_use]
+            pub const fn from_bytes(bytes: [u8; $len]) -> Self {
+                Self(bytes)
+            }
+
+            /// Returns the bytes of the ID.
+            #[must_use]
+            pub const fn get_bytes(&self) -> &[u8; $len] {
+                &self.0
+            }
+
+            /// Returns the bytes of the ID by value.
+            #[must_use]
+            pub const fn into_bytes(self) -> [u8; $len] {
+                self.0
+            }
+        }
+
+        impl ::core::convert::From<[u8; $len]> for $name {
+            fn from(bytes: [u8; $len]) -> Self {
+                Self(bytes)
+            }
+        }
+
+        impl ::core::convert::From<$name> for [u8; $len] {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl ::core::convert::AsRef<[u8]> for $name {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl ::core::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                let text = $crate::encoded_id::encode(Self::ENCODING, &self.0)
+                    .map_err(|_| ::core::fmt::Error)?;
+                f.write_str(&text)
+            }
+        }
+
+        impl ::core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.debug_tuple(stringify!($name))
+                    .field(&::std::string::ToString::to_string(self))
+                    .finish()
+            }
+        }
+
+        impl ::core::str::FromStr for $name {
+            type Err = $crate::SerialiseError;
+
+            fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                $crate::encoded_id::decode(stringify!($name), Self::ENCODING, s).map(Self)
+            }
+        }
+
+        impl ::core::convert::TryFrom<&str> for $name {
+            type Error = $crate::SerialiseError;
+
+            fn try_from(s: &str) -> ::core::result::Result<Self, Self::Error> {
+                s.parse()
+            }
+        }
+
+        $crate::__encoded_id_serde!($name);
+    };
+}
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __encoded_id_serde {
+    ($name:ident) => {
+        impl $crate::__serde::Serialize for $name {
+            fn serialize<S: $crate::__serde::Serializer>(
+                &self,
+                serializer: S,
+            ) -> ::core::result::Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> $crate::__serde::Deserialize<'de> for $name {
+            fn deserialize<D: $crate::__serde::Deserializer<'de>>(
+                deserializer: D,
+            ) -> ::core::result::Result<Self, D::Error> {
+                $crate::encoded_id::deserialize(deserializer)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __encoded_id_serde {
+    ($name:ident) => {};
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "base58", feature = "hex"))]
+mod tests {
+
+    use crate::Encoding;
+
+    crate::encoded_id!(
+        /// An order ID
+        pub OrderId, Encoding::Base58, 16
+    );
+    crate::encoded_id!(ShortId, Encoding::Hex, 4);
+
+    #[test]
+    fn test_round_trips_through_display_and_from_str() {
+        let id = OrderId::from_bytes([0, 0, 7, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 255]);
+        let text = id.to_string();
+        assert!(matches!(text.parse::<OrderId>(), Ok(parsed) if parsed == id));
+        assert_eq!(format!("{id:?}"), format!("OrderId(\"{text}\")"));
+        assert_eq!(
+            ShortId::from([0xde, 0xad, 0xbe, 0xef]).to_string(),
+            "deadbeef"
+        );
+        assert!(
+            matches!(ShortId::try_from("DEADBEEF"), Ok(id) if id.into_bytes() == [0xde, 0xad, 0xbe, 0xef])
+        );
+    }
+
+    #[test]
+    fn test_rejects_wrong_length_and_invalid_text() {
+        assert!(
+            matches!("deadbe".parse::<ShortId>(), Err(e) if e.to_string().contains("ShortId must decode to 4 bytes"))
+        );
+        assert!("deadbeef00".parse::<ShortId>().is_err());
+        assert!("zzzzzzzz".parse::<ShortId>().is_err());
+        assert!("0OIl".parse::<OrderId>().is_err());
+        assert_eq!(OrderId::LEN, 16);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_uses_encoded_string() {
+        let id = ShortId::from_bytes([1, 2, 3, 4]);
+        assert_eq!(
+            serde_json::to_string(&id).unwrap_or_default(),
+            "\"01020304\""
+        );
+        assert!(
+            matches!(serde_json::from_str::<ShortId>("\"01020304\""), Ok(parsed) if parsed == id)
+        );
+        assert!(serde_json::from_str::<ShortId>("\"0102\"").is_err());
+        assert!(serde_json::from_str::<ShortId>("16909060").is_err());
+    }
+}
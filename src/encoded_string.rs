@@ -1,20 +1,65 @@
-use std::sync::Arc;
+use std::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
 
 use crate::{
-    Base36, ByteVec, Encoder, Encoding, SerialiseError,
-    algorithm::{Base58, Base64, Hex, Uuencode},
+    ByteVec, Encoder, Encoding, ErrorKind, SerialiseError, byte_vec::ct_eq, checksum::Adler32,
+    instrument,
 };
 
+#[cfg(feature = "base32")]
+use crate::algorithm::Base32;
+#[cfg(feature = "base36")]
+use crate::algorithm::Base36;
+#[cfg(feature = "base45")]
+use crate::algorithm::Base45;
+#[cfg(feature = "base58")]
+use crate::algorithm::Base58;
+#[cfg(feature = "base62")]
+use crate::algorithm::Base62;
+#[cfg(feature = "base64")]
+use crate::algorithm::Base64;
+#[cfg(feature = "base94")]
+use crate::algorithm::Base94;
+#[cfg(feature = "binhex")]
+use crate::algorithm::BinHex;
+#[cfg(feature = "escaped")]
+use crate::algorithm::Escaped;
+#[cfg(feature = "filename-safe")]
+use crate::algorithm::FilenameSafe;
+#[cfg(feature = "hex")]
+use crate::algorithm::Hex;
+#[cfg(feature = "intel-hex")]
+use crate::algorithm::IntelHex;
+#[cfg(feature = "line-dump")]
+use crate::algorithm::LineDump;
+#[cfg(feature = "srec")]
+use crate::algorithm::Srec;
+#[cfg(feature = "uuencode")]
+use crate::algorithm::Uuencode;
+
 /// String representation of serialized data.
 ///
 /// This type represents data that has been serialized into a string format,
 /// along with information about which serialization format was used.
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+///
+/// Strings produced by [`ByteVec::try_encode`] also record the length and an
+/// Adler-32 checksum of the original bytes, which are verified on decode so a
+/// truncated or corrupted copy is rejected instead of decoding to the wrong
+/// value. This metadata is not part of the string and is ignored by
+/// comparisons.
+#[derive(Clone, Debug)]
 pub struct EncodedString {
     /// The format used to serialize the data
     encoding: Encoding,
     /// The serialized string representation
     string: String,
+    /// Length of the original bytes, if recorded at encode time
+    byte_length: Option<usize>,
+    /// Adler-32 checksum of the original bytes, if recorded at encode time
+    checksum: Option<u32>,
 }
 
 impl EncodedString {
@@ -25,7 +70,74 @@ impl EncodedString {
     /// * `string` - The serialized string representation
     #[must_use = "This creates a new SerialString instance but does nothing if unused"]
     pub const fn new(encoding: Encoding, string: String) -> Self {
-        Self { encoding, string }
+        Self {
+            encoding,
+            string,
+            byte_length: None,
+            checksum: None,
+        }
+    }
+
+    /// Records the length and checksum of the bytes this string encodes.
+    ///
+    /// # Arguments
+    /// * `bytes` - The original bytes
+    #[must_use = "This returns the encoded string with integrity metadata but does nothing if unused"]
+    pub fn with_integrity(self, bytes: &[u8]) -> Self {
+        self.with_metadata(bytes.len(), Adler32::checksum(bytes))
+    }
+
+    /// Restores integrity metadata stored alongside the string.
+    ///
+    /// # Arguments
+    /// * `byte_length` - The length of the original bytes
+    /// * `checksum` - The Adler-32 checksum of the original bytes
+    #[must_use = "This returns the encoded string with integrity metadata but does nothing if unused"]
+    pub const fn with_metadata(mut self, byte_length: usize, checksum: u32) -> Self {
+        self.byte_length = Some(byte_length);
+        self.checksum = Some(checksum);
+        self
+    }
+
+    /// Returns the length of the original bytes, if it was recorded.
+    #[must_use = "This returns the original byte length but does nothing if unused"]
+    pub const fn get_byte_length(&self) -> Option<usize> {
+        self.byte_length
+    }
+
+    /// Returns the Adler-32 checksum of the original bytes, if it was recorded.
+    #[must_use = "This returns the checksum but does nothing if unused"]
+    pub const fn get_checksum(&self) -> Option<u32> {
+        self.checksum
+    }
+
+    /// Checks decoded bytes against the recorded length and checksum.
+    ///
+    /// Succeeds if no metadata was recorded.
+    ///
+    /// # Arguments
+    /// * `bytes` - The decoded bytes
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the length or checksum does not match.
+    pub fn verify(&self, bytes: &[u8]) -> Result<(), SerialiseError> {
+        if let Some(byte_length) = self.byte_length
+            && byte_length != bytes.len()
+        {
+            return Err(SerialiseError::new(format!(
+                "decoded {} bytes but {byte_length} were encoded; the string may be truncated",
+                bytes.len()
+            )));
+        }
+        if let Some(checksum) = self.checksum
+            && checksum != Adler32::checksum(bytes)
+        {
+            return Err(SerialiseError::new(
+                "decoded bytes do not match the recorded checksum".to_string(),
+            )
+            .with_kind(ErrorKind::ChecksumMismatch));
+        }
+        Ok(())
     }
 
     /// Returns the format used to serialize the data.
@@ -46,43 +158,277 @@ impl EncodedString {
         &self.string
     }
 
+    /// Returns the encoded text as a `&str`.
+    #[must_use = "This returns the encoded text but does nothing if unused"]
+    pub const fn as_str(&self) -> &str {
+        self.string.as_str()
+    }
+
+    /// Returns the encoded text, dropping the encoding and any integrity
+    /// metadata.
+    #[must_use = "This returns the encoded text but does nothing if unused"]
+    pub fn into_string(self) -> String {
+        self.string
+    }
+
+    /// Returns the length of the encoded text in bytes, like [`str::len`].
+    #[must_use = "This returns the length but does nothing if unused"]
+    pub const fn len(&self) -> usize {
+        self.string.len()
+    }
+
+    /// Returns `true` if the encoded text is empty.
+    #[must_use = "This returns whether the text is empty but does nothing if unused"]
+    pub const fn is_empty(&self) -> bool {
+        self.string.is_empty()
+    }
+
+    /// Returns the characters of the encoded text, like [`str::chars`].
+    #[must_use = "This returns an iterator but does nothing if unused"]
+    pub fn chars(&self) -> std::str::Chars<'_> {
+        self.string.chars()
+    }
+
+    /// Writes the string with its encoding name as a prefix, e.g. `base58:2NEpo7TZRRrLZSi2U`.
+    ///
+    /// # Returns
+    /// The tagged string, which [`EncodedString::from_tagged`] parses back
+    #[must_use = "This returns the tagged string but does nothing if unused"]
+    pub fn to_tagged(&self) -> String {
+        format!("{}:{}", self.encoding, self.string)
+    }
+
+    /// Parses a string written by [`EncodedString::to_tagged`].
+    ///
+    /// The encoding name is matched case-insensitively and everything after the
+    /// first `:` is the payload.
+    ///
+    /// # Arguments
+    /// * `tagged` - The `<encoding>:<payload>` string
+    ///
+    /// # Returns
+    /// The encoded string
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if there is no `:` or the encoding name is unknown.
+    pub fn from_tagged(tagged: &str) -> Result<Self, SerialiseError> {
+        let Some((name, payload)) = tagged.split_once(':') else {
+            return Err(SerialiseError::new(format!(
+                "tagged string '{tagged}' is missing its '<encoding>:' prefix"
+            )));
+        };
+        Ok(Self::new(name.parse()?, payload.to_string()))
+    }
+
+    /// Re-encodes the string into the canonical form for its encoding.
+    ///
+    /// The string is decoded and encoded again, so case, padding and whitespace
+    /// end up exactly as the encoder writes them. Record formats (Intel HEX,
+    /// S-record) are re-encoded from address zero with gaps filled.
+    ///
+    /// # Returns
+    /// The canonical encoded string
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the string cannot be decoded.
+    #[must_use = "canonicalizing returns a result that must be handled"]
+    pub fn canonicalize(&self) -> Result<Self, SerialiseError> {
+        self.try_decode()?.try_encode(self.encoding)
+    }
+
+    /// Compares the decoded bytes of two encoded strings.
+    ///
+    /// Strings that differ only in representation, or are in different
+    /// encodings, are equal if they decode to the same bytes.
+    ///
+    /// # Arguments
+    /// * `other` - The encoded string to compare with
+    ///
+    /// # Returns
+    /// `true` if both strings decode to the same bytes
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if either string cannot be decoded.
+    #[must_use = "comparing returns a result that must be handled"]
+    pub fn eq_semantic(&self, other: &Self) -> Result<bool, SerialiseError> {
+        Ok(self.try_decode()?.get_bytes() == other.try_decode()?.get_bytes())
+    }
+
+    /// Compares the encoding and text with `other` in time that depends only on
+    /// the lengths, not on where the texts first differ.
+    ///
+    /// Use this instead of `==` to check encoded secrets such as API keys. The
+    /// encoding and the lengths are not hidden. Strings that differ only in
+    /// representation, such as hex case, are not equal; canonicalize both first
+    /// if that matters.
+    ///
+    /// # Arguments
+    /// * `other` - The encoded string to compare with
+    ///
+    /// # Returns
+    /// `true` if both have the same encoding and text
+    #[must_use = "This returns whether the strings are equal but does nothing if unused"]
+    pub fn ct_eq(&self, other: &Self) -> bool {
+        self.encoding == other.encoding && ct_eq(self.string.as_bytes(), other.string.as_bytes())
+    }
+
     /// Attempts to decode an encoded string into this type.
     ///
     /// # Errors
     /// Returns `Err` if the underlying decoding fails.
     #[must_use = "decoding returns a result that must be handled"]
-    fn try_decode(&self) -> Result<Arc<ByteVec>, SerialiseError>
+    pub(crate) fn try_decode(&self) -> Result<Arc<ByteVec>, SerialiseError>
     where
         Self: Sized,
     {
-        match self.get_encoding() {
-            Encoding::Base36 => match Base36::try_decode(self) {
-                Ok(bytes) => Ok(Arc::new(ByteVec::new(Arc::clone(&bytes)))),
-                Err(e) => Err(SerialiseError::new(e.to_string())),
-            },
-            Encoding::Base58 => match Base58::try_decode(self) {
-                Ok(bytes) => Ok(Arc::new(ByteVec::new(Arc::clone(&bytes)))),
-                Err(e) => Err(SerialiseError::new(e.to_string())),
-            },
-            Encoding::Base64 => match Base64::try_decode(self) {
-                Ok(bytes) => Ok(Arc::new(ByteVec::new(Arc::clone(&bytes)))),
-                Err(e) => Err(SerialiseError::new(e.to_string())),
-            },
-            Encoding::Hex => match Hex::try_decode(self) {
-                Ok(bytes) => Ok(Arc::new(ByteVec::new(Arc::clone(&bytes)))),
-                Err(e) => Err(SerialiseError::new(e.to_string())),
-            },
-            Encoding::Uuencode => match Uuencode::try_decode(self) {
-                Ok(bytes) => Ok(Arc::new(ByteVec::new(Arc::clone(&bytes)))),
-                Err(e) => Err(SerialiseError::new(e.to_string())),
-            },
+        let decoded =
+            instrument::record("decode", self.encoding, self.string.len(), || {
+                match self.get_encoding() {
+                    #[cfg(feature = "base36")]
+                    Encoding::Base36 => {
+                        Base36::try_decode(self).map(|bytes| Arc::new(ByteVec::new(bytes)))
+                    }
+                    #[cfg(feature = "base58")]
+                    Encoding::Base58 => {
+                        Base58::try_decode(self).map(|bytes| Arc::new(ByteVec::new(bytes)))
+                    }
+                    #[cfg(feature = "base64")]
+                    Encoding::Base64 => {
+                        Base64::try_decode(self).map(|bytes| Arc::new(ByteVec::new(bytes)))
+                    }
+                    #[cfg(feature = "hex")]
+                    Encoding::Hex => {
+                        Hex::try_decode(self).map(|bytes| Arc::new(ByteVec::new(bytes)))
+                    }
+                    #[cfg(feature = "uuencode")]
+                    Encoding::Uuencode => {
+                        Uuencode::try_decode(self).map(|bytes| Arc::new(ByteVec::new(bytes)))
+                    }
+                    #[cfg(feature = "intel-hex")]
+                    Encoding::IntelHex => {
+                        IntelHex::try_decode(self).map(|bytes| Arc::new(ByteVec::new(bytes)))
+                    }
+                    #[cfg(feature = "srec")]
+                    Encoding::Srec => {
+                        Srec::try_decode(self).map(|bytes| Arc::new(ByteVec::new(bytes)))
+                    }
+                    #[cfg(feature = "binhex")]
+                    Encoding::BinHex => {
+                        BinHex::try_decode(self).map(|bytes| Arc::new(ByteVec::new(bytes)))
+                    }
+                    #[cfg(feature = "base32")]
+                    Encoding::Base32 => {
+                        Base32::try_decode(self).map(|bytes| Arc::new(ByteVec::new(bytes)))
+                    }
+                    #[cfg(feature = "base62")]
+                    Encoding::Base62 => {
+                        Base62::try_decode(self).map(|bytes| Arc::new(ByteVec::new(bytes)))
+                    }
+                    #[cfg(feature = "base45")]
+                    Encoding::Base45 => {
+                        Base45::try_decode(self).map(|bytes| Arc::new(ByteVec::new(bytes)))
+                    }
+                    #[cfg(feature = "line-dump")]
+                    Encoding::LineDump => {
+                        LineDump::try_decode(self).map(|bytes| Arc::new(ByteVec::new(bytes)))
+                    }
+                    #[cfg(feature = "filename-safe")]
+                    Encoding::FilenameSafe => {
+                        FilenameSafe::try_decode(self).map(|bytes| Arc::new(ByteVec::new(bytes)))
+                    }
+                    #[cfg(feature = "escaped")]
+                    Encoding::Escaped => {
+                        Escaped::try_decode(self).map(|bytes| Arc::new(ByteVec::new(bytes)))
+                    }
+                    #[cfg(feature = "base94")]
+                    Encoding::Base94 => {
+                        Base94::try_decode(self).map(|bytes| Arc::new(ByteVec::new(bytes)))
+                    }
+                }
+            })?;
+        let decoded = self.restore_leading_zeros(decoded);
+        self.verify(decoded.get_bytes())?;
+        Ok(decoded)
+    }
+
+    /// Restores the leading zero bytes that encodings of numeric values
+    /// (Base36, Base58 and Base62) do not keep, using the recorded byte
+    /// length. Other encodings, and strings without metadata, are unchanged.
+    fn restore_leading_zeros(&self, decoded: Arc<ByteVec>) -> Arc<ByteVec> {
+        let bytes = decoded.get_bytes();
+        let Some(len) = self
+            .byte_length
+            .filter(|&len| self.encoding.is_numeric() && len != bytes.len())
+        else {
+            return decoded;
+        };
+        let value = &bytes[bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len())..];
+        if value.len() > len {
+            return decoded;
         }
+        let mut padded = vec![0; len - value.len()];
+        padded.extend_from_slice(value);
+        Arc::new(ByteVec::new(Arc::new(padded)))
+    }
+}
+
+// There is deliberately no `Borrow<str>`: equality, hashing and ordering
+// include the encoding, so two strings with the same text but different
+// encodings are unequal while their `str`s are equal, which `Borrow` forbids.
+impl AsRef<str> for EncodedString {
+    fn as_ref(&self) -> &str {
+        &self.string
+    }
+}
+
+impl From<EncodedString> for String {
+    fn from(encoded: EncodedString) -> Self {
+        encoded.string
+    }
+}
+
+impl PartialEq for EncodedString {
+    fn eq(&self, other: &Self) -> bool {
+        self.encoding == other.encoding && self.string == other.string
+    }
+}
+
+impl Eq for EncodedString {}
+
+/// Hashes the encoding and text, matching `PartialEq`, which ignores the
+/// integrity metadata.
+impl Hash for EncodedString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.encoding.hash(state);
+        self.string.hash(state);
     }
 }
 
+impl PartialOrd for EncodedString {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for EncodedString {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.encoding
+            .cmp(&other.encoding)
+            .then_with(|| self.string.cmp(&other.string))
+    }
+}
+
+/// Writes the encoded string, honouring width, fill, alignment and precision.
+///
+/// The alternate form (`{:#}`) prefixes the encoding name, e.g. `base58:2NEpo7TZRRrLZSi2U`,
+/// giving a self-describing single token for logs.
 impl std::fmt::Display for EncodedString {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.string)
+        if f.alternate() {
+            f.pad(&self.to_tagged())
+        } else {
+            f.pad(&self.string)
+        }
     }
 }
 
@@ -116,6 +462,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[cfg(feature = "base36")]
     fn test_decoable_decode_base36() {
         #[derive(Debug, PartialEq)]
         struct TestType {
@@ -147,6 +494,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "base58")]
     fn test_decoable_decode_base58() {
         #[derive(Debug, PartialEq)]
         struct TestType {
@@ -178,6 +526,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "base64")]
     fn test_decoable_decode_base64() {
         #[derive(Debug, PartialEq)]
         struct TestType {
@@ -209,6 +558,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "hex")]
     fn test_decoable_decode_hex() {
         #[derive(Debug, PartialEq)]
         struct TestType {
@@ -240,6 +590,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "uuencode")]
     fn test_decoable_decode_uuencode() {
         #[derive(Debug, PartialEq)]
         struct TestType {
@@ -269,4 +620,144 @@ mod tests {
             b"0123456789abcdefghijklmnopqrstuvwxyz"
         );
     }
+
+    #[test]
+    #[cfg(feature = "base64")]
+    fn test_canonicalize_base64() {
+        let encoded = EncodedString::new(Encoding::Base64, " Zm9vYg\n".to_string());
+        assert!(matches!(
+            encoded.canonicalize(),
+            Ok(canonical) if canonical == EncodedString::new(Encoding::Base64, "Zm9vYg==".to_string())
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "hex")]
+    fn test_canonicalize_hex_lowercases() {
+        let encoded = EncodedString::new(Encoding::Hex, "DEADBEEF".to_string());
+        assert!(matches!(
+            encoded.canonicalize(),
+            Ok(canonical) if canonical.get_string() == "deadbeef"
+        ));
+    }
+
+    #[test]
+    #[cfg(all(feature = "base64", feature = "hex"))]
+    fn test_eq_semantic_compares_decoded_bytes() {
+        let hex = EncodedString::new(Encoding::Hex, "666F6F".to_string());
+        let base64 = EncodedString::new(Encoding::Base64, "Zm9v".to_string());
+        let other = EncodedString::new(Encoding::Base64, "Zm9w".to_string());
+        assert!(matches!(hex.eq_semantic(&base64), Ok(true)));
+        assert!(matches!(hex.eq_semantic(&other), Ok(false)));
+        assert!(
+            hex.eq_semantic(&EncodedString::new(Encoding::Hex, "x".to_string()))
+                .is_err()
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "base64", feature = "hex"))]
+    fn test_ct_eq() {
+        let key = EncodedString::new(Encoding::Hex, "666f6f".to_string());
+        assert!(key.ct_eq(&EncodedString::new(Encoding::Hex, "666f6f".to_string())));
+        assert!(!key.ct_eq(&EncodedString::new(Encoding::Hex, "666f6e".to_string())));
+        assert!(!key.ct_eq(&EncodedString::new(Encoding::Hex, "666F6F".to_string())));
+        assert!(!key.ct_eq(&EncodedString::new(Encoding::Base64, "666f6f".to_string())));
+    }
+
+    #[test]
+    #[cfg(feature = "hex")]
+    fn test_truncated_string_fails_verification() {
+        let encoded = ByteVec::new(Arc::new(b"hello".to_vec()))
+            .try_encode(Encoding::Hex)
+            .unwrap_or_else(|_| EncodedString::new(Encoding::Hex, String::new()));
+        assert_eq!(encoded.get_byte_length(), Some(5));
+        assert!(encoded.try_decode().is_ok());
+
+        let truncated = EncodedString::new(Encoding::Hex, encoded.get_string()[..8].to_string())
+            .with_metadata(5, encoded.get_checksum().unwrap_or_default());
+        assert_eq!(
+            truncated,
+            EncodedString::new(Encoding::Hex, "68656c6c".to_string())
+        );
+        assert!(truncated.try_decode().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "base58")]
+    fn test_numeric_encodings_keep_leading_zeros() {
+        for bytes in [vec![], vec![0], vec![0, 0, 1, 2]] {
+            let encoded = ByteVec::new(Arc::new(bytes.clone()))
+                .try_encode(Encoding::Base58)
+                .unwrap_or_else(|_| EncodedString::new(Encoding::Base58, String::new()));
+            assert!(matches!(encoded.try_decode(), Ok(d) if d.get_bytes() == bytes));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "hex")]
+    fn test_corrupted_string_fails_checksum() {
+        let encoded =
+            EncodedString::new(Encoding::Hex, "68656c6c6f".to_string()).with_integrity(b"hellp");
+        assert!(encoded.try_decode().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "hex")]
+    fn test_display_formatting_flags() {
+        let encoded = EncodedString::new(Encoding::Hex, "cafe".to_string());
+        assert_eq!(format!("{encoded}"), "cafe");
+        assert_eq!(format!("{encoded:#}"), "hex:cafe");
+        assert_eq!(format!("{encoded:*^8}"), "**cafe**");
+        assert_eq!(format!("{encoded:<#10}|"), "hex:cafe  |");
+    }
+
+    #[test]
+    #[cfg(feature = "intel-hex")]
+    fn test_tagged_round_trip() {
+        let encoded = EncodedString::new(Encoding::IntelHex, ":00000001FF\n".to_string());
+        let tagged = encoded.to_tagged();
+        assert_eq!(tagged, "intel-hex::00000001FF\n");
+        assert!(matches!(EncodedString::from_tagged(&tagged), Ok(parsed) if parsed == encoded));
+    }
+
+    #[test]
+    #[cfg(all(feature = "base58", feature = "hex"))]
+    fn test_hash_set_and_sorting() {
+        use std::collections::HashSet;
+
+        let mut encoded = [
+            EncodedString::new(Encoding::Hex, "cafe".to_string()),
+            EncodedString::new(Encoding::Base58, "2".to_string()),
+            EncodedString::new(Encoding::Hex, "cafe".to_string()).with_integrity(&[0xca, 0xfe]),
+        ];
+        let unique: HashSet<&EncodedString> = encoded.iter().collect();
+        assert_eq!(unique.len(), 2);
+        encoded.sort();
+        assert_eq!(encoded[0].get_encoding(), Encoding::Base58);
+    }
+
+    #[test]
+    fn test_from_tagged_invalid_is_err() {
+        assert!(EncodedString::from_tagged("no prefix").is_err());
+        assert!(EncodedString::from_tagged("base99:abc").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "hex")]
+    fn test_str_passthroughs() {
+        fn takes_str(s: impl AsRef<str>) -> usize {
+            s.as_ref().len()
+        }
+
+        let encoded = EncodedString::new(Encoding::Hex, "cafe".to_string());
+        assert_eq!(encoded.as_str(), "cafe");
+        assert_eq!(encoded.len(), 4);
+        assert!(!encoded.is_empty());
+        assert_eq!(encoded.chars().rev().collect::<String>(), "efac");
+        assert_eq!(takes_str(&encoded), 4);
+        assert_eq!(String::from(encoded.clone()), "cafe");
+        assert_eq!(encoded.into_string(), "cafe");
+        assert!(EncodedString::new(Encoding::Hex, String::new()).is_empty());
+    }
 }
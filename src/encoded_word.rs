@@ -0,0 +1,290 @@
+use crate::{Base64, SerialiseError};
+
+/// Longest encoded-word RFC 2047 allows, including the `=?charset?X?` and `?=`.
+const MAX_WORD_LEN: usize = 75;
+
+/// Prefix of the encoded-words this module writes, without the encoding letter.
+const PREFIX: &str = "=?UTF-8?";
+
+/// How the text inside an encoded-word is encoded.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WordEncoding {
+    /// `B`: Base64, compact for non-Latin text
+    B,
+    /// `Q`: quoted-printable variant, readable for mostly-ASCII text
+    Q,
+}
+
+impl WordEncoding {
+    const fn letter(self) -> char {
+        match self {
+            Self::B => 'B',
+            Self::Q => 'Q',
+        }
+    }
+
+    /// Returns true if `b` can appear literally in Q-encoded text anywhere in a
+    /// header, including phrases (RFC 2047 section 5, rule 3).
+    const fn is_q_literal(b: u8) -> bool {
+        b.is_ascii_alphanumeric() || matches!(b, b'!' | b'*' | b'+' | b'-' | b'/')
+    }
+
+    /// Returns the length of `bytes` once encoded.
+    fn encoded_len(self, bytes: &[u8]) -> usize {
+        match self {
+            Self::B => bytes.len().div_ceil(3) * 4,
+            Self::Q => bytes
+                .iter()
+                .map(|&b| {
+                    if Self::is_q_literal(b) || b == b' ' {
+                        1
+                    } else {
+                        3
+                    }
+                })
+                .sum(),
+        }
+    }
+
+    fn encode_text(self, bytes: &[u8]) -> Result<String, SerialiseError> {
+        match self {
+            Self::B => Base64::try_to_base64(bytes),
+            Self::Q => Ok(bytes
+                .iter()
+                .map(|&b| match b {
+                    b' ' => "_".to_string(),
+                    b if Self::is_q_literal(b) => char::from(b).to_string(),
+                    b => format!("={b:02X}"),
+                })
+                .collect()),
+        }
+    }
+
+    fn decode_text(self, text: &str) -> Result<Vec<u8>, SerialiseError> {
+        match self {
+            Self::B => Base64::try_from_base64(text, 0),
+            Self::Q => {
+                let mut out = Vec::with_capacity(text.len());
+                let mut bytes = text.bytes();
+                while let Some(b) = bytes.next() {
+                    match b {
+                        b'_' => out.push(b' '),
+                        b'=' => {
+                            let hex = [bytes.next(), bytes.next()];
+                            let byte = match hex {
+                                [Some(hi), Some(lo)] => std::str::from_utf8(&[hi, lo])
+                                    .ok()
+                                    .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+                                _ => None,
+                            };
+                            out.push(byte.ok_or_else(|| {
+                                SerialiseError::new("invalid Q-encoded escape".to_string())
+                            })?);
+                        }
+                        b => out.push(b),
+                    }
+                }
+                Ok(out)
+            }
+        }
+    }
+}
+
+/// Encodes `text` as one or more UTF-8 encoded-words of at most 75 characters.
+///
+/// Words are split between characters, never inside one, so each word decodes
+/// on its own as RFC 2047 requires. Join them with `"\r\n "` to fold a long
+/// header, or with a space.
+///
+/// # Arguments
+/// * `text` - The header text to encode
+/// * `encoding` - Whether to use `B` or `Q` encoding
+///
+/// # Returns
+/// The encoded-words in order, or a single empty word for empty text
+///
+/// # Errors
+/// Returns `SerialiseError` if encoding fails.
+pub fn encode(text: &str, encoding: WordEncoding) -> Result<Vec<String>, SerialiseError> {
+    let budget = MAX_WORD_LEN - PREFIX.len() - "X??=".len();
+    let mut chunks: Vec<&str> = Vec::new();
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        let end = i + c.len_utf8();
+        if i > start && encoding.encoded_len(&text.as_bytes()[start..end]) > budget {
+            chunks.push(&text[start..i]);
+            start = i;
+        }
+    }
+    chunks.push(&text[start..]);
+
+    chunks
+        .into_iter()
+        .map(|chunk| {
+            Ok(format!(
+                "{PREFIX}{}?{}?=",
+                encoding.letter(),
+                encoding.encode_text(chunk.as_bytes())?
+            ))
+        })
+        .collect()
+}
+
+/// Splits `=?charset?X?text?=` into its parts, returning `None` if `word` is not
+/// a well-formed encoded-word.
+fn parse_word(word: &str) -> Option<(&str, WordEncoding, &str)> {
+    let inner = word.strip_prefix("=?")?.strip_suffix("?=")?;
+    let mut parts = inner.splitn(3, '?');
+    let charset = parts.next()?;
+    let encoding = match parts.next()? {
+        "B" | "b" => WordEncoding::B,
+        "Q" | "q" => WordEncoding::Q,
+        _ => return None,
+    };
+    let text = parts.next()?;
+    if charset.is_empty() || text.contains('?') || text.contains(char::is_whitespace) {
+        return None;
+    }
+    // RFC 2231 allows a language suffix, e.g. `UTF-8*en`.
+    let charset = charset.split('*').next()?;
+    Some((charset, encoding, text))
+}
+
+/// Converts bytes in `charset` to a string.
+fn bytes_to_string(charset: &str, bytes: Vec<u8>) -> Result<String, SerialiseError> {
+    match charset.to_ascii_lowercase().as_str() {
+        "utf-8" | "us-ascii" => String::from_utf8(bytes)
+            .map_err(|_| SerialiseError::new(format!("encoded-word is not valid {charset}"))),
+        "iso-8859-1" | "latin1" => Ok(bytes.into_iter().map(char::from).collect()),
+        _ => Err(SerialiseError::new(format!(
+            "unsupported encoded-word charset '{charset}'"
+        ))),
+    }
+}
+
+/// Decodes a header value, replacing every encoded-word with its text.
+///
+/// Text outside encoded-words is kept as is, and whitespace between two
+/// adjacent encoded-words is dropped (RFC 2047 section 6.2). Adjacent words in
+/// the same charset are joined before conversion, so text that a sender split
+/// part way through a character still decodes. Anything that looks like an
+/// encoded-word but is malformed is kept as plain text.
+///
+/// # Arguments
+/// * `header` - The header value to decode
+///
+/// # Returns
+/// The decoded header text
+///
+/// # Errors
+/// Returns `SerialiseError` if an encoded-word uses a charset other than UTF-8,
+/// US-ASCII or ISO-8859-1, or its text is not valid for its encoding.
+pub fn decode(header: &str) -> Result<String, SerialiseError> {
+    let mut out = String::with_capacity(header.len());
+    // Charset and bytes of adjacent encoded-words not yet converted
+    let mut pending: Option<(&str, Vec<u8>)> = None;
+    // Whitespace seen since the last encoded-word, dropped if another follows
+    let mut gap = "";
+    let mut rest = header;
+
+    while !rest.is_empty() {
+        let space_len = rest.len() - rest.trim_start().len();
+        if space_len > 0 {
+            let (space, tail) = rest.split_at(space_len);
+            rest = tail;
+            if pending.is_some() {
+                gap = space;
+            } else {
+                out.push_str(space);
+            }
+            continue;
+        }
+
+        let token_len = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let (token, tail) = rest.split_at(token_len);
+        rest = tail;
+
+        if let Some((charset, encoding, text)) = parse_word(token) {
+            let bytes = encoding.decode_text(text)?;
+            pending = match pending.take() {
+                Some((current, mut buffered)) if current.eq_ignore_ascii_case(charset) => {
+                    buffered.extend(bytes);
+                    Some((current, buffered))
+                }
+                Some((current, buffered)) => {
+                    out.push_str(&bytes_to_string(current, buffered)?);
+                    Some((charset, bytes))
+                }
+                None => Some((charset, bytes)),
+            };
+        } else {
+            if let Some((charset, bytes)) = pending.take() {
+                out.push_str(&bytes_to_string(charset, bytes)?);
+                out.push_str(gap);
+            }
+            out.push_str(token);
+        }
+        gap = "";
+    }
+
+    if let Some((charset, bytes)) = pending {
+        out.push_str(&bytes_to_string(charset, bytes)?);
+        out.push_str(gap);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_encode_splits_at_75_chars() {
+        let text = "Grüße aus Köln, ".repeat(8);
+        for encoding in [WordEncoding::B, WordEncoding::Q] {
+            let words = encode(&text, encoding).unwrap_or_default();
+            assert!(words.len() > 1);
+            assert!(words.iter().all(|word| word.len() <= MAX_WORD_LEN));
+            assert!(matches!(decode(&words.join("\r\n ")), Ok(decoded) if decoded == text));
+        }
+        assert!(matches!(
+            encode("a b?", WordEncoding::Q).as_deref(),
+            Ok([word]) if word == "=?UTF-8?Q?a_b=3F?="
+        ));
+    }
+
+    #[test]
+    fn test_decode_rfc_examples() {
+        assert!(matches!(
+            decode("=?ISO-8859-1?Q?Keld_J=F8rn_Simonsen?= <keld@dkuug.dk>").as_deref(),
+            Ok("Keld Jørn Simonsen <keld@dkuug.dk>")
+        ));
+        assert!(matches!(
+            decode("(=?ISO-8859-1?Q?a?= b)").as_deref(),
+            Ok("(=?ISO-8859-1?Q?a?= b)")
+        ));
+        assert!(matches!(
+            decode("=?ISO-8859-1?Q?a?= b").as_deref(),
+            Ok("a b")
+        ));
+        assert!(matches!(
+            decode("=?ISO-8859-1?Q?a?=  \r\n =?ISO-8859-1?Q?b?=").as_deref(),
+            Ok("ab")
+        ));
+        assert!(matches!(
+            decode("=?UTF-8?B?w6k=?= =?UTF-8?B?dA==?=").as_deref(),
+            Ok("ét")
+        ));
+    }
+
+    #[test]
+    fn test_decode_invalid_is_err() {
+        assert!(decode("=?KOI8-R?B?w6k=?=").is_err());
+        assert!(decode("=?UTF-8?Q?=ZZ?=").is_err());
+        assert!(matches!(
+            decode("=?UTF-8?X?abc?=").as_deref(),
+            Ok("=?UTF-8?X?abc?=")
+        ));
+    }
+}
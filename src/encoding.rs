@@ -1,17 +1,570 @@
+use std::str::FromStr;
+
+use crate::{CharSet, SerialiseError, charset};
+
+/// `log2` of the big-number bases, in billionths and rounded down, so lengths
+/// computed with them are never too short.
+#[cfg(feature = "base36")]
+const LOG2_36: u128 = 5_169_925_001;
+#[cfg(feature = "base58")]
+const LOG2_58: u128 = 5_857_980_995;
+#[cfg(feature = "base62")]
+const LOG2_62: u128 = 5_954_196_310;
+
+/// Returns the most digits a number of `len` bytes needs in a base whose
+/// `log2` is `log2_base` billionths. Zero is written as one digit.
+#[cfg(any(feature = "base36", feature = "base58", feature = "base62"))]
+const fn max_digits(len: usize, log2_base: u128) -> usize {
+    let digits = (len as u128 * 8 * 1_000_000_000).div_ceil(log2_base);
+    if digits == 0 {
+        1
+    } else if digits > usize::MAX as u128 {
+        usize::MAX
+    } else {
+        digits as usize
+    }
+}
+
+/// Returns the S-record length of `len` bytes: a header, 16-byte data records
+/// of 7 characters plus 2 per address and data byte, a record count and a
+/// termination record.
+#[cfg(feature = "srec")]
+const fn srec_len(len: usize) -> usize {
+    let address_chars = 2 * if len <= 0x1_0000 {
+        2
+    } else if len <= 0x100_0000 {
+        3
+    } else {
+        4
+    };
+    let records = len.div_ceil(16);
+    let count_chars = if records <= 0xffff {
+        11
+    } else if records <= 0xff_ffff {
+        13
+    } else {
+        0
+    };
+    records
+        .saturating_mul(7 + address_chars)
+        .saturating_add(len.saturating_mul(2))
+        .saturating_add(11 + count_chars + 7 + address_chars)
+}
+
+/// Returns the most `BinHex` characters for `len` bytes. The header, forks and
+/// CRCs at most double under run-length encoding, then are written 4
+/// characters per 3 bytes between colons, in lines of 64 after the banner.
+#[cfg(feature = "binhex")]
+const fn binhex_len(len: usize) -> usize {
+    use crate::algorithm::binhex::{BANNER, DEFAULT_NAME, LINE_LENGTH};
+
+    let stream = len.saturating_add(DEFAULT_NAME.len() + 26);
+    let compressed = stream.saturating_mul(2);
+    let chars = compressed
+        .saturating_add(compressed.div_ceil(3))
+        .saturating_add(2);
+    chars
+        .saturating_add(chars.div_ceil(LINE_LENGTH))
+        .saturating_add(BANNER.len() + 2)
+}
+
+/// Returns the line dump length of `len` bytes: lines of
+/// `<len> <base64> <crc>`, then `end <len> <crc>`, each with a line break.
+#[cfg(feature = "line-dump")]
+const fn line_dump_len(len: usize) -> usize {
+    use crate::algorithm::line_dump::BYTES_PER_LINE;
+
+    let tail = len % BYTES_PER_LINE;
+    let tail_chars = if tail == 0 {
+        0
+    } else {
+        13 + tail.div_ceil(3) * 4
+    };
+    let mut digits = 1;
+    let mut rest = len / 10;
+    while rest > 0 {
+        digits += 1;
+        rest /= 10;
+    }
+    (len / BYTES_PER_LINE)
+        .saturating_mul(13 + BYTES_PER_LINE / 3 * 4)
+        .saturating_add(tail_chars + 14 + digits)
+}
+
 /// Supported serialization formats.
 ///
 /// This enum represents the different formats that can be used to serialize
 /// data structures into string representations.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, PartialOrd, Ord)]
+///
+/// Encodings order by declaration, which only ever gains variants at the end,
+/// so sorted lists keep their order across releases.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Hash)]
 pub enum Encoding {
     /// Base36 encoding (0-9 and A-Z)
+    #[cfg(feature = "base36")]
     Base36,
     /// Base58 encoding (Bitcoin-style, excluding similar-looking characters)
+    #[cfg(feature = "base58")]
     Base58,
     /// Standard Base64 encoding
+    #[cfg(feature = "base64")]
     Base64,
     /// U-U Encoding format
+    #[cfg(feature = "uuencode")]
     Uuencode,
     /// Hexadecimal encoding (0-9 and A-F)
+    #[cfg(feature = "hex")]
     Hex,
+    /// Intel HEX record format
+    #[cfg(feature = "intel-hex")]
+    IntelHex,
+    /// Motorola S-record format
+    #[cfg(feature = "srec")]
+    Srec,
+    /// `BinHex` 4.0 format
+    #[cfg(feature = "binhex")]
+    BinHex,
+    /// Base32 encoding (RFC 4648, A-Z and 2-7)
+    #[cfg(feature = "base32")]
+    Base32,
+    /// Base62 encoding (0-9, A-Z and a-z)
+    #[cfg(feature = "base62")]
+    Base62,
+    /// Base45 encoding (RFC 9285, the QR code alphanumeric set)
+    #[cfg(feature = "base45")]
+    Base45,
+    /// Line dump with a length and CRC-32 on every line
+    #[cfg(feature = "line-dump")]
+    LineDump,
+    /// Unpadded lowercase Base32, safe as a file name on any filesystem
+    #[cfg(feature = "filename-safe")]
+    FilenameSafe,
+    /// Printable ASCII with `\xNN` escapes, readable in logs
+    #[cfg(feature = "escaped")]
+    Escaped,
+    /// Base94 encoding (every printable ASCII character except space)
+    #[cfg(feature = "base94")]
+    Base94,
+}
+
+impl Encoding {
+    /// Every encoding enabled in this build, in declaration order.
+    pub const ALL: &'static [Self] = &[
+        #[cfg(feature = "base36")]
+        Self::Base36,
+        #[cfg(feature = "base58")]
+        Self::Base58,
+        #[cfg(feature = "base64")]
+        Self::Base64,
+        #[cfg(feature = "uuencode")]
+        Self::Uuencode,
+        #[cfg(feature = "hex")]
+        Self::Hex,
+        #[cfg(feature = "intel-hex")]
+        Self::IntelHex,
+        #[cfg(feature = "srec")]
+        Self::Srec,
+        #[cfg(feature = "binhex")]
+        Self::BinHex,
+        #[cfg(feature = "base32")]
+        Self::Base32,
+        #[cfg(feature = "base62")]
+        Self::Base62,
+        #[cfg(feature = "base45")]
+        Self::Base45,
+        #[cfg(feature = "line-dump")]
+        Self::LineDump,
+        #[cfg(feature = "filename-safe")]
+        Self::FilenameSafe,
+        #[cfg(feature = "escaped")]
+        Self::Escaped,
+        #[cfg(feature = "base94")]
+        Self::Base94,
+    ];
+
+    /// Returns an iterator over every encoding enabled in this build.
+    pub fn iter() -> impl Iterator<Item = Self> {
+        Self::ALL.iter().copied()
+    }
+
+    /// Returns the short lowercase name of the encoding, matching its cargo feature.
+    ///
+    /// # Returns
+    /// The encoding name, e.g. `"base58"` or `"intel-hex"`
+    #[must_use = "This returns the encoding name but does nothing if unused"]
+    pub const fn get_name(self) -> &'static str {
+        match self {
+            #[cfg(feature = "base36")]
+            Self::Base36 => "base36",
+            #[cfg(feature = "base58")]
+            Self::Base58 => "base58",
+            #[cfg(feature = "base64")]
+            Self::Base64 => "base64",
+            #[cfg(feature = "uuencode")]
+            Self::Uuencode => "uuencode",
+            #[cfg(feature = "hex")]
+            Self::Hex => "hex",
+            #[cfg(feature = "intel-hex")]
+            Self::IntelHex => "intel-hex",
+            #[cfg(feature = "srec")]
+            Self::Srec => "srec",
+            #[cfg(feature = "binhex")]
+            Self::BinHex => "binhex",
+            #[cfg(feature = "base32")]
+            Self::Base32 => "base32",
+            #[cfg(feature = "base62")]
+            Self::Base62 => "base62",
+            #[cfg(feature = "base45")]
+            Self::Base45 => "base45",
+            #[cfg(feature = "line-dump")]
+            Self::LineDump => "line-dump",
+            #[cfg(feature = "filename-safe")]
+            Self::FilenameSafe => "filename-safe",
+            #[cfg(feature = "escaped")]
+            Self::Escaped => "escaped",
+            #[cfg(feature = "base94")]
+            Self::Base94 => "base94",
+        }
+    }
+
+    /// Returns the digits the encoding writes its data with.
+    ///
+    /// Padding, separators, line breaks and record headers are not included.
+    ///
+    /// # Returns
+    /// The digits in value order, e.g. `"0123456789abcdef"` for hex
+    #[must_use = "This returns the alphabet but does nothing if unused"]
+    pub const fn get_alphabet(self) -> &'static str {
+        match self {
+            #[cfg(feature = "base36")]
+            Self::Base36 => "0123456789abcdefghijklmnopqrstuvwxyz",
+            #[cfg(feature = "base58")]
+            Self::Base58 => "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz",
+            #[cfg(feature = "base64")]
+            Self::Base64 => "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/",
+            #[cfg(feature = "uuencode")]
+            Self::Uuencode => "`!\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_",
+            #[cfg(feature = "hex")]
+            Self::Hex => "0123456789abcdef",
+            #[cfg(feature = "intel-hex")]
+            Self::IntelHex => "0123456789ABCDEF",
+            #[cfg(feature = "srec")]
+            Self::Srec => "0123456789ABCDEF",
+            #[cfg(feature = "binhex")]
+            Self::BinHex => "!\"#$%&'()*+,-012345689@ABCDEFGHIJKLMNPQRSTUVXYZ[`abcdefhijklmpqr",
+            #[cfg(feature = "base32")]
+            Self::Base32 => "ABCDEFGHIJKLMNOPQRSTUVWXYZ234567",
+            #[cfg(feature = "base62")]
+            Self::Base62 => "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz",
+            #[cfg(feature = "base45")]
+            Self::Base45 => "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:",
+            #[cfg(feature = "line-dump")]
+            Self::LineDump => "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/",
+            #[cfg(feature = "filename-safe")]
+            Self::FilenameSafe => "abcdefghijklmnopqrstuvwxyz234567",
+            #[cfg(feature = "escaped")]
+            Self::Escaped => {
+                " !\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~"
+            }
+            #[cfg(feature = "base94")]
+            Self::Base94 => {
+                "!\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~"
+            }
+        }
+    }
+
+    /// Returns true if decoding distinguishes upper and lower case letters.
+    #[must_use = "This returns whether the encoding is case-sensitive but does nothing if unused"]
+    pub const fn is_case_sensitive(self) -> bool {
+        match self {
+            #[cfg(feature = "base36")]
+            Self::Base36 => false,
+            #[cfg(feature = "hex")]
+            Self::Hex => false,
+            #[cfg(feature = "intel-hex")]
+            Self::IntelHex => false,
+            #[cfg(feature = "filename-safe")]
+            Self::FilenameSafe => false,
+            #[allow(unreachable_patterns)]
+            _ => true,
+        }
+    }
+
+    /// Returns true if the encoding writes the bytes as one number, as Base36,
+    /// Base58 and Base62 do, so leading zero bytes are not kept and must be
+    /// restored from a known length.
+    #[must_use = "This returns whether the encoding is numeric but does nothing if unused"]
+    pub const fn is_numeric(self) -> bool {
+        match self {
+            #[cfg(feature = "base36")]
+            Self::Base36 => true,
+            #[cfg(feature = "base58")]
+            Self::Base58 => true,
+            #[cfg(feature = "base62")]
+            Self::Base62 => true,
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+
+    /// Returns the bits of data each digit of the alphabet carries.
+    ///
+    /// This is `log2` of the alphabet size and ignores padding, line breaks and
+    /// record overhead, so line-based formats use more characters than it
+    /// suggests.
+    ///
+    /// # Returns
+    /// The bits per character, e.g. `4.0` for hex or about `5.86` for Base58
+    #[must_use = "This returns the density but does nothing if unused"]
+    pub fn get_bits_per_char(self) -> f64 {
+        f64::from(u32::try_from(self.get_alphabet().len()).unwrap_or(u32::MAX)).log2()
+    }
+
+    /// Returns the most characters [`ByteVec::try_encode`](crate::ByteVec::try_encode)
+    /// writes for `len` bytes in this encoding, for sizing buffers.
+    ///
+    /// The bound holds for any bytes, including runs that `BinHex` escapes,
+    /// and is exact for every encoding except Base36, Base58, Base62 and
+    /// `BinHex`, where it may be a character or so over. Arithmetic saturates,
+    /// so the result is never too small. Being `const`, it can size arrays;
+    /// see [`max_encoded_len`](crate::const_encode::max_encoded_len).
+    ///
+    /// # Arguments
+    /// * `len` - The number of bytes to encode
+    ///
+    /// # Returns
+    /// The maximum encoded length in bytes of text
+    #[must_use = "This returns the maximum length but does nothing if unused"]
+    pub const fn get_max_encoded_len(self, len: usize) -> usize {
+        match self {
+            #[cfg(feature = "base36")]
+            Self::Base36 => max_digits(len, LOG2_36),
+            #[cfg(feature = "base58")]
+            Self::Base58 => max_digits(len, LOG2_58),
+            #[cfg(feature = "base62")]
+            Self::Base62 => max_digits(len, LOG2_62),
+            #[cfg(feature = "base64")]
+            Self::Base64 => len.div_ceil(3).saturating_mul(4),
+            #[cfg(feature = "hex")]
+            Self::Hex => len.saturating_mul(2),
+            // 45 bytes per line: a length character, 4 characters per 3
+            // bytes and a line break, then a closing "`" line.
+            #[cfg(feature = "uuencode")]
+            Self::Uuencode => {
+                let tail = len % 45;
+                let tail_chars = if tail == 0 {
+                    0
+                } else {
+                    2 + tail.div_ceil(3) * 4
+                };
+                (len / 45).saturating_mul(62).saturating_add(tail_chars + 2)
+            }
+            // 16-byte data records of 12 characters plus 2 per byte, a 16
+            // character extended address record per further 64 KiB and a
+            // 12 character end-of-file record.
+            #[cfg(feature = "intel-hex")]
+            Self::IntelHex => {
+                let segments = len.saturating_sub(1) / 0x1_0000;
+                len.div_ceil(16)
+                    .saturating_mul(12)
+                    .saturating_add(len.saturating_mul(2))
+                    .saturating_add(segments.saturating_mul(16))
+                    .saturating_add(12)
+            }
+            #[cfg(feature = "srec")]
+            Self::Srec => srec_len(len),
+            #[cfg(feature = "binhex")]
+            Self::BinHex => binhex_len(len),
+            #[cfg(feature = "base32")]
+            Self::Base32 => len.div_ceil(5).saturating_mul(8),
+            #[cfg(feature = "base45")]
+            Self::Base45 => (len / 2).saturating_mul(3).saturating_add(len % 2 * 2),
+            #[cfg(feature = "line-dump")]
+            Self::LineDump => line_dump_len(len),
+            #[cfg(feature = "filename-safe")]
+            Self::FilenameSafe => {
+                if len == 0 {
+                    1
+                } else {
+                    len.saturating_mul(8).div_ceil(5)
+                }
+            }
+            #[cfg(feature = "escaped")]
+            Self::Escaped => len.saturating_mul(4),
+            #[cfg(feature = "base94")]
+            Self::Base94 => {
+                use crate::algorithm::base94::BLOCK_CHARS;
+
+                (len / 9)
+                    .saturating_mul(11)
+                    .saturating_add(BLOCK_CHARS[len % 9])
+            }
+        }
+    }
+
+    /// Returns the enabled encoding with the shortest output for a payload of
+    /// `payload_len` bytes that writes only characters in `allowed`.
+    ///
+    /// Lengths are measured by encoding a sample of `payload_len` bytes, so
+    /// headers, padding and line breaks count, and encodings that cannot
+    /// encode that many bytes, such as filename-safe past its limit, are
+    /// skipped. Ties go to the encoding declared first.
+    ///
+    /// # Arguments
+    /// * `allowed` - The characters the channel allows
+    /// * `payload_len` - The typical number of bytes to encode
+    ///
+    /// # Returns
+    /// The densest encoding whose output fits `allowed`, or `None` if no
+    /// enabled encoding fits
+    #[must_use = "This returns the suggested encoding but does nothing if unused"]
+    pub fn best_for(allowed: &CharSet, payload_len: usize) -> Option<Self> {
+        let sample = charset::sample(payload_len);
+        Self::iter()
+            .filter(|&encoding| charset::fits(encoding, allowed))
+            .filter_map(|encoding| {
+                sample
+                    .try_encode(encoding)
+                    .ok()
+                    .map(|encoded| (encoded.get_string().len(), encoding))
+            })
+            .min()
+            .map(|(_, encoding)| encoding)
+    }
+}
+
+impl std::fmt::Display for Encoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(self.get_name())
+    }
+}
+
+/// Parses an encoding from its name as returned by [`Encoding::get_name`], ignoring case.
+impl FromStr for Encoding {
+    type Err = SerialiseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            #[cfg(feature = "base36")]
+            "base36" => Ok(Self::Base36),
+            #[cfg(feature = "base58")]
+            "base58" => Ok(Self::Base58),
+            #[cfg(feature = "base64")]
+            "base64" => Ok(Self::Base64),
+            #[cfg(feature = "uuencode")]
+            "uuencode" => Ok(Self::Uuencode),
+            #[cfg(feature = "hex")]
+            "hex" => Ok(Self::Hex),
+            #[cfg(feature = "intel-hex")]
+            "intel-hex" => Ok(Self::IntelHex),
+            #[cfg(feature = "srec")]
+            "srec" => Ok(Self::Srec),
+            #[cfg(feature = "binhex")]
+            "binhex" => Ok(Self::BinHex),
+            #[cfg(feature = "base32")]
+            "base32" => Ok(Self::Base32),
+            #[cfg(feature = "base62")]
+            "base62" => Ok(Self::Base62),
+            #[cfg(feature = "base45")]
+            "base45" => Ok(Self::Base45),
+            #[cfg(feature = "line-dump")]
+            "line-dump" => Ok(Self::LineDump),
+            #[cfg(feature = "filename-safe")]
+            "filename-safe" => Ok(Self::FilenameSafe),
+            #[cfg(feature = "escaped")]
+            "escaped" => Ok(Self::Escaped),
+            #[cfg(feature = "base94")]
+            "base94" => Ok(Self::Base94),
+            _ => Err(SerialiseError::new(format!("unknown encoding '{s}'"))),
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "base58", feature = "intel-hex"))]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_display_base58() {
+        assert_eq!(Encoding::Base58.to_string(), "base58");
+    }
+
+    #[test]
+    fn test_display_intel_hex() {
+        assert_eq!(format!("{:>10}", Encoding::IntelHex), " intel-hex");
+    }
+
+    #[test]
+    fn test_metadata() {
+        assert_eq!(Encoding::iter().count(), Encoding::ALL.len());
+        assert_eq!(Encoding::IntelHex.get_alphabet().len(), 16);
+        assert!(Encoding::Base58.is_case_sensitive());
+        assert!(!Encoding::IntelHex.is_case_sensitive());
+        assert!(Encoding::Base58.is_numeric() && !Encoding::IntelHex.is_numeric());
+        assert!((Encoding::Base58.get_bits_per_char() - 58f64.log2()).abs() < 1e-9);
+        assert!(
+            Encoding::iter().all(|e| e.get_alphabet().is_ascii() && e.get_bits_per_char() >= 4.0)
+        );
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert!(matches!("base58".parse::<Encoding>(), Ok(Encoding::Base58)));
+        assert!(matches!(
+            "Intel-HEX".parse::<Encoding>(),
+            Ok(Encoding::IntelHex)
+        ));
+        assert!("base99".parse::<Encoding>().is_err());
+        assert!(
+            Encoding::iter()
+                .all(|e| matches!(e.get_name().parse::<Encoding>(), Ok(parsed) if parsed == e))
+        );
+    }
+
+    #[test]
+    fn test_max_encoded_len_bounds_output() {
+        use crate::ByteVec;
+        use std::sync::Arc;
+
+        let patterns: [fn(usize) -> u8; 4] = [
+            |_| 0x00,
+            |_| 0xff,
+            |_| 0x90,
+            |i| u8::try_from(i % 251).unwrap_or_default(),
+        ];
+        for encoding in Encoding::iter() {
+            for len in (0..100).chain([1000, 4096]) {
+                for pattern in patterns {
+                    let bytes = ByteVec::new(Arc::new((0..len).map(pattern).collect()));
+                    let Ok(encoded) = bytes.try_encode(encoding) else {
+                        continue;
+                    };
+                    let max = encoding.get_max_encoded_len(len);
+                    assert!(encoded.len() <= max, "{encoding} {len} {max}");
+                    if pattern(0) != 0xff || encoding.get_name() == "binhex" {
+                        continue;
+                    }
+                    if matches!(encoding.get_name(), "base36" | "base58" | "base62") {
+                        assert!(encoded.len() + 1 >= max, "{encoding} {len} {max}");
+                    } else {
+                        assert_eq!(encoded.len(), max, "{encoding} {len}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "base58")]
+    fn test_max_encoded_len_sizes_arrays() {
+        use crate::const_encode::max_encoded_len;
+
+        const BUF: [u8; max_encoded_len::<32>(Encoding::Base58)] =
+            [0; max_encoded_len::<32>(Encoding::Base58)];
+        assert_eq!(BUF.len(), 44);
+        assert_eq!(Encoding::Base58.get_max_encoded_len(0), 1);
+        assert_eq!(Encoding::Base58.get_max_encoded_len(usize::MAX), usize::MAX);
+    }
 }
@@ -0,0 +1,234 @@
+use std::sync::Arc;
+
+use crate::{ByteVec, EncodedString, Encoding, ErrorKind, SerialiseError, checksum::Crc32};
+
+/// Every envelope starts with these characters.
+pub const PREFIX: &str = "xx";
+
+/// The version of the envelope format written by this crate.
+pub const VERSION: u32 = 1;
+
+/// Number of hex digits in the checksum at the end of a version 1 envelope.
+const CHECKSUM_LEN: usize = 8;
+
+/// Returns the character that identifies `encoding` in a version 1 envelope.
+///
+/// These characters are part of the format and never change; new encodings
+/// get new characters.
+#[must_use = "This returns the encoding character but does nothing if unused"]
+pub const fn encoding_char(encoding: Encoding) -> char {
+    match encoding {
+        #[cfg(feature = "base36")]
+        Encoding::Base36 => 'k',
+        #[cfg(feature = "base58")]
+        Encoding::Base58 => 'm',
+        #[cfg(feature = "base64")]
+        Encoding::Base64 => 'b',
+        #[cfg(feature = "uuencode")]
+        Encoding::Uuencode => 'u',
+        #[cfg(feature = "hex")]
+        Encoding::Hex => 'h',
+        #[cfg(feature = "intel-hex")]
+        Encoding::IntelHex => 'i',
+        #[cfg(feature = "srec")]
+        Encoding::Srec => 's',
+        #[cfg(feature = "binhex")]
+        Encoding::BinHex => 'x',
+        #[cfg(feature = "base32")]
+        Encoding::Base32 => 't',
+        #[cfg(feature = "base62")]
+        Encoding::Base62 => 'g',
+        #[cfg(feature = "base45")]
+        Encoding::Base45 => 'q',
+        #[cfg(feature = "line-dump")]
+        Encoding::LineDump => 'l',
+        #[cfg(feature = "filename-safe")]
+        Encoding::FilenameSafe => 'f',
+        #[cfg(feature = "escaped")]
+        Encoding::Escaped => 'e',
+        #[cfg(feature = "base94")]
+        Encoding::Base94 => 'p',
+    }
+}
+
+/// Returns the version of an envelope without checking the rest of it.
+///
+/// The format promises that every version starts with [`PREFIX`] followed by
+/// the version as decimal digits without leading zeros and then a character
+/// that is not a digit, so this works on envelopes written by any later
+/// version of the crate. Use it to route envelopes this version cannot open.
+///
+/// # Arguments
+/// * `envelope` - The envelope text
+///
+/// # Returns
+/// The format version, e.g. `1`
+///
+/// # Errors
+/// Returns `SerialiseError` if `envelope` does not start with [`PREFIX`] and a
+/// version number.
+pub fn get_version(envelope: &str) -> Result<u32, SerialiseError> {
+    let Some(rest) = envelope.strip_prefix(PREFIX) else {
+        return Err(SerialiseError::new(format!(
+            "envelope must start with '{PREFIX}'"
+        )));
+    };
+    let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+    rest[..digits]
+        .parse::<u32>()
+        .ok()
+        .filter(|_| !rest.starts_with('0'))
+        .ok_or_else(|| SerialiseError::new("envelope has no version number".to_string()))
+}
+
+/// Wraps an encoded string in a version 1 envelope.
+///
+/// The envelope is `xx1`, one character naming the encoding (see
+/// [`encoding_char`]), the payload unchanged and the CRC-32 of everything
+/// before it as eight lowercase hex digits.
+///
+/// # Arguments
+/// * `encoded` - The encoded string to wrap
+///
+/// # Returns
+/// The envelope text
+#[must_use = "This returns the envelope but does nothing if unused"]
+pub fn seal(encoded: &EncodedString) -> String {
+    let body = format!(
+        "{PREFIX}{VERSION}{}{}",
+        encoding_char(encoded.get_encoding()),
+        encoded.get_string()
+    );
+    let checksum = Crc32::checksum(body.as_bytes());
+    format!("{body}{checksum:08x}")
+}
+
+/// Checks an envelope and returns the encoded string inside it.
+///
+/// # Arguments
+/// * `envelope` - The envelope text
+///
+/// # Returns
+/// The payload with its encoding
+///
+/// # Errors
+/// Returns `SerialiseError` if `envelope` is not an envelope, has a version
+/// other than 1, names an encoding this build does not have, or fails its
+/// checksum, in which case the kind is `ErrorKind::ChecksumMismatch`.
+pub fn open(envelope: &str) -> Result<EncodedString, SerialiseError> {
+    let version = get_version(envelope)?;
+    if version != VERSION {
+        return Err(SerialiseError::new(format!(
+            "envelope version {version} is not supported, only version {VERSION}"
+        )));
+    }
+    let header_len = PREFIX.len() + 1;
+    let Some(tag) = envelope[header_len..].chars().next() else {
+        return Err(SerialiseError::new(
+            "envelope has no encoding character".to_string(),
+        ));
+    };
+    let Some(encoding) = Encoding::iter().find(|&e| encoding_char(e) == tag) else {
+        return Err(SerialiseError::new(format!(
+            "envelope encoding '{tag}' is not known or not enabled in this build"
+        )));
+    };
+    let body_end = envelope
+        .len()
+        .checked_sub(CHECKSUM_LEN)
+        .filter(|&end| end >= header_len + tag.len_utf8())
+        .filter(|&end| envelope.is_char_boundary(end))
+        .ok_or_else(|| SerialiseError::new("envelope has no checksum".to_string()))?;
+
+    let (body, checksum) = envelope.split_at(body_end);
+    let expected = format!("{:08x}", Crc32::checksum(body.as_bytes()));
+    if checksum != expected {
+        return Err(SerialiseError::new(format!(
+            "envelope checksum is '{checksum}', expected '{expected}'"
+        ))
+        .with_kind(ErrorKind::ChecksumMismatch));
+    }
+    Ok(EncodedString::new(
+        encoding,
+        body[header_len + tag.len_utf8()..].to_string(),
+    ))
+}
+
+/// Encodes `bytes` and wraps them in a version 1 envelope.
+///
+/// # Arguments
+/// * `bytes` - The bytes to encode
+/// * `encoding` - The encoding of the payload
+///
+/// # Returns
+/// The envelope text
+///
+/// # Errors
+/// Returns `SerialiseError` if the bytes cannot be encoded with `encoding`.
+pub fn encode(bytes: &[u8], encoding: Encoding) -> Result<String, SerialiseError> {
+    let encoded = ByteVec::new(Arc::new(bytes.to_vec())).try_encode(encoding)?;
+    Ok(seal(&encoded))
+}
+
+/// Opens an envelope and decodes its payload.
+///
+/// # Arguments
+/// * `envelope` - The envelope text
+///
+/// # Returns
+/// The decoded bytes
+///
+/// # Errors
+/// Returns `SerialiseError` if the envelope cannot be opened, as for [`open`],
+/// or its payload does not decode.
+pub fn decode(envelope: &str) -> Result<Arc<ByteVec>, SerialiseError> {
+    open(envelope)?.try_decode()
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "base58", feature = "hex"))]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let envelope = encode(b"hello", Encoding::Hex).unwrap_or_default();
+        assert!(envelope.starts_with("xx1h68656c6c6f"));
+        assert_eq!(envelope.len(), "xx1h68656c6c6f".len() + 8);
+        assert!(matches!(decode(&envelope), Ok(b) if b.get_bytes() == b"hello"));
+
+        let envelope = encode(b"hello", Encoding::Base58).unwrap_or_default();
+        assert!(matches!(
+            open(&envelope),
+            Ok(e) if e.get_encoding() == Encoding::Base58
+        ));
+    }
+
+    #[test]
+    fn test_corruption_is_checksum_mismatch() {
+        let envelope = encode(b"hello", Encoding::Hex).unwrap_or_default();
+        let corrupted = envelope.replacen("68", "69", 1);
+        assert!(matches!(
+            open(&corrupted),
+            Err(e) if e.get_kind() == ErrorKind::ChecksumMismatch
+        ));
+        let retagged = envelope.replacen("xx1h", "xx1m", 1);
+        assert!(open(&retagged).is_err());
+        assert!(open("xx1h").is_err());
+    }
+
+    #[test]
+    fn test_future_versions_are_recognised() {
+        assert!(matches!(get_version("xx1h00"), Ok(1)));
+        assert!(matches!(get_version("xx12whatever"), Ok(12)));
+        assert!(matches!(
+            open("xx2h00000000"),
+            Err(e) if e.get_message().contains("version 2 is not supported")
+        ));
+        assert!(get_version("yy1h").is_err());
+        assert!(get_version("xxh").is_err());
+        assert!(get_version("xx01h").is_err());
+        assert!(open("xx1?00000000").is_err());
+    }
+}
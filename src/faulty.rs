@@ -0,0 +1,263 @@
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use crate::{EncodedString, Encoder, SerialiseError};
+
+/// A fault injected by [`FaultyEncoder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// The call returned an error instead of running
+    Error,
+    /// The character at `position` was replaced by another character of the
+    /// same text, so it stays in the alphabet
+    Substitute {
+        /// The character index replaced
+        position: usize,
+    },
+    /// The text was cut to its first `len` characters
+    Truncate {
+        /// The number of characters kept
+        len: usize,
+    },
+    /// The character at `position` was written twice
+    Duplicate {
+        /// The character index repeated
+        position: usize,
+    },
+}
+
+/// Wraps encoder `E` to fail or corrupt data at random, for testing how an
+/// application handles decode failures and damaged encoded text.
+///
+/// Faults come from a generator seeded by [`new`](Self::new), so the same
+/// seed and calls inject the same faults every run. On each call an error is
+/// returned with probability [`error_rate`](Self::error_rate); otherwise the
+/// text is corrupted with probability [`corrupt_rate`](Self::corrupt_rate),
+/// after encoding or before decoding. Both rates start at zero.
+///
+/// Requires the `fault-injection` feature, which is not part of `all`.
+#[derive(Debug, Clone)]
+pub struct FaultyEncoder<E> {
+    /// The generator state
+    state: u64,
+    /// The chance of an error per call
+    error_rate: f64,
+    /// The chance of corrupting the text per call
+    corrupt_rate: f64,
+    /// Every fault injected so far, in order
+    injected: Vec<Fault>,
+    /// The wrapped encoder
+    encoder: PhantomData<E>,
+}
+
+impl<E: Encoder> FaultyEncoder<E> {
+    /// Creates a wrapper that injects no faults until given a rate.
+    ///
+    /// # Arguments
+    /// * `seed` - The seed for choosing faults
+    #[must_use = "This returns the wrapper but does nothing if unused"]
+    pub const fn new(seed: u64) -> Self {
+        Self {
+            state: seed,
+            error_rate: 0.0,
+            corrupt_rate: 0.0,
+            injected: Vec::new(),
+            encoder: PhantomData,
+        }
+    }
+
+    /// Sets the chance, from 0.0 to 1.0, that a call returns an error.
+    #[must_use = "This returns the wrapper but does nothing if unused"]
+    pub const fn error_rate(mut self, rate: f64) -> Self {
+        self.error_rate = rate;
+        self
+    }
+
+    /// Sets the chance, from 0.0 to 1.0, that a call corrupts the text.
+    #[must_use = "This returns the wrapper but does nothing if unused"]
+    pub const fn corrupt_rate(mut self, rate: f64) -> Self {
+        self.corrupt_rate = rate;
+        self
+    }
+
+    /// Returns every fault injected so far, in order.
+    #[must_use = "This returns the faults but does nothing if unused"]
+    pub fn get_injected(&self) -> &[Fault] {
+        &self.injected
+    }
+
+    /// Encodes `bytes` with `E`, possibly failing or corrupting the result.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes to encode
+    ///
+    /// # Returns
+    /// The encoded text, which may be corrupted
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` for an injected error or if `E` fails.
+    pub fn try_encode(&mut self, bytes: Arc<Vec<u8>>) -> Result<EncodedString, SerialiseError> {
+        self.maybe_fail("encode")?;
+        let encoded = E::try_encode(bytes)?;
+        Ok(self.maybe_corrupt(&encoded))
+    }
+
+    /// Decodes `encoded` with `E`, possibly failing or corrupting the text
+    /// first.
+    ///
+    /// # Arguments
+    /// * `encoded` - The text to decode
+    ///
+    /// # Returns
+    /// The decoded bytes, which may come from corrupted text
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` for an injected error or if `E` fails.
+    pub fn try_decode(&mut self, encoded: &EncodedString) -> Result<Arc<Vec<u8>>, SerialiseError> {
+        self.maybe_fail("decode")?;
+        let encoded = self.maybe_corrupt(encoded);
+        E::try_decode(&encoded)
+    }
+
+    /// Returns the next number from the `SplitMix64` generator.
+    const fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns `true` with probability `rate`.
+    #[allow(clippy::cast_precision_loss)]
+    fn chance(&mut self, rate: f64) -> bool {
+        // The top 53 bits give every double in [0, 1) a step apart.
+        ((self.next() >> 11) as f64 / (1u64 << 53) as f64) < rate
+    }
+
+    /// Returns a number below `bound`, which must not be zero.
+    fn below(&mut self, bound: usize) -> usize {
+        usize::try_from(self.next() % bound as u64).unwrap_or_default()
+    }
+
+    /// Returns an injected error with probability `error_rate`.
+    fn maybe_fail(&mut self, operation: &str) -> Result<(), SerialiseError> {
+        if !self.chance(self.error_rate) {
+            return Ok(());
+        }
+        self.injected.push(Fault::Error);
+        Err(SerialiseError::new(format!(
+            "injected fault: {operation} failed"
+        )))
+    }
+
+    /// Returns `encoded`, corrupted with probability `corrupt_rate`.
+    fn maybe_corrupt(&mut self, encoded: &EncodedString) -> EncodedString {
+        let mut chars: Vec<char> = encoded.get_string().chars().collect();
+        if chars.is_empty() || !self.chance(self.corrupt_rate) {
+            return encoded.clone();
+        }
+        let position = self.below(chars.len());
+        let fault = match self.below(3) {
+            0 => {
+                let replacement = chars[self.below(chars.len())];
+                // Without a different character to use, truncating is the
+                // only change that stays in the alphabet.
+                if let Some(&other) = chars.iter().find(|&&c| c != chars[position]) {
+                    chars[position] = if replacement == chars[position] {
+                        other
+                    } else {
+                        replacement
+                    };
+                    Fault::Substitute { position }
+                } else {
+                    chars.truncate(position);
+                    Fault::Truncate { len: position }
+                }
+            }
+            1 => {
+                chars.truncate(position);
+                Fault::Truncate { len: position }
+            }
+            _ => {
+                chars.insert(position, chars[position]);
+                Fault::Duplicate { position }
+            }
+        };
+        self.injected.push(fault);
+        EncodedString::new(encoded.get_encoding(), chars.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "hex")]
+mod tests {
+
+    use super::*;
+    use crate::Hex;
+
+    fn payload() -> Arc<Vec<u8>> {
+        Arc::new(b"payload of some length".to_vec())
+    }
+
+    #[test]
+    fn test_no_faults_by_default() {
+        let mut faulty = FaultyEncoder::<Hex>::new(1);
+        for _ in 0..100 {
+            let Ok(encoded) = faulty.try_encode(payload()) else {
+                return;
+            };
+            assert!(matches!(faulty.try_decode(&encoded), Ok(b) if b == payload()));
+        }
+        assert!(faulty.get_injected().is_empty());
+    }
+
+    #[test]
+    fn test_same_seed_same_faults() {
+        let run = |seed| {
+            let mut faulty = FaultyEncoder::<Hex>::new(seed)
+                .error_rate(0.2)
+                .corrupt_rate(0.5);
+            let texts: Vec<Option<String>> = (0..50)
+                .map(|_| {
+                    faulty
+                        .try_encode(payload())
+                        .ok()
+                        .map(|e| e.get_string().clone())
+                })
+                .collect();
+            (texts, faulty.get_injected().to_vec())
+        };
+        assert_eq!(run(7), run(7));
+        assert_ne!(run(7), run(8));
+        let (texts, faults) = run(7);
+        assert!(texts.iter().any(Option::is_none));
+        assert!(faults.contains(&Fault::Error));
+        assert!(faults.iter().any(|f| matches!(f, Fault::Substitute { .. })));
+        assert!(faults.iter().any(|f| matches!(f, Fault::Truncate { .. })));
+        assert!(faults.iter().any(|f| matches!(f, Fault::Duplicate { .. })));
+    }
+
+    #[test]
+    fn test_corruption_changes_text() {
+        let mut faulty = FaultyEncoder::<Hex>::new(3).corrupt_rate(1.0);
+        let clean = Hex::try_encode(payload())
+            .ok()
+            .map(|e| e.get_string().clone());
+        for _ in 0..50 {
+            let corrupted = faulty
+                .try_encode(payload())
+                .ok()
+                .map(|e| e.get_string().clone());
+            assert!(corrupted.is_some());
+            assert_ne!(corrupted, clean);
+        }
+        assert_eq!(faulty.get_injected().len(), 50);
+        let mut always_fails = FaultyEncoder::<Hex>::new(3).error_rate(1.0);
+        assert!(
+            always_fails
+                .try_decode(&EncodedString::new(crate::Encoding::Hex, "00".to_string()))
+                .is_err()
+        );
+    }
+}
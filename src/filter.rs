@@ -0,0 +1,208 @@
+use crate::{Decoder, SerialiseError};
+
+/// Decorations to remove from encoded text before it is decoded.
+///
+/// Stages combine, e.g. `InputFilter::NONE.whitespace().comments()`. Filtering
+/// works line by line, so it can be applied to a whole string with
+/// [`InputFilter::apply`] or to input arriving in pieces with
+/// [`InputFilter::attach`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct InputFilter {
+    /// Whether to drop ASCII whitespace, including line breaks
+    whitespace: bool,
+    /// Whether to drop everything from `#` to the end of the line
+    comments: bool,
+    /// Whether to drop `>` quoting and the indentation around it at the start of a line
+    quoting: bool,
+}
+
+impl InputFilter {
+    /// Passes the input through unchanged.
+    pub const NONE: Self = Self {
+        whitespace: false,
+        comments: false,
+        quoting: false,
+    };
+
+    /// Every stage: whitespace, `#` comments and `>` quoting.
+    pub const ALL: Self = Self {
+        whitespace: true,
+        comments: true,
+        quoting: true,
+    };
+
+    /// Drops ASCII whitespace, including line breaks.
+    #[must_use = "This returns the updated filter but does nothing if unused"]
+    pub const fn whitespace(mut self) -> Self {
+        self.whitespace = true;
+        self
+    }
+
+    /// Drops everything from `#` to the end of the line.
+    ///
+    /// Do not use with an alphabet that contains `#`.
+    #[must_use = "This returns the updated filter but does nothing if unused"]
+    pub const fn comments(mut self) -> Self {
+        self.comments = true;
+        self
+    }
+
+    /// Drops `>` quoting, as in replied-to email, along with spaces and tabs
+    /// between and before the `>` characters at the start of a line.
+    #[must_use = "This returns the updated filter but does nothing if unused"]
+    pub const fn quoting(mut self) -> Self {
+        self.quoting = true;
+        self
+    }
+
+    /// Removes the configured decorations from a whole string.
+    ///
+    /// # Arguments
+    /// * `input` - The decorated text
+    ///
+    /// # Returns
+    /// The text with the decorations removed
+    #[must_use = "This returns the filtered text but does nothing if unused"]
+    pub fn apply(self, input: &str) -> String {
+        let mut state = FilterState::new();
+        input
+            .chars()
+            .filter(|&c| !c.is_ascii() || state.keep(self, c as u8))
+            .collect()
+    }
+
+    /// Wraps an incremental decoder so its input is filtered as it arrives.
+    ///
+    /// # Arguments
+    /// * `decoder` - The decoder to feed the filtered input to
+    ///
+    /// # Returns
+    /// A decoder that removes the decorations and then decodes
+    #[must_use = "This creates a decoder but does nothing if unused"]
+    pub const fn attach<D: Decoder>(self, decoder: D) -> FilteredDecoder<D> {
+        FilteredDecoder {
+            filter: self,
+            state: FilterState::new(),
+            inner: decoder,
+            scratch: Vec::new(),
+        }
+    }
+}
+
+/// Position within the current line, carried across pieces of input.
+#[derive(Debug, Clone, Copy)]
+struct FilterState {
+    /// Whether only quoting and indentation have been seen on this line
+    line_start: bool,
+    /// Whether a `#` has been seen on this line
+    in_comment: bool,
+}
+
+impl FilterState {
+    const fn new() -> Self {
+        Self {
+            line_start: true,
+            in_comment: false,
+        }
+    }
+
+    /// Returns true if `c` survives `filter`.
+    const fn keep(&mut self, filter: InputFilter, c: u8) -> bool {
+        if c == b'\n' {
+            *self = Self::new();
+            return !filter.whitespace;
+        }
+        if self.in_comment {
+            return false;
+        }
+        if filter.quoting && self.line_start && matches!(c, b'>' | b' ' | b'\t') {
+            return false;
+        }
+        self.line_start = false;
+        if filter.comments && c == b'#' {
+            self.in_comment = true;
+            return false;
+        }
+        !(filter.whitespace && c.is_ascii_whitespace())
+    }
+}
+
+/// An incremental decoder that filters its input first.
+///
+/// Created by [`InputFilter::attach`]. Line state carries across pieces, so a
+/// comment or quote prefix may be split anywhere.
+#[derive(Debug)]
+pub struct FilteredDecoder<D: Decoder> {
+    filter: InputFilter,
+    state: FilterState,
+    inner: D,
+    scratch: Vec<u8>,
+}
+
+impl<D: Decoder> FilteredDecoder<D> {
+    /// Returns the wrapped decoder.
+    #[must_use = "This returns the decoder but does nothing if unused"]
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+}
+
+impl<D: Decoder> Decoder for FilteredDecoder<D> {
+    fn feed(&mut self, input: &[u8]) -> Result<&[u8], SerialiseError> {
+        self.scratch.clear();
+        for &c in input {
+            if !c.is_ascii() || self.state.keep(self.filter, c) {
+                self.scratch.push(c);
+            }
+        }
+        self.inner.feed(&self.scratch)
+    }
+
+    fn finish(&mut self) -> Result<&[u8], SerialiseError> {
+        self.state = FilterState::new();
+        self.inner.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const BLOCK: &str = "# key material\n> > Zm9v  # first\n>\tYmFy\n";
+
+    #[test]
+    fn test_apply() {
+        assert_eq!(InputFilter::ALL.apply(BLOCK), "Zm9vYmFy");
+        assert_eq!(
+            InputFilter::NONE.comments().apply(BLOCK),
+            "\n> > Zm9v  \n>\tYmFy\n"
+        );
+        assert_eq!(
+            InputFilter::NONE.quoting().apply("> a > b\n>>c"),
+            "a > b\nc"
+        );
+        assert_eq!(InputFilter::NONE.apply(BLOCK), BLOCK);
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn test_attach_across_pieces() {
+        let mut decoder = InputFilter::ALL.attach(crate::Base64Decoder::new());
+        let mut out: Vec<u8> = Vec::new();
+        for piece in BLOCK.as_bytes().chunks(3) {
+            out.extend_from_slice(decoder.feed(piece).unwrap_or_default());
+        }
+        assert!(decoder.finish().is_ok());
+        assert_eq!(out, b"foobar");
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn test_unfiltered_decorations_are_err() {
+        let mut decoder = InputFilter::NONE
+            .whitespace()
+            .attach(crate::Base64Decoder::new());
+        assert!(decoder.feed(BLOCK.as_bytes()).is_err());
+    }
+}
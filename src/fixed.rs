@@ -0,0 +1,190 @@
+use crate::SerialiseError;
+
+#[cfg(feature = "base32")]
+use crate::Base32;
+#[cfg(feature = "base64")]
+use crate::Base64;
+#[cfg(feature = "hex")]
+use crate::Hex;
+
+/// An encoding whose output length depends only on the input length, so it can
+/// be written into fixed-capacity strings.
+///
+/// Every `IN_BLOCK` input bytes, or part of one, produce `OUT_BLOCK` output
+/// characters.
+pub trait FixedExpansion {
+    /// Input bytes per block
+    const IN_BLOCK: usize;
+    /// Output characters per block, including padding
+    const OUT_BLOCK: usize;
+
+    /// Writes the encoding of `input` to the start of `output`.
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `output` is shorter than [`encoded_len`].
+    fn encode_slice(input: &[u8], output: &mut [u8]) -> Result<usize, SerialiseError>;
+}
+
+#[cfg(feature = "hex")]
+impl FixedExpansion for Hex {
+    const IN_BLOCK: usize = 1;
+    const OUT_BLOCK: usize = 2;
+
+    fn encode_slice(input: &[u8], output: &mut [u8]) -> Result<usize, SerialiseError> {
+        Self::encode_slice(input, output)
+    }
+}
+
+#[cfg(feature = "base32")]
+impl FixedExpansion for Base32 {
+    const IN_BLOCK: usize = 5;
+    const OUT_BLOCK: usize = 8;
+
+    fn encode_slice(input: &[u8], output: &mut [u8]) -> Result<usize, SerialiseError> {
+        Self::encode_slice(input, output)
+    }
+}
+
+#[cfg(feature = "base64")]
+impl FixedExpansion for Base64 {
+    const IN_BLOCK: usize = 3;
+    const OUT_BLOCK: usize = 4;
+
+    fn encode_slice(input: &[u8], output: &mut [u8]) -> Result<usize, SerialiseError> {
+        Self::encode_slice(input, output)
+    }
+}
+
+/// Returns the length of the encoding of `len` bytes in `E`.
+#[must_use]
+pub const fn encoded_len<E: FixedExpansion>(len: usize) -> usize {
+    len.div_ceil(E::IN_BLOCK) * E::OUT_BLOCK
+}
+
+/// Encodes `bytes` into a stack buffer of `N` bytes and passes the output to `f`.
+fn with_encoded<E: FixedExpansion, const N: usize, T>(
+    bytes: &[u8],
+    f: impl FnOnce(&str) -> Result<T, SerialiseError>,
+) -> Result<T, SerialiseError> {
+    let mut buf = [0u8; N];
+    let len = E::encode_slice(bytes, &mut buf)?;
+    let text = core::str::from_utf8(&buf[..len])
+        .map_err(|_| SerialiseError::new("encoded output is not ASCII".to_string()))?;
+    f(text)
+}
+
+/// Encodes `bytes` into a `heapless::String` of capacity `N`.
+///
+/// # Arguments
+/// * `bytes` - The bytes to encode
+///
+/// # Returns
+/// The encoded string
+///
+/// # Errors
+/// Returns `SerialiseError` if the output is longer than `N`.
+#[cfg(feature = "heapless")]
+pub fn to_heapless<E: FixedExpansion, const N: usize>(
+    bytes: &[u8],
+) -> Result<heapless::String<N>, SerialiseError> {
+    with_encoded::<E, N, _>(bytes, |text| {
+        heapless::String::try_from(text)
+            .map_err(|_| SerialiseError::new(format!("output does not fit in {N} bytes")))
+    })
+}
+
+/// Encodes a byte array into a `heapless::String` of capacity `N`, checking at
+/// compile time that `N` is large enough.
+///
+/// # Arguments
+/// * `bytes` - The bytes to encode
+///
+/// # Returns
+/// The encoded string
+#[cfg(feature = "heapless")]
+#[must_use]
+pub fn array_to_heapless<E: FixedExpansion, const M: usize, const N: usize>(
+    bytes: &[u8; M],
+) -> heapless::String<N> {
+    const {
+        assert!(
+            N >= encoded_len::<E>(M),
+            "capacity is too small for the output"
+        );
+    };
+    to_heapless::<E, N>(bytes).unwrap_or_default()
+}
+
+/// Encodes `bytes` into an `arrayvec::ArrayString` of capacity `N`.
+///
+/// # Arguments
+/// * `bytes` - The bytes to encode
+///
+/// # Returns
+/// The encoded string
+///
+/// # Errors
+/// Returns `SerialiseError` if the output is longer than `N`.
+#[cfg(feature = "arrayvec")]
+pub fn to_array_string<E: FixedExpansion, const N: usize>(
+    bytes: &[u8],
+) -> Result<arrayvec::ArrayString<N>, SerialiseError> {
+    with_encoded::<E, N, _>(bytes, |text| {
+        arrayvec::ArrayString::from(text)
+            .map_err(|_| SerialiseError::new(format!("output does not fit in {N} bytes")))
+    })
+}
+
+/// Encodes a byte array into an `arrayvec::ArrayString` of capacity `N`,
+/// checking at compile time that `N` is large enough.
+///
+/// # Arguments
+/// * `bytes` - The bytes to encode
+///
+/// # Returns
+/// The encoded string
+#[cfg(feature = "arrayvec")]
+#[must_use]
+pub fn array_to_array_string<E: FixedExpansion, const M: usize, const N: usize>(
+    bytes: &[u8; M],
+) -> arrayvec::ArrayString<N> {
+    const {
+        assert!(
+            N >= encoded_len::<E>(M),
+            "capacity is too small for the output"
+        );
+    };
+    to_array_string::<E, N>(bytes).unwrap_or_default()
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "hex", feature = "base32", feature = "base64"))]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_encoded_len() {
+        assert_eq!(encoded_len::<Hex>(5), 10);
+        assert_eq!(encoded_len::<Base32>(6), 16);
+        assert_eq!(encoded_len::<Base64>(5), 8);
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn test_heapless() {
+        assert!(matches!(to_heapless::<Base64, 8>(b"hello"), Ok(s) if s == "aGVsbG8="));
+        assert!(to_heapless::<Base64, 7>(b"hello").is_err());
+        let s: heapless::String<16> = array_to_heapless::<Base32, 6, 16>(b"foobar");
+        assert_eq!(s, "MZXW6YTBOI======");
+    }
+
+    #[cfg(feature = "arrayvec")]
+    #[test]
+    fn test_array_string() {
+        assert!(matches!(to_array_string::<Hex, 4>(b"\x01\xab"), Ok(s) if s.as_str() == "01ab"));
+        assert!(to_array_string::<Hex, 3>(b"\x01\xab").is_err());
+        let s = array_to_array_string::<Base64, 5, 8>(b"hello");
+        assert_eq!(s.as_str(), "aGVsbG8=");
+    }
+}
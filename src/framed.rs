@@ -0,0 +1,256 @@
+use std::io::{self, BufRead, Read};
+
+use crate::{Decoder, SerialiseError};
+
+/// Transport framing wrapped around encoded text.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Framing {
+    /// Plain lines, each fed to the decoder without its line ending.
+    Lines,
+    /// HTTP/1.1 chunked transfer coding: hex chunk sizes, chunk data and a
+    /// zero-size last chunk followed by optional trailers.
+    HttpChunked,
+    /// Server-sent events: the value of each `data:` line, with other fields,
+    /// comments and blank lines dropped.
+    Sse,
+}
+
+/// Returns `line` without its trailing `\n` or `\r\n`.
+fn trim_line_ending(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+/// Reads encoded text through transport framing and decodes it, as one
+/// [`Read`] of the decoded bytes.
+///
+/// Created with [`FramedReader::new`]. Each line or chunk is fed to the
+/// decoder as it is read, so the whole payload is never held in memory.
+/// Decoding errors are returned as [`io::ErrorKind::InvalidData`] and
+/// convert back to the original `SerialiseError`.
+#[derive(Debug)]
+pub struct FramedReader<R: BufRead, D: Decoder> {
+    /// The framed input
+    reader: R,
+    /// The framing to strip
+    framing: Framing,
+    /// The decoder fed the unframed text
+    decoder: D,
+    /// The current line or chunk of input
+    frame: Vec<u8>,
+    /// Decoded bytes not yet returned
+    pending: Vec<u8>,
+    /// Offset of the next byte of `pending` to return
+    position: usize,
+    /// True once the input and the decoder are finished
+    done: bool,
+}
+
+impl<R: BufRead, D: Decoder> FramedReader<R, D> {
+    /// Creates a reader that strips `framing` from `reader` and decodes the
+    /// rest with `decoder`.
+    ///
+    /// # Arguments
+    /// * `reader` - The framed input
+    /// * `framing` - The framing to strip
+    /// * `decoder` - The decoder for the encoded text inside the framing
+    ///
+    /// # Returns
+    /// The reader
+    #[must_use = "This creates a reader but does nothing if unused"]
+    pub const fn new(reader: R, framing: Framing, decoder: D) -> Self {
+        Self {
+            reader,
+            framing,
+            decoder,
+            frame: Vec::new(),
+            pending: Vec::new(),
+            position: 0,
+            done: false,
+        }
+    }
+
+    /// Returns the framing being stripped.
+    #[must_use = "This returns the framing but does nothing if unused"]
+    pub const fn get_framing(&self) -> Framing {
+        self.framing
+    }
+
+    /// Returns the wrapped reader and decoder.
+    #[must_use = "This returns the reader and decoder but does nothing if unused"]
+    pub fn into_inner(self) -> (R, D) {
+        (self.reader, self.decoder)
+    }
+
+    /// Reads and decodes the rest of the input.
+    ///
+    /// # Returns
+    /// The decoded bytes
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if reading fails, the framing is malformed or
+    /// the text inside it does not decode.
+    pub fn decode_all(mut self) -> Result<Vec<u8>, SerialiseError> {
+        let mut out = Vec::new();
+        self.read_to_end(&mut out)?;
+        Ok(out)
+    }
+
+    /// Reads the next line into `frame`, returning false at the end of input.
+    fn read_line(&mut self) -> io::Result<bool> {
+        self.frame.clear();
+        Ok(self.reader.read_until(b'\n', &mut self.frame)? > 0)
+    }
+
+    /// Reads the next piece of encoded text into `frame`, returning false at
+    /// the end of the framed input.
+    fn next_frame(&mut self) -> Result<bool, SerialiseError> {
+        match self.framing {
+            Framing::Lines => {
+                let more = self.read_line()?;
+                let len = trim_line_ending(&self.frame).len();
+                self.frame.truncate(len);
+                Ok(more)
+            }
+            Framing::Sse => {
+                while self.read_line()? {
+                    let line = trim_line_ending(&self.frame);
+                    if let Some(value) = line.strip_prefix(b"data:") {
+                        let value = value.strip_prefix(b" ").unwrap_or(value);
+                        let (start, end) = (line.len() - value.len(), line.len());
+                        self.frame.truncate(end);
+                        self.frame.drain(..start);
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+            Framing::HttpChunked => self.next_chunk(),
+        }
+    }
+
+    /// Reads the next chunk of HTTP chunked input into `frame`, returning
+    /// false after the last chunk and its trailers.
+    fn next_chunk(&mut self) -> Result<bool, SerialiseError> {
+        if !self.read_line()? {
+            return Err(SerialiseError::new(
+                "chunked input ended before the last chunk".to_string(),
+            ));
+        }
+        let header = trim_line_ending(&self.frame);
+        let digits = header
+            .split(|&c| c == b';')
+            .next()
+            .unwrap_or_default()
+            .trim_ascii();
+        let size = std::str::from_utf8(digits)
+            .ok()
+            .filter(|digits| !digits.is_empty())
+            .and_then(|digits| usize::from_str_radix(digits, 16).ok())
+            .ok_or_else(|| {
+                SerialiseError::new(format!(
+                    "invalid chunk size line '{}'",
+                    String::from_utf8_lossy(header)
+                ))
+            })?;
+        if size == 0 {
+            // Skip any trailer fields up to the blank line that ends them.
+            while self.read_line()? && !trim_line_ending(&self.frame).is_empty() {}
+            return Ok(false);
+        }
+        self.frame.resize(size, 0);
+        self.reader.read_exact(&mut self.frame)?;
+        let mut ending = Vec::with_capacity(2);
+        self.reader.read_until(b'\n', &mut ending)?;
+        if ending.is_empty() || !trim_line_ending(&ending).is_empty() {
+            return Err(SerialiseError::new(format!(
+                "chunk of {size} bytes is not followed by a line ending"
+            )));
+        }
+        Ok(true)
+    }
+
+    /// Refills `pending` with the next decoded bytes, if any.
+    fn fill(&mut self) -> Result<(), SerialiseError> {
+        self.pending.clear();
+        self.position = 0;
+        while self.pending.is_empty() && !self.done {
+            if self.next_frame()? {
+                self.pending
+                    .extend_from_slice(self.decoder.feed(&self.frame)?);
+            } else {
+                self.pending.extend_from_slice(self.decoder.finish()?);
+                self.done = true;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: BufRead, D: Decoder> Read for FramedReader<R, D> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position >= self.pending.len() {
+            self.fill()?;
+        }
+        let available = &self.pending[self.position..];
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.position += len;
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "base64")]
+mod tests {
+
+    use super::*;
+    use crate::Base64Decoder;
+
+    fn decode(framing: Framing, input: &str) -> Result<Vec<u8>, SerialiseError> {
+        FramedReader::new(input.as_bytes(), framing, Base64Decoder::new()).decode_all()
+    }
+
+    #[test]
+    fn test_sse() {
+        let input =
+            ": keep-alive\nevent: log\ndata: aGVsbG8g\r\nid: 1\n\ndata:d29y\ndata: bGQ=\n\n";
+        assert!(matches!(decode(Framing::Sse, input), Ok(b) if b == b"hello world"));
+        assert!(matches!(decode(Framing::Sse, "event: ping\n\n"), Ok(b) if b.is_empty()));
+        assert!(decode(Framing::Sse, "data: a!==\n").is_err());
+        assert!(matches!(
+            decode(Framing::Lines, "aGVsbG8g\r\nd29ybGQ=\n"),
+            Ok(b) if b == b"hello world"
+        ));
+    }
+
+    #[test]
+    fn test_http_chunked() {
+        let input = "6\r\naGVsbG\r\nb;ext=1\r\n8gd29y\nbGQ=\r\n0\r\nX-Trailer: 1\r\n\r\n";
+        assert!(matches!(decode(Framing::HttpChunked, input), Ok(b) if b == b"hello world"));
+
+        let mut reader =
+            FramedReader::new(input.as_bytes(), Framing::HttpChunked, Base64Decoder::new());
+        let mut out = Vec::new();
+        let mut buf = [0u8; 2];
+        while let Ok(len @ 1..) = reader.read(&mut buf) {
+            out.extend_from_slice(&buf[..len]);
+        }
+        assert_eq!(out, b"hello world");
+    }
+
+    #[test]
+    fn test_malformed_chunks_are_err() {
+        for bad in [
+            "",
+            "6\r\naGVsbG\r\n",
+            "zz\r\naGVs\r\n0\r\n\r\n",
+            "4\r\naGVsbG8g\r\n0\r\n\r\n",
+            "8\r\naGVs\r\n",
+        ] {
+            assert!(decode(Framing::HttpChunked, bad).is_err(), "{bad:?}");
+        }
+        let error = io::Error::from(SerialiseError::new("bad".to_string()));
+        assert_eq!(SerialiseError::from(error).get_message(), "bad");
+    }
+}
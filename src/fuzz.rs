@@ -0,0 +1,276 @@
+use std::sync::Arc;
+
+use crate::{ByteVec, EncodedString, Encoding, validate};
+
+/// Decodes `text` with `encoding`, then checks that [`validate`] agrees and
+/// that the decoded bytes survive another encode and decode.
+fn check_decode(encoding: Encoding, text: &str) {
+    let decoded = EncodedString::new(encoding, text.to_string()).try_decode();
+    assert_eq!(
+        validate(encoding, text).is_ok(),
+        decoded.is_ok(),
+        "{encoding}: validate and decode disagree on {text:?}"
+    );
+    let Ok(decoded) = decoded else {
+        return;
+    };
+    // Encoders may refuse some values decoders accept, such as file names
+    // over the length limit; only values they accept must round trip.
+    let Ok(encoded) = decoded.try_encode(encoding) else {
+        return;
+    };
+    let again = encoded.try_decode();
+    assert!(
+        matches!(&again, Ok(bytes) if bytes.get_bytes() == decoded.get_bytes()),
+        "{encoding}: {text:?} decoded to {:02x?}, which re-encoded to {:?} and decoded to {again:?}",
+        decoded.get_bytes(),
+        encoded.get_string()
+    );
+}
+
+/// Fuzz target for every decoder: reads `data` as text and decodes it with
+/// every enabled encoding.
+///
+/// # Panics
+/// Panics if a decoder panics, if [`validate`] and decoding disagree, or if
+/// accepted text decodes to bytes that do not survive an encode and decode.
+pub fn decode_all(data: &[u8]) {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+    for encoding in Encoding::iter() {
+        check_decode(encoding, text);
+    }
+}
+
+/// Fuzz target for one decoder at a time: the first byte of `data` picks the
+/// encoding and the rest is decoded as text, so the fuzzer can learn each
+/// format separately.
+///
+/// # Panics
+/// Panics for the same reasons as [`decode_all`].
+pub fn decode_one(data: &[u8]) {
+    let Some((&selector, rest)) = data.split_first() else {
+        return;
+    };
+    let Ok(text) = std::str::from_utf8(rest) else {
+        return;
+    };
+    let encoding = Encoding::ALL[usize::from(selector) % Encoding::ALL.len()];
+    check_decode(encoding, text);
+}
+
+/// Fuzz target for every encoder: encodes `data` with every enabled encoding
+/// and decodes it back.
+///
+/// # Panics
+/// Panics if an encoder or decoder panics, or if the decoded bytes differ
+/// from `data`, ignoring leading zero bytes for encodings of numeric values.
+pub fn round_trip_all(data: &[u8]) {
+    let bytes = ByteVec::new(Arc::new(data.to_vec()));
+    for encoding in Encoding::iter() {
+        let Ok(encoded) = bytes.try_encode(encoding) else {
+            continue;
+        };
+        let decoded = encoded.try_decode();
+        assert!(
+            matches!(&decoded, Ok(d) if same_value(encoding, d.get_bytes(), data)),
+            "{encoding}: {data:02x?} encoded to {:?} and decoded to {decoded:?}",
+            encoded.get_string()
+        );
+    }
+}
+
+/// Returns true if `decoded` is `original`, or for encodings of numeric
+/// values, the same number.
+fn same_value(encoding: Encoding, decoded: &[u8], original: &[u8]) -> bool {
+    if !encoding.is_numeric() {
+        return decoded == original;
+    }
+    let value = |b: &[u8]| {
+        let start = b.iter().position(|&b| b != 0).unwrap_or(b.len());
+        b[start..].to_vec()
+    };
+    value(decoded) == value(original)
+}
+
+/// Compares an encoder and decoder with a reference implementation on `data`,
+/// which is encoded as bytes and decoded as text.
+///
+/// The encodings must be identical. Text the reference decodes must decode to
+/// the same bytes; text only this crate decodes must be the reference
+/// encoding of the result once `normalise` removes documented leniencies, so
+/// any other non-canonical text this crate accepts is reported.
+#[cfg(any(feature = "base64", feature = "hex"))]
+fn differential(
+    name: &str,
+    data: &[u8],
+    ours: (impl Fn(&[u8]) -> String, impl Fn(&str) -> Option<Vec<u8>>),
+    reference: (impl Fn(&[u8]) -> String, impl Fn(&str) -> Option<Vec<u8>>),
+    normalise: impl Fn(&str) -> String,
+) {
+    let (encode, decode) = ours;
+    let (reference_encode, reference_decode) = reference;
+    assert_eq!(
+        encode(data),
+        reference_encode(data),
+        "{name}: encodings of {data:02x?} differ"
+    );
+
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+    let (bytes, expected) = (decode(text), reference_decode(text));
+    assert!(
+        bytes.is_some() || expected.is_none(),
+        "{name}: {text:?} rejected, the reference decodes it to {expected:02x?}"
+    );
+    match (bytes, expected) {
+        (Some(bytes), Some(expected)) => {
+            assert_eq!(bytes, expected, "{name}: {text:?} decoded differently");
+        }
+        (Some(bytes), None) => assert_eq!(
+            normalise(text),
+            normalise(&reference_encode(&bytes)),
+            "{name}: non-canonical {text:?} accepted as {bytes:02x?}"
+        ),
+        _ => {}
+    }
+}
+
+/// Differential fuzz target comparing this crate's Base64 with a reference
+/// implementation, such as the `base64` crate's `STANDARD` engine.
+///
+/// Whitespace and missing padding, which this crate's decoder accepts, are the
+/// only differences allowed.
+///
+/// # Arguments
+/// * `data` - The fuzzer input
+/// * `reference_encode` - The reference padded standard Base64 encoder
+/// * `reference_decode` - The reference decoder, `None` for invalid text
+///
+/// # Panics
+/// Panics if the implementations disagree.
+#[cfg(feature = "base64")]
+pub fn differential_base64(
+    data: &[u8],
+    reference_encode: impl Fn(&[u8]) -> String,
+    reference_decode: impl Fn(&str) -> Option<Vec<u8>>,
+) {
+    differential(
+        "base64",
+        data,
+        (
+            |bytes: &[u8]| crate::Base64::try_to_base64(bytes).unwrap_or_default(),
+            |text: &str| crate::Base64::try_from_base64(text, 0).ok(),
+        ),
+        (reference_encode, reference_decode),
+        |text| {
+            text.chars()
+                .filter(|&c| c != '=' && !c.is_ascii_whitespace())
+                .collect()
+        },
+    );
+}
+
+/// Differential fuzz target comparing this crate's hex with a reference
+/// implementation, such as the `hex` crate's `encode` and `decode`.
+///
+/// Whitespace, which this crate's decoder skips, is the only difference
+/// allowed.
+///
+/// # Arguments
+/// * `data` - The fuzzer input
+/// * `reference_encode` - The reference lowercase hex encoder
+/// * `reference_decode` - The reference decoder, `None` for invalid text
+///
+/// # Panics
+/// Panics if the implementations disagree.
+#[cfg(feature = "hex")]
+pub fn differential_hex(
+    data: &[u8],
+    reference_encode: impl Fn(&[u8]) -> String,
+    reference_decode: impl Fn(&str) -> Option<Vec<u8>>,
+) {
+    differential(
+        "hex",
+        data,
+        (
+            |bytes: &[u8]| crate::Hex::try_to_hex(bytes).unwrap_or_default(),
+            |text: &str| crate::Hex::try_from_hex(text).ok(),
+        ),
+        (reference_encode, reference_decode),
+        |text| {
+            text.chars()
+                .filter(|c| !c.is_ascii_whitespace())
+                .map(|c| c.to_ascii_lowercase())
+                .collect()
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Deterministic pseudo-random inputs, mostly printable so the decoders
+    /// get past their character checks.
+    fn inputs() -> impl Iterator<Item = Vec<u8>> {
+        let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state.to_le_bytes()[0]
+        };
+        (0..2000).map(move |i| {
+            let len = usize::from(next()) % (8 + i % 64);
+            (0..len)
+                .map(|_| match next() {
+                    b @ 0..=191 => b' ' + b % 95,
+                    b => b,
+                })
+                .collect()
+        })
+    }
+
+    #[test]
+    fn test_decode_targets() {
+        for input in inputs() {
+            decode_all(&input);
+            decode_one(&input);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_target() {
+        for input in inputs() {
+            round_trip_all(&input);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "hex")]
+    fn test_differential_hex() {
+        let reference_encode = |bytes: &[u8]| {
+            bytes
+                .iter()
+                .flat_map(|&b| [b >> 4, b & 0x0f])
+                .map(|nibble| char::from(crate::alphabet::HEX[usize::from(nibble)]))
+                .collect::<String>()
+        };
+        let reference_decode = |text: &str| {
+            if !text.len().is_multiple_of(2) || !text.bytes().all(|c| c.is_ascii_hexdigit()) {
+                return None;
+            }
+            (0..text.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok())
+                .collect()
+        };
+        for input in inputs().chain([b"0aF9".to_vec(), b" 0a\n".to_vec()]) {
+            differential_hex(&input, reference_encode, reference_decode);
+        }
+    }
+}
@@ -0,0 +1,281 @@
+use std::{fmt::Display, ops::Range};
+
+use crate::alphabet;
+
+/// Number of bytes on each row of a hexdump.
+pub const BYTES_PER_ROW: usize = 16;
+
+/// Column of the first hex digit on a row, after the offset.
+pub(crate) const HEX_COLUMN: usize = 10;
+
+/// Column of the first character of the ASCII column on a row.
+pub(crate) const ASCII_COLUMN: usize = HEX_COLUMN + BYTES_PER_ROW * 3 + 1 + 2;
+
+/// Returns the column of the first hex digit of byte `i` on a row.
+pub(crate) const fn hex_column(i: usize) -> usize {
+    HEX_COLUMN + i * 3 + if i >= BYTES_PER_ROW / 2 { 1 } else { 0 }
+}
+
+/// Returns the bytes of `bytes` on the row starting at `start`, which may be
+/// short or empty at the end.
+pub(crate) fn row_bytes(bytes: &[u8], start: usize) -> &[u8] {
+    let start = start.min(bytes.len());
+    &bytes[start..bytes.len().min(start + BYTES_PER_ROW)]
+}
+
+/// Which column of a hexdump row a [`Cell`] is in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Column {
+    /// The two hex digits of a byte
+    Hex,
+    /// The printable character of a byte, or `.`
+    Ascii,
+}
+
+/// One byte's text in one column of a hexdump, passed to the paint callback
+/// of [`HexDump::lines_with`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cell<'a> {
+    /// Offset of the byte in the dumped bytes
+    offset: usize,
+    /// The byte
+    byte: u8,
+    /// The column the text is in
+    column: Column,
+    /// The text laid out for the byte, e.g. `"4f"` or `"O"`
+    text: &'a str,
+    /// The label of the range the byte is in, if any
+    label: Option<&'a str>,
+}
+
+impl Cell<'_> {
+    /// Returns the offset of the byte in the dumped bytes.
+    #[must_use = "This returns the offset but does nothing if unused"]
+    pub const fn get_offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the byte.
+    #[must_use = "This returns the byte but does nothing if unused"]
+    pub const fn get_byte(&self) -> u8 {
+        self.byte
+    }
+
+    /// Returns the column the text is in.
+    #[must_use = "This returns the column but does nothing if unused"]
+    pub const fn get_column(&self) -> Column {
+        self.column
+    }
+
+    /// Returns the text laid out for the byte.
+    #[must_use = "This returns the text but does nothing if unused"]
+    pub const fn get_text(&self) -> &str {
+        self.text
+    }
+
+    /// Returns the label of the range the byte is in, if any.
+    #[must_use = "This returns the label but does nothing if unused"]
+    pub const fn get_label(&self) -> Option<&str> {
+        self.label
+    }
+}
+
+/// Renders one row as `<offset>  <hex>  |<ascii>|`, passing the text of each
+/// byte through `paint`.
+pub(crate) fn render_row<'l>(
+    start: usize,
+    bytes: &[u8],
+    label: impl Fn(usize) -> Option<&'l str>,
+    paint: &mut impl FnMut(&Cell<'_>) -> String,
+) -> String {
+    let mut line = format!("{start:08x}  ");
+    for i in 0..BYTES_PER_ROW {
+        if i == BYTES_PER_ROW / 2 {
+            line.push(' ');
+        }
+        let Some(&byte) = bytes.get(i) else {
+            line.push_str("   ");
+            continue;
+        };
+        let hex = [
+            alphabet::HEX[usize::from(byte >> 4)],
+            alphabet::HEX[usize::from(byte & 0x0f)],
+        ];
+        line.push_str(&paint(&Cell {
+            offset: start + i,
+            byte,
+            column: Column::Hex,
+            text: std::str::from_utf8(&hex).unwrap_or_default(),
+            label: label(start + i),
+        }));
+        line.push(' ');
+    }
+    line.push_str(" |");
+    for (i, &byte) in bytes.iter().enumerate() {
+        let printable = if byte.is_ascii_graphic() || byte == b' ' {
+            byte
+        } else {
+            b'.'
+        };
+        line.push_str(&paint(&Cell {
+            offset: start + i,
+            byte,
+            column: Column::Ascii,
+            text: std::str::from_utf8(std::slice::from_ref(&printable)).unwrap_or_default(),
+            label: label(start + i),
+        }));
+    }
+    line.push('|');
+    line
+}
+
+/// Returns the text of a cell unchanged.
+fn plain(cell: &Cell<'_>) -> String {
+    cell.get_text().to_string()
+}
+
+/// A hexdump of bytes with optional labelled ranges, such as the fields of a
+/// protocol message.
+///
+/// Each row shows the offset, 16 bytes in hex and as ASCII, and the labels of
+/// the ranges on the row. [`HexDump::lines_with`] passes the text of every
+/// byte through a callback with its label, so a TUI or CLI tool can colour
+/// each field while the dump keeps its layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HexDump<'a> {
+    /// The bytes to dump
+    bytes: &'a [u8],
+    /// Labelled ranges, in the order they were added
+    labels: Vec<(Range<usize>, String)>,
+}
+
+impl<'a> HexDump<'a> {
+    /// Creates a hexdump of `bytes` with no labels.
+    #[must_use = "This returns the hexdump but does nothing if unused"]
+    pub const fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            labels: Vec::new(),
+        }
+    }
+
+    /// Labels a range of bytes, e.g. `0..4` as `"magic"`.
+    ///
+    /// Where ranges overlap, the one added last wins, so label a message
+    /// before the fields inside it.
+    ///
+    /// # Arguments
+    /// * `range` - The offsets to label
+    /// * `label` - The label
+    #[must_use = "This returns the updated hexdump but does nothing if unused"]
+    pub fn label(mut self, range: Range<usize>, label: impl Into<String>) -> Self {
+        self.labels.push((range, label.into()));
+        self
+    }
+
+    /// Returns the label of the byte at `offset`, if it is in a labelled range.
+    #[must_use = "This returns the label but does nothing if unused"]
+    pub fn get_label(&self, offset: usize) -> Option<&str> {
+        self.labels
+            .iter()
+            .rev()
+            .find(|(range, _)| range.contains(&offset))
+            .map(|(_, label)| label.as_str())
+    }
+
+    /// Renders the dump one row at a time.
+    ///
+    /// # Returns
+    /// The lines of the dump, without line endings
+    pub fn lines(&self) -> impl Iterator<Item = String> + '_ {
+        self.lines_with(plain)
+    }
+
+    /// Renders the dump one row at a time, passing the text of every byte in
+    /// both columns through `paint`.
+    ///
+    /// `paint` may wrap the text, e.g. in ANSI colour codes chosen from the
+    /// cell's label, but should not change its visible width.
+    ///
+    /// # Arguments
+    /// * `paint` - Returns the text to write for a cell
+    ///
+    /// # Returns
+    /// The lines of the dump, without line endings
+    pub fn lines_with<'s, F>(&'s self, mut paint: F) -> impl Iterator<Item = String> + 's
+    where
+        F: FnMut(&Cell<'_>) -> String + 's,
+    {
+        (0..self.bytes.len().div_ceil(BYTES_PER_ROW)).map(move |row| {
+            let start = row * BYTES_PER_ROW;
+            let bytes = row_bytes(self.bytes, start);
+            let mut line = render_row(start, bytes, |offset| self.get_label(offset), &mut paint);
+            let mut names: Vec<&str> = Vec::new();
+            for name in (start..start + bytes.len()).filter_map(|offset| self.get_label(offset)) {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+            if !names.is_empty() {
+                let width = ASCII_COLUMN + BYTES_PER_ROW + 1;
+                let visible = ASCII_COLUMN + bytes.len() + 1;
+                line.push_str(&" ".repeat(width - visible + 2));
+                line.push_str(&names.join(", "));
+            }
+            line
+        })
+    }
+}
+
+impl Display for HexDump<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in self.lines() {
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_layout() {
+        let dump = HexDump::new(b"Hello, world!\n\x00\xffmore").to_string();
+        assert_eq!(
+            dump,
+            "00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a 00 ff  |Hello, world!...|\n\
+             00000010  6d 6f 72 65                                       |more|\n"
+        );
+        assert_eq!(HexDump::new(b"").to_string(), "");
+    }
+
+    #[test]
+    fn test_labels() {
+        let dump = HexDump::new(b"\x01\x00\x05hello")
+            .label(0..8, "message")
+            .label(0..1, "version")
+            .label(1..3, "length");
+        assert_eq!(dump.get_label(0), Some("version"));
+        assert_eq!(dump.get_label(5), Some("message"));
+        assert_eq!(dump.get_label(8), None);
+        let lines: Vec<String> = dump.lines().collect();
+        let labels = format!("|...hello|{}version, length, message", " ".repeat(10));
+        assert!(lines[0].ends_with(&labels));
+    }
+
+    #[test]
+    fn test_paint_callback() {
+        let dump = HexDump::new(b"\x01\x00\x05hi").label(1..3, "length");
+        let lines: Vec<String> = dump
+            .lines_with(|cell| match (cell.get_label(), cell.get_column()) {
+                (Some("length"), Column::Hex) => format!("[{}]", cell.get_text()),
+                _ => cell.get_text().to_string(),
+            })
+            .collect();
+        assert!(lines[0].starts_with("00000000  01 [00] [05] 68 69"));
+        assert!(lines[0].contains("|...hi|"));
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{Encoding, SerialiseError};
+
+/// The `log` target of every record written by this crate.
+#[cfg(feature = "log")]
+pub const TARGET: &str = "base_xx";
+
+/// Runs an encode or decode and, with the `log` feature, records how it went.
+///
+/// Successes are logged at debug level and failures at warn level, each with
+/// the operation, encoding, input length and duration in microseconds as
+/// key-values. Without the feature this only calls `run`.
+///
+/// This writes one `log` event after `run` returns; it does not open a
+/// `tracing` span, so the record is not nested under the caller's spans.
+///
+/// # Arguments
+/// * `operation` - `"encode"` or `"decode"`
+/// * `encoding` - The encoding used
+/// * `input_len` - The length of the input, in bytes or characters
+/// * `run` - The operation itself
+///
+/// # Returns
+/// What `run` returned
+///
+/// # Errors
+/// Returns the error from `run`.
+#[cfg(feature = "log")]
+pub fn record<T>(
+    operation: &'static str,
+    encoding: Encoding,
+    input_len: usize,
+    run: impl FnOnce() -> Result<T, SerialiseError>,
+) -> Result<T, SerialiseError> {
+    let start = std::time::Instant::now();
+    let result = run();
+    let micros = u64::try_from(start.elapsed().as_micros()).unwrap_or(u64::MAX);
+    let encoding = encoding.get_name();
+    match &result {
+        Ok(_) => log::debug!(
+            target: TARGET,
+            operation, encoding, input_len, micros;
+            "{operation} {encoding} succeeded"
+        ),
+        Err(error) => log::warn!(
+            target: TARGET,
+            operation, encoding, input_len, micros;
+            "{operation} {encoding} failed: {}", error.get_message()
+        ),
+    }
+    result
+}
+
+/// Runs an encode or decode and, with the `log` feature, records how it went.
+#[cfg(not(feature = "log"))]
+#[inline]
+pub fn record<T>(
+    _operation: &'static str,
+    _encoding: Encoding,
+    _input_len: usize,
+    run: impl FnOnce() -> Result<T, SerialiseError>,
+) -> Result<T, SerialiseError> {
+    run()
+}
+
+#[cfg(test)]
+#[cfg(feature = "log")]
+mod tests {
+
+    use super::*;
+    use std::fmt::Write;
+    use std::sync::{Mutex, Once};
+
+    /// Records captured by [`Capture`], as (level, key-values, message).
+    static RECORDS: Mutex<Vec<(log::Level, String, String)>> = Mutex::new(Vec::new());
+
+    struct Capture;
+
+    struct Collect(String);
+
+    impl<'kvs> log::kv::VisitSource<'kvs> for Collect {
+        fn visit_pair(
+            &mut self,
+            key: log::kv::Key<'kvs>,
+            value: log::kv::Value<'kvs>,
+        ) -> Result<(), log::kv::Error> {
+            let _ = write!(self.0, "{key}={value} ");
+            Ok(())
+        }
+    }
+
+    impl log::Log for Capture {
+        fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+            metadata.target() == TARGET
+        }
+
+        fn log(&self, record: &log::Record<'_>) {
+            if !self.enabled(record.metadata()) {
+                return;
+            }
+            let mut kvs = Collect(String::new());
+            let _ = record.key_values().visit(&mut kvs);
+            if let Ok(mut records) = RECORDS.lock() {
+                records.push((record.level(), kvs.0, record.args().to_string()));
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    static CAPTURE: Capture = Capture;
+    static INIT: Once = Once::new();
+
+    fn captured(encoding: Encoding) -> Vec<(log::Level, String, String)> {
+        RECORDS
+            .lock()
+            .map(|records| {
+                records
+                    .iter()
+                    .filter(|(_, kvs, _)| kvs.contains(&format!("encoding={encoding} ")))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn install() {
+        INIT.call_once(|| {
+            let _ = log::set_logger(&CAPTURE);
+            log::set_max_level(log::LevelFilter::Debug);
+        });
+    }
+
+    #[test]
+    fn test_record_passes_result_through() {
+        install();
+        // Any enabled encoding will do, as the record only names it.
+        let Some(&encoding) = Encoding::ALL.first() else {
+            return;
+        };
+        assert!(matches!(record("encode", encoding, 3, || Ok(7)), Ok(7)));
+        let failed: Result<(), _> = record("decode", encoding, 3, || {
+            Err(SerialiseError::new("odd length".to_string()))
+        });
+        assert!(failed.is_err());
+        assert!(captured(encoding).iter().any(|(level, _, message)| {
+            *level == log::Level::Warn
+                && *message == format!("decode {encoding} failed: odd length")
+        }));
+    }
+
+    #[test]
+    #[cfg(feature = "base45")]
+    fn test_logs_success_with_key_values() {
+        install();
+        let bytes = crate::ByteVec::new(std::sync::Arc::new(b"AB".to_vec()));
+        assert!(bytes.try_encode(Encoding::Base45).is_ok());
+        let records = captured(Encoding::Base45);
+        assert!(records.iter().any(|(level, kvs, message)| {
+            *level == log::Level::Debug
+                && kvs.contains("operation=encode ")
+                && kvs.contains("input_len=2 ")
+                && kvs.contains("micros=")
+                && message == "encode base45 succeeded"
+        }));
+    }
+
+    #[test]
+    #[cfg(feature = "base94")]
+    fn test_logs_failure_as_warning() {
+        install();
+        let text = crate::EncodedString::new(Encoding::Base94, "a b\u{7f}".to_string());
+        assert!(text.try_decode().is_err());
+        let records = captured(Encoding::Base94);
+        assert!(records.iter().any(|(level, kvs, message)| {
+            *level == log::Level::Warn
+                && kvs.contains("operation=decode ")
+                && kvs.contains("input_len=4 ")
+                && message.starts_with("decode base94 failed: ")
+        }));
+    }
+}
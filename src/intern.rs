@@ -0,0 +1,262 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use crate::{ByteVec, EncodedString, Encoding, SerialiseError};
+
+/// Hit and miss counts of an [`InternPool`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct InternStats {
+    /// Lookups answered from the pool
+    hits: u64,
+    /// Lookups that had to encode or store a new value
+    misses: u64,
+    /// Values held by the pool
+    entries: usize,
+}
+
+impl InternStats {
+    /// Returns the number of lookups answered from the pool.
+    #[must_use = "This returns the hit count but does nothing if unused"]
+    pub const fn get_hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Returns the number of lookups that had to encode or store a new value.
+    #[must_use = "This returns the miss count but does nothing if unused"]
+    pub const fn get_misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Returns the number of values held by the pool.
+    #[must_use = "This returns the entry count but does nothing if unused"]
+    pub const fn get_entries(&self) -> usize {
+        self.entries
+    }
+
+    /// Returns the fraction of lookups answered from the pool, from 0 to 1,
+    /// or 0 before any lookups.
+    #[must_use = "This returns the hit rate but does nothing if unused"]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+/// The values held by an [`InternPool`].
+#[derive(Debug, Default)]
+struct Entries {
+    /// Encodings of byte strings, by encoding and then bytes
+    encoded: HashMap<Encoding, HashMap<Vec<u8>, Arc<EncodedString>>>,
+    /// Strings passed to [`InternPool::intern`]
+    interned: HashSet<Arc<EncodedString>>,
+    /// Total number of values in both maps
+    len: usize,
+}
+
+/// A pool of shared [`EncodedString`]s for workloads that encode the same
+/// small set of values over and over, such as metric labels or IDs.
+///
+/// [`InternPool::encode`] encodes each distinct input once and then hands out
+/// the same `Arc`, so repeated values cost a hash lookup and share one
+/// allocation. The pool can be shared between threads.
+#[derive(Debug, Default)]
+pub struct InternPool {
+    /// The values held, behind a lock so the pool can be shared
+    entries: Mutex<Entries>,
+    /// The most values to hold, if limited
+    max_entries: Option<usize>,
+    /// Lookups answered from the pool
+    hits: AtomicU64,
+    /// Lookups that had to encode or store a new value
+    misses: AtomicU64,
+}
+
+impl InternPool {
+    /// Creates an empty pool with no size limit.
+    #[must_use = "This returns the pool but does nothing if unused"]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty pool that holds at most `max_entries` values.
+    ///
+    /// Once full, new values are still encoded and returned but not kept, so
+    /// a stream of unique values cannot grow the pool without bound.
+    #[must_use = "This returns the pool but does nothing if unused"]
+    pub fn with_max_entries(max_entries: usize) -> Self {
+        Self {
+            max_entries: Some(max_entries),
+            ..Self::default()
+        }
+    }
+
+    /// Locks the entries, carrying on if another thread panicked while
+    /// holding the lock, as every update leaves the maps consistent.
+    fn lock(&self) -> std::sync::MutexGuard<'_, Entries> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns true if the pool has room for another value.
+    fn has_room(&self, entries: &Entries) -> bool {
+        self.max_entries.is_none_or(|max| entries.len < max)
+    }
+
+    /// Encodes `bytes`, returning the pooled value if the same bytes have
+    /// been encoded with the same encoding before.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes to encode
+    /// * `encoding` - The encoding to use
+    ///
+    /// # Returns
+    /// The shared encoded string, with integrity metadata as from
+    /// [`ByteVec::try_encode`]
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if encoding fails; failures are not pooled.
+    pub fn encode(
+        &self,
+        bytes: &[u8],
+        encoding: Encoding,
+    ) -> Result<Arc<EncodedString>, SerialiseError> {
+        if let Some(found) = self
+            .lock()
+            .encoded
+            .get(&encoding)
+            .and_then(|by_bytes| by_bytes.get(bytes))
+        {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(Arc::clone(found));
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let encoded = Arc::new(ByteVec::new(Arc::new(bytes.to_vec())).try_encode(encoding)?);
+
+        let mut entries = self.lock();
+        if let Some(found) = entries
+            .encoded
+            .get(&encoding)
+            .and_then(|by_bytes| by_bytes.get(bytes))
+        {
+            return Ok(Arc::clone(found));
+        }
+        if self.has_room(&entries) {
+            entries
+                .encoded
+                .entry(encoding)
+                .or_default()
+                .insert(bytes.to_vec(), Arc::clone(&encoded));
+            entries.len += 1;
+        }
+        drop(entries);
+        Ok(encoded)
+    }
+
+    /// Returns the pooled copy of `encoded`, adding it if no equal string,
+    /// with the same encoding and text, is pooled yet.
+    ///
+    /// # Arguments
+    /// * `encoded` - The encoded string
+    ///
+    /// # Returns
+    /// The shared encoded string
+    pub fn intern(&self, encoded: EncodedString) -> Arc<EncodedString> {
+        let mut entries = self.lock();
+        if let Some(found) = entries.interned.get(&encoded) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Arc::clone(found);
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let encoded = Arc::new(encoded);
+        if self.has_room(&entries) {
+            entries.interned.insert(Arc::clone(&encoded));
+            entries.len += 1;
+        }
+        encoded
+    }
+
+    /// Returns the hit and miss counts and the number of values held.
+    #[must_use = "This returns the statistics but does nothing if unused"]
+    pub fn get_stats(&self) -> InternStats {
+        InternStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.len(),
+        }
+    }
+
+    /// Returns the number of values held.
+    #[must_use = "This returns the number of values but does nothing if unused"]
+    pub fn len(&self) -> usize {
+        self.lock().len
+    }
+
+    /// Returns true if the pool holds no values.
+    #[must_use = "This returns whether the pool is empty but does nothing if unused"]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops every value and resets the statistics. Values already handed
+    /// out stay valid.
+    pub fn clear(&self) {
+        *self.lock() = Entries::default();
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "hex")]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_encode_shares_values() {
+        let pool = InternPool::new();
+        let first = pool.encode(b"id-1", Encoding::Hex);
+        let second = pool.encode(b"id-1", Encoding::Hex);
+        assert!(matches!((&first, &second), (Ok(a), Ok(b)) if Arc::ptr_eq(a, b)));
+        assert!(matches!(&first, Ok(e) if e.get_string() == "69642d31"));
+        assert!(pool.encode(b"id-2", Encoding::Hex).is_ok());
+
+        let stats = pool.get_stats();
+        assert_eq!((stats.get_hits(), stats.get_misses()), (1, 2));
+        assert_eq!(stats.get_entries(), 2);
+        assert!((stats.hit_rate() - 1.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_intern() {
+        let pool = InternPool::new();
+        let a = pool.intern(EncodedString::new(Encoding::Hex, "ff".to_string()));
+        let b = pool.intern(EncodedString::new(Encoding::Hex, "ff".to_string()));
+        let c = pool.intern(EncodedString::new(Encoding::Hex, "00".to_string()));
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &c));
+        assert_eq!(pool.len(), 2);
+        pool.clear();
+        assert!(pool.is_empty());
+        assert_eq!(pool.get_stats(), InternStats::default());
+    }
+
+    #[test]
+    fn test_max_entries() {
+        let pool = InternPool::with_max_entries(1);
+        assert!(pool.encode(b"a", Encoding::Hex).is_ok());
+        assert!(matches!(pool.encode(b"b", Encoding::Hex), Ok(e) if e.get_string() == "62"));
+        assert!(pool.encode(b"b", Encoding::Hex).is_ok());
+        assert_eq!(pool.len(), 1);
+        assert_eq!(pool.get_stats().get_misses(), 3);
+    }
+}
@@ -0,0 +1,204 @@
+use crate::{Base64, Base64Config, SerialiseError, alphabet};
+
+use super::as_text;
+
+/// A Base64 engine with the same output and strictness as the `base64`
+/// crate's `GeneralPurpose` engine of the same name.
+///
+/// Encoding gives byte-for-byte the same text. Decoding accepts exactly what
+/// the `base64` crate accepts: no whitespace, padding only and always when
+/// the engine pads, and no set bits after the last byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GeneralPurpose {
+    /// Whether `-` and `_` replace `+` and `/`
+    url_safe: bool,
+    /// Whether output is padded with `=`, and input must be
+    pad: bool,
+}
+
+/// The standard alphabet, padded, like `base64::engine::general_purpose::STANDARD`.
+pub const STANDARD: GeneralPurpose = GeneralPurpose {
+    url_safe: false,
+    pad: true,
+};
+
+/// The standard alphabet, unpadded, like `general_purpose::STANDARD_NO_PAD`.
+pub const STANDARD_NO_PAD: GeneralPurpose = GeneralPurpose {
+    url_safe: false,
+    pad: false,
+};
+
+/// The URL-safe alphabet, padded, like `general_purpose::URL_SAFE`.
+pub const URL_SAFE: GeneralPurpose = GeneralPurpose {
+    url_safe: true,
+    pad: true,
+};
+
+/// The URL-safe alphabet, unpadded, like `general_purpose::URL_SAFE_NO_PAD`.
+pub const URL_SAFE_NO_PAD: GeneralPurpose = GeneralPurpose {
+    url_safe: true,
+    pad: false,
+};
+
+impl GeneralPurpose {
+    /// Returns whether this engine uses the URL-safe alphabet.
+    #[must_use = "This returns the alphabet choice but does nothing if unused"]
+    pub const fn get_url_safe(&self) -> bool {
+        self.url_safe
+    }
+
+    /// Returns whether this engine pads with `=`.
+    #[must_use = "This returns the padding choice but does nothing if unused"]
+    pub const fn get_pad(&self) -> bool {
+        self.pad
+    }
+
+    /// Encodes `input`, like `Engine::encode`.
+    ///
+    /// # Arguments
+    /// * `input` - The bytes to encode
+    ///
+    /// # Returns
+    /// The Base64 text
+    #[must_use = "This returns the encoded text but does nothing if unused"]
+    pub fn encode<T: AsRef<[u8]>>(&self, input: T) -> String {
+        let config = if self.pad {
+            Base64Config::STANDARD
+        } else {
+            Base64Config::NO_PAD
+        };
+        let standard = Base64::try_to_base64_with(input.as_ref(), config).unwrap_or_default();
+        if !self.url_safe {
+            return standard;
+        }
+        alphabet::transcode_alphabet(
+            &standard,
+            alphabet::BASE64_STANDARD,
+            alphabet::BASE64_URL_SAFE,
+        )
+        .unwrap_or_default()
+    }
+
+    /// Encodes `input` onto the end of `output`, like `Engine::encode_string`.
+    ///
+    /// # Arguments
+    /// * `input` - The bytes to encode
+    /// * `output` - The string to append to
+    pub fn encode_string<T: AsRef<[u8]>>(&self, input: T, output: &mut String) {
+        output.push_str(&self.encode(input));
+    }
+
+    /// Decodes `input`, like `Engine::decode`.
+    ///
+    /// # Arguments
+    /// * `input` - The Base64 text
+    ///
+    /// # Returns
+    /// The decoded bytes
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` for any input the `base64` crate's engine
+    /// rejects.
+    pub fn decode<T: AsRef<[u8]>>(&self, input: T) -> Result<Vec<u8>, SerialiseError> {
+        let text = as_text(input.as_ref(), "base64")?;
+        let (from, name) = if self.url_safe {
+            (alphabet::BASE64_URL_SAFE, "URL-safe base64")
+        } else {
+            (alphabet::BASE64_STANDARD, "base64")
+        };
+        let data = text.trim_end_matches('=');
+        if let Some((position, c)) = data
+            .char_indices()
+            .find(|&(_, c)| !u8::try_from(c).is_ok_and(|b| from.contains(&b)))
+        {
+            return Err(SerialiseError::new(format!(
+                "invalid {name} character '{c}' at position {position}"
+            )));
+        }
+        if data.len() % 4 == 1 {
+            return Err(SerialiseError::new(format!(
+                "{name} length {} is not one an encoder produces",
+                data.len()
+            )));
+        }
+        let standard = alphabet::transcode_alphabet(data, from, alphabet::BASE64_STANDARD)?;
+        let bytes = Base64::try_from_base64_with(&standard, Base64Config::NO_PAD)?;
+        if self.encode(&bytes) != text {
+            return Err(SerialiseError::new(
+                if self.pad == (data.len() == text.len()) {
+                    format!("{name} has set bits after the last byte or misplaced padding")
+                } else if self.pad {
+                    format!("{name} is missing its padding")
+                } else {
+                    format!("{name} must not be padded")
+                },
+            ));
+        }
+        Ok(bytes)
+    }
+
+    /// Decodes `input` onto the end of `output`, like `Engine::decode_vec`.
+    ///
+    /// # Arguments
+    /// * `input` - The Base64 text
+    /// * `output` - The buffer to append to, unchanged on error
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` for any input [`decode`](Self::decode) rejects.
+    pub fn decode_vec<T: AsRef<[u8]>>(
+        &self,
+        input: T,
+        output: &mut Vec<u8>,
+    ) -> Result<(), SerialiseError> {
+        output.extend(self.decode(input)?);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_encode_matches_base64_crate() {
+        // Outputs of the base64 crate's engines for the same inputs.
+        assert_eq!(STANDARD.encode(b"\xfb\xff\xbf"), "+/+/");
+        assert_eq!(STANDARD.encode(b"ab"), "YWI=");
+        assert_eq!(STANDARD_NO_PAD.encode(b"ab"), "YWI");
+        assert_eq!(URL_SAFE.encode(b"\xfb\xff\xbfa"), "-_-_YQ==");
+        assert_eq!(URL_SAFE_NO_PAD.encode(b"\xfb\xff\xbfa"), "-_-_YQ");
+        let mut out = String::from("x=");
+        STANDARD.encode_string("hi", &mut out);
+        assert_eq!(out, "x=aGk=");
+    }
+
+    #[test]
+    fn test_decode_round_trips() {
+        for engine in [STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD] {
+            for len in 0..10u8 {
+                let bytes: Vec<u8> = (0..len).map(|i| i.wrapping_mul(97) ^ 0xfb).collect();
+                let text = engine.encode(&bytes);
+                assert!(
+                    matches!(engine.decode(&text), Ok(b) if b == bytes),
+                    "{text}"
+                );
+            }
+        }
+        let mut out = vec![1];
+        assert!(STANDARD.decode_vec("Ag==", &mut out).is_ok());
+        assert_eq!(out, [1, 2]);
+    }
+
+    #[test]
+    fn test_decode_is_as_strict_as_base64_crate() {
+        for bad in ["YWI", "YW I=", "YWI=\n", "YWJ=", "-_-_", "YQ=", "Y", "Y==="] {
+            assert!(STANDARD.decode(bad).is_err(), "{bad}");
+        }
+        for bad in ["YWI=", "+/+/", "YWJ"] {
+            assert!(URL_SAFE_NO_PAD.decode(bad).is_err(), "{bad}");
+        }
+        assert!(STANDARD.decode(b"\xff").is_err());
+        assert!(matches!(STANDARD.decode(""), Ok(b) if b.is_empty()));
+    }
+}
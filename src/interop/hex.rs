@@ -0,0 +1,104 @@
+use crate::{Hex, SerialiseError};
+
+use super::as_text;
+
+/// Encodes `data` as lowercase hex, like `hex::encode`.
+///
+/// # Arguments
+/// * `data` - The bytes to encode
+///
+/// # Returns
+/// The hex text
+#[must_use = "This returns the encoded text but does nothing if unused"]
+pub fn encode<T: AsRef<[u8]>>(data: T) -> String {
+    Hex::try_to_hex(data.as_ref()).unwrap_or_default()
+}
+
+/// Encodes `data` as uppercase hex, like `hex::encode_upper`.
+///
+/// # Arguments
+/// * `data` - The bytes to encode
+///
+/// # Returns
+/// The hex text
+#[must_use = "This returns the encoded text but does nothing if unused"]
+pub fn encode_upper<T: AsRef<[u8]>>(data: T) -> String {
+    encode(data).to_ascii_uppercase()
+}
+
+/// Decodes hex in either case, like `hex::decode`.
+///
+/// Unlike [`Hex::try_from_hex`], whitespace is rejected, as the `hex` crate
+/// rejects it.
+///
+/// # Arguments
+/// * `data` - The hex text
+///
+/// # Returns
+/// The decoded bytes
+///
+/// # Errors
+/// Returns `SerialiseError` if `data` has an odd length or a character that
+/// is not a hex digit.
+pub fn decode<T: AsRef<[u8]>>(data: T) -> Result<Vec<u8>, SerialiseError> {
+    let text = as_text(data.as_ref(), "hex")?;
+    if let Some((position, c)) = text.char_indices().find(|(_, c)| !c.is_ascii_hexdigit()) {
+        return Err(SerialiseError::new(format!(
+            "invalid hex character '{c}' at position {position}"
+        )));
+    }
+    Hex::try_from_hex(text)
+}
+
+/// Decodes hex into `out`, which must be exactly half as long, like
+/// `hex::decode_to_slice`.
+///
+/// # Arguments
+/// * `data` - The hex text
+/// * `out` - The buffer to fill
+///
+/// # Errors
+/// Returns `SerialiseError` if `data` is not valid for [`decode`] or does not
+/// decode to exactly `out.len()` bytes.
+pub fn decode_to_slice<T: AsRef<[u8]>>(data: T, out: &mut [u8]) -> Result<(), SerialiseError> {
+    let data = data.as_ref();
+    if data.len() != out.len() * 2 {
+        return Err(SerialiseError::new(format!(
+            "hex of {} characters does not fill {} bytes",
+            data.len(),
+            out.len()
+        )));
+    }
+    out.copy_from_slice(&decode(data)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_encode_matches_hex_crate() {
+        assert_eq!(encode(b"\x00\xab\xff"), "00abff");
+        assert_eq!(encode_upper([0x0a, 0xbc]), "0ABC");
+        assert_eq!(encode(""), "");
+    }
+
+    #[test]
+    fn test_decode_either_case() {
+        assert!(matches!(decode("00abFF"), Ok(b) if b == [0x00, 0xab, 0xff]));
+        let mut out = [0u8; 2];
+        assert!(decode_to_slice("0ABC", &mut out).is_ok());
+        assert_eq!(out, [0x0a, 0xbc]);
+    }
+
+    #[test]
+    fn test_decode_is_as_strict_as_hex_crate() {
+        for bad in ["abc", "ab cd", "ab\n", "zz", "0x00"] {
+            assert!(decode(bad).is_err(), "{bad}");
+        }
+        assert!(decode_to_slice("abcd", &mut [0u8; 3]).is_err());
+        assert!(decode(b"\xc3\xa9").is_err());
+    }
+}
@@ -0,0 +1,16 @@
+/// Drop-in stand-ins for `base64::engine::general_purpose` engines.
+pub mod base64;
+
+/// Drop-in stand-ins for the `hex` crate's functions.
+pub mod hex;
+
+/// Returns `input` as text, for decoders that take `AsRef<[u8]>` as the
+/// ecosystem crates do.
+fn as_text<'a>(input: &'a [u8], name: &str) -> Result<&'a str, crate::SerialiseError> {
+    std::str::from_utf8(input).map_err(|e| {
+        crate::SerialiseError::new(format!(
+            "invalid {name} character at position {}",
+            e.valid_up_to()
+        ))
+    })
+}
@@ -0,0 +1,286 @@
+use std::sync::Arc;
+
+use crate::{EncodedString, Encoder, Encoding, SerialiseError};
+
+/// Checks the laws every [`Encoder`] must obey, over a set of sample inputs:
+///
+/// - round trip: decoding the encoding of some bytes gives those bytes back
+/// - canonical form: encoding is deterministic, and re-encoding the decoded
+///   bytes gives exactly the text that was decoded
+/// - invalid characters: text with a character outside the encoding's
+///   alphabet inserted does not decode
+///
+/// Run it from a test against any codec, built-in or third-party, alongside
+/// [`conformance::certify`](crate::conformance) where known-answer vectors
+/// exist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncoderLaws {
+    /// The inputs to check the laws with
+    samples: Vec<Vec<u8>>,
+    /// Whether the codec encodes the numeric value of the bytes, losing
+    /// leading zero bytes
+    numeric: bool,
+}
+
+impl EncoderLaws {
+    /// Creates the laws with the default samples: no bytes, every single byte,
+    /// runs of zeros and `0xff`, leading zeros, and longer inputs covering
+    /// every byte value and every length up to 64.
+    #[must_use = "This returns the laws but does nothing if unused"]
+    pub fn new() -> Self {
+        let mut samples: Vec<Vec<u8>> = vec![Vec::new()];
+        samples.extend((0..=255u8).map(|b| vec![b]));
+        samples.extend([vec![0; 5], vec![0xff; 17], vec![0, 0, 1], vec![0, 0xff, 0]]);
+        samples.extend((1..=64u8).map(|len| (1..=len).map(|i| i.wrapping_mul(37)).collect()));
+        samples.push((0..=255).collect());
+        samples.push((0..=255).rev().collect());
+        Self {
+            samples,
+            numeric: false,
+        }
+    }
+
+    /// Adds an input to check the laws with.
+    #[must_use = "This returns the updated laws but does nothing if unused"]
+    pub fn sample(mut self, bytes: impl Into<Vec<u8>>) -> Self {
+        self.samples.push(bytes.into());
+        self
+    }
+
+    /// Relaxes the round trip law for codecs that encode the numeric value of
+    /// the bytes, such as Base58 and Base36, so leading zero bytes may be lost.
+    #[must_use = "This returns the updated laws but does nothing if unused"]
+    pub const fn numeric(mut self) -> Self {
+        self.numeric = true;
+        self
+    }
+
+    /// Drops the samples longer than `len` bytes, for codecs with a length
+    /// limit such as [`FilenameSafe`](crate::FilenameSafe).
+    #[must_use = "This returns the updated laws but does nothing if unused"]
+    pub fn max_len(mut self, len: usize) -> Self {
+        self.samples.retain(|bytes| bytes.len() <= len);
+        self
+    }
+
+    /// Returns the inputs the laws are checked with.
+    #[must_use = "This returns the samples but does nothing if unused"]
+    pub fn get_samples(&self) -> &[Vec<u8>] {
+        &self.samples
+    }
+
+    /// Checks every law against the encoder `E`.
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` listing every broken law with the sample that
+    /// broke it, or if `E` cannot encode empty input, which is used to find its
+    /// encoding.
+    pub fn check<E: Encoder>(&self) -> Result<(), SerialiseError> {
+        let encoding = E::try_encode(Arc::new(Vec::new()))?.get_encoding();
+        let invalid = invalid_chars(encoding);
+        let mut failures = Vec::new();
+        for bytes in &self.samples {
+            let encoded = match E::try_encode(Arc::new(bytes.clone())) {
+                Ok(encoded) => encoded,
+                Err(e) => {
+                    failures.push(format!("{bytes:02x?} failed to encode: {e}"));
+                    continue;
+                }
+            };
+            let text = encoded.get_string();
+            let value = |b: &[u8]| {
+                if self.numeric {
+                    b.iter()
+                        .position(|&b| b != 0)
+                        .map_or(&[][..], |start| &b[start..])
+                        .to_vec()
+                } else {
+                    b.to_vec()
+                }
+            };
+
+            match E::try_decode(&encoded) {
+                Ok(decoded) if value(&decoded) == value(bytes) => {}
+                Ok(decoded) => failures.push(format!(
+                    "round trip: '{text}' decoded to {decoded:02x?}, expected {bytes:02x?}"
+                )),
+                Err(e) => failures.push(format!("round trip: '{text}' failed to decode: {e}")),
+            }
+
+            match E::try_encode(Arc::new(bytes.clone())) {
+                Ok(again) if again.get_string() == text => {}
+                _ => failures.push(format!(
+                    "canonical form: {bytes:02x?} did not encode to '{text}' twice"
+                )),
+            }
+            if let Ok(decoded) = E::try_decode(&encoded)
+                && !matches!(E::try_encode(decoded), Ok(again) if again.get_string() == text)
+            {
+                failures.push(format!(
+                    "canonical form: '{text}' did not re-encode to itself"
+                ));
+            }
+
+            if text.is_empty() {
+                continue;
+            }
+            let middle = middle_of_data(text, |c| in_alphabet(encoding, c));
+            for c in &invalid {
+                let mut corrupted = text.clone();
+                corrupted.insert(middle, *c);
+                if E::try_decode(&EncodedString::new(encoding, corrupted.clone())).is_ok() {
+                    failures.push(format!("invalid character: '{corrupted}' decoded"));
+                }
+            }
+        }
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(SerialiseError::new(failures.join(", ")))
+        }
+    }
+}
+
+impl Default for EncoderLaws {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the byte offset of the middle of the longest run of alphabet
+/// characters in `text`, which is where line-based formats keep their data
+/// rather than in headers, comments or trailers.
+fn middle_of_data(text: &str, in_alphabet: impl Fn(char) -> bool) -> usize {
+    let mut longest = 0..0;
+    let mut run_start = None;
+    for (i, c) in text.char_indices().chain([(text.len(), '\n')]) {
+        match (in_alphabet(c) && i < text.len(), run_start) {
+            (true, None) => run_start = Some(i),
+            (false, Some(start)) => {
+                if i - start > longest.len() {
+                    longest = start..i;
+                }
+                run_start = None;
+            }
+            _ => {}
+        }
+    }
+    let middle = longest.start + longest.len() / 2;
+    (0..=middle)
+        .rev()
+        .find(|&i| text.is_char_boundary(i))
+        .unwrap_or_default()
+}
+
+/// Returns true if `c` is in `encoding`'s alphabet, in either case if the
+/// encoding is case-insensitive.
+fn in_alphabet(encoding: Encoding, c: char) -> bool {
+    let alphabet = encoding.get_alphabet();
+    if encoding.is_case_sensitive() {
+        alphabet.contains(c)
+    } else {
+        alphabet.chars().any(|a| a.eq_ignore_ascii_case(&c))
+    }
+}
+
+/// Returns characters that are not in `encoding`'s alphabet in either case:
+/// the printable ASCII characters other than `=` and whitespace, a control
+/// character and a non-ASCII letter.
+fn invalid_chars(encoding: Encoding) -> Vec<char> {
+    ('!'..='~')
+        .filter(|&c| c != '=' && !in_alphabet(encoding, c))
+        .chain(['\u{1}', '\u{e9}'])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_built_in_codecs() {
+        let laws = EncoderLaws::new();
+        let results: Vec<(&str, Result<(), SerialiseError>)> = vec![
+            #[cfg(feature = "base32")]
+            ("base32", laws.check::<crate::Base32>()),
+            #[cfg(feature = "base36")]
+            ("base36", laws.clone().numeric().check::<crate::Base36>()),
+            #[cfg(feature = "base45")]
+            ("base45", laws.check::<crate::Base45>()),
+            #[cfg(feature = "base58")]
+            ("base58", laws.clone().numeric().check::<crate::Base58>()),
+            #[cfg(feature = "base62")]
+            ("base62", laws.clone().numeric().check::<crate::Base62>()),
+            #[cfg(feature = "base64")]
+            ("base64", laws.check::<crate::Base64>()),
+            #[cfg(feature = "base94")]
+            ("base94", laws.check::<crate::Base94>()),
+            #[cfg(feature = "binhex")]
+            ("binhex", laws.check::<crate::BinHex>()),
+            #[cfg(feature = "escaped")]
+            ("escaped", laws.check::<crate::Escaped>()),
+            #[cfg(feature = "filename-safe")]
+            (
+                "filename-safe",
+                laws.clone().max_len(159).check::<crate::FilenameSafe>(),
+            ),
+            #[cfg(feature = "hex")]
+            ("hex", laws.check::<crate::Hex>()),
+            #[cfg(feature = "intel-hex")]
+            ("intel-hex", laws.check::<crate::IntelHex>()),
+            #[cfg(feature = "line-dump")]
+            ("line-dump", laws.check::<crate::LineDump>()),
+            #[cfg(feature = "srec")]
+            ("srec", laws.check::<crate::Srec>()),
+            #[cfg(feature = "uuencode")]
+            ("uuencode", laws.check::<crate::Uuencode>()),
+        ];
+        for (name, result) in results {
+            let message = result.err().map(|e| e.to_string()).unwrap_or_default();
+            assert!(message.is_empty(), "{name}: {message}");
+        }
+    }
+
+    /// Hex that skips characters it does not know instead of rejecting them.
+    #[cfg(feature = "hex")]
+    struct LenientHex {}
+
+    #[cfg(feature = "hex")]
+    impl Encoder for LenientHex {
+        fn try_encode(bytes: Arc<Vec<u8>>) -> Result<EncodedString, SerialiseError> {
+            crate::Hex::try_encode(bytes)
+        }
+        fn try_decode(encoded: &EncodedString) -> Result<Arc<Vec<u8>>, SerialiseError> {
+            let text: String = encoded
+                .get_string()
+                .chars()
+                .filter(char::is_ascii_hexdigit)
+                .collect();
+            crate::Hex::try_decode(&EncodedString::new(encoded.get_encoding(), text))
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "hex")]
+    fn test_reports_broken_laws() {
+        let laws = EncoderLaws::new().max_len(0).sample([0xab]);
+        assert_eq!(laws.get_samples().len(), 2);
+        assert!(matches!(
+            laws.check::<LenientHex>(),
+            Err(e) if e.get_message().starts_with("invalid character: 'a!b' decoded, ")
+        ));
+        assert!(laws.check::<crate::Hex>().is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "base58")]
+    fn test_numeric_allows_lost_leading_zeros() {
+        let laws = EncoderLaws::new().max_len(0).sample([0, 0, 7]);
+        assert!(matches!(
+            laws.check::<crate::Base58>(),
+            Err(e) if e.get_message().starts_with("round trip: ")
+        ));
+        assert!(laws.numeric().check::<crate::Base58>().is_ok());
+    }
+}
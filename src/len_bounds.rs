@@ -0,0 +1,208 @@
+use crate::{Encoding, SerialiseError};
+
+#[cfg(feature = "base94")]
+use crate::algorithm::base94::BLOCK_CHARS;
+
+/// Decoded lengths of text in an RFC 4648 style encoding that writes each
+/// whole block as `block_chars` characters, where `tail_chars[i]` is the
+/// length of a final partial block of `i` bytes.
+///
+/// Text padded to whole blocks may end in a partial block, so its minimum
+/// counts the shortest one.
+#[cfg(any(
+    feature = "base32",
+    feature = "base45",
+    feature = "base64",
+    feature = "base94",
+    feature = "filename-safe",
+    feature = "hex"
+))]
+fn block_bounds(
+    encoding: Encoding,
+    len: usize,
+    block_chars: usize,
+    tail_chars: &[usize],
+    padded: bool,
+) -> Result<(usize, usize), SerialiseError> {
+    let block_bytes = tail_chars.len();
+    let (blocks, rest) = (len / block_chars, len % block_chars);
+    if let Some(tail) = tail_chars.iter().position(|&chars| chars == rest) {
+        let whole = blocks * block_bytes + tail;
+        if rest == 0 && padded && blocks > 0 {
+            return Ok((whole - block_bytes + 1, whole));
+        }
+        return Ok((whole, whole));
+    }
+    Err(SerialiseError::new(format!(
+        "no {encoding} text is {len} characters long"
+    )))
+}
+
+/// Decoded lengths of a number written in `encoding`'s digits without
+/// leading zero digits, which is how every big-number encoder writes it.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+#[cfg(any(feature = "base36", feature = "base58", feature = "base62"))]
+fn number_bounds(encoding: Encoding, len: usize) -> (usize, usize) {
+    // Rounding can only move the float a hair from the true value, so widen
+    // by a hair to keep both bounds safe.
+    let bits = encoding.get_bits_per_char();
+    let bytes = |digits: usize| digits as f64 * bits / 8.0;
+    let max = (bytes(len) + 1e-9).ceil() as usize;
+    let min = (bytes(len.saturating_sub(1)) - 1e-9).floor() as usize + 1;
+    // Encoders write no bytes as empty text or a single zero digit, which
+    // decoders read back as one zero byte.
+    if len <= 1 {
+        return (0, 1);
+    }
+    (min.min(max), max)
+}
+
+/// Returns the fewest and most bytes that text of `encoded_len` characters
+/// can decode to in `encoding`, so input of an impossible length can be
+/// rejected before decoding it.
+///
+/// This is cheap for every encoding, which matters for Base36, Base58 and
+/// Base62, whose decoding takes time quadratic in the length. Bounds are for
+/// text as encoders write it: whitespace is not expected, and numbers have no
+/// leading zero digits. Formats with headers, line framing or run-length
+/// compression, such as Intel HEX or `BinHex`, only have a minimum of zero
+/// and a loose maximum.
+///
+/// # Arguments
+/// * `encoding` - The encoding of the text
+/// * `encoded_len` - The length of the text in characters
+///
+/// # Returns
+/// The minimum and maximum decoded lengths in bytes
+///
+/// # Errors
+/// Returns `SerialiseError` if no text of `encoded_len` characters decodes in
+/// `encoding`, such as odd-length hex.
+pub fn decoded_len_bounds(
+    encoding: Encoding,
+    encoded_len: usize,
+) -> Result<(usize, usize), SerialiseError> {
+    let len = encoded_len;
+    match encoding {
+        #[cfg(feature = "hex")]
+        Encoding::Hex => block_bounds(encoding, len, 2, &[0], false),
+        #[cfg(feature = "base32")]
+        Encoding::Base32 => block_bounds(encoding, len, 8, &[0, 2, 4, 5, 7], true),
+        #[cfg(feature = "base64")]
+        Encoding::Base64 => block_bounds(encoding, len, 4, &[0, 2, 3], true),
+        #[cfg(feature = "base45")]
+        Encoding::Base45 => block_bounds(encoding, len, 3, &[0, 2], false),
+        #[cfg(feature = "base94")]
+        Encoding::Base94 => block_bounds(encoding, len, 11, &BLOCK_CHARS[..9], false),
+        #[cfg(feature = "filename-safe")]
+        Encoding::FilenameSafe if len == 1 => Ok((0, 0)),
+        #[cfg(feature = "filename-safe")]
+        Encoding::FilenameSafe => block_bounds(encoding, len, 8, &[0, 2, 4, 5, 7], false),
+        #[cfg(feature = "base36")]
+        Encoding::Base36 => Ok(number_bounds(encoding, len)),
+        #[cfg(feature = "base58")]
+        Encoding::Base58 => Ok(number_bounds(encoding, len)),
+        #[cfg(feature = "base62")]
+        Encoding::Base62 => Ok(number_bounds(encoding, len)),
+        #[cfg(feature = "escaped")]
+        Encoding::Escaped => Ok((len.div_ceil(4), len)),
+        #[cfg(feature = "intel-hex")]
+        Encoding::IntelHex => Ok((0, len / 2)),
+        #[cfg(feature = "srec")]
+        Encoding::Srec => Ok((0, len / 2)),
+        #[cfg(feature = "uuencode")]
+        Encoding::Uuencode => Ok((0, len / 4 * 3)),
+        #[cfg(feature = "line-dump")]
+        Encoding::LineDump => Ok((0, len / 4 * 3)),
+        // Each run-length marker and count, two bytes, repeats a byte up to
+        // 254 more times.
+        #[cfg(feature = "binhex")]
+        Encoding::BinHex => Ok((0, (len / 4 * 3).saturating_mul(127).saturating_add(1))),
+        #[allow(unreachable_patterns)]
+        _ => Ok((0, usize::MAX)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::ByteVec;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_block_encodings() {
+        #[cfg(feature = "hex")]
+        {
+            assert!(matches!(
+                decoded_len_bounds(Encoding::Hex, 64),
+                Ok((32, 32))
+            ));
+            assert!(decoded_len_bounds(Encoding::Hex, 5).is_err());
+        }
+        #[cfg(feature = "base64")]
+        {
+            assert!(matches!(
+                decoded_len_bounds(Encoding::Base64, 44),
+                Ok((31, 33))
+            ));
+            assert!(matches!(
+                decoded_len_bounds(Encoding::Base64, 43),
+                Ok((32, 32))
+            ));
+            assert!(decoded_len_bounds(Encoding::Base64, 5).is_err());
+        }
+        #[cfg(feature = "base32")]
+        {
+            assert!(matches!(
+                decoded_len_bounds(Encoding::Base32, 56),
+                Ok((31, 35))
+            ));
+            assert!(decoded_len_bounds(Encoding::Base32, 3).is_err());
+        }
+        #[cfg(feature = "base45")]
+        assert!(matches!(
+            decoded_len_bounds(Encoding::Base45, 5),
+            Ok((3, 3))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "base58")]
+    fn test_rejects_short_hash() {
+        let Ok((_, max)) = decoded_len_bounds(Encoding::Base58, 5) else {
+            return;
+        };
+        assert!(max < 32);
+        assert!(
+            matches!(decoded_len_bounds(Encoding::Base58, 44), Ok((min, max)) if min <= 32 && 32 <= max)
+        );
+    }
+
+    #[test]
+    fn test_bounds_hold_for_encoder_output() {
+        for encoding in Encoding::iter() {
+            for len in [0usize, 1, 2, 3, 5, 8, 9, 31, 32, 33, 100] {
+                for fill in [0x00u8, 0x01, 0xff] {
+                    let mut bytes = vec![fill; len];
+                    if let Some(first) = bytes.first_mut() {
+                        *first = 0x80;
+                    }
+                    let Ok(encoded) = ByteVec::new(Arc::new(bytes)).try_encode(encoding) else {
+                        continue;
+                    };
+                    let text = encoded.get_string();
+                    let bounds = decoded_len_bounds(encoding, text.chars().count());
+                    assert!(
+                        matches!(bounds, Ok((min, max)) if min <= len && len <= max),
+                        "{encoding} {len} {fill} {bounds:?}"
+                    );
+                }
+            }
+        }
+    }
+}
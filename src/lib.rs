@@ -4,34 +4,337 @@
 //!
 //! See [`ByteVec`], [`EncodedString`], and [`Encoding`] for the main entry points.
 #![deny(missing_docs)]
+#![deny(unsafe_code)]
 
 //! Types and algorithms for encoding/decoding data.
 
+#[cfg(not(any(
+    feature = "base36",
+    feature = "base58",
+    feature = "base64",
+    feature = "hex",
+    feature = "uuencode",
+    feature = "intel-hex",
+    feature = "srec",
+    feature = "binhex",
+    feature = "base32",
+    feature = "base62",
+    feature = "base45",
+    feature = "line-dump",
+    feature = "filename-safe",
+    feature = "escaped",
+    feature = "base94",
+)))]
+compile_error!("base_xx requires at least one algorithm feature, e.g. `features = [\"hex\"]`");
+
+/// Timing and logging of encode and decode calls.
+pub(crate) mod instrument;
+
+/// Git-style abbreviated prefixes of encoded identifiers.
+pub mod abbrev;
+
 /// Serialization algorithms and implementations.
 pub mod algorithm;
 
+/// Standard alphabets and translation between them.
+pub mod alphabet;
+
+/// Conversions between `ByteVec` and the `bytes` crate's buffers.
+#[cfg(feature = "bytes")]
+pub mod bytes_interop;
+
+/// Check-digit schemes for codes that people type.
+pub mod check;
+
+/// Iterator adapters between character streams and bytes.
+pub mod char_stream;
+
+/// Parsing data URIs and extracting them from documents.
+#[cfg(feature = "base64")]
+pub mod data_uri;
+
+/// Trying several encodings in order until one decodes the input.
+pub mod decode_policy;
+
+/// CRC-16, CRC-32, CRC-32C and Adler-32 checksums with streaming updates.
+pub mod checksum;
+
+/// CSS hex colors such as `#ff8800` and `#f80`.
+#[cfg(feature = "hex")]
+pub mod color_hex;
+
+/// Checks of encoded output against channel character restrictions.
+pub mod charset;
+
+/// Compile-time encoders for constant byte strings.
+#[cfg(any(feature = "base32", feature = "base64", feature = "hex"))]
+pub mod const_encode;
+
 /// Encoder trait for encoding and decoding data.
 pub mod encoder;
 
+/// The `encoded_id!` macro for fixed-length ID newtypes, and its helpers.
+pub mod encoded_id;
+
+/// Incremental decoder trait for input that arrives in pieces.
+pub mod decoder;
+
 /// Raw byte representation of serializable data.
 pub mod byte_vec;
 
+/// Encoding and decoding many inputs at once.
+pub mod batch;
+
+/// Known-answer test vectors for certifying encoders.
+#[cfg(feature = "conformance")]
+pub mod conformance;
+
+/// Builder for encodes with options.
+pub mod encode_builder;
+
+/// Seedable fault injection for testing code that consumes encoded data.
+#[cfg(feature = "fault-injection")]
+pub mod faulty;
+
+/// Fuzz targets for the decoders and differential checks against other crates.
+#[cfg(feature = "fuzzing")]
+pub mod fuzz;
+
+/// Stand-ins for the `base64` and `hex` crates' APIs, for gradual migration.
+#[cfg(feature = "interop")]
+pub mod interop;
+
+/// Shared encoded strings for values encoded over and over.
+pub mod intern;
+
+/// Reusable working buffers for the big-integer codecs.
+#[cfg(any(feature = "base36", feature = "base58", feature = "base62"))]
+pub mod scratch;
+
+/// Round-trip, canonical form and invalid character laws for encoders.
+pub mod laws;
+
+/// RFC 2047 encoded-words for non-ASCII email headers.
+#[cfg(feature = "base64")]
+pub mod encoded_word;
+
 /// String representation of serialized data.
 pub mod encoded_string;
 
+/// A versioned, checksummed envelope that names its encoding.
+pub mod envelope;
+
+/// Recognising and normalizing decimal digits from other scripts.
+pub mod digits;
+
+/// Offset-by-offset comparison of byte strings rendered as a hexdump.
+pub mod diff;
+
+/// Hexdumps with labelled byte ranges for inspecting binary data.
+pub mod hexdump;
+
+/// Tokens that change every time window, for cache-busting links.
+#[cfg(feature = "base62")]
+pub mod rotating_token;
+
+/// Encoding bytes as DNS labels and domain names.
+#[cfg(feature = "base32")]
+pub mod dns;
+
+/// Removing comments, quoting and whitespace around encoded text.
+pub mod filter;
+
+/// Decoding text inside HTTP chunked or server-sent event framing.
+pub mod framed;
+
+/// Encoding into fixed-capacity strings that do not allocate.
+#[cfg(all(
+    any(feature = "heapless", feature = "arrayvec"),
+    any(feature = "base32", feature = "base64", feature = "hex")
+))]
+pub mod fixed;
+
+/// Bounds on decoded lengths, for rejecting input before decoding it.
+pub mod len_bounds;
+
+/// Line endings for multi-line output.
+pub mod line_ending;
+
+/// Text forms of IPv4, IPv6 and MAC addresses.
+pub mod net;
+
+/// Dotted object identifiers and their DER encodings.
+pub mod oid;
+
+/// Order-preserving Base64 and composite keys that sort field by field.
+#[cfg(feature = "base64")]
+pub mod ordered_key;
+
+/// Printable backups with numbered, checksummed lines for retyping.
+#[cfg(feature = "base32")]
+pub mod paper;
+
+/// Splitting payloads into numbered parts for size-limited channels.
+pub mod multipart;
+
+/// Decoding encoded data followed by other text.
+pub mod prefix;
+
+/// Unbiased random strings over any alphabet.
+pub mod random;
+
+/// Conversion of numeric strings between arbitrary radixes.
+pub mod radix;
+
+/// Cursor for reading integers out of decoded bytes.
+pub mod reader;
+
+/// Re-wrapping encoded text to a different line length.
+pub mod rechunk;
+
+/// The same bytes written in several encodings at once, for debugging.
+pub mod render;
+
+/// Finding embedded encoded data in arbitrary buffers.
+#[cfg(any(feature = "base64", feature = "hex", feature = "uuencode"))]
+pub mod scan;
+
+/// Decoding secrets without revealing where they are invalid.
+#[cfg(any(feature = "base32", feature = "base64", feature = "hex"))]
+pub mod secret;
+
 /// Error type for serialization operations.
 pub mod serialise_error;
 
+/// Entropy and byte frequency statistics.
+pub mod stats;
+
 /// Supported serialization formats.
 pub mod encoding;
 
+/// Streaming conversion of text from one encoding to another.
+#[cfg(any(feature = "base32", feature = "base64", feature = "hex"))]
+pub mod transcode;
+
+/// XOR, ROT13 and bit rotation transforms, and pipelines that chain them
+/// with decoding and encoding.
+pub mod transform;
+
+/// Tokens that are safe unescaped in URLs and HTTP headers.
+#[cfg(feature = "base64")]
+pub mod url_token;
+
+/// Syntax checks for encoded strings that do not keep the decoded bytes.
+pub mod validate;
+
+/// Encoding binary payloads for the smallest QR code.
+#[cfg(all(feature = "base45", feature = "base64"))]
+pub mod qr;
+
+/// "Did you mean" suggestions for mistyped codes.
+pub mod suggest;
+
+/// Cutting encoded strings short at a boundary where they still decode.
+#[cfg(any(feature = "base32", feature = "base64", feature = "hex"))]
+pub mod truncate;
+
+/// Fixed-width identifiers that sort by timestamp.
+pub mod sortable_id;
+
+/// Reed–Solomon check characters over Base58 that correct typing mistakes.
+#[cfg(feature = "base58")]
+pub mod reed_solomon;
+
+/// Multi-threaded search for Base58 vanity prefixes.
+#[cfg(feature = "vanity")]
+pub mod vanity;
+
+pub use abbrev::{PrefixIndex, shortest_unique_prefix};
+#[cfg(feature = "base32")]
+pub use algorithm::base32::{Base32, Base32Alphabet, Base32Config, Base32Decoder};
+#[cfg(feature = "base36")]
 pub use algorithm::base36::Base36;
+#[cfg(feature = "base45")]
+pub use algorithm::base45::Base45;
+#[cfg(feature = "base58")]
 pub use algorithm::base58::Base58;
-pub use algorithm::base64::Base64;
-pub use algorithm::hex::Hex;
-pub use algorithm::uuencode::Uuencode;
+#[cfg(feature = "base62")]
+pub use algorithm::base62::Base62;
+#[cfg(feature = "base64")]
+pub use algorithm::base64::{Base64, Base64Config, Base64Decoder};
+#[cfg(feature = "base94")]
+pub use algorithm::base94::Base94;
+#[cfg(feature = "binhex")]
+pub use algorithm::binhex::BinHex;
+#[cfg(feature = "escaped")]
+pub use algorithm::escaped::Escaped;
+#[cfg(feature = "filename-safe")]
+pub use algorithm::filename_safe::FilenameSafe;
+#[cfg(feature = "hex")]
+pub use algorithm::hex::{Hex, HexDecoder};
+#[cfg(feature = "intel-hex")]
+pub use algorithm::intel_hex::IntelHex;
+#[cfg(feature = "line-dump")]
+pub use algorithm::line_dump::LineDump;
+#[cfg(any(feature = "intel-hex", feature = "srec"))]
+pub use algorithm::memory_image::MemoryImage;
+#[cfg(feature = "srec")]
+pub use algorithm::srec::Srec;
+#[cfg(feature = "uuencode")]
+pub use algorithm::uuencode::{FileMode, Uuencode, UuencodeConfig};
+pub use batch::{
+    EncodeIter, decode_batch, decode_batch_parallel, encode_batch, encode_batch_parallel,
+};
 pub use byte_vec::ByteVec;
+pub use char_stream::{BytesToChars, CharsToBytes, DecodeChars, EncodeBytes};
+pub use charset::{CharSet, CharsetPolicy, densest_encoding, fits_charset};
+pub use check::{CheckScheme, Damm, Luhn, Mod97, Verhoeff};
+pub use decode_policy::DecodePolicy;
+pub use decoder::Decoder;
+pub use diff::ByteDiff;
+pub use digits::{normalize_digits, unicode_digit_value};
+pub use encode_builder::EncodeBuilder;
 pub use encoded_string::EncodedString;
 pub use encoder::Encoder;
 pub use encoding::Encoding;
-pub use serialise_error::SerialiseError;
+#[cfg(feature = "fault-injection")]
+pub use faulty::{Fault, FaultyEncoder};
+pub use filter::{FilteredDecoder, InputFilter};
+pub use framed::{FramedReader, Framing};
+pub use hexdump::HexDump;
+pub use intern::{InternPool, InternStats};
+pub use laws::EncoderLaws;
+pub use len_bounds::decoded_len_bounds;
+pub use line_ending::LineEnding;
+pub use net::{AddressFormat, Ipv4, Ipv6, Mac};
+pub use prefix::{TrailingData, decode_prefix};
+#[cfg(all(feature = "base45", feature = "base64"))]
+pub use qr::{QrErrorCorrection, QrMode, QrPayload, validate_alphanumeric};
+pub use random::random_string;
+pub use reader::ByteReader;
+pub use rechunk::{Rechunker, rechunk};
+#[cfg(feature = "base58")]
+pub use reed_solomon::{Correction, ReedSolomon58};
+pub use render::Renderings;
+#[cfg(feature = "base62")]
+pub use rotating_token::RotatingToken;
+#[cfg(any(feature = "base36", feature = "base58", feature = "base62"))]
+pub use scratch::Scratch;
+#[cfg(any(feature = "base32", feature = "base64", feature = "hex"))]
+pub use secret::decode_secret;
+pub use serialise_error::{ErrorKind, SerialiseError};
+pub use sortable_id::{SortableBase, SortableIdFormat};
+pub use stats::Stats;
+pub use suggest::{edit_distance, suggest_checked, suggest_from, suggest_lookalikes};
+#[cfg(any(feature = "base32", feature = "base64", feature = "hex"))]
+pub use transcode::Transcoder;
+pub use transform::{Pipeline, Transform};
+#[cfg(any(feature = "base32", feature = "base64", feature = "hex"))]
+pub use truncate::truncate_safely;
+pub use validate::validate;
+#[cfg(feature = "vanity")]
+pub use vanity::{VanityMatch, VanitySearch};
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use serde as __serde;
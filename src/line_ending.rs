@@ -0,0 +1,45 @@
+/// Line ending written between lines of multi-line output.
+///
+/// Decoders accept both regardless of which one was used to encode.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n`, the Unix convention
+    #[default]
+    Lf,
+    /// `\r\n`, required by SMTP and other internet protocols
+    CrLf,
+}
+
+impl LineEnding {
+    /// Returns the characters of this line ending.
+    #[must_use = "This returns the line ending but does nothing if unused"]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::CrLf => "\r\n",
+        }
+    }
+
+    /// Breaks ASCII `text` into lines of at most `width` characters separated by
+    /// this line ending, with no line ending after the last line.
+    pub(crate) fn wrap(self, text: &str, width: usize) -> String {
+        text.as_bytes()
+            .chunks(width.max(1))
+            .map(String::from_utf8_lossy)
+            .collect::<Vec<_>>()
+            .join(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(LineEnding::Lf.wrap("abcdefg", 3), "abc\ndef\ng");
+        assert_eq!(LineEnding::CrLf.wrap("abcdef", 3), "abc\r\ndef");
+        assert_eq!(LineEnding::CrLf.wrap("", 3), "");
+    }
+}
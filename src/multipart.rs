@@ -0,0 +1,429 @@
+use std::sync::Arc;
+
+use crate::{ByteVec, EncodedString, Encoding, SerialiseError, checksum::Adler32};
+
+/// Sequence field of the parity part.
+const PARITY: &str = "p";
+
+/// The most data parts a message may be split into. The part count in a
+/// header sizes the table [`join`] and [`missing`] fill in, so it is bounded
+/// rather than trusted.
+pub const MAX_PARTS: usize = 65_536;
+
+/// One part parsed from its text form.
+struct Part {
+    /// The 1-based sequence number, or `None` for the parity part
+    seq: Option<usize>,
+    /// The number of data parts
+    total: usize,
+    /// The length of the whole payload in bytes
+    len: usize,
+    /// The Adler-32 checksum of the whole payload
+    checksum: u32,
+    /// The decoded bytes of this part
+    bytes: Vec<u8>,
+}
+
+impl Part {
+    fn parse(text: &str, encoding: Encoding) -> Result<Self, SerialiseError> {
+        let invalid = || SerialiseError::new(format!("invalid multipart header in '{text}'"));
+        let mut fields = text.splitn(4, ':');
+        let (Some(position), Some(len), Some(checksum), Some(payload)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            return Err(invalid());
+        };
+        let (seq, total) = position.split_once('/').ok_or_else(invalid)?;
+        let seq = if seq == PARITY {
+            None
+        } else {
+            Some(seq.parse::<usize>().map_err(|_| invalid())?)
+        };
+        let total = total.parse::<usize>().map_err(|_| invalid())?;
+        if total == 0 || seq.is_some_and(|seq| seq == 0 || seq > total) {
+            return Err(invalid());
+        }
+        let len = len.parse::<usize>().map_err(|_| invalid())?;
+        // Every data part carries at least one byte, or an empty payload has one part.
+        if total > len.max(1) || total > MAX_PARTS {
+            return Err(SerialiseError::new(format!(
+                "multipart header in '{text}' claims {total} parts, more than {} allows",
+                if total > MAX_PARTS {
+                    format!("the limit of {MAX_PARTS}")
+                } else {
+                    format!("a payload of {len} bytes")
+                }
+            )));
+        }
+
+        let mut bytes = EncodedString::new(encoding, payload.to_string())
+            .try_decode()?
+            .get_bytes()
+            .to_vec();
+        // Numeric encodings drop a chunk's leading zero bytes, which its
+        // length restores.
+        if encoding.is_numeric() {
+            let expected = part_len(seq, total, len);
+            let start = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len());
+            if bytes.len() - start <= expected {
+                let mut padded = vec![0; expected - (bytes.len() - start)];
+                padded.extend_from_slice(&bytes[start..]);
+                bytes = padded;
+            }
+        }
+        Ok(Self {
+            seq,
+            total,
+            len,
+            checksum: u32::from_str_radix(checksum, 16).map_err(|_| invalid())?,
+            bytes,
+        })
+    }
+}
+
+/// Returns the length of part `seq`, or of the parity part for `None`, when
+/// `len` bytes are split into `total` parts as [`split`] does: every part but
+/// the last holds `len.div_ceil(total)` bytes.
+fn part_len(seq: Option<usize>, total: usize, len: usize) -> usize {
+    let chunk = len.div_ceil(total);
+    seq.map_or(chunk, |seq| {
+        len.saturating_sub((seq - 1).saturating_mul(chunk))
+            .min(chunk)
+    })
+}
+
+/// Returns the encoded length of `len` bytes in `encoding`, using the largest
+/// value so the length holds for any bytes.
+fn encoded_len(encoding: Encoding, len: usize) -> Result<usize, SerialiseError> {
+    Ok(ByteVec::new(Arc::new(vec![0xff; len]))
+        .try_encode(encoding)?
+        .get_string()
+        .len())
+}
+
+/// Splits `byte_vec` into numbered text parts of at most `max_chars` characters.
+///
+/// Each part is `<seq>/<total>:<length>:<checksum>:<payload>`, where the
+/// length and Adler-32 checksum describe the whole payload, so parts from
+/// different messages are not mixed up and the reassembled bytes are verified.
+/// With `parity`, a part numbered `p` holding the XOR of all data parts is
+/// added, which lets [`join`] rebuild any single lost part. This suits channels
+/// with a message size limit, such as SMS or a sequence of QR codes.
+///
+/// Every part but the last holds the same number of bytes, so each part's
+/// length follows from its header. [`join`] uses this to restore the leading
+/// zero bytes that Base36, Base58 and Base62 payloads drop.
+///
+/// # Arguments
+/// * `byte_vec` - The bytes to split
+/// * `encoding` - The encoding for the payload of each part
+/// * `max_chars` - The longest a part may be, including its header
+/// * `parity` - Whether to add an XOR parity part
+///
+/// # Returns
+/// The data parts in order, followed by the parity part if requested
+///
+/// # Errors
+/// Returns `SerialiseError` if encoding fails, `max_chars` is too small to
+/// hold a header and one byte of payload, or the payload would need more than
+/// [`MAX_PARTS`] parts.
+pub fn split(
+    byte_vec: &ByteVec,
+    encoding: Encoding,
+    max_chars: usize,
+    parity: bool,
+) -> Result<Vec<String>, SerialiseError> {
+    let bytes = byte_vec.get_bytes();
+    let checksum = Adler32::checksum(bytes);
+    // Sequence numbers never exceed the byte count, so this header is the longest
+    let header_len = format!("{PARITY}{0}/{0}:{0}:{checksum:08x}:", bytes.len().max(1)).len();
+    let budget = max_chars.saturating_sub(header_len);
+
+    // Largest chunk whose encoding fits the budget; encoded length grows with
+    // the chunk, so a binary search finds it.
+    let (mut low, mut high) = (0, bytes.len().max(1));
+    while low < high {
+        let mid = (low + high).div_ceil(2);
+        if encoded_len(encoding, mid)? <= budget {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    if low == 0 {
+        return Err(SerialiseError::new(format!(
+            "{max_chars} characters cannot hold a multipart header and payload in {encoding}"
+        )));
+    }
+
+    // Spread the bytes evenly over the parts this needs, so each part's length
+    // follows from the header.
+    let chunk = bytes.len().div_ceil(bytes.len().div_ceil(low).max(1));
+    let chunks: Vec<&[u8]> = if bytes.is_empty() {
+        vec![bytes]
+    } else {
+        bytes.chunks(chunk).collect()
+    };
+    let total = chunks.len();
+    if total > MAX_PARTS {
+        return Err(SerialiseError::new(format!(
+            "{} bytes in parts of {max_chars} characters need {total} parts, more than {MAX_PARTS}",
+            bytes.len()
+        )));
+    }
+    let part = |seq: &str, chunk: &[u8]| -> Result<String, SerialiseError> {
+        let payload = ByteVec::new(Arc::new(chunk.to_vec())).try_encode(encoding)?;
+        Ok(format!(
+            "{seq}/{total}:{}:{checksum:08x}:{}",
+            bytes.len(),
+            payload.get_string()
+        ))
+    };
+
+    let mut parts = chunks
+        .iter()
+        .enumerate()
+        .map(|(i, chunk)| part(&(i + 1).to_string(), chunk))
+        .collect::<Result<Vec<String>, SerialiseError>>()?;
+    if parity {
+        let mut xor = vec![0u8; chunk];
+        for chunk in &chunks {
+            xor.iter_mut().zip(*chunk).for_each(|(x, b)| *x ^= b);
+        }
+        parts.push(part(PARITY, &xor)?);
+    }
+    Ok(parts)
+}
+
+/// The parts of one message received so far.
+struct Message {
+    /// The bytes of each data part, `None` where it is missing
+    slots: Vec<Option<Vec<u8>>>,
+    /// The bytes of the parity part, if received
+    parity: Option<Vec<u8>>,
+    /// The length of the whole payload in bytes
+    len: usize,
+    /// The Adler-32 checksum of the whole payload
+    checksum: u32,
+}
+
+/// Parses parts and checks they all belong to the same message.
+fn parse_parts<S: AsRef<str>>(parts: &[S], encoding: Encoding) -> Result<Message, SerialiseError> {
+    let mut slots: Vec<Option<Vec<u8>>> = Vec::new();
+    let mut parity: Option<Vec<u8>> = None;
+    let mut message: Option<(usize, usize, u32)> = None;
+    for text in parts {
+        let part = Part::parse(text.as_ref(), encoding)?;
+        let this = (part.total, part.len, part.checksum);
+        if message.is_some_and(|message| message != this) {
+            return Err(SerialiseError::new(
+                "parts belong to different messages".to_string(),
+            ));
+        }
+        message = Some(this);
+        slots.resize(part.total, None);
+        match part.seq {
+            Some(seq) => slots[seq - 1] = Some(part.bytes),
+            None => parity = Some(part.bytes),
+        }
+    }
+    let Some((_, len, checksum)) = message else {
+        return Err(SerialiseError::new("no parts to join".to_string()));
+    };
+    Ok(Message {
+        slots,
+        parity,
+        len,
+        checksum,
+    })
+}
+
+/// Returns the sequence numbers of the data parts not in `parts`.
+///
+/// # Arguments
+/// * `parts` - The parts received so far, in any order
+/// * `encoding` - The encoding the parts were split with
+///
+/// # Returns
+/// The missing 1-based sequence numbers, in order
+///
+/// # Errors
+/// Returns `SerialiseError` if a part is malformed or claims more parts than
+/// its payload length or [`MAX_PARTS`] allows, parts from different messages
+/// are mixed, or `parts` is empty.
+pub fn missing<S: AsRef<str>>(
+    parts: &[S],
+    encoding: Encoding,
+) -> Result<Vec<usize>, SerialiseError> {
+    Ok(parse_parts(parts, encoding)?
+        .slots
+        .iter()
+        .enumerate()
+        .filter(|(_, slot)| slot.is_none())
+        .map(|(i, _)| i + 1)
+        .collect())
+}
+
+/// Reassembles parts written by [`split`], in any order.
+///
+/// A single missing data part is rebuilt from the parity part if it was
+/// received. Duplicate parts are accepted.
+///
+/// # Arguments
+/// * `parts` - The received parts
+/// * `encoding` - The encoding the parts were split with
+///
+/// # Returns
+/// The original bytes
+///
+/// # Errors
+/// Returns `SerialiseError` listing the missing sequence numbers if parts are
+/// missing and cannot be rebuilt, or if a part is malformed, parts from
+/// different messages are mixed, or the reassembled bytes fail the checksum.
+pub fn join<S: AsRef<str>>(parts: &[S], encoding: Encoding) -> Result<ByteVec, SerialiseError> {
+    let Message {
+        mut slots,
+        parity,
+        len,
+        checksum,
+    } = parse_parts(parts, encoding)?;
+    let gaps: Vec<usize> = slots
+        .iter()
+        .enumerate()
+        .filter(|(_, slot)| slot.is_none())
+        .map(|(i, _)| i)
+        .collect();
+
+    match (gaps.as_slice(), parity) {
+        ([], _) => {}
+        (&[gap], Some(parity)) => {
+            let mut rebuilt = parity;
+            for chunk in slots.iter().flatten() {
+                rebuilt.iter_mut().zip(chunk).for_each(|(x, b)| *x ^= b);
+            }
+            // Only the last part can be shorter than the parity part
+            if gap + 1 == slots.len() {
+                rebuilt.truncate(len.saturating_sub(rebuilt.len() * gap));
+            }
+            slots[gap] = Some(rebuilt);
+        }
+        (gaps, _) => {
+            let numbers: Vec<String> = gaps.iter().map(|gap| (gap + 1).to_string()).collect();
+            return Err(SerialiseError::new(format!(
+                "missing parts {} of {}",
+                numbers.join(", "),
+                slots.len()
+            )));
+        }
+    }
+
+    let bytes: Vec<u8> = slots.into_iter().flatten().flatten().collect();
+    if bytes.len() != len || Adler32::checksum(&bytes) != checksum {
+        return Err(SerialiseError::new(
+            "reassembled parts fail the length or checksum check".to_string(),
+        ));
+    }
+    Ok(ByteVec::new(Arc::new(bytes)))
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "base45", feature = "base64", feature = "hex"))]
+mod tests {
+
+    use super::*;
+
+    fn sample() -> ByteVec {
+        ByteVec::new(Arc::new((0..=255).cycle().take(1000).collect()))
+    }
+
+    #[test]
+    fn test_split_and_join() {
+        let parts = split(&sample(), Encoding::Base64, 160, false).unwrap_or_default();
+        assert_eq!(parts.len(), 11);
+        assert!(parts.iter().all(|part| part.len() <= 160));
+        assert!(parts[0].starts_with("1/11:1000:"));
+
+        let reversed: Vec<&String> = parts.iter().rev().collect();
+        assert!(matches!(join(&reversed, Encoding::Base64), Ok(joined) if joined == sample()));
+    }
+
+    #[test]
+    fn test_parity_rebuilds_one_part() {
+        let parts = split(&sample(), Encoding::Base45, 200, true).unwrap_or_default();
+        assert!(parts.last().is_some_and(|part| part.starts_with("p/")));
+        for lost in 0..parts.len() {
+            let received: Vec<&String> = parts
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| i != lost)
+                .map(|(_, part)| part)
+                .collect();
+            assert!(matches!(join(&received, Encoding::Base45), Ok(joined) if joined == sample()));
+        }
+    }
+
+    #[test]
+    fn test_missing_parts_are_reported() {
+        let parts = split(&sample(), Encoding::Base64, 160, true).unwrap_or_default();
+        let received: Vec<&String> = parts
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != 1 && i != 4)
+            .map(|(_, part)| part)
+            .collect();
+        assert!(matches!(
+            missing(&received, Encoding::Base64).as_deref(),
+            Ok([2, 5])
+        ));
+        assert!(matches!(
+            join(&received, Encoding::Base64),
+            Err(e) if e.get_message() == "missing parts 2, 5 of 11"
+        ));
+        assert!(split(&sample(), Encoding::Base64, 20, false).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "base58")]
+    fn test_numeric_encoding_keeps_leading_zeros() {
+        let zeros = ByteVec::new(Arc::new(
+            (0..100).map(|i| if i % 10 < 3 { 0 } else { 7 }).collect(),
+        ));
+        for payload in [
+            zeros,
+            ByteVec::new(Arc::new(vec![0; 25])),
+            ByteVec::new(Arc::new(vec![])),
+        ] {
+            let parts = split(&payload, Encoding::Base58, 40, true).unwrap_or_default();
+            assert!(parts.len() > 1);
+            assert!(matches!(join(&parts, Encoding::Base58), Ok(joined) if joined == payload));
+            for lost in 0..parts.len() {
+                let received: Vec<&String> = parts
+                    .iter()
+                    .enumerate()
+                    .filter(|&(i, _)| i != lost)
+                    .map(|(_, part)| part)
+                    .collect();
+                assert!(
+                    matches!(join(&received, Encoding::Base58), Ok(joined) if joined == payload)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_implausible_part_count_is_err() {
+        for header in [
+            "1/18446744073709551615:1:00000001:00",
+            "1/100000000:1:00000001:00",
+            "1/2:0:00000001:",
+            "1/100000000:100000000:00000001:00",
+        ] {
+            assert!(join(&[header], Encoding::Hex).is_err(), "{header}");
+            assert!(missing(&[header], Encoding::Hex).is_err(), "{header}");
+        }
+        assert!(matches!(
+            missing(&["1/3:3:00000001:00"], Encoding::Hex).as_deref(),
+            Ok([2, 3])
+        ));
+    }
+}
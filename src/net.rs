@@ -0,0 +1,235 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::{SerialiseError, alphabet, serialise_error::check_ascii};
+
+/// A fixed-length byte string with a conventional text form, such as a
+/// network address.
+///
+/// Formats work on plain bytes like the encodings, so network tools can
+/// convert addresses read off the wire without building `std::net` types or
+/// pulling in another crate.
+pub trait AddressFormat {
+    /// Returns the name of the format, used in error messages.
+    fn get_name(&self) -> &'static str;
+
+    /// Returns the number of bytes in an address.
+    fn get_len(&self) -> usize;
+
+    /// Writes `bytes` in the format's text form.
+    ///
+    /// # Arguments
+    /// * `bytes` - The address bytes, in network order
+    ///
+    /// # Returns
+    /// The address text
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `bytes` is not exactly [`AddressFormat::get_len`] bytes.
+    fn encode(&self, bytes: &[u8]) -> Result<String, SerialiseError>;
+
+    /// Parses an address written in the format's text form.
+    ///
+    /// # Arguments
+    /// * `text` - The address text
+    ///
+    /// # Returns
+    /// The address bytes, in network order
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `text` is not a valid address.
+    fn decode(&self, text: &str) -> Result<Vec<u8>, SerialiseError>;
+
+    /// Checks that `text` is a valid address.
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` for exactly the inputs [`AddressFormat::decode`] rejects.
+    fn validate(&self, text: &str) -> Result<(), SerialiseError> {
+        self.decode(text).map(|_| ())
+    }
+}
+
+/// Returns `bytes` as an array of `N` bytes, or an error naming `format`.
+fn exact<const N: usize>(bytes: &[u8], format: &str) -> Result<[u8; N], SerialiseError> {
+    bytes.try_into().map_err(|_| {
+        SerialiseError::new(format!(
+            "{format} address must be {N} bytes, got {}",
+            bytes.len()
+        ))
+    })
+}
+
+/// IPv4 addresses as dotted quads, such as `192.0.2.1`.
+///
+/// Decoding rejects octets with leading zeros, which some parsers read as
+/// octal.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ipv4;
+
+impl AddressFormat for Ipv4 {
+    fn get_name(&self) -> &'static str {
+        "IPv4"
+    }
+
+    fn get_len(&self) -> usize {
+        4
+    }
+
+    fn encode(&self, bytes: &[u8]) -> Result<String, SerialiseError> {
+        Ok(Ipv4Addr::from(exact::<4>(bytes, self.get_name())?).to_string())
+    }
+
+    fn decode(&self, text: &str) -> Result<Vec<u8>, SerialiseError> {
+        text.parse::<Ipv4Addr>()
+            .map(|addr| addr.octets().to_vec())
+            .map_err(|_| SerialiseError::new(format!("'{text}' is not a dotted-quad IPv4 address")))
+    }
+}
+
+/// IPv6 addresses in colon-hex form, such as `2001:db8::1`.
+///
+/// Encoding follows RFC 5952: lowercase, no leading zeros, the longest run of
+/// two or more zero groups compressed to `::`, and IPv4-mapped addresses
+/// ending in a dotted quad. Decoding accepts any RFC 4291 form, in either case.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ipv6;
+
+impl AddressFormat for Ipv6 {
+    fn get_name(&self) -> &'static str {
+        "IPv6"
+    }
+
+    fn get_len(&self) -> usize {
+        16
+    }
+
+    fn encode(&self, bytes: &[u8]) -> Result<String, SerialiseError> {
+        Ok(Ipv6Addr::from(exact::<16>(bytes, self.get_name())?).to_string())
+    }
+
+    fn decode(&self, text: &str) -> Result<Vec<u8>, SerialiseError> {
+        text.parse::<Ipv6Addr>()
+            .map(|addr| addr.octets().to_vec())
+            .map_err(|_| SerialiseError::new(format!("'{text}' is not an IPv6 address")))
+    }
+}
+
+/// MAC addresses as six pairs of hex digits, such as `00:1a:2b:3c:4d:5e`.
+///
+/// Encoding writes lowercase digits separated by colons. Decoding accepts
+/// either case and colons or hyphens, as Windows writes them, but not a mix.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Mac;
+
+impl AddressFormat for Mac {
+    fn get_name(&self) -> &'static str {
+        "MAC"
+    }
+
+    fn get_len(&self) -> usize {
+        6
+    }
+
+    fn encode(&self, bytes: &[u8]) -> Result<String, SerialiseError> {
+        let bytes = exact::<6>(bytes, self.get_name())?;
+        let mut out = String::with_capacity(17);
+        for (i, &b) in bytes.iter().enumerate() {
+            if i > 0 {
+                out.push(':');
+            }
+            out.push(char::from(alphabet::HEX[usize::from(b >> 4)]));
+            out.push(char::from(alphabet::HEX[usize::from(b & 0x0f)]));
+        }
+        Ok(out)
+    }
+
+    fn decode(&self, text: &str) -> Result<Vec<u8>, SerialiseError> {
+        check_ascii(text, self.get_name())?;
+        let invalid = || SerialiseError::new(format!("'{text}' is not a MAC address"));
+        let separator = text.as_bytes().get(2).copied().ok_or_else(invalid)?;
+        if !matches!(separator, b':' | b'-') {
+            return Err(invalid());
+        }
+        let groups: Vec<&str> = text.split(char::from(separator)).collect();
+        if groups.len() != 6 {
+            return Err(invalid());
+        }
+        groups
+            .iter()
+            .map(|group| {
+                if group.len() == 2 && group.bytes().all(|c| c.is_ascii_hexdigit()) {
+                    u8::from_str_radix(group, 16).map_err(|_| invalid())
+                } else {
+                    Err(invalid())
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        assert!(matches!(
+            Ipv4.encode(&[192, 0, 2, 1]).as_deref(),
+            Ok("192.0.2.1")
+        ));
+        let mut v6 = [0u8; 16];
+        v6[..4].copy_from_slice(&[0x20, 0x01, 0x0d, 0xb8]);
+        v6[15] = 1;
+        assert!(matches!(Ipv6.encode(&v6).as_deref(), Ok("2001:db8::1")));
+        assert!(matches!(Ipv6.encode(&[0; 16]).as_deref(), Ok("::")));
+        assert!(matches!(
+            Mac.encode(&[0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e]).as_deref(),
+            Ok("00:1a:2b:3c:4d:5e")
+        ));
+        assert!(Ipv4.encode(&[1, 2, 3]).is_err());
+        assert!(Mac.encode(&[0; 8]).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let formats: [&dyn AddressFormat; 3] = [&Ipv4, &Ipv6, &Mac];
+        for format in formats {
+            let bytes: Vec<u8> = (0..format.get_len())
+                .map(|i| u8::try_from(i * 37 % 256).unwrap_or_default())
+                .collect();
+            let text = format.encode(&bytes).unwrap_or_default();
+            assert!(
+                matches!(format.decode(&text), Ok(b) if b == bytes),
+                "{text}"
+            );
+            assert!(format.validate(&text).is_ok());
+        }
+        assert!(matches!(
+            Ipv6.decode("2001:DB8:0:0:0:0:0:1"),
+            Ok(b) if matches!(Ipv6.encode(&b).as_deref(), Ok("2001:db8::1"))
+        ));
+        assert!(
+            matches!(Mac.decode("00-1A-2B-3C-4D-5E"), Ok(b) if b == [0, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e])
+        );
+    }
+
+    #[test]
+    fn test_invalid_is_err() {
+        for bad in ["", "1.2.3", "1.2.3.256", "01.2.3.4", "1.2.3.4 "] {
+            assert!(Ipv4.decode(bad).is_err(), "{bad}");
+        }
+        for bad in ["", "1::2::3", "2001:db8::1::", "12345::", "::g"] {
+            assert!(Ipv6.decode(bad).is_err(), "{bad}");
+        }
+        for bad in [
+            "",
+            "00:1a:2b:3c:4d",
+            "00:1a-2b:3c:4d:5e",
+            "0:1a:2b:3c:4d:5e",
+            "00:1a:2b:3c:4d:+e",
+            "00:1a:2b:3c:4d:5\u{e9}",
+        ] {
+            assert!(Mac.decode(bad).is_err(), "{bad}");
+        }
+    }
+}
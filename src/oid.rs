@@ -0,0 +1,264 @@
+use crate::SerialiseError;
+
+/// DER tag of an OBJECT IDENTIFIER.
+const TAG: u8 = 0x06;
+
+/// Parses the arcs of a dotted OID, such as `1.2.840.113549`.
+fn parse_arcs(dotted: &str) -> Result<Vec<u128>, SerialiseError> {
+    let arcs = dotted
+        .split('.')
+        .map(|arc| {
+            let canonical = !arc.is_empty()
+                && arc.bytes().all(|c| c.is_ascii_digit())
+                && (arc == "0" || !arc.starts_with('0'));
+            canonical
+                .then(|| arc.parse::<u128>().ok())
+                .flatten()
+                .ok_or_else(|| {
+                    SerialiseError::new(format!("'{arc}' is not a valid arc in OID '{dotted}'"))
+                })
+        })
+        .collect::<Result<Vec<u128>, _>>()?;
+    match arcs.as_slice() {
+        [first, second, ..] if *first <= 2 && (*first == 2 || *second < 40) => Ok(arcs),
+        [_, _, ..] => Err(SerialiseError::new(format!(
+            "OID '{dotted}' must start with 0 or 1 followed by an arc below 40, or with 2"
+        ))),
+        _ => Err(SerialiseError::new(format!(
+            "OID '{dotted}' must have at least two arcs"
+        ))),
+    }
+}
+
+/// Appends `value` in base 128, most significant group first, with the top
+/// bit set on every byte but the last.
+fn push_base128(out: &mut Vec<u8>, value: u128) {
+    let groups = (u128::BITS - value.leading_zeros()).div_ceil(7).max(1);
+    for i in (0..groups).rev() {
+        let group = ((value >> (7 * i)) & 0x7f).to_le_bytes()[0];
+        out.push(if i == 0 { group } else { group | 0x80 });
+    }
+}
+
+/// Encodes a dotted OID, such as `1.2.840.113549`, as the content octets of
+/// its DER encoding, without the tag and length.
+///
+/// # Arguments
+/// * `dotted` - The OID in dotted decimal form
+///
+/// # Returns
+/// The content octets
+///
+/// # Errors
+/// Returns `SerialiseError` if an arc is empty, not decimal, has a leading
+/// zero or does not fit in 128 bits, or the OID has fewer than two arcs or
+/// an invalid first or second arc.
+pub fn encode(dotted: &str) -> Result<Vec<u8>, SerialiseError> {
+    let arcs = parse_arcs(dotted)?;
+    let first = (arcs[0] * 40)
+        .checked_add(arcs[1])
+        .ok_or_else(|| SerialiseError::new(format!("second arc of OID '{dotted}' is too large")))?;
+    let mut out = Vec::with_capacity(arcs.len() * 2);
+    for &arc in std::iter::once(&first).chain(&arcs[2..]) {
+        push_base128(&mut out, arc);
+    }
+    Ok(out)
+}
+
+/// Encodes a dotted OID as a complete DER OBJECT IDENTIFIER: the tag `06`,
+/// the length and the content octets.
+///
+/// # Arguments
+/// * `dotted` - The OID in dotted decimal form
+///
+/// # Returns
+/// The DER encoding
+///
+/// # Errors
+/// Returns `SerialiseError` for the same inputs as [`encode`].
+pub fn encode_der(dotted: &str) -> Result<Vec<u8>, SerialiseError> {
+    let content = encode(dotted)?;
+    let mut out = Vec::with_capacity(content.len() + 4);
+    out.push(TAG);
+    if content.len() < 0x80 {
+        out.push(u8::try_from(content.len()).unwrap_or_default());
+    } else {
+        let len = content.len().to_be_bytes();
+        let start = len.iter().position(|&b| b != 0).unwrap_or(len.len() - 1);
+        out.push(0x80 | u8::try_from(len.len() - start).unwrap_or_default());
+        out.extend_from_slice(&len[start..]);
+    }
+    out.extend_from_slice(&content);
+    Ok(out)
+}
+
+/// Decodes the content octets of a DER OBJECT IDENTIFIER to dotted form.
+///
+/// # Arguments
+/// * `content` - The content octets, without the tag and length
+///
+/// # Returns
+/// The OID in dotted decimal form, such as `1.2.840.113549`
+///
+/// # Errors
+/// Returns `SerialiseError` if `content` is empty, ends in the middle of an
+/// arc, has an arc with a redundant leading `0x80` byte, as DER forbids, or
+/// has an arc that does not fit in 128 bits.
+pub fn decode(content: &[u8]) -> Result<String, SerialiseError> {
+    if content.is_empty() {
+        return Err(SerialiseError::new("OID has no content octets".to_string()));
+    }
+    let mut arcs: Vec<u128> = Vec::new();
+    let mut value: u128 = 0;
+    let mut start = true;
+    for (position, &b) in content.iter().enumerate() {
+        if start && b == 0x80 {
+            return Err(SerialiseError::new(format!(
+                "OID arc at byte {position} has a redundant leading 0x80"
+            )));
+        }
+        if value.leading_zeros() < 7 {
+            return Err(SerialiseError::new(format!(
+                "OID arc at byte {position} does not fit in 128 bits"
+            )));
+        }
+        value = (value << 7) | u128::from(b & 0x7f);
+        start = b & 0x80 == 0;
+        if start {
+            arcs.push(value);
+            value = 0;
+        }
+    }
+    if !start {
+        return Err(SerialiseError::new(
+            "OID ends in the middle of an arc".to_string(),
+        ));
+    }
+
+    let first = arcs[0];
+    let (a, b) = match first {
+        0..40 => (0, first),
+        40..80 => (1, first - 40),
+        _ => (2, first - 80),
+    };
+    let mut dotted = format!("{a}.{b}");
+    for arc in &arcs[1..] {
+        dotted.push('.');
+        dotted.push_str(&arc.to_string());
+    }
+    Ok(dotted)
+}
+
+/// Decodes a complete DER OBJECT IDENTIFIER, tag and length included, to
+/// dotted form.
+///
+/// # Arguments
+/// * `der` - The DER encoding
+///
+/// # Returns
+/// The OID in dotted decimal form
+///
+/// # Errors
+/// Returns `SerialiseError` if the tag is not `06`, the length is not in
+/// DER's minimal form or does not match the bytes that follow, or the
+/// content is invalid as for [`decode`].
+pub fn decode_der(der: &[u8]) -> Result<String, SerialiseError> {
+    let [tag, len, rest @ ..] = der else {
+        return Err(SerialiseError::new("DER OID is truncated".to_string()));
+    };
+    if *tag != TAG {
+        return Err(SerialiseError::new(format!(
+            "DER tag 0x{tag:02x} is not an OBJECT IDENTIFIER"
+        )));
+    }
+    let (len, content) = if len & 0x80 == 0 {
+        (usize::from(*len), rest)
+    } else {
+        let count = usize::from(len & 0x7f);
+        let bytes = rest
+            .get(..count)
+            .filter(|b| b.first().is_some_and(|&b| b != 0));
+        let Some(bytes) = bytes.filter(|b| b.len() <= size_of::<usize>()) else {
+            return Err(SerialiseError::new("DER OID length is invalid".to_string()));
+        };
+        let len = bytes.iter().fold(0usize, |n, &b| (n << 8) | usize::from(b));
+        if len < 0x80 {
+            return Err(SerialiseError::new(
+                "DER OID length is not in minimal form".to_string(),
+            ));
+        }
+        (len, &rest[count..])
+    };
+    if content.len() != len {
+        return Err(SerialiseError::new(format!(
+            "DER OID length is {len} but {} bytes follow",
+            content.len()
+        )));
+    }
+    decode(content)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        assert!(matches!(
+            encode("1.2.840.113549"),
+            Ok(b) if b == [0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d]
+        ));
+        assert!(matches!(
+            encode_der("2.5.4.3"),
+            Ok(b) if b == [0x06, 0x03, 0x55, 0x04, 0x03]
+        ));
+        assert!(matches!(encode("2.999.3"), Ok(b) if b == [0x88, 0x37, 0x03]));
+        assert!(matches!(encode("0.0"), Ok(b) if b == [0x00]));
+        let long = format!("1.2{}", ".1".repeat(200));
+        assert!(matches!(encode_der(&long), Ok(b) if b[..3] == [0x06, 0x81, 201]));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for dotted in [
+            "1.2.840.113549.1.1.11",
+            "2.5.29.17",
+            "0.39",
+            "2.25.329800735698586629295641978511506172918",
+            &format!("1.3{}", ".6".repeat(100)),
+        ] {
+            let der = encode_der(dotted).unwrap_or_default();
+            assert!(matches!(decode_der(&der), Ok(d) if d == dotted), "{dotted}");
+        }
+    }
+
+    #[test]
+    fn test_invalid_is_err() {
+        for bad in [
+            "",
+            "1",
+            "1.",
+            "1..2",
+            "3.1",
+            "1.40",
+            "01.2",
+            "1.2.a",
+            "1.+2",
+            "1.2.340282366920938463463374607431768211456",
+        ] {
+            assert!(encode(bad).is_err(), "{bad}");
+        }
+        for bad in [&[][..], &[0x2a, 0x86], &[0x2a, 0x80, 0x01], &[0xff; 20]] {
+            assert!(decode(bad).is_err(), "{bad:02x?}");
+        }
+        for bad in [
+            &[0x06][..],
+            &[0x04, 0x01, 0x2a],
+            &[0x06, 0x02, 0x2a],
+            &[0x06, 0x81, 0x01, 0x2a],
+        ] {
+            assert!(decode_der(bad).is_err(), "{bad:02x?}");
+        }
+    }
+}
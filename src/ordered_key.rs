@@ -0,0 +1,188 @@
+use crate::{Base64, Base64Config, SerialiseError, alphabet};
+
+/// Ends every field of a composite key. It sorts before every character of
+/// [`alphabet::BASE64_ORDERED`], so a field that is a prefix of another sorts
+/// first, as its bytes do.
+pub const TERMINATOR: char = '!';
+
+/// Encodes `bytes` so that encodings compare as strings in the same order as
+/// the bytes compare as byte strings.
+///
+/// The text is unpadded Base64 in [`alphabet::BASE64_ORDERED`], whose
+/// characters are in ASCII order. Unused trailing bits are zero, so a byte
+/// string that is a prefix of another encodes to a string that sorts first.
+///
+/// # Arguments
+/// * `bytes` - The bytes to encode
+///
+/// # Returns
+/// The order-preserving text, empty for no bytes
+///
+/// # Errors
+/// Returns `SerialiseError` if Base64 encoding fails.
+pub fn encode(bytes: &[u8]) -> Result<String, SerialiseError> {
+    let standard = Base64::try_to_base64_with(bytes, Base64Config::NO_PAD)?;
+    alphabet::transcode_alphabet(
+        &standard,
+        alphabet::BASE64_STANDARD,
+        alphabet::BASE64_ORDERED,
+    )
+}
+
+/// Decodes text written by [`encode`], accepting nothing else.
+///
+/// # Arguments
+/// * `text` - The order-preserving text, without a terminator
+///
+/// # Returns
+/// The decoded bytes
+///
+/// # Errors
+/// Returns `SerialiseError` if `text` contains a character outside the
+/// alphabet, has a length no encoder produces or is not in canonical form.
+pub fn decode(text: &str) -> Result<Vec<u8>, SerialiseError> {
+    if let Some((position, c)) = text
+        .char_indices()
+        .find(|&(_, c)| !u8::try_from(c).is_ok_and(|b| alphabet::BASE64_ORDERED.contains(&b)))
+    {
+        return Err(SerialiseError::new(format!(
+            "invalid ordered base64 character '{c}' at position {position}"
+        )));
+    }
+    if text.len() % 4 == 1 {
+        return Err(SerialiseError::new(format!(
+            "ordered base64 length {} is not one an encoder produces",
+            text.len()
+        )));
+    }
+    let standard =
+        alphabet::transcode_alphabet(text, alphabet::BASE64_ORDERED, alphabet::BASE64_STANDARD)?;
+    let bytes = Base64::try_from_base64_with(&standard, Base64Config::NO_PAD)?;
+    if encode(&bytes)? != text {
+        return Err(SerialiseError::new(
+            "ordered base64 is not in canonical form".to_string(),
+        ));
+    }
+    Ok(bytes)
+}
+
+/// Encodes the fields of a composite key, each followed by [`TERMINATOR`].
+///
+/// Keys compare as strings in the same order as their fields compare as
+/// tuples of byte strings: by the first field, then the second, and so on,
+/// with a key that runs out of fields first sorting first. This is the
+/// layout of byte strings in tuple encodings such as the `FoundationDB`
+/// tuple layer, kept printable.
+///
+/// # Arguments
+/// * `fields` - The byte fields of the key, in order
+///
+/// # Returns
+/// The key
+///
+/// # Errors
+/// Returns `SerialiseError` if Base64 encoding fails.
+pub fn encode_key<I>(fields: I) -> Result<String, SerialiseError>
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    let mut key = String::new();
+    for field in fields {
+        key.push_str(&encode(field.as_ref())?);
+        key.push(TERMINATOR);
+    }
+    Ok(key)
+}
+
+/// Splits a key written by [`encode_key`] back into its fields.
+///
+/// # Arguments
+/// * `key` - The key
+///
+/// # Returns
+/// The decoded fields, in order
+///
+/// # Errors
+/// Returns `SerialiseError` if `key` does not end in [`TERMINATOR`] or a
+/// field is not valid as for [`decode`].
+pub fn decode_key(key: &str) -> Result<Vec<Vec<u8>>, SerialiseError> {
+    let Some(fields) = key.strip_suffix(TERMINATOR) else {
+        return Err(SerialiseError::new(if key.is_empty() {
+            "ordered key has no fields".to_string()
+        } else {
+            format!("ordered key does not end in '{TERMINATOR}'")
+        }));
+    };
+    fields
+        .split(TERMINATOR)
+        .enumerate()
+        .map(|(i, field)| decode(field).map_err(|e| e.with_context(format!("field {i}"))))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for bytes in [&b""[..], b"\0", b"\0\0", b"a", b"ab", b"abc", &[0xff; 7]] {
+            let text = encode(bytes).unwrap_or_default();
+            assert!(matches!(decode(&text), Ok(b) if b == bytes), "{text}");
+        }
+        let fields: [&[u8]; 4] = [b"users", b"", b"\x00\xff", b"42"];
+        let key = encode_key(fields).unwrap_or_default();
+        assert_eq!(key.matches(TERMINATOR).count(), 4);
+        assert!(matches!(decode_key(&key), Ok(f) if f == fields));
+    }
+
+    #[test]
+    fn test_preserves_order() {
+        let mut values: Vec<Vec<u8>> = vec![vec![]];
+        for a in [0u8, 1, 0x7f, 0x80, 0xfe, 0xff] {
+            values.push(vec![a]);
+            for b in [0u8, 1, 0x3f, 0x40, 0xff] {
+                values.push(vec![a, b]);
+                values.push(vec![a, b, a]);
+                values.push(vec![b, a, b, a]);
+            }
+        }
+        values.sort();
+        values.dedup();
+        let encoded: Vec<String> = values
+            .iter()
+            .map(|v| encode(v).unwrap_or_default())
+            .collect();
+        assert!(encoded.windows(2).all(|w| w[0] < w[1]));
+
+        let mut keys: Vec<[&[u8]; 2]> = Vec::new();
+        for a in values.iter().take(12) {
+            for b in values.iter().take(12) {
+                keys.push([a, b]);
+            }
+        }
+        keys.sort();
+        let encoded: Vec<String> = keys
+            .iter()
+            .map(|&key| encode_key(key).unwrap_or_default())
+            .collect();
+        assert!(encoded.windows(2).all(|w| w[0] < w[1]));
+        assert!(
+            encode_key([b"a"]).unwrap_or_default()
+                < encode_key([&b"a"[..], b""]).unwrap_or_default()
+        );
+    }
+
+    #[test]
+    fn test_invalid_is_err() {
+        for bad in ["A", "AB=", "AB+", "A!", "-1"] {
+            assert!(decode(bad).is_err(), "{bad}");
+        }
+        for bad in ["", "YQ", "YQ!AB", "YQ!A!"] {
+            assert!(decode_key(bad).is_err(), "{bad}");
+        }
+        assert!(matches!(decode_key("!"), Ok(f) if f == [Vec::<u8>::new()]));
+    }
+}
@@ -0,0 +1,367 @@
+use std::{fmt::Write, sync::Arc};
+
+use crate::{
+    Base32, Base32Config, ByteVec, ErrorKind, SerialiseError,
+    checksum::{Crc16, Crc32},
+};
+
+/// First word of the header line.
+const MAGIC: &str = "PAPER";
+
+/// Version of the layout written by [`render`].
+const VERSION: u32 = 1;
+
+/// Layout of a paper backup.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PaperConfig {
+    /// Data bytes on each line
+    bytes_per_line: usize,
+    /// Base32 characters in each space-separated group
+    group_len: usize,
+}
+
+impl PaperConfig {
+    /// Ten bytes, sixteen characters, per line in groups of four.
+    pub const DEFAULT: Self = Self {
+        bytes_per_line: 10,
+        group_len: 4,
+    };
+
+    /// Creates a layout.
+    ///
+    /// # Arguments
+    /// * `bytes_per_line` - Data bytes on each line
+    /// * `group_len` - Characters in each group of a line
+    ///
+    /// # Returns
+    /// The layout
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if either is zero.
+    pub fn new(bytes_per_line: usize, group_len: usize) -> Result<Self, SerialiseError> {
+        if bytes_per_line == 0 || group_len == 0 {
+            return Err(SerialiseError::new(
+                "paper backup lines and groups must not be empty".to_string(),
+            ));
+        }
+        Ok(Self {
+            bytes_per_line,
+            group_len,
+        })
+    }
+}
+
+impl Default for PaperConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// A data line of a backup that passed its check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaperLine {
+    /// The number printed at the start of the line
+    number: usize,
+    /// The bytes on the line
+    bytes: Vec<u8>,
+}
+
+impl PaperLine {
+    /// Returns the number printed at the start of the line.
+    #[must_use = "This returns the line number but does nothing if unused"]
+    pub const fn get_number(&self) -> usize {
+        self.number
+    }
+
+    /// Returns the bytes on the line.
+    #[must_use = "This returns the bytes but does nothing if unused"]
+    pub fn get_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+/// Returns the check characters of line `number` holding `bytes`: the
+/// CRC-16 of the line number and bytes, as four Base32 characters.
+fn line_check(number: usize, bytes: &[u8]) -> String {
+    let mut crc = Crc16::new();
+    crc.update(&u64::try_from(number).unwrap_or(u64::MAX).to_be_bytes());
+    crc.update(bytes);
+    Base32::try_to_base32_with(&crc.get_value().to_be_bytes(), Base32Config::NO_PAD)
+        .unwrap_or_default()
+}
+
+/// Renders `bytes` with the default layout. See [`render_with`].
+#[must_use = "This returns the backup text but does nothing if unused"]
+pub fn render(bytes: &ByteVec) -> String {
+    render_with(bytes, PaperConfig::DEFAULT)
+}
+
+/// Renders `bytes` as a backup to print and keep offline, such as a key.
+///
+/// The first line gives the format version, the length and the CRC-32 of the
+/// whole. Each following line has its number, the line's bytes in unpadded
+/// Base32 split into groups, and four check characters covering the number
+/// and bytes, so a mistyped or skipped line is found when it is typed back:
+///
+/// ```text
+/// PAPER 1 12 E79AA9C2
+/// 1 JBSW Y3DP FQQH O33S  EQOA
+/// 2 NRSA  BQCQ
+/// ```
+///
+/// # Arguments
+/// * `bytes` - The bytes to back up
+/// * `config` - The layout
+///
+/// # Returns
+/// The backup text, one line per row with a trailing line break
+#[must_use = "This returns the backup text but does nothing if unused"]
+pub fn render_with(bytes: &ByteVec, config: PaperConfig) -> String {
+    let data = bytes.get_bytes();
+    let lines = data.len().div_ceil(config.bytes_per_line);
+    let width = lines.to_string().len();
+    let mut out = format!(
+        "{MAGIC} {VERSION} {} {:08X}\n",
+        data.len(),
+        Crc32::checksum(data)
+    );
+    for (i, chunk) in data.chunks(config.bytes_per_line).enumerate() {
+        let number = i + 1;
+        let encoded = Base32::try_to_base32_with(chunk, Base32Config::NO_PAD).unwrap_or_default();
+        let groups: Vec<&str> = encoded
+            .as_bytes()
+            .chunks(config.group_len)
+            .map(|group| std::str::from_utf8(group).unwrap_or_default())
+            .collect();
+        let _ = writeln!(
+            out,
+            "{number:0width$} {}  {}",
+            groups.join(" "),
+            line_check(number, chunk)
+        );
+    }
+    out
+}
+
+/// Returns Base32 text as typed back in, with case and the digits people
+/// write for the letters they resemble corrected.
+fn normalise(text: &str) -> String {
+    text.chars()
+        .filter(|&c| c != '-')
+        .map(|c| match c.to_ascii_uppercase() {
+            '0' => 'O',
+            '1' => 'I',
+            '8' => 'B',
+            c => c,
+        })
+        .collect()
+}
+
+/// The header of a backup: its length and CRC-32.
+fn parse_header(line: &str) -> Result<(usize, u32), SerialiseError> {
+    let invalid = || {
+        SerialiseError::new(format!(
+            "'{line}' is not a paper backup header, expected '{MAGIC} {VERSION} <length> <crc>'"
+        ))
+    };
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let [magic, version, len, crc] = words.as_slice() else {
+        return Err(invalid());
+    };
+    if !magic.eq_ignore_ascii_case(MAGIC) {
+        return Err(invalid());
+    }
+    if version.parse::<u32>().map_err(|_| invalid())? != VERSION {
+        return Err(SerialiseError::new(format!(
+            "paper backup version {version} is not supported, expected {VERSION}"
+        )));
+    }
+    let len = len.parse().map_err(|_| invalid())?;
+    let crc = u32::from_str_radix(crc, 16).map_err(|_| invalid())?;
+    Ok((len, crc))
+}
+
+/// Decodes one data line to its number and bytes.
+fn parse_line(line: &str) -> Result<PaperLine, SerialiseError> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let [number, data @ .., check] = words.as_slice() else {
+        return Err(SerialiseError::new(
+            "line needs a number and check characters".to_string(),
+        ));
+    };
+    let number: usize = number
+        .trim_end_matches([':', '.'])
+        .parse()
+        .map_err(|_| SerialiseError::new(format!("'{number}' is not a line number")))?;
+    let bytes = Base32::try_from_base32_with(&normalise(&data.concat()), Base32Config::NO_PAD)
+        .map_err(|e| e.with_context(format!("line {number}")))?;
+    if normalise(check) != line_check(number, &bytes) {
+        return Err(SerialiseError::new(format!(
+            "line {number} does not match its check characters"
+        ))
+        .with_kind(ErrorKind::ChecksumMismatch));
+    }
+    Ok(PaperLine { number, bytes })
+}
+
+/// Decodes each data line of a backup on its own, so the lines that were
+/// mistyped can be found and typed again.
+///
+/// The header and blank lines are skipped.
+///
+/// # Arguments
+/// * `text` - The backup as typed back in
+///
+/// # Returns
+/// For each data line, its position among the lines of `text`, counting from
+/// 1, and either the line or the reason it failed
+#[must_use = "This returns the decoded lines but does nothing if unused"]
+pub fn parse_lines(text: &str) -> Vec<(usize, Result<PaperLine, SerialiseError>)> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .skip(1)
+        .map(|(i, line)| (i + 1, parse_line(line)))
+        .collect()
+}
+
+/// Decodes a backup written by [`render`], as typed back in by a person.
+///
+/// Case, extra spaces, hyphens and the digits `0`, `1` and `8` typed for the
+/// letters `O`, `I` and `B` are all forgiven. Lines may come in any order.
+///
+/// # Arguments
+/// * `text` - The backup
+///
+/// # Returns
+/// The bytes backed up
+///
+/// # Errors
+/// Returns `SerialiseError` naming every line that is wrong or missing, with
+/// [`ErrorKind::ChecksumMismatch`] if only check characters failed, or if the
+/// header is missing or the whole does not match its length and CRC-32.
+pub fn parse(text: &str) -> Result<ByteVec, SerialiseError> {
+    let header = text
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .ok_or_else(|| SerialiseError::new("paper backup is empty".to_string()))?;
+    let (len, crc) = parse_header(header)?;
+
+    let mut lines: Vec<PaperLine> = Vec::new();
+    let mut problems: Vec<String> = Vec::new();
+    let mut all_checksums = true;
+    for (_, line) in parse_lines(text) {
+        match line {
+            Ok(line) => lines.push(line),
+            Err(e) => {
+                all_checksums &= e.get_kind() == ErrorKind::ChecksumMismatch;
+                problems.push(e.to_string());
+            }
+        }
+    }
+    lines.sort_by_key(PaperLine::get_number);
+    let mut expected = 1;
+    for line in &lines {
+        let number = line.number;
+        if number < expected {
+            all_checksums = false;
+            problems.push(format!("line {number} appears more than once"));
+        } else if number == expected + 1 {
+            all_checksums = false;
+            problems.push(format!("line {expected} is missing"));
+        } else if number > expected {
+            all_checksums = false;
+            problems.push(format!("lines {expected} to {} are missing", number - 1));
+        }
+        expected = expected.max(number + 1);
+    }
+    if !problems.is_empty() {
+        let error = SerialiseError::new(problems.join("; "));
+        return Err(if all_checksums {
+            error.with_kind(ErrorKind::ChecksumMismatch)
+        } else {
+            error
+        });
+    }
+
+    let bytes: Vec<u8> = lines.into_iter().flat_map(|line| line.bytes).collect();
+    if bytes.len() != len {
+        return Err(SerialiseError::new(format!(
+            "paper backup has {} bytes, its header says {len}; lines may be missing from the end",
+            bytes.len()
+        )));
+    }
+    if Crc32::checksum(&bytes) != crc {
+        return Err(
+            SerialiseError::new("paper backup does not match its CRC-32".to_string())
+                .with_kind(ErrorKind::ChecksumMismatch),
+        );
+    }
+    Ok(ByteVec::new(Arc::new(bytes)))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn backup(bytes: &[u8]) -> String {
+        render(&ByteVec::new(Arc::new(bytes.to_vec())))
+    }
+
+    #[test]
+    fn test_render_round_trip() {
+        let text = backup(b"Hello, world");
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("PAPER 1 12 "));
+        assert!(lines[1].starts_with("1 JBSW Y3DP FQQH O33S  "));
+        for bytes in [&b""[..], &[0; 10], &(0..=255).collect::<Vec<u8>>()] {
+            let text = backup(bytes);
+            assert!(
+                matches!(parse(&text), Ok(b) if b.get_bytes() == bytes),
+                "{text}"
+            );
+        }
+        let Ok(config) = PaperConfig::new(4, 5) else {
+            return;
+        };
+        let text = render_with(&ByteVec::new(Arc::new(vec![7; 9])), config);
+        assert_eq!(text.lines().count(), 4);
+        assert!(matches!(parse(&text), Ok(b) if b.get_bytes() == [7; 9]));
+        assert!(PaperConfig::new(0, 4).is_err());
+    }
+
+    #[test]
+    fn test_forgiving_parse() {
+        let bytes: Vec<u8> = (0..40u8).map(|i| i.wrapping_mul(7)).collect();
+        let text = backup(&bytes);
+        let mut lines: Vec<String> = text.lines().map(str::to_lowercase).collect();
+        lines.swap(1, 2);
+        let retyped = format!("\n  {}\n\n", lines.join("\n").replace('o', "0"));
+        assert!(matches!(parse(&retyped), Ok(b) if b.get_bytes() == bytes));
+    }
+
+    #[test]
+    fn test_reports_bad_lines() {
+        let bytes: Vec<u8> = (0..40).collect();
+        let text = backup(&bytes);
+        let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+        // Swap two characters of line 2 and drop line 4.
+        let line = &mut lines[2];
+        let i = line.find(' ').unwrap_or_default() + 1;
+        let swapped: String = line[i..i + 2].chars().rev().collect();
+        line.replace_range(i..i + 2, &swapped);
+        lines.remove(4);
+        let damaged = lines.join("\n");
+        let results = parse_lines(&damaged);
+        assert!(results[1].1.is_err());
+        assert!(results[0].1.is_ok() && results[2].1.is_ok());
+        assert!(matches!(parse(&damaged), Err(e) if e.to_string().contains("line 2")));
+
+        let short = text.lines().take(4).collect::<Vec<_>>().join("\n");
+        assert!(matches!(parse(&short), Err(e) if e.to_string().contains("header says 40")));
+        assert!(parse("PAPER 2 0 00000000").is_err());
+        assert!(parse("").is_err());
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{EncodedString, Encoding, SerialiseError};
+
+/// What [`decode_prefix`] does with input left after the encoded data.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum TrailingData {
+    /// Fail if anything follows the encoded data, even a line break
+    #[default]
+    Reject,
+    /// Decode the encoded data and report where it ended
+    Ignore,
+}
+
+/// Returns the padding character `encoding` may end with, or an error for the
+/// line-based formats, whose records cannot be told apart from other text by
+/// their characters.
+fn padding(encoding: Encoding) -> Result<Option<u8>, SerialiseError> {
+    match encoding {
+        #[cfg(feature = "base32")]
+        Encoding::Base32 => Ok(Some(b'=')),
+        #[cfg(feature = "base64")]
+        Encoding::Base64 => Ok(Some(b'=')),
+        #[cfg(feature = "base36")]
+        Encoding::Base36 => Ok(None),
+        #[cfg(feature = "base45")]
+        Encoding::Base45 => Ok(None),
+        #[cfg(feature = "base58")]
+        Encoding::Base58 => Ok(None),
+        #[cfg(feature = "base62")]
+        Encoding::Base62 => Ok(None),
+        #[cfg(feature = "hex")]
+        Encoding::Hex => Ok(None),
+        #[cfg(feature = "filename-safe")]
+        Encoding::FilenameSafe => Ok(None),
+        #[cfg(feature = "base94")]
+        Encoding::Base94 => Ok(None),
+        #[cfg(feature = "escaped")]
+        Encoding::Escaped => Err(SerialiseError::new(
+            "escaped text cannot be told apart from the text after it".to_string(),
+        )),
+        #[allow(unreachable_patterns)]
+        _ => Err(SerialiseError::new(format!(
+            "{encoding} is line-based and cannot be decoded as a prefix"
+        ))),
+    }
+}
+
+/// Decodes the encoded data at the start of `input`, which may be followed by
+/// other text, such as a `)` or a line break when scraping encoded blobs out of
+/// logs.
+///
+/// The data is the longest run of alphabet characters at the start of
+/// `input`, in either case for case-insensitive encodings, followed by any
+/// padding. Whitespace ends the data, so wrapped output is not joined.
+///
+/// # Arguments
+/// * `encoding` - The encoding of the data; the line-based formats are not
+///   supported
+/// * `input` - The text starting with encoded data
+/// * `trailing` - Whether text after the data is an error
+///
+/// # Returns
+/// The decoded bytes and the byte offset in `input` where the data ended
+///
+/// # Errors
+/// Returns `SerialiseError` if `input` does not start with encoded data, the
+/// data is not valid, `encoding` is line-based, or text follows the data and
+/// `trailing` is [`TrailingData::Reject`].
+pub fn decode_prefix(
+    encoding: Encoding,
+    input: &str,
+    trailing: TrailingData,
+) -> Result<(Vec<u8>, usize), SerialiseError> {
+    let padding = padding(encoding)?;
+    let alphabet = encoding.get_alphabet().as_bytes();
+    let in_alphabet = |c: &u8| {
+        alphabet.contains(c)
+            || (!encoding.is_case_sensitive()
+                && (alphabet.contains(&c.to_ascii_lowercase())
+                    || alphabet.contains(&c.to_ascii_uppercase())))
+    };
+
+    let bytes = input.as_bytes();
+    let data = bytes.iter().take_while(|c| in_alphabet(c)).count();
+    let end = data
+        + bytes[data..]
+            .iter()
+            .take_while(|&&c| Some(c) == padding)
+            .count();
+    if data == 0 {
+        return Err(SerialiseError::new(format!(
+            "input does not start with {encoding} data"
+        )));
+    }
+    if trailing == TrailingData::Reject && end < input.len() {
+        return Err(SerialiseError::new(format!(
+            "unexpected text after {encoding} data at position {end}"
+        )));
+    }
+
+    let decoded = EncodedString::new(encoding, input[..end].to_string())
+        .try_decode()
+        .map_err(|e| e.with_context(format!("decoding {encoding} data ending at {end}")))?;
+    Ok((decoded.get_bytes().to_vec(), end))
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "base64", feature = "hex", feature = "intel-hex"))]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_decode_prefix_reports_end() {
+        let line = "token=Zm9vYg==) user=bob";
+        let (bytes, end) =
+            decode_prefix(Encoding::Base64, &line[6..], TrailingData::Ignore).unwrap_or_default();
+        assert_eq!(bytes, b"foob");
+        assert_eq!(&line[6 + end..], ") user=bob");
+        assert!(matches!(
+            decode_prefix(Encoding::Hex, "DEADbeef\n", TrailingData::Ignore),
+            Ok((bytes, 8)) if bytes == [0xde, 0xad, 0xbe, 0xef]
+        ));
+    }
+
+    #[test]
+    fn test_reject_trailing() {
+        assert!(matches!(
+            decode_prefix(Encoding::Hex, "deadbeef", TrailingData::Reject),
+            Ok((_, 8))
+        ));
+        assert!(matches!(
+            decode_prefix(Encoding::Hex, "deadbeef\n", TrailingData::Reject),
+            Err(e) if e.get_message() == "unexpected text after hex data at position 8"
+        ));
+    }
+
+    #[test]
+    fn test_invalid_prefix_is_err() {
+        assert!(decode_prefix(Encoding::Hex, ")dead", TrailingData::Ignore).is_err());
+        assert!(decode_prefix(Encoding::Hex, "abc)", TrailingData::Ignore).is_err());
+        assert!(decode_prefix(Encoding::IntelHex, ":00000001FF", TrailingData::Ignore).is_err());
+    }
+}
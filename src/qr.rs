@@ -0,0 +1,321 @@
+use crate::{ByteVec, EncodedString, Encoding, SerialiseError, alphabet};
+
+/// Data codewords per QR code version (1-40) at each error correction level.
+const DATA_CODEWORDS: [[u16; 4]; 40] = [
+    [19, 16, 13, 9],
+    [34, 28, 22, 16],
+    [55, 44, 34, 26],
+    [80, 64, 48, 36],
+    [108, 86, 62, 46],
+    [136, 108, 76, 60],
+    [156, 124, 88, 66],
+    [194, 154, 110, 86],
+    [232, 182, 132, 100],
+    [274, 216, 154, 122],
+    [324, 254, 180, 140],
+    [370, 290, 206, 158],
+    [428, 334, 244, 180],
+    [461, 365, 261, 197],
+    [523, 415, 295, 223],
+    [589, 453, 325, 253],
+    [647, 507, 367, 283],
+    [721, 563, 397, 313],
+    [795, 627, 445, 341],
+    [861, 669, 485, 385],
+    [932, 714, 512, 406],
+    [1006, 782, 568, 442],
+    [1094, 860, 614, 464],
+    [1174, 914, 664, 514],
+    [1276, 1000, 718, 538],
+    [1370, 1062, 754, 596],
+    [1468, 1128, 808, 628],
+    [1531, 1193, 871, 661],
+    [1631, 1267, 911, 701],
+    [1735, 1373, 985, 745],
+    [1843, 1455, 1033, 793],
+    [1955, 1541, 1115, 845],
+    [2071, 1631, 1171, 901],
+    [2191, 1725, 1231, 961],
+    [2306, 1812, 1286, 986],
+    [2434, 1914, 1354, 1054],
+    [2566, 1992, 1426, 1096],
+    [2702, 2102, 1502, 1142],
+    [2812, 2216, 1582, 1222],
+    [2956, 2334, 1666, 1276],
+];
+
+/// QR code data encoding mode.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum QrMode {
+    /// Alphanumeric mode: 0-9, A-Z and ` $%*+-./:` at 5.5 bits per character
+    Alphanumeric,
+    /// Byte mode: any 8-bit data at 8 bits per character
+    Byte,
+}
+
+impl QrMode {
+    /// Returns the number of bits in a single segment of `chars` characters in
+    /// this mode, including the mode and character count headers.
+    const fn segment_bits(self, chars: usize, version: usize) -> usize {
+        let count_bits = match (self, version) {
+            (Self::Alphanumeric, ..=9) => 9,
+            (Self::Alphanumeric, ..=26) => 11,
+            (Self::Alphanumeric, _) => 13,
+            (Self::Byte, ..=9) => 8,
+            (Self::Byte, _) => 16,
+        };
+        let data_bits = match self {
+            Self::Alphanumeric => chars / 2 * 11 + chars % 2 * 6,
+            Self::Byte => chars * 8,
+        };
+        4 + count_bits + data_bits
+    }
+
+    /// Returns the smallest QR code version (1-40) that holds `chars` characters
+    /// in this mode as a single segment.
+    ///
+    /// # Arguments
+    /// * `chars` - The number of characters to store
+    /// * `level` - The error correction level
+    ///
+    /// # Returns
+    /// The version, or `None` if even version 40 is too small
+    #[must_use = "This returns the version but does nothing if unused"]
+    pub fn min_version(self, chars: usize, level: QrErrorCorrection) -> Option<u8> {
+        (1..=40u8)
+            .zip(DATA_CODEWORDS)
+            .find_map(|(version, codewords)| {
+                let capacity = usize::from(codewords[level as usize]) * 8;
+                (self.segment_bits(chars, usize::from(version)) <= capacity).then_some(version)
+            })
+    }
+}
+
+/// Checks that `text` only uses the QR code alphanumeric set, `0-9`, `A-Z`
+/// and ` $%*+-./:`, so it can be stored in alphanumeric mode by QR libraries
+/// that support no other mode.
+///
+/// # Errors
+/// Returns `SerialiseError` naming the first character outside the set, such
+/// as a lowercase letter.
+pub fn validate_alphanumeric(text: &str) -> Result<(), SerialiseError> {
+    match text
+        .char_indices()
+        .find(|&(_, c)| !u8::try_from(c).is_ok_and(|b| alphabet::BASE45.contains(&b)))
+    {
+        None => Ok(()),
+        Some((position, c)) => Err(SerialiseError::new(format!(
+            "'{c}' at position {position} is not in the QR alphanumeric set"
+        ))),
+    }
+}
+
+/// QR code error correction level.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum QrErrorCorrection {
+    /// Recovers about 7% of codewords
+    Low,
+    /// Recovers about 15% of codewords
+    Medium,
+    /// Recovers about 25% of codewords
+    Quartile,
+    /// Recovers about 30% of codewords
+    High,
+}
+
+/// Binary data encoded as text for the smallest QR code.
+///
+/// Base45 text fits alphanumeric mode at 8.25 bits per input byte, while Base64
+/// text needs byte mode at about 10.7 bits per input byte, so Base45 wins for
+/// all but the smallest payloads where the header sizes decide.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QrPayload {
+    /// The encoded text to put in the QR code
+    encoded: EncodedString,
+    /// The QR mode to encode the text with
+    mode: QrMode,
+    /// The smallest QR code version that holds the text
+    version: u8,
+}
+
+impl QrPayload {
+    /// Encodes `byte_vec` as Base45 or Base64, whichever needs the smaller QR code.
+    ///
+    /// When both fit the same version the one with fewer bits is chosen, which
+    /// leaves more room for the error correction to absorb damage.
+    ///
+    /// # Arguments
+    /// * `byte_vec` - The bytes to encode
+    /// * `level` - The error correction level the QR code will use
+    ///
+    /// # Returns
+    /// The encoded text with its QR mode and version
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if encoding fails or the payload does not fit in a
+    /// version 40 QR code with either encoding.
+    pub fn optimize(byte_vec: &ByteVec, level: QrErrorCorrection) -> Result<Self, SerialiseError> {
+        let mut best: Option<(u8, usize, EncodedString, QrMode)> = None;
+        for (encoding, mode) in [
+            (Encoding::Base45, QrMode::Alphanumeric),
+            (Encoding::Base64, QrMode::Byte),
+        ] {
+            let encoded = byte_vec.try_encode(encoding)?;
+            let chars = encoded.get_string().len();
+            let Some(version) = mode.min_version(chars, level) else {
+                continue;
+            };
+            let bits = mode.segment_bits(chars, usize::from(version));
+            if best
+                .as_ref()
+                .is_none_or(|&(v, b, ..)| (version, bits) < (v, b))
+            {
+                best = Some((version, bits, encoded, mode));
+            }
+        }
+
+        best.map(|(version, _, encoded, mode)| Self {
+            encoded,
+            mode,
+            version,
+        })
+        .ok_or_else(|| {
+            SerialiseError::new(format!(
+                "{} bytes do not fit in a QR code at {level:?} error correction",
+                byte_vec.get_bytes().len()
+            ))
+        })
+    }
+
+    /// Encodes `byte_vec` as Base45 for alphanumeric mode, whatever its size,
+    /// for QR libraries that only support that mode.
+    ///
+    /// # Arguments
+    /// * `byte_vec` - The bytes to encode
+    /// * `level` - The error correction level the QR code will use
+    ///
+    /// # Returns
+    /// The Base45 text with its version
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the payload does not fit in a version 40 QR
+    /// code in alphanumeric mode.
+    pub fn alphanumeric(
+        byte_vec: &ByteVec,
+        level: QrErrorCorrection,
+    ) -> Result<Self, SerialiseError> {
+        Self::from_alphanumeric(byte_vec.try_encode(Encoding::Base45)?, level)
+    }
+
+    /// Wraps text from any encoding for alphanumeric mode, such as uppercase
+    /// Base32 without padding or Base36 in uppercase, after checking every
+    /// character is in the QR alphanumeric set.
+    ///
+    /// # Arguments
+    /// * `encoded` - The encoded text
+    /// * `level` - The error correction level the QR code will use
+    ///
+    /// # Returns
+    /// The text with its version
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the text has a character outside the set,
+    /// as [`validate_alphanumeric`] reports, or does not fit in a version 40
+    /// QR code.
+    pub fn from_alphanumeric(
+        encoded: EncodedString,
+        level: QrErrorCorrection,
+    ) -> Result<Self, SerialiseError> {
+        validate_alphanumeric(encoded.get_string())?;
+        let chars = encoded.get_string().len();
+        let version = QrMode::Alphanumeric
+            .min_version(chars, level)
+            .ok_or_else(|| {
+                SerialiseError::new(format!(
+                    "{chars} characters do not fit in a QR code at {level:?} error correction"
+                ))
+            })?;
+        Ok(Self {
+            encoded,
+            mode: QrMode::Alphanumeric,
+            version,
+        })
+    }
+
+    /// Returns the encoded text.
+    #[must_use = "This returns the encoded string but does nothing if unused"]
+    pub const fn get_encoded(&self) -> &EncodedString {
+        &self.encoded
+    }
+
+    /// Returns the QR mode to encode the text with.
+    #[must_use = "This returns the mode but does nothing if unused"]
+    pub const fn get_mode(&self) -> QrMode {
+        self.mode
+    }
+
+    /// Returns the smallest QR code version that holds the text.
+    #[must_use = "This returns the version but does nothing if unused"]
+    pub const fn get_version(&self) -> u8 {
+        self.version
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn test_min_version_matches_capacity_tables() {
+        let level = QrErrorCorrection::Low;
+        assert_eq!(QrMode::Alphanumeric.min_version(25, level), Some(1));
+        assert_eq!(QrMode::Alphanumeric.min_version(26, level), Some(2));
+        assert_eq!(QrMode::Byte.min_version(2953, level), Some(40));
+        assert_eq!(QrMode::Byte.min_version(2954, level), None);
+        assert_eq!(
+            QrMode::Byte.min_version(1273, QrErrorCorrection::High),
+            Some(40)
+        );
+    }
+
+    #[test]
+    fn test_optimize_prefers_base45() {
+        let bytes = ByteVec::new(Arc::new(vec![0xa5; 100]));
+        let payload = QrPayload::optimize(&bytes, QrErrorCorrection::Medium);
+        assert!(matches!(
+            &payload,
+            Ok(payload) if payload.get_mode() == QrMode::Alphanumeric
+                && payload.get_encoded().get_encoding() == Encoding::Base45
+                && payload.get_version() == 6
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "base32")]
+    fn test_alphanumeric() {
+        let bytes = ByteVec::new(Arc::new(vec![0xa5; 2]));
+        assert!(matches!(
+            QrPayload::alphanumeric(&bytes, QrErrorCorrection::Low),
+            Ok(p) if p.get_mode() == QrMode::Alphanumeric && p.get_version() == 1
+        ));
+        let upper = EncodedString::new(Encoding::Base32, "MFRGG".to_string());
+        assert!(QrPayload::from_alphanumeric(upper, QrErrorCorrection::Low).is_ok());
+        for bad in ["mfrgg", "MFRGG===", "ABC\n", "\u{c9}"] {
+            assert!(validate_alphanumeric(bad).is_err(), "{bad}");
+            let encoded = EncodedString::new(Encoding::Base32, bad.to_string());
+            assert!(QrPayload::from_alphanumeric(encoded, QrErrorCorrection::Low).is_err());
+        }
+        assert!(validate_alphanumeric("HTTPS://EXAMPLE.COM/A B$%*+-").is_ok());
+        let large = ByteVec::new(Arc::new(vec![0; 3000]));
+        assert!(QrPayload::alphanumeric(&large, QrErrorCorrection::Low).is_err());
+    }
+
+    #[test]
+    fn test_optimize_too_large_is_err() {
+        let bytes = ByteVec::new(Arc::new(vec![0; 4000]));
+        assert!(QrPayload::optimize(&bytes, QrErrorCorrection::Low).is_err());
+    }
+}
@@ -0,0 +1,144 @@
+use crate::{SerialiseError, alphabet, serialise_error::check_ascii};
+
+/// Smallest supported radix.
+pub const MIN_RADIX: u32 = 2;
+
+/// Largest supported radix.
+pub const MAX_RADIX: u32 = 62;
+
+fn check_radix(radix: u32) -> Result<(), SerialiseError> {
+    if (MIN_RADIX..=MAX_RADIX).contains(&radix) {
+        Ok(())
+    } else {
+        Err(SerialiseError::new(format!(
+            "radix {radix} is outside the supported range {MIN_RADIX}-{MAX_RADIX}"
+        )))
+    }
+}
+
+/// Returns the value of digit `c` in `radix`, if it is a valid digit.
+const fn digit_value(c: u8, radix: u32) -> Option<u32> {
+    let value = match c {
+        b'0'..=b'9' => c - b'0',
+        b'a'..=b'z' if radix <= 36 => c - b'a' + 10,
+        b'A'..=b'Z' if radix <= 36 => c - b'A' + 10,
+        b'A'..=b'Z' => c - b'A' + 10,
+        b'a'..=b'z' => c - b'a' + 36,
+        _ => return None,
+    };
+    if (value as u32) < radix {
+        Some(value as u32)
+    } else {
+        None
+    }
+}
+
+/// Converts a numeric string from one radix to another.
+///
+/// Radixes up to 36 use the digits 0-9 then a-z, are case-insensitive on input
+/// and are written in lowercase. Radixes 37 to 62 use 0-9, A-Z then a-z and are
+/// case-sensitive. The result has no leading zeros, so zero is written as `"0"`.
+/// Values of any size are supported.
+///
+/// # Arguments
+/// * `input` - The number to convert, without sign or prefix
+/// * `from_radix` - The radix `input` is written in
+/// * `to_radix` - The radix to convert to
+///
+/// # Returns
+/// The number written in `to_radix`
+///
+/// # Errors
+/// Returns `SerialiseError` if either radix is outside 2-62, `input` is empty,
+/// or `input` contains a character that is not a digit in `from_radix`. A
+/// non-ASCII character, including a digit from another script, is reported as
+/// [`ErrorKind::NonAsciiInput`](crate::ErrorKind::NonAsciiInput).
+pub fn convert(input: &str, from_radix: u32, to_radix: u32) -> Result<String, SerialiseError> {
+    check_radix(from_radix)?;
+    check_radix(to_radix)?;
+
+    let s = input.trim();
+    check_ascii(s, &format!("radix {from_radix}"))?;
+    if s.is_empty() {
+        return Err(SerialiseError::new(
+            "cannot convert an empty number".to_string(),
+        ));
+    }
+
+    // Little-endian digits in `to_radix`.
+    let mut digits: Vec<u32> = vec![];
+    for c in s.bytes() {
+        let Some(value) = digit_value(c, from_radix) else {
+            return Err(SerialiseError::new(format!(
+                "invalid radix {from_radix} digit '{}'",
+                char::from(c)
+            )));
+        };
+
+        let mut carry = value;
+        for d in &mut digits {
+            let v = *d * from_radix + carry;
+            *d = v % to_radix;
+            carry = v / to_radix;
+        }
+        while carry > 0 {
+            digits.push(carry % to_radix);
+            carry /= to_radix;
+        }
+    }
+
+    if digits.is_empty() {
+        return Ok("0".to_string());
+    }
+
+    let symbols: &[u8] = if to_radix <= 36 {
+        alphabet::BASE36
+    } else {
+        alphabet::BASE62
+    };
+    Ok(digits
+        .iter()
+        .rev()
+        .map(|&d| char::from(symbols[d as usize]))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_convert() {
+        assert!(matches!(convert("deadbeef", 16, 36), Ok(s) if s == "1ps9wxb"));
+        assert!(matches!(convert("DEADBEEF", 16, 10), Ok(s) if s == "3735928559"));
+        assert!(matches!(convert("3735928559", 10, 62), Ok(s) if s == "44pZgF"));
+        assert!(matches!(convert("0000", 2, 62), Ok(s) if s == "0"));
+    }
+
+    #[test]
+    fn test_convert_large_values() {
+        let value = "f".repeat(64);
+        let decimal = convert(&value, 16, 10).unwrap_or_default();
+        assert_eq!(
+            decimal,
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+        );
+        assert!(matches!(convert(&decimal, 10, 16), Ok(s) if s == value));
+    }
+
+    #[test]
+    fn test_convert_invalid_is_err() {
+        assert!(convert("12", 1, 10).is_err());
+        assert!(convert("12", 10, 63).is_err());
+        assert!(convert("", 10, 16).is_err());
+        assert!(convert("19", 8, 10).is_err());
+        assert!(matches!(
+            convert("1\u{0662}", 10, 16),
+            Err(e) if e.get_kind() == crate::ErrorKind::NonAsciiInput { position: 1 }
+        ));
+        assert!(
+            matches!(convert(&crate::normalize_digits("1\u{0662}"), 10, 16), Ok(s) if s == "c")
+        );
+    }
+}
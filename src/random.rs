@@ -0,0 +1,155 @@
+use crate::SerialiseError;
+
+/// Returns the number of bits of entropy in a uniformly random string.
+///
+/// # Arguments
+/// * `alphabet_len` - The number of characters in the alphabet
+/// * `len` - The length of the string
+///
+/// # Returns
+/// `len * log2(alphabet_len)`, e.g. about 95.3 bits for 16 Base62 characters
+#[must_use = "This returns the entropy but does nothing if unused"]
+#[allow(clippy::cast_precision_loss)]
+pub fn entropy_bits(alphabet_len: usize, len: usize) -> f64 {
+    if alphabet_len == 0 {
+        return 0.0;
+    }
+    len as f64 * (alphabet_len as f64).log2()
+}
+
+/// Returns the shortest length of uniformly random string that has at least
+/// `bits` bits of entropy.
+///
+/// # Arguments
+/// * `alphabet_len` - The number of characters in the alphabet, at least 2
+/// * `bits` - The entropy needed, e.g. 128 for a session token
+///
+/// # Returns
+/// The length, e.g. 22 for 128 bits of Base62
+#[must_use = "This returns the length but does nothing if unused"]
+pub fn len_for_bits(alphabet_len: usize, bits: u32) -> usize {
+    (0..=usize::try_from(bits).unwrap_or(usize::MAX))
+        .find(|&len| entropy_bits(alphabet_len.max(2), len) >= f64::from(bits))
+        .unwrap_or(usize::MAX)
+}
+
+/// Generates a string whose characters are drawn uniformly from `alphabet`.
+///
+/// Encoding random bytes in Base62 or Base58, or taking a byte modulo the
+/// alphabet length, makes some characters more likely than others. This draws
+/// each character by rejection sampling instead: random bytes are masked to
+/// the smallest power of two that covers the alphabet and values past its end
+/// are thrown away, so every character is equally likely. At most half of the
+/// draws are thrown away.
+///
+/// The crate has no random number generator of its own; `fill` supplies the
+/// random bytes, e.g. from a closure calling `getrandom::fill`, or from a
+/// seeded generator in tests.
+///
+/// # Arguments
+/// * `alphabet` - The characters to draw from, 1 to 256 distinct ASCII characters
+/// * `len` - The number of characters to generate
+/// * `fill` - Fills a buffer with random bytes
+///
+/// # Returns
+/// The random string
+///
+/// # Errors
+/// Returns `SerialiseError` if `alphabet` is empty, longer than 256
+/// characters, not ASCII or repeats a character.
+pub fn random_string(
+    alphabet: &[u8],
+    len: usize,
+    mut fill: impl FnMut(&mut [u8]),
+) -> Result<String, SerialiseError> {
+    if alphabet.is_empty() || alphabet.len() > 256 {
+        return Err(SerialiseError::new(format!(
+            "alphabet must have 1 to 256 characters, not {}",
+            alphabet.len()
+        )));
+    }
+    if !alphabet.is_ascii() {
+        return Err(SerialiseError::new(
+            "alphabet must only contain ASCII characters".to_string(),
+        ));
+    }
+    let mut seen = [false; 128];
+    for &c in alphabet {
+        if std::mem::replace(&mut seen[usize::from(c)], true) {
+            return Err(SerialiseError::new(format!(
+                "alphabet repeats the character '{}'",
+                char::from(c)
+            )));
+        }
+    }
+
+    let mask = alphabet.len().next_power_of_two() - 1;
+    let mut out = String::with_capacity(len);
+    let mut buf = [0u8; 64];
+    while out.len() < len {
+        fill(&mut buf);
+        out.extend(
+            buf.iter()
+                .map(|&b| usize::from(b) & mask)
+                .filter(|&i| i < alphabet.len())
+                .map(|i| char::from(alphabet[i]))
+                .take(len - out.len()),
+        );
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::alphabet;
+
+    /// A small xorshift generator so the tests are repeatable.
+    fn xorshift(seed: u64) -> impl FnMut(&mut [u8]) {
+        let mut state = seed;
+        move |buf| {
+            for b in buf {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                *b = state.to_le_bytes()[0];
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_string_is_unbiased() {
+        let text = random_string(alphabet::BASE62, 62_000, xorshift(1)).unwrap_or_default();
+        assert_eq!(text.len(), 62_000);
+        let mut counts = [0usize; 128];
+        for c in text.bytes() {
+            counts[usize::from(c)] += 1;
+        }
+        for &c in alphabet::BASE62 {
+            let count = counts[usize::from(c)];
+            assert!(
+                (850..1150).contains(&count),
+                "{} drawn {count} times",
+                char::from(c)
+            );
+        }
+    }
+
+    #[test]
+    fn test_entropy() {
+        assert!((entropy_bits(62, 16) - 95.27).abs() < 0.01);
+        assert!((entropy_bits(16, 32) - 128.0).abs() < f64::EPSILON);
+        assert_eq!(len_for_bits(62, 128), 22);
+        assert_eq!(len_for_bits(16, 128), 32);
+        assert_eq!(len_for_bits(58, 0), 0);
+    }
+
+    #[test]
+    fn test_invalid_alphabet_is_err() {
+        assert!(random_string(b"", 4, xorshift(2)).is_err());
+        assert!(random_string(b"abca", 4, xorshift(2)).is_err());
+        assert!(random_string("ab\u{e9}".as_bytes(), 4, xorshift(2)).is_err());
+        assert!(matches!(random_string(b"x", 3, xorshift(2)), Ok(s) if s == "xxx"));
+    }
+}
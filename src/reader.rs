@@ -0,0 +1,183 @@
+use crate::SerialiseError;
+
+/// Cursor for reading integers and byte runs out of decoded bytes without
+/// copying them into another buffer.
+///
+/// Created by [`ByteVec::reader`](crate::ByteVec::reader), or with
+/// [`ByteReader::new`] over any slice. A failed read leaves the position where
+/// it was.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ByteReader<'a> {
+    /// The bytes being read
+    bytes: &'a [u8],
+    /// Offset of the next byte to read
+    position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    /// Creates a reader positioned at the start of `bytes`.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes to read
+    #[must_use = "This creates a reader but does nothing if unused"]
+    pub const fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    /// Returns the offset of the next byte to read.
+    #[must_use = "This returns the position but does nothing if unused"]
+    pub const fn get_position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the bytes not yet read.
+    #[must_use = "This returns the remaining bytes but does nothing if unused"]
+    pub fn get_remaining(&self) -> &'a [u8] {
+        &self.bytes[self.position..]
+    }
+
+    /// Returns true if every byte has been read.
+    #[must_use = "This returns whether the reader is empty but does nothing if unused"]
+    pub const fn is_empty(&self) -> bool {
+        self.position >= self.bytes.len()
+    }
+
+    /// Reads the next `len` bytes.
+    ///
+    /// # Arguments
+    /// * `len` - The number of bytes to read
+    ///
+    /// # Returns
+    /// The bytes, borrowed from the underlying slice
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if fewer than `len` bytes remain.
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], SerialiseError> {
+        let remaining = self.get_remaining();
+        if remaining.len() < len {
+            return Err(SerialiseError::new(format!(
+                "unexpected end of data: needed {len} bytes at position {}, {} remaining",
+                self.position,
+                remaining.len()
+            )));
+        }
+        self.position += len;
+        Ok(&remaining[..len])
+    }
+
+    /// Reads the next `N` bytes as an array.
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if fewer than `N` bytes remain.
+    pub fn read_array<const N: usize>(&mut self) -> Result<[u8; N], SerialiseError> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.read_bytes(N)?);
+        Ok(array)
+    }
+
+    /// Skips the next `len` bytes.
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if fewer than `len` bytes remain.
+    pub fn skip(&mut self, len: usize) -> Result<(), SerialiseError> {
+        self.read_bytes(len).map(|_| ())
+    }
+
+    /// Reads one byte.
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if no bytes remain.
+    pub fn read_u8(&mut self) -> Result<u8, SerialiseError> {
+        self.read_array().map(u8::from_be_bytes)
+    }
+
+    /// Reads a big-endian `u16`.
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if fewer than 2 bytes remain.
+    pub fn read_u16_be(&mut self) -> Result<u16, SerialiseError> {
+        self.read_array().map(u16::from_be_bytes)
+    }
+
+    /// Reads a little-endian `u16`.
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if fewer than 2 bytes remain.
+    pub fn read_u16_le(&mut self) -> Result<u16, SerialiseError> {
+        self.read_array().map(u16::from_le_bytes)
+    }
+
+    /// Reads a big-endian `u32`.
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if fewer than 4 bytes remain.
+    pub fn read_u32_be(&mut self) -> Result<u32, SerialiseError> {
+        self.read_array().map(u32::from_be_bytes)
+    }
+
+    /// Reads a little-endian `u32`.
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if fewer than 4 bytes remain.
+    pub fn read_u32_le(&mut self) -> Result<u32, SerialiseError> {
+        self.read_array().map(u32::from_le_bytes)
+    }
+
+    /// Reads a big-endian `u64`.
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if fewer than 8 bytes remain.
+    pub fn read_u64_be(&mut self) -> Result<u64, SerialiseError> {
+        self.read_array().map(u64::from_be_bytes)
+    }
+
+    /// Reads a little-endian `u64`.
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if fewer than 8 bytes remain.
+    pub fn read_u64_le(&mut self) -> Result<u64, SerialiseError> {
+        self.read_array().map(u64::from_le_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_read_integers() {
+        let bytes = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09];
+        let mut reader = ByteReader::new(&bytes);
+        assert!(matches!(reader.read_u8(), Ok(0x01)));
+        assert!(matches!(reader.read_u16_be(), Ok(0x0203)));
+        assert!(matches!(reader.read_u16_le(), Ok(0x0504)));
+        assert!(matches!(reader.read_u32_le(), Ok(0x0908_0706)));
+        assert!(reader.is_empty());
+
+        let mut reader = ByteReader::new(&bytes[1..]);
+        assert!(matches!(reader.read_u64_be(), Ok(0x0203_0405_0607_0809)));
+    }
+
+    #[test]
+    fn test_read_bytes_and_remaining() {
+        let bytes = b"\x00\x03abcrest";
+        let mut reader = ByteReader::new(bytes);
+        let len = reader.read_u16_be().unwrap_or_default();
+        assert!(matches!(reader.read_bytes(len.into()), Ok(b"abc")));
+        assert_eq!(reader.get_position(), 5);
+        assert_eq!(reader.get_remaining(), b"rest");
+    }
+
+    #[test]
+    fn test_short_read_keeps_position() {
+        let mut reader = ByteReader::new(&[0xaa, 0xbb, 0xcc]);
+        assert!(reader.skip(1).is_ok());
+        assert!(matches!(
+            reader.read_u32_be(),
+            Err(e) if e.get_message() == "unexpected end of data: needed 4 bytes at position 1, 2 remaining"
+        ));
+        assert_eq!(reader.get_position(), 1);
+        assert!(matches!(reader.read_u16_le(), Ok(0xccbb)));
+    }
+}
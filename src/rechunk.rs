@@ -0,0 +1,227 @@
+use crate::{EncodedString, Encoding, LineEnding, SerialiseError};
+
+/// Returns true if each character of `encoding` stands for a fixed number of
+/// bits and decoders skip line breaks, so lines can be re-broken anywhere
+/// without decoding.
+const fn rewraps_as_text(encoding: Encoding) -> bool {
+    match encoding {
+        #[cfg(feature = "base32")]
+        Encoding::Base32 => true,
+        #[cfg(feature = "base64")]
+        Encoding::Base64 => true,
+        #[cfg(feature = "hex")]
+        Encoding::Hex => true,
+        #[allow(unreachable_patterns)]
+        _ => false,
+    }
+}
+
+/// Push-style re-wrapper for Base32, Base64 and hex text.
+///
+/// Input can be fed in arbitrary pieces wrapped at any width, with LF or CRLF
+/// line endings, and each call returns the output that is ready so far. The
+/// characters are checked against the alphabet but never decoded.
+#[derive(Debug)]
+pub struct Rechunker {
+    /// The encoding of the text
+    encoding: Encoding,
+    /// Output line length, or `None` for a single line
+    width: Option<usize>,
+    /// Line ending written between output lines
+    line_ending: LineEnding,
+    /// Characters of the current output line
+    line: String,
+    /// Whether a line has been written since the last reset
+    started: bool,
+    /// Number of input characters consumed since the last reset
+    position: usize,
+    out: String,
+}
+
+impl Rechunker {
+    /// Creates a re-wrapper.
+    ///
+    /// # Arguments
+    /// * `encoding` - The encoding of the text
+    /// * `width` - The output line length, or zero for a single line
+    /// * `line_ending` - The line ending written between output lines
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `encoding` is not Base32, Base64 or hex.
+    pub fn new(
+        encoding: Encoding,
+        width: usize,
+        line_ending: LineEnding,
+    ) -> Result<Self, SerialiseError> {
+        if !rewraps_as_text(encoding) {
+            return Err(SerialiseError::new(format!(
+                "{encoding} cannot be re-wrapped without decoding"
+            )));
+        }
+        Ok(Self {
+            encoding,
+            width: if width == 0 { None } else { Some(width) },
+            line_ending,
+            line: String::new(),
+            started: false,
+            position: 0,
+            out: String::new(),
+        })
+    }
+
+    fn is_digit(&self, c: char) -> bool {
+        let alphabet = self.encoding.get_alphabet();
+        c == '='
+            || alphabet.contains(c)
+            || (!self.encoding.is_case_sensitive()
+                && (alphabet.contains(c.to_ascii_lowercase())
+                    || alphabet.contains(c.to_ascii_uppercase())))
+    }
+
+    fn emit_line(&mut self) {
+        if self.started {
+            self.out.push_str(self.line_ending.as_str());
+        }
+        self.out.push_str(&self.line);
+        self.line.clear();
+        self.started = true;
+    }
+
+    /// Feeds the next piece of encoded text.
+    ///
+    /// # Arguments
+    /// * `input` - The next piece of encoded text
+    ///
+    /// # Returns
+    /// The re-wrapped text from this piece, valid until the next call
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the input has a character outside the
+    /// alphabet other than whitespace or `=` padding
+    pub fn feed(&mut self, input: &str) -> Result<&str, SerialiseError> {
+        self.out.clear();
+        for c in input.chars() {
+            let position = self.position;
+            self.position += 1;
+            if c.is_ascii_whitespace() {
+                continue;
+            }
+            if !self.is_digit(c) {
+                return Err(SerialiseError::new(format!(
+                    "invalid {} character '{c}' at position {position}",
+                    self.encoding
+                )));
+            }
+            self.line.push(c);
+            if self.width == Some(self.line.len()) {
+                self.emit_line();
+            }
+        }
+        Ok(&self.out)
+    }
+
+    /// Signals the end of the input and resets the re-wrapper for reuse.
+    ///
+    /// # Returns
+    /// The last, possibly short, line
+    pub fn finish(&mut self) -> &str {
+        self.out.clear();
+        if !self.line.is_empty() {
+            self.emit_line();
+        }
+        self.started = false;
+        self.position = 0;
+        &self.out
+    }
+}
+
+/// Re-wraps encoded text to a new line length, e.g. from 64-character PEM
+/// lines to 76-character MIME lines.
+///
+/// Base32, Base64 and hex are re-wrapped as text, since line breaks carry no
+/// data; other encodings are decoded and encoded again with
+/// [`EncodeBuilder`](crate::encode_builder::EncodeBuilder), so they fail if the
+/// encoding cannot be wrapped.
+///
+/// # Arguments
+/// * `encoding` - The encoding of `input`
+/// * `input` - The encoded text, wrapped at any width
+/// * `width` - The output line length, or zero for a single line
+/// * `line_ending` - The line ending written between output lines
+///
+/// # Returns
+/// The text broken into lines of at most `width` characters, with no line
+/// ending after the last line
+///
+/// # Errors
+/// Returns `SerialiseError` if `input` is not valid for `encoding`, or
+/// `encoding` cannot be wrapped.
+pub fn rechunk(
+    encoding: Encoding,
+    input: &str,
+    width: usize,
+    line_ending: LineEnding,
+) -> Result<EncodedString, SerialiseError> {
+    if rewraps_as_text(encoding) {
+        let mut rechunker = Rechunker::new(encoding, width, line_ending)?;
+        let mut text = rechunker.feed(input)?.to_string();
+        text.push_str(rechunker.finish());
+        return Ok(EncodedString::new(encoding, text));
+    }
+    EncodedString::new(encoding, input.to_string())
+        .try_decode()?
+        .encode_with(encoding)
+        .wrap(width)
+        .line_ending(line_ending)
+        .finish()
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "base64", feature = "hex", feature = "uuencode"))]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_rechunk_base64() {
+        let pem = "QUJDREVG\r\nR0hJSktM\r\nTU5PUA==";
+        assert!(matches!(
+            rechunk(Encoding::Base64, pem, 6, LineEnding::Lf),
+            Ok(encoded) if encoded.get_string() == "QUJDRE\nVGR0hJ\nSktMTU\n5PUA=="
+        ));
+        assert!(matches!(
+            rechunk(Encoding::Hex, "de\nad\nbe\nef", 0, LineEnding::Lf),
+            Ok(encoded) if encoded.get_string() == "deadbeef"
+        ));
+    }
+
+    #[test]
+    fn test_rechunker_pieces() {
+        let rechunker = Rechunker::new(Encoding::Hex, 4, LineEnding::CrLf);
+        assert!(rechunker.is_ok());
+        let Ok(mut rechunker) = rechunker else {
+            return;
+        };
+        let mut out = String::new();
+        for piece in ["dea", "db\nee", "f0", "1"] {
+            out.push_str(rechunker.feed(piece).unwrap_or_default());
+        }
+        out.push_str(rechunker.finish());
+        assert_eq!(out, "dead\r\nbeef\r\n01");
+        assert!(matches!(
+            rechunker.feed("ab-c"),
+            Err(e) if e.get_message() == "invalid hex character '-' at position 2"
+        ));
+    }
+
+    #[test]
+    fn test_rechunk_falls_back_to_transcode() {
+        assert!(Rechunker::new(Encoding::Uuencode, 60, LineEnding::Lf).is_err());
+        let uu = crate::Uuencode::to_uuencode(b"hello");
+        assert!(rechunk(Encoding::Uuencode, &uu, 60, LineEnding::Lf).is_err());
+        assert!(matches!(
+            rechunk(Encoding::Uuencode, &uu, 0, LineEnding::Lf),
+            Ok(encoded) if encoded.get_string() == &uu
+        ));
+    }
+}
@@ -0,0 +1,496 @@
+use crate::{Base58, CheckScheme, ErrorKind, SerialiseError, alphabet, check::digit_values};
+
+const ALPHABET: &[u8; 58] = alphabet::BASE58_BITCOIN;
+const DIGITS: [Option<u8>; 256] = alphabet::reverse_lookup(ALPHABET);
+
+/// The order of the field the code works in, the smallest prime above 58.
+const P: u32 = 59;
+
+/// A primitive element of GF(59), whose powers give every nonzero element.
+const GENERATOR: u32 = 2;
+
+/// Longest code, check characters included, that fits in one Reed–Solomon
+/// block: there are 58 nonzero elements to locate characters with.
+const MAX_CODE_LEN: usize = 58;
+
+const fn add(a: u32, b: u32) -> u32 {
+    (a + b) % P
+}
+
+const fn sub(a: u32, b: u32) -> u32 {
+    (a + P - b) % P
+}
+
+const fn mul(a: u32, b: u32) -> u32 {
+    a * b % P
+}
+
+const fn pow(mut base: u32, mut exp: usize) -> u32 {
+    let mut result = 1;
+    base %= P;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul(result, base);
+        }
+        base = mul(base, base);
+        exp >>= 1;
+    }
+    result
+}
+
+/// The multiplicative inverse of a nonzero element, by Fermat's little theorem.
+const fn inv(a: u32) -> u32 {
+    pow(a, P as usize - 2)
+}
+
+/// Evaluates a polynomial with coefficients lowest degree first.
+fn eval(poly: &[u32], x: u32) -> u32 {
+    poly.iter().rev().fold(0, |acc, &c| add(mul(acc, x), c))
+}
+
+/// A character changed by [`ReedSolomon58::correct`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Correction {
+    /// Character offset of the change in the code, check characters included
+    position: usize,
+    /// The character that was there
+    found: char,
+    /// The character it was replaced with
+    corrected: char,
+}
+
+impl Correction {
+    /// Returns the character offset of the change in the code.
+    #[must_use = "This returns the position but does nothing if unused"]
+    pub const fn get_position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the character that was there.
+    #[must_use = "This returns the character but does nothing if unused"]
+    pub const fn get_found(&self) -> char {
+        self.found
+    }
+
+    /// Returns the character it was replaced with.
+    #[must_use = "This returns the character but does nothing if unused"]
+    pub const fn get_corrected(&self) -> char {
+        self.corrected
+    }
+}
+
+/// A Reed–Solomon code over the Base58 alphabet, appending check characters
+/// that let [`correct`](Self::correct) repair mistyped characters instead of
+/// only detecting them.
+///
+/// The code works in GF(59), the smallest field with at least 58 elements.
+/// Correcting `errors` characters takes `2 * errors` parity characters, and one
+/// more character is chosen so that every parity value is one of the 58
+/// alphabet characters rather than the field's 59th element. Characters outside
+/// the alphabet, such as `0` or `l`, count as errors. Dropped or inserted
+/// characters shift everything after them and are not corrected.
+///
+/// With more wrong characters than the code corrects, `correct` often changes
+/// the code into a different valid one rather than failing, so keep the
+/// payload's own checksum, as in Bitcoin addresses, to catch that.
+/// [`strip`](CheckScheme::strip) only verifies, and catches up to twice as many
+/// wrong characters.
+///
+/// A code, check characters included, holds at most 58 characters, which
+/// covers addresses and 32-byte keys.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ReedSolomon58 {
+    /// The number of wrong characters that can be corrected
+    errors: usize,
+}
+
+impl ReedSolomon58 {
+    /// Corrects one wrong character, with three check characters.
+    pub const ONE: Self = Self { errors: 1 };
+
+    /// Corrects up to two wrong characters, with five check characters.
+    pub const TWO: Self = Self { errors: 2 };
+
+    /// Creates a code that corrects up to `errors` wrong characters.
+    ///
+    /// # Arguments
+    /// * `errors` - The number of wrong characters to correct
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `errors` is zero, or so large that no data
+    /// would fit beside the check characters.
+    pub fn new(errors: usize) -> Result<Self, SerialiseError> {
+        if errors == 0 || 2 * errors + 2 > MAX_CODE_LEN {
+            return Err(SerialiseError::new(format!(
+                "reed-solomon base58 can correct 1 to {} errors, not {errors}",
+                (MAX_CODE_LEN - 2) / 2
+            )));
+        }
+        Ok(Self { errors })
+    }
+
+    /// Returns the number of wrong characters the code corrects.
+    #[must_use = "This returns the error count but does nothing if unused"]
+    pub const fn get_errors(&self) -> usize {
+        self.errors
+    }
+
+    /// Encodes `bytes` as Base58 and appends the check characters.
+    ///
+    /// As in Bitcoin addresses, each leading zero byte is written as one `1`,
+    /// so [`decode`](Self::decode) returns exactly `bytes`, empty or not.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes to encode
+    ///
+    /// # Returns
+    /// The Base58 text followed by its check characters
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the code would be longer than 58 characters.
+    pub fn encode(&self, bytes: &[u8]) -> Result<String, SerialiseError> {
+        let zeros = bytes.iter().take_while(|&&b| b == 0).count();
+        let mut text = "1".repeat(zeros);
+        if zeros < bytes.len() {
+            text.push_str(&Base58::to_base58(&bytes[zeros..]));
+        }
+        self.append(&text)
+    }
+
+    /// Corrects `code` and decodes the Base58 text before its check characters.
+    ///
+    /// Each leading `1` decodes to one zero byte, as written by
+    /// [`encode`](Self::encode).
+    ///
+    /// # Arguments
+    /// * `code` - Base58 text followed by its check characters
+    ///
+    /// # Returns
+    /// The decoded bytes and the corrections made
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` for anything [`correct`](Self::correct) rejects.
+    pub fn decode(&self, code: &str) -> Result<(Vec<u8>, Vec<Correction>), SerialiseError> {
+        let (digits, corrections) = self.correct(code)?;
+        let number = digits.trim_start_matches('1');
+        let mut bytes = vec![0; digits.len() - number.len()];
+        if !number.is_empty() {
+            bytes.extend(Base58::base58_to_bytes(number)?);
+        }
+        Ok((bytes, corrections))
+    }
+
+    /// Repairs up to [`get_errors`](Self::get_errors) wrong characters in
+    /// `code` and removes the check characters.
+    ///
+    /// # Arguments
+    /// * `code` - Base58 text followed by its check characters
+    ///
+    /// # Returns
+    /// The corrected text without check characters, and each change made in
+    /// order of position
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` with [`ErrorKind::ChecksumMismatch`] if more
+    /// characters are wrong than the code corrects, or if `code` is shorter
+    /// than its check characters or longer than 58 characters.
+    pub fn correct(&self, code: &str) -> Result<(String, Vec<Correction>), SerialiseError> {
+        let found: Vec<char> = code.chars().collect();
+        let n = found.len();
+        if n < self.get_check_len() || n > MAX_CODE_LEN {
+            return Err(SerialiseError::new(format!(
+                "reed-solomon base58 code of {n} characters is not between {} and {MAX_CODE_LEN}",
+                self.get_check_len()
+            )));
+        }
+        let mut values: Vec<u32> = found
+            .iter()
+            .map(|&c| {
+                u8::try_from(c)
+                    .ok()
+                    .and_then(|b| DIGITS[usize::from(b)])
+                    .map_or(0, u32::from)
+            })
+            .collect();
+        self.repair(&mut values)?;
+        let mut corrections = Vec::new();
+        let mut text = String::with_capacity(n);
+        for (position, (&value, &found)) in values.iter().zip(&found).enumerate() {
+            let corrected = char::from(ALPHABET[value as usize]);
+            if corrected != found {
+                corrections.push(Correction {
+                    position,
+                    found,
+                    corrected,
+                });
+            }
+            text.push(corrected);
+        }
+        text.truncate(n - self.get_check_len());
+        Ok((text, corrections))
+    }
+
+    /// Fixes the wrong values of a received code in place, by syndrome
+    /// decoding with Berlekamp–Massey, a Chien search and Forney's formula.
+    fn repair(self, values: &mut [u32]) -> Result<(), SerialiseError> {
+        let syndromes = self.syndromes(values);
+        if syndromes.iter().all(|&s| s == 0) {
+            return Ok(());
+        }
+        let locator = berlekamp_massey(&syndromes);
+        let degree = locator.len() - 1;
+        let uncorrectable = || {
+            SerialiseError::new(format!(
+                "reed-solomon base58 code has more than {} wrong characters",
+                self.errors
+            ))
+            .with_kind(ErrorKind::ChecksumMismatch)
+        };
+        if degree > self.errors {
+            return Err(uncorrectable());
+        }
+        // Omega(x) = S(x) * Lambda(x) mod x^(2e), the error evaluator.
+        let evaluator: Vec<u32> = (0..syndromes.len())
+            .map(|k| {
+                (0..=k.min(degree)).fold(0, |acc, i| add(acc, mul(locator[i], syndromes[k - i])))
+            })
+            .collect();
+        let derivative: Vec<u32> = locator
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(i, &c)| mul(c, u32::try_from(i % P as usize).unwrap_or_default()))
+            .collect();
+        let n = values.len();
+        let mut fixed = 0;
+        for (index, value) in values.iter_mut().enumerate() {
+            let x_inv = inv(pow(GENERATOR, n - 1 - index));
+            if eval(&locator, x_inv) != 0 {
+                continue;
+            }
+            let denominator = eval(&derivative, x_inv);
+            if denominator == 0 {
+                return Err(uncorrectable());
+            }
+            let error = sub(0, mul(eval(&evaluator, x_inv), inv(denominator)));
+            *value = sub(*value, error);
+            if *value >= P - 1 {
+                return Err(uncorrectable());
+            }
+            fixed += 1;
+        }
+        if fixed != degree || self.syndromes(values).iter().any(|&s| s != 0) {
+            return Err(uncorrectable());
+        }
+        Ok(())
+    }
+
+    /// Evaluates the received code at the roots of the generator polynomial,
+    /// `GENERATOR^1` to `GENERATOR^(2e)`, all zero for a valid code.
+    fn syndromes(self, values: &[u32]) -> Vec<u32> {
+        (1..=2 * self.errors)
+            .map(|j| {
+                let x = pow(GENERATOR, j);
+                values.iter().fold(0, |acc, &v| add(mul(acc, x), v))
+            })
+            .collect()
+    }
+
+    /// Returns the parity values for `message`, highest degree first: the
+    /// negated remainder of `message(x) * x^(2e)` divided by the generator.
+    fn parity(self, message: &[u32]) -> Vec<u32> {
+        let generator = self.generator();
+        let parity_len = 2 * self.errors;
+        let mut remainder = message.to_vec();
+        remainder.resize(message.len() + parity_len, 0);
+        for i in 0..message.len() {
+            let coefficient = remainder[i];
+            if coefficient == 0 {
+                continue;
+            }
+            for (j, &g) in generator.iter().enumerate().skip(1) {
+                remainder[i + j] = sub(remainder[i + j], mul(coefficient, g));
+            }
+        }
+        remainder[message.len()..]
+            .iter()
+            .map(|&r| sub(0, r))
+            .collect()
+    }
+
+    /// The monic generator polynomial, highest degree first, with roots
+    /// `GENERATOR^1` to `GENERATOR^(2e)`.
+    fn generator(self) -> Vec<u32> {
+        let mut generator = vec![1];
+        for j in 1..=2 * self.errors {
+            let root = pow(GENERATOR, j);
+            generator.push(0);
+            for i in (1..generator.len()).rev() {
+                generator[i] = sub(generator[i], mul(root, generator[i - 1]));
+            }
+        }
+        generator
+    }
+}
+
+/// Finds the error locator polynomial, lowest degree first, of the shortest
+/// linear recurrence that generates `syndromes`.
+fn berlekamp_massey(syndromes: &[u32]) -> Vec<u32> {
+    let mut locator = vec![1];
+    let mut previous = vec![1];
+    let mut length = 0;
+    let mut shift = 1;
+    let mut last_discrepancy = 1;
+    for n in 0..syndromes.len() {
+        let discrepancy = (1..=length).fold(syndromes[n], |acc, i| {
+            add(
+                acc,
+                mul(locator.get(i).copied().unwrap_or(0), syndromes[n - i]),
+            )
+        });
+        if discrepancy == 0 {
+            shift += 1;
+            continue;
+        }
+        let scale = mul(discrepancy, inv(last_discrepancy));
+        let mut next = locator.clone();
+        next.resize(next.len().max(previous.len() + shift), 0);
+        for (i, &b) in previous.iter().enumerate() {
+            next[i + shift] = sub(next[i + shift], mul(scale, b));
+        }
+        if 2 * length <= n {
+            length = n + 1 - length;
+            previous = std::mem::replace(&mut locator, next);
+            last_discrepancy = discrepancy;
+            shift = 1;
+        } else {
+            locator = next;
+            shift += 1;
+        }
+    }
+    locator.truncate(length + 1);
+    locator
+}
+
+impl CheckScheme for ReedSolomon58 {
+    fn get_name(&self) -> &'static str {
+        "reed-solomon base58"
+    }
+
+    fn get_check_len(&self) -> usize {
+        2 * self.errors + 1
+    }
+
+    fn compute(&self, digits: &str) -> Result<String, SerialiseError> {
+        let values = digit_values(self.get_name(), digits, |c| {
+            DIGITS[usize::from(c)].map(u32::from)
+        })?;
+        if values.len() + self.get_check_len() > MAX_CODE_LEN {
+            return Err(SerialiseError::new(format!(
+                "reed-solomon base58 code of {} characters is longer than {MAX_CODE_LEN}",
+                values.len() + self.get_check_len()
+            )));
+        }
+        // The parity values depend linearly on the extra character, and each
+        // is 58 for exactly one choice of it, so one of the first 2e + 1
+        // choices keeps them all in the alphabet.
+        let mut message = values;
+        message.push(0);
+        for extra in 0..P - 1 {
+            if let Some(last) = message.last_mut() {
+                *last = extra;
+            }
+            let parity = self.parity(&message);
+            if parity.iter().all(|&p| p < P - 1) {
+                return Ok(std::iter::once(extra)
+                    .chain(parity)
+                    .map(|v| char::from(ALPHABET[v as usize]))
+                    .collect());
+            }
+        }
+        Err(SerialiseError::new(
+            "reed-solomon base58 found no parity inside the alphabet".to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const ADDRESS: &str = "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2";
+
+    #[test]
+    fn test_append_and_strip() {
+        for scheme in [ReedSolomon58::ONE, ReedSolomon58::TWO] {
+            let code = scheme.append(ADDRESS).unwrap_or_default();
+            assert_eq!(code.len(), ADDRESS.len() + scheme.get_check_len());
+            assert!(matches!(scheme.strip(&code), Ok(ADDRESS)));
+            assert!(
+                matches!(scheme.correct(&code), Ok((text, fixes)) if text == ADDRESS && fixes.is_empty())
+            );
+        }
+        let bytes = [0xfe, 0x01, 0x80];
+        let code = ReedSolomon58::TWO.encode(&bytes).unwrap_or_default();
+        assert!(matches!(ReedSolomon58::TWO.decode(&code), Ok((decoded, _)) if decoded == bytes));
+    }
+
+    #[test]
+    fn test_encode_keeps_leading_zero_bytes() {
+        let mut address = [0x5a; 25];
+        address[0] = 0x00;
+        let inputs: [&[u8]; 5] = [
+            &[],
+            &[0x00],
+            &[0x00, 0x00],
+            &[0x00, 0xf6, 0xe9, 0xd2],
+            &address,
+        ];
+        for bytes in inputs {
+            let code = ReedSolomon58::ONE.encode(bytes).unwrap_or_default();
+            assert!(
+                matches!(ReedSolomon58::ONE.decode(&code), Ok((decoded, _)) if decoded == bytes),
+                "{code}"
+            );
+        }
+        let code = ReedSolomon58::ONE
+            .encode(&[0x00, 0xf6, 0xe9, 0xd2])
+            .unwrap_or_default();
+        assert!(code.starts_with(&format!("1{}", Base58::to_base58(&[0xf6, 0xe9, 0xd2]))));
+        assert!(matches!(ReedSolomon58::ONE.encode(&[]), Ok(code) if code.len() == 3));
+    }
+
+    #[test]
+    fn test_corrects_up_to_two_errors_anywhere() {
+        let scheme = ReedSolomon58::TWO;
+        let code: Vec<char> = scheme.append(ADDRESS).unwrap_or_default().chars().collect();
+        for first in 0..code.len() {
+            for second in [first, (first * 7 + 3) % code.len()] {
+                let mut typo = code.clone();
+                typo[first] = if typo[first] == 'z' { '0' } else { 'z' };
+                typo[second] = if code[second] == 'l' { '2' } else { 'l' };
+                let typo: String = typo.into_iter().collect();
+                assert!(
+                    matches!(scheme.correct(&typo), Ok((text, corrections)) if text == ADDRESS
+                        && corrections.len() <= 2
+                        && corrections.iter().all(|c| code[c.get_position()] == c.get_corrected())),
+                    "{typo}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_too_many_errors_is_err() {
+        let code = ReedSolomon58::ONE.append(ADDRESS).unwrap_or_default();
+        let typo = format!("zz{}", &code[2..]);
+        assert!(matches!(
+            ReedSolomon58::ONE.correct(&typo),
+            Err(e) if e.get_kind() == ErrorKind::ChecksumMismatch
+        ));
+        assert!(ReedSolomon58::new(0).is_err());
+        assert!(ReedSolomon58::ONE.append(&"2".repeat(56)).is_err());
+        assert!(ReedSolomon58::ONE.correct("ab").is_err());
+    }
+}
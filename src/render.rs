@@ -0,0 +1,150 @@
+use std::fmt::Display;
+
+use crate::{ByteVec, Encoding};
+
+/// The encodings shown by [`ByteVec::render_all`], in display order.
+const RENDERED: &[Encoding] = &[
+    #[cfg(feature = "hex")]
+    Encoding::Hex,
+    #[cfg(feature = "base64")]
+    Encoding::Base64,
+    #[cfg(feature = "base58")]
+    Encoding::Base58,
+    #[cfg(feature = "base36")]
+    Encoding::Base36,
+    #[cfg(feature = "escaped")]
+    Encoding::Escaped,
+];
+
+/// The same bytes written in several encodings at once, for debugging.
+///
+/// Created by [`ByteVec::render_all`]. Displays as one line per encoding,
+/// with the encoding names aligned in a column, after a line giving the
+/// length:
+///
+/// ```text
+/// length   5 bytes
+/// hex      68656c6c6f
+/// base64   aGVsbG8=
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Renderings {
+    /// The number of bytes rendered
+    len: usize,
+    /// Each encoding and the bytes written in it, or why encoding failed
+    rows: Vec<(Encoding, Result<String, String>)>,
+}
+
+impl Renderings {
+    /// Renders `bytes` in every encoding shown by [`ByteVec::render_all`].
+    pub(crate) fn new(bytes: &ByteVec) -> Self {
+        let rows = RENDERED
+            .iter()
+            .map(|&encoding| {
+                let rendered = bytes
+                    .try_encode(encoding)
+                    .map(|encoded| encoded.get_string().clone())
+                    .map_err(|e| e.get_message().clone());
+                (encoding, rendered)
+            })
+            .collect();
+        Self {
+            len: bytes.len(),
+            rows,
+        }
+    }
+
+    /// Returns the number of bytes rendered.
+    #[must_use = "This returns the length but does nothing if unused"]
+    pub const fn get_len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the encodings shown, in display order.
+    #[must_use = "This returns the encodings but does nothing if unused"]
+    pub fn encodings(&self) -> impl Iterator<Item = Encoding> + '_ {
+        self.rows.iter().map(|(encoding, _)| *encoding)
+    }
+
+    /// Returns the bytes written in `encoding`.
+    ///
+    /// # Arguments
+    /// * `encoding` - The encoding to look up
+    ///
+    /// # Returns
+    /// The encoded text, or `None` if `encoding` is not shown or failed
+    #[must_use = "This returns the encoded text but does nothing if unused"]
+    pub fn get(&self, encoding: Encoding) -> Option<&str> {
+        self.rows
+            .iter()
+            .find(|(e, _)| *e == encoding)
+            .and_then(|(_, rendered)| rendered.as_deref().ok())
+    }
+}
+
+impl Display for Renderings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self
+            .encodings()
+            .map(|encoding| encoding.get_name().len())
+            .chain(["length".len()])
+            .max()
+            .unwrap_or_default();
+        let unit = if self.len == 1 { "byte" } else { "bytes" };
+        writeln!(f, "{:width$}  {} {unit}", "length", self.len)?;
+        for (encoding, rendered) in &self.rows {
+            match rendered {
+                Ok(text) => writeln!(f, "{:width$}  {text}", encoding.get_name())?,
+                Err(message) => writeln!(f, "{:width$}  <{message}>", encoding.get_name())?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(
+    feature = "base36",
+    feature = "base58",
+    feature = "base64",
+    feature = "escaped",
+    feature = "hex"
+))]
+mod tests {
+
+    use std::sync::Arc;
+
+    use super::*;
+
+    fn hello() -> ByteVec {
+        ByteVec::new(Arc::new(b"hello\n".to_vec()))
+    }
+
+    #[test]
+    fn test_render_all() {
+        let renderings = hello().render_all();
+        assert_eq!(renderings.get_len(), 6);
+        assert_eq!(renderings.get(Encoding::Hex), Some("68656c6c6f0a"));
+        assert_eq!(renderings.get(Encoding::Base64), Some("aGVsbG8K"));
+        assert_eq!(renderings.get(Encoding::Escaped), Some("hello\\x0a"));
+        assert!(renderings.get(Encoding::Base58).is_some());
+        assert_eq!(renderings.encodings().count(), 5);
+    }
+
+    #[test]
+    fn test_display_aligns_names() {
+        let text = hello().render_all().to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0], "length   6 bytes");
+        assert_eq!(lines[1], "hex      68656c6c6f0a");
+        assert_eq!(lines[5], "escaped  hello\\x0a");
+    }
+
+    #[test]
+    fn test_empty() {
+        let renderings = ByteVec::new(Arc::new(Vec::new())).render_all();
+        assert_eq!(renderings.get(Encoding::Hex), Some(""));
+        assert!(renderings.to_string().starts_with("length   0 bytes\n"));
+    }
+}
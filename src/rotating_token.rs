@@ -0,0 +1,248 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::{Base62, ErrorKind, SerialiseError};
+
+/// Byte written before the payload so Base62, which drops leading zero
+/// bytes, keeps payloads that start with zeros.
+const MARKER: u8 = 1;
+
+/// Bytes of the window counter at the end of a token.
+const COUNTER_LEN: usize = 8;
+
+/// Short-lived tokens that change every time window, for cache-busting query
+/// strings and links that should stop working after a while.
+///
+/// A token is the Base62 encoding of the payload followed by the number of
+/// the time window it was made in, so the same payload gives a new token each
+/// window. Decoding accepts tokens from the current window and up to
+/// `skew` windows either side, to allow for clocks that disagree.
+///
+/// Tokens are not signed: anyone can make a token for any payload and
+/// window. Put a MAC in the payload if tokens must not be forged.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RotatingToken {
+    /// The length of a window, in seconds
+    window_secs: u64,
+    /// The number of windows before or after the current one still accepted
+    skew: u64,
+}
+
+impl RotatingToken {
+    /// Creates a format with windows of `window` that accepts only tokens
+    /// from the current window.
+    ///
+    /// # Arguments
+    /// * `window` - The length of a window, a whole number of seconds
+    ///
+    /// # Returns
+    /// The token format
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `window` is shorter than a second or not a
+    /// whole number of seconds.
+    pub fn new(window: Duration) -> Result<Self, SerialiseError> {
+        if window.as_secs() == 0 || window.subsec_nanos() != 0 {
+            return Err(SerialiseError::new(format!(
+                "token window must be a whole number of seconds, got {window:?}"
+            )));
+        }
+        Ok(Self {
+            window_secs: window.as_secs(),
+            skew: 0,
+        })
+    }
+
+    /// Also accepts tokens up to `windows` windows older or newer than the
+    /// current one.
+    #[must_use = "This returns the token format but does nothing if unused"]
+    pub const fn with_skew(mut self, windows: u64) -> Self {
+        self.skew = windows;
+        self
+    }
+
+    /// Returns the length of a window.
+    #[must_use = "This returns the window length but does nothing if unused"]
+    pub const fn get_window(&self) -> Duration {
+        Duration::from_secs(self.window_secs)
+    }
+
+    /// Returns the number of windows either side of the current one that are
+    /// still accepted.
+    #[must_use = "This returns the skew but does nothing if unused"]
+    pub const fn get_skew(&self) -> u64 {
+        self.skew
+    }
+
+    /// Returns the number of the window `time` falls in.
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `time` is before the Unix epoch.
+    pub fn window_at(&self, time: SystemTime) -> Result<u64, SerialiseError> {
+        let since_epoch = time
+            .duration_since(UNIX_EPOCH)
+            .map_err(|_| SerialiseError::new("token time is before the Unix epoch".to_string()))?;
+        Ok(since_epoch.as_secs() / self.window_secs)
+    }
+
+    /// Makes a token for `payload` in the current window.
+    ///
+    /// # Arguments
+    /// * `payload` - The bytes to carry, such as a resource version
+    ///
+    /// # Returns
+    /// The token, in Base62
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the system clock is before the Unix epoch.
+    pub fn encode(&self, payload: &[u8]) -> Result<String, SerialiseError> {
+        self.encode_at(payload, SystemTime::now())
+    }
+
+    /// Makes a token for `payload` in the window `time` falls in.
+    ///
+    /// # Arguments
+    /// * `payload` - The bytes to carry
+    /// * `time` - The time to make the token for
+    ///
+    /// # Returns
+    /// The token, in Base62
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `time` is before the Unix epoch.
+    pub fn encode_at(&self, payload: &[u8], time: SystemTime) -> Result<String, SerialiseError> {
+        let window = self.window_at(time)?;
+        let mut bytes = Vec::with_capacity(1 + payload.len() + COUNTER_LEN);
+        bytes.push(MARKER);
+        bytes.extend_from_slice(payload);
+        bytes.extend_from_slice(&window.to_be_bytes());
+        Ok(Base62::to_base62(&bytes))
+    }
+
+    /// Reads the payload and window number of a token without checking its
+    /// window against the clock.
+    ///
+    /// # Arguments
+    /// * `token` - The token
+    ///
+    /// # Returns
+    /// The payload and the number of the window the token was made in
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `token` is not valid Base62 or was not
+    /// made by a `RotatingToken`.
+    pub fn decode_window(token: &str) -> Result<(Vec<u8>, u64), SerialiseError> {
+        let bytes = Base62::base62_to_bytes(token)?;
+        let malformed = || SerialiseError::new(format!("'{token}' is not a rotating token"));
+        let (&marker, rest) = bytes.split_first().ok_or_else(malformed)?;
+        if marker != MARKER || rest.len() < COUNTER_LEN {
+            return Err(malformed());
+        }
+        let (payload, counter) = rest.split_at(rest.len() - COUNTER_LEN);
+        let counter = counter.try_into().map_err(|_| malformed())?;
+        Ok((payload.to_vec(), u64::from_be_bytes(counter)))
+    }
+
+    /// Decodes a token, checking that it was made in the current window or
+    /// within the skew of it.
+    ///
+    /// # Arguments
+    /// * `token` - The token
+    ///
+    /// # Returns
+    /// The payload
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` with [`ErrorKind::Expired`] if the token's
+    /// window is outside the accepted range, or `SerialiseError` if the token
+    /// is malformed or the system clock is before the Unix epoch.
+    pub fn decode(&self, token: &str) -> Result<Vec<u8>, SerialiseError> {
+        self.decode_at(token, SystemTime::now())
+    }
+
+    /// Decodes a token as [`RotatingToken::decode`] does, at `time` rather
+    /// than now.
+    ///
+    /// # Arguments
+    /// * `token` - The token
+    /// * `time` - The time to check the token's window against
+    ///
+    /// # Returns
+    /// The payload
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` with [`ErrorKind::Expired`] if the token's
+    /// window is outside the accepted range, or `SerialiseError` if the token
+    /// is malformed or `time` is before the Unix epoch.
+    pub fn decode_at(&self, token: &str, time: SystemTime) -> Result<Vec<u8>, SerialiseError> {
+        let (payload, window) = Self::decode_window(token)?;
+        let current = self.window_at(time)?;
+        if window.abs_diff(current) > self.skew {
+            return Err(SerialiseError::new(format!(
+                "token is from window {window}, accepted windows are {} to {}",
+                current.saturating_sub(self.skew),
+                current.saturating_add(self.skew)
+            ))
+            .with_kind(ErrorKind::Expired));
+        }
+        Ok(payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let Ok(tokens) = RotatingToken::new(Duration::from_mins(1)) else {
+            return;
+        };
+        for payload in [&b""[..], b"\0\0v1", b"style.css@42"] {
+            let token = tokens.encode_at(payload, at(6_000)).unwrap_or_default();
+            assert!(token.bytes().all(|c| c.is_ascii_alphanumeric()));
+            assert!(matches!(tokens.decode_at(&token, at(6_059)), Ok(p) if p == payload));
+            assert!(matches!(RotatingToken::decode_window(&token), Ok((p, 100)) if p == payload));
+        }
+        assert!(matches!(
+            tokens.encode(b"now").map(|t| tokens.decode(&t)),
+            Ok(Ok(_))
+        ));
+    }
+
+    #[test]
+    fn test_rotates_and_expires() {
+        let Ok(tokens) = RotatingToken::new(Duration::from_mins(1)) else {
+            return;
+        };
+        let first = tokens.encode_at(b"v1", at(6_000)).unwrap_or_default();
+        let second = tokens.encode_at(b"v1", at(6_060)).unwrap_or_default();
+        assert_ne!(first, second);
+        assert!(matches!(
+            tokens.decode_at(&first, at(6_060)),
+            Err(e) if e.get_kind() == ErrorKind::Expired
+        ));
+
+        let skewed = tokens.with_skew(1);
+        assert_eq!(skewed.get_skew(), 1);
+        assert!(skewed.decode_at(&first, at(6_060)).is_ok());
+        assert!(skewed.decode_at(&second, at(6_000)).is_ok());
+        assert!(skewed.decode_at(&first, at(6_120)).is_err());
+    }
+
+    #[test]
+    fn test_invalid_is_err() {
+        assert!(RotatingToken::new(Duration::ZERO).is_err());
+        assert!(RotatingToken::new(Duration::from_millis(1_500)).is_err());
+        assert!(
+            matches!(RotatingToken::new(Duration::from_secs(5)), Ok(t) if t.get_window() == Duration::from_secs(5))
+        );
+        for bad in ["", "abc!", "1", &Base62::to_base62(&[2; 12])] {
+            assert!(RotatingToken::decode_window(bad).is_err(), "{bad}");
+        }
+    }
+}
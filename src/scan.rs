@@ -0,0 +1,259 @@
+use std::ops::Range;
+
+use crate::Encoding;
+
+#[cfg(feature = "base64")]
+use crate::Base64;
+#[cfg(feature = "hex")]
+use crate::Hex;
+#[cfg(feature = "uuencode")]
+use crate::{FileMode, Uuencode};
+
+/// Number of decoded bytes kept as a preview of each candidate.
+pub const PREVIEW_LEN: usize = 32;
+
+/// A region of the scanned buffer that decodes as an encoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    /// The encoding the region decodes as
+    encoding: Encoding,
+    /// Byte offsets of the region in the scanned buffer
+    span: Range<usize>,
+    /// The number of decoded bytes
+    decoded_len: usize,
+    /// The first decoded bytes, at most [`PREVIEW_LEN`]
+    preview: Vec<u8>,
+}
+
+impl Candidate {
+    fn new(encoding: Encoding, span: Range<usize>, mut decoded: Vec<u8>) -> Self {
+        let decoded_len = decoded.len();
+        decoded.truncate(PREVIEW_LEN);
+        Self {
+            encoding,
+            span,
+            decoded_len,
+            preview: decoded,
+        }
+    }
+
+    /// Returns the encoding the region decodes as.
+    #[must_use = "This returns the encoding but does nothing if unused"]
+    pub const fn get_encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Returns the byte offsets of the region in the scanned buffer.
+    #[must_use = "This returns the span but does nothing if unused"]
+    pub const fn get_span(&self) -> &Range<usize> {
+        &self.span
+    }
+
+    /// Returns the number of bytes the region decodes to.
+    #[must_use = "This returns the decoded length but does nothing if unused"]
+    pub const fn get_decoded_len(&self) -> usize {
+        self.decoded_len
+    }
+
+    /// Returns the first decoded bytes, at most [`PREVIEW_LEN`].
+    #[must_use = "This returns the preview but does nothing if unused"]
+    pub fn get_preview(&self) -> &[u8] {
+        &self.preview
+    }
+}
+
+/// Finds regions of `input` that look like embedded encoded data.
+///
+/// Three kinds of region are reported, each only if it decodes:
+/// * uuencoded files, from a `begin <mode> <name>` line to the `end` line
+/// * runs of at least `min_len` hex digits of even length
+/// * runs of at least `min_len` Base64 characters, with optional padding, that
+///   are not all hex digits
+///
+/// Runs inside a uuencoded file are not reported separately. Short runs of
+/// letters are valid Base64, so `min_len` is what keeps ordinary words out; 16
+/// to 24 suits most logs.
+///
+/// # Arguments
+/// * `input` - The buffer to scan, which need not be text
+/// * `min_len` - The shortest run of hex or Base64 characters to report
+///
+/// # Returns
+/// The candidates in order of their start offset
+#[must_use = "This returns the candidates but does nothing if unused"]
+pub fn scan(input: &[u8], min_len: usize) -> Vec<Candidate> {
+    #[allow(unused_mut)]
+    let mut found: Vec<Candidate> = Vec::new();
+    #[cfg(feature = "uuencode")]
+    found.extend(uuencode_blocks(input));
+    #[cfg(any(feature = "base64", feature = "hex"))]
+    {
+        let runs: Vec<Candidate> = runs(input, min_len)
+            .filter(|run| {
+                !found
+                    .iter()
+                    .any(|block| block.span.contains(&run.span.start))
+            })
+            .collect();
+        found.extend(runs);
+    }
+    #[cfg(not(any(feature = "base64", feature = "hex")))]
+    let _ = min_len;
+    found.sort_by_key(|candidate| candidate.span.start);
+    found
+}
+
+/// Returns true if `c` can be part of a hex or Base64 run.
+#[cfg(any(feature = "base64", feature = "hex"))]
+const fn is_run_char(c: u8) -> bool {
+    if cfg!(feature = "base64") {
+        c.is_ascii_alphanumeric() || c == b'+' || c == b'/'
+    } else {
+        c.is_ascii_hexdigit()
+    }
+}
+
+/// Finds the hex and Base64 runs of at least `min_len` characters that decode.
+#[cfg(any(feature = "base64", feature = "hex"))]
+fn runs(input: &[u8], min_len: usize) -> impl Iterator<Item = Candidate> + '_ {
+    let mut start = 0;
+    std::iter::from_fn(move || {
+        while start < input.len() {
+            let body = input[start..]
+                .iter()
+                .take_while(|&&c| is_run_char(c))
+                .count();
+            if body == 0 {
+                start += 1;
+                continue;
+            }
+            let padding = if cfg!(feature = "base64") {
+                input[start + body..]
+                    .iter()
+                    .take(2)
+                    .take_while(|&&c| c == b'=')
+                    .count()
+            } else {
+                0
+            };
+            let span = start..start + body + padding;
+            start = span.end;
+            if span.len() < min_len {
+                continue;
+            }
+            let text = String::from_utf8_lossy(&input[span.clone()]).into_owned();
+            if let Some(candidate) = classify(&text, span) {
+                return Some(candidate);
+            }
+        }
+        None
+    })
+}
+
+/// Decodes a run as hex if it only has hex digits, and as Base64 otherwise.
+#[cfg(any(feature = "base64", feature = "hex"))]
+fn classify(text: &str, span: Range<usize>) -> Option<Candidate> {
+    #[cfg(feature = "hex")]
+    if text.bytes().all(|c| c.is_ascii_hexdigit()) && text.len().is_multiple_of(2) {
+        return Hex::try_from_hex(text)
+            .ok()
+            .map(|bytes| Candidate::new(Encoding::Hex, span, bytes));
+    }
+    #[cfg(feature = "base64")]
+    if let Ok(bytes) = Base64::try_from_base64(text, 0) {
+        return Some(Candidate::new(Encoding::Base64, span, bytes));
+    }
+    None
+}
+
+/// Finds uuencoded files, from a valid `begin` line to the next `end` line.
+#[cfg(feature = "uuencode")]
+fn uuencode_blocks(input: &[u8]) -> Vec<Candidate> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for line in input.split_inclusive(|&c| c == b'\n') {
+        lines.push((offset, line));
+        offset += line.len();
+    }
+    // Only the line ending is removed, as a trailing space is a uuencode digit
+    let text = |line: &[u8]| {
+        String::from_utf8_lossy(line)
+            .trim_end_matches(['\r', '\n'])
+            .to_string()
+    };
+
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let (start, line) = lines[i];
+        i += 1;
+        let header = text(line);
+        let mut fields = header.splitn(3, ' ');
+        let is_begin = fields.next() == Some("begin")
+            && fields
+                .next()
+                .is_some_and(|mode| mode.parse::<FileMode>().is_ok())
+            && fields.next().is_some_and(|name| !name.is_empty());
+        if !is_begin {
+            continue;
+        }
+        let Some(end) = lines[i..].iter().position(|&(_, line)| text(line) == "end") else {
+            continue;
+        };
+        let body: Vec<String> = lines[i..i + end]
+            .iter()
+            .map(|&(_, line)| text(line))
+            .collect();
+        let (end_offset, end_line) = lines[i + end];
+        if let Ok(bytes) = Uuencode::from_uuencode(&body.join("\n")) {
+            found.push(Candidate::new(
+                Encoding::Uuencode,
+                start..end_offset + end_line.len(),
+                bytes,
+            ));
+            i += end + 1;
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "base64", feature = "hex", feature = "uuencode"))]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_scan_finds_runs() {
+        let log = b"GET /api?token=c2VjcmV0LWFwaS1rZXk= 200 sha=9f86d081884c7d65 ok";
+        let found = scan(log, 16);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].get_encoding(), Encoding::Base64);
+        assert_eq!(&log[found[0].get_span().clone()], b"c2VjcmV0LWFwaS1rZXk=");
+        assert_eq!(found[0].get_preview(), b"secret-api-key");
+        assert_eq!(found[1].get_encoding(), Encoding::Hex);
+        assert_eq!(found[1].get_decoded_len(), 8);
+    }
+
+    #[test]
+    fn test_scan_finds_uuencode_block() {
+        let mut input = b"mail body\nbegin 644 hello.txt\n".to_vec();
+        input.extend(Uuencode::to_uuencode(&[b'x'; 100]).as_bytes());
+        input.extend(b"end\ntrailer\n");
+        let found = scan(&input, 16);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].get_encoding(), Encoding::Uuencode);
+        assert_eq!(found[0].get_span().start, 10);
+        assert_eq!(&input[found[0].get_span().end..], b"trailer\n");
+        assert_eq!(found[0].get_decoded_len(), 100);
+        assert_eq!(found[0].get_preview().len(), PREVIEW_LEN);
+    }
+
+    #[test]
+    fn test_scan_skips_short_and_invalid() {
+        assert!(scan(b"the quick brown fox", 16).is_empty());
+        assert!(scan(b"abcdefghijklmnopq", 16).is_empty());
+        assert!(scan(b"begin 999 x\nM\nend\n", 16).is_empty());
+        assert!(scan(&[0xff, 0x00, 0x80], 1).is_empty());
+    }
+}
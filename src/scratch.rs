@@ -0,0 +1,191 @@
+#![cfg_attr(
+    all(feature = "no-panic", not(test)),
+    deny(
+        clippy::indexing_slicing,
+        clippy::panic,
+        clippy::unreachable,
+        clippy::unwrap_used,
+        clippy::expect_used
+    )
+)]
+
+#[cfg(feature = "thread-local-scratch")]
+use std::cell::RefCell;
+
+/// Largest buffer, in bytes, kept in the thread-local scratch between calls,
+/// so one huge input does not pin its buffers for the life of the thread.
+#[cfg(feature = "thread-local-scratch")]
+const MAX_RETAINED: usize = 64 * 1024;
+
+/// Working buffers for the big-integer codecs (Base36, Base58 and Base62).
+///
+/// Those codecs do long division over the whole input, which needs buffers
+/// as large as the input. With the default `thread-local-scratch` feature
+/// each thread keeps one `Scratch` and reuses it; callers who want explicit
+/// control, or build without that feature, can keep their own and pass it to
+/// `encode_with_scratch` and `decode_with_scratch`, which then only allocate
+/// when an input is larger than any before.
+#[derive(Debug, Clone, Default)]
+pub struct Scratch {
+    /// 32-bit limbs of the number being divided, each held in a `u64`
+    pub(crate) words: Vec<u64>,
+    /// Bytes of the number being divided or built
+    pub(crate) bytes: Vec<u8>,
+    /// The encoded digits
+    pub(crate) text: Vec<u8>,
+}
+
+impl Scratch {
+    /// Creates empty buffers, which grow on first use.
+    #[must_use = "This returns the scratch buffers but does nothing if unused"]
+    pub const fn new() -> Self {
+        Self {
+            words: Vec::new(),
+            bytes: Vec::new(),
+            text: Vec::new(),
+        }
+    }
+
+    /// Creates buffers large enough to encode or decode `len` bytes without
+    /// growing.
+    #[must_use = "This returns the scratch buffers but does nothing if unused"]
+    pub fn with_capacity(len: usize) -> Self {
+        Self {
+            words: Vec::with_capacity(len.div_ceil(4)),
+            bytes: Vec::with_capacity(len),
+            text: Vec::with_capacity(len * 2),
+        }
+    }
+
+    /// Empties the buffers and releases memory beyond `len` bytes in each.
+    pub fn shrink_to(&mut self, len: usize) {
+        self.words.clear();
+        self.bytes.clear();
+        self.text.clear();
+        self.words.shrink_to(len / 8);
+        self.bytes.shrink_to(len);
+        self.text.shrink_to(len);
+    }
+
+    /// Returns the encoded digits written by the last encode.
+    pub(crate) fn get_text(&self) -> &str {
+        std::str::from_utf8(&self.text).unwrap_or_default()
+    }
+}
+
+#[cfg(feature = "thread-local-scratch")]
+thread_local! {
+    /// This thread's scratch buffers.
+    static SCRATCH: RefCell<Scratch> = const { RefCell::new(Scratch::new()) };
+}
+
+/// Runs `f` with this thread's scratch buffers, or with fresh ones if the
+/// `thread-local-scratch` feature is off or the buffers are already in use.
+pub(crate) fn with_scratch<R>(f: impl FnOnce(&mut Scratch) -> R) -> R {
+    #[cfg(feature = "thread-local-scratch")]
+    {
+        SCRATCH.with(|cell| match cell.try_borrow_mut() {
+            Ok(mut scratch) => {
+                let result = f(&mut scratch);
+                scratch.shrink_to(MAX_RETAINED);
+                result
+            }
+            Err(_) => f(&mut Scratch::new()),
+        })
+    }
+    #[cfg(not(feature = "thread-local-scratch"))]
+    {
+        f(&mut Scratch::new())
+    }
+}
+
+/// Multiplies the little-endian number in `acc` by `base` and adds `digit`,
+/// growing it as needed. Used by the decoders, which build the number one
+/// digit at a time.
+pub(crate) fn mul_add(acc: &mut Vec<u8>, base: u32, digit: u32) {
+    let mut carry = digit;
+    for b in acc.iter_mut() {
+        let v = u32::from(*b) * base + carry;
+        *b = v.to_le_bytes()[0];
+        carry = v >> 8;
+    }
+    while carry > 0 {
+        acc.push(carry.to_le_bytes()[0]);
+        carry >>= 8;
+    }
+}
+
+/// Turns the little-endian number in `acc` into big-endian bytes without
+/// leading zeros, keeping a single zero byte for zero.
+pub(crate) fn finish_be(acc: &mut Vec<u8>) {
+    while acc.len() > 1 && acc.last() == Some(&0) {
+        acc.pop();
+    }
+    acc.reverse();
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "base36", feature = "base58", feature = "base62"))]
+mod tests {
+
+    use super::*;
+    use crate::{Base36, Base58, Base62};
+
+    #[test]
+    fn test_matches_allocating_codecs() {
+        let mut scratch = Scratch::new();
+        for bytes in [
+            &b""[..],
+            b"\0\0",
+            b"\0\x01\xff",
+            b"hello world",
+            &[0xff; 100],
+        ] {
+            assert_eq!(
+                Base58::encode_with_scratch(bytes, &mut scratch),
+                Base58::to_base58(bytes)
+            );
+            assert_eq!(
+                Base36::encode_with_scratch(bytes, &mut scratch),
+                Base36::to_base36(bytes)
+            );
+            assert_eq!(
+                Base62::encode_with_scratch(bytes, &mut scratch),
+                Base62::to_base62(bytes)
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_with_scratch() {
+        let mut scratch = Scratch::new();
+        assert!(matches!(
+            Base58::decode_with_scratch("StV1DL6CwTryKyV", &mut scratch),
+            Ok(b"hello world")
+        ));
+        assert!(matches!(
+            Base36::decode_with_scratch(" 0 ", &mut scratch),
+            Ok([0])
+        ));
+        assert!(matches!(
+            Base62::decode_with_scratch("00G8", &mut scratch),
+            Ok([0x03, 0xe8])
+        ));
+        assert!(Base58::decode_with_scratch("0OIl", &mut scratch).is_err());
+        assert!(Base36::decode_with_scratch("z!", &mut scratch).is_err());
+    }
+
+    #[test]
+    fn test_reuses_buffers() {
+        let mut scratch = Scratch::with_capacity(64);
+        let capacities = |s: &Scratch| (s.words.capacity(), s.bytes.capacity(), s.text.capacity());
+        let before = capacities(&scratch);
+        for _ in 0..3 {
+            assert!(!Base58::encode_with_scratch(&[0xab; 64], &mut scratch).is_empty());
+            assert!(Base62::decode_with_scratch("zzzzzzzz", &mut scratch).is_ok());
+        }
+        assert_eq!(capacities(&scratch), before);
+        scratch.shrink_to(0);
+        assert_eq!(capacities(&scratch), (0, 0, 0));
+    }
+}
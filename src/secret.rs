@@ -0,0 +1,184 @@
+use std::hint::black_box;
+
+use crate::{Encoding, SerialiseError};
+
+/// Returns `-1` if `lo <= c <= hi` and `0` otherwise, without branching.
+///
+/// Both differences are in `-255..=255`, so the sign bit survives the `&` only
+/// if both are negative and the shift leaves exactly the sign.
+const fn in_range(c: i16, lo: i16, hi: i16) -> i16 {
+    ((lo - 1 - c) & (c - hi - 1)) >> 8
+}
+
+/// Maps a hex digit to its value, or to a negative number if it is not one.
+#[cfg(feature = "hex")]
+const fn hex_digit(c: u8) -> i16 {
+    let c = c as i16;
+    let mut value = -1;
+    value += in_range(c, 0x30, 0x39) & (c - 0x2f);
+    value += in_range(c, 0x41, 0x46) & (c - 0x36);
+    value += in_range(c, 0x61, 0x66) & (c - 0x56);
+    value
+}
+
+/// Maps an RFC 4648 Base32 digit to its value, or to a negative number if it
+/// is not one.
+#[cfg(feature = "base32")]
+const fn base32_digit(c: u8) -> i16 {
+    let c = c as i16;
+    let mut value = -1;
+    value += in_range(c, 0x41, 0x5a) & (c - 0x40);
+    value += in_range(c, 0x32, 0x37) & (c - 0x17);
+    value
+}
+
+/// Maps an RFC 4648 Base64 digit to its value, or to a negative number if it
+/// is not one.
+#[cfg(feature = "base64")]
+const fn base64_digit(c: u8) -> i16 {
+    let c = c as i16;
+    let mut value = -1;
+    value += in_range(c, 0x41, 0x5a) & (c - 0x40);
+    value += in_range(c, 0x61, 0x7a) & (c - 0x46);
+    value += in_range(c, 0x30, 0x39) & (c + 5);
+    value += in_range(c, 0x2b, 0x2b) & 63;
+    value += in_range(c, 0x2f, 0x2f) & 64;
+    value
+}
+
+/// Decodes a secret, such as an auth token, so that a failure reveals nothing
+/// about which characters were wrong.
+///
+/// Every character is looked up with arithmetic rather than a table or
+/// branches, the whole input is processed even after an invalid character,
+/// and every invalid character gives the same error. The length of the input
+/// and its padding are not hidden, and neither is whether it was valid.
+///
+/// Whitespace is not skipped, since a token with a line break in it is already
+/// malformed. Only the standard alphabets are supported: hex in either case,
+/// uppercase Base32 and Base64 with `+` and `/`, with optional `=` padding.
+///
+/// # Arguments
+/// * `encoding` - The encoding of the secret: hex, Base32 or Base64
+/// * `input` - The encoded secret
+///
+/// # Returns
+/// The decoded bytes
+///
+/// # Errors
+/// Returns `SerialiseError` if `encoding` is not supported, `input` has a
+/// length no encoder could have produced, or `input` is not valid, without
+/// saying where.
+pub fn decode_secret(encoding: Encoding, input: &str) -> Result<Vec<u8>, SerialiseError> {
+    let (bits_per_digit, block, decode_digit): (u32, usize, fn(u8) -> i16) = match encoding {
+        #[cfg(feature = "hex")]
+        Encoding::Hex => (4, 2, hex_digit),
+        #[cfg(feature = "base32")]
+        Encoding::Base32 => (5, 8, base32_digit),
+        #[cfg(feature = "base64")]
+        Encoding::Base64 => (6, 4, base64_digit),
+        #[allow(unreachable_patterns)]
+        _ => {
+            return Err(SerialiseError::new(format!(
+                "{encoding} is not supported for secret decoding"
+            )));
+        }
+    };
+
+    let bytes = input.as_bytes();
+    let body = if bits_per_digit == 4 {
+        bytes
+    } else {
+        let padding = bytes.iter().rev().take_while(|&&c| c == b'=').count();
+        if padding > 0 && !bytes.len().is_multiple_of(block) {
+            return Err(SerialiseError::new(format!("invalid {encoding} padding")));
+        }
+        &bytes[..bytes.len() - padding]
+    };
+    let bits = body.len() * bits_per_digit as usize;
+    if bits % 8 >= bits_per_digit as usize {
+        return Err(SerialiseError::new(format!("invalid {encoding} length")));
+    }
+
+    let mask = (1 << bits_per_digit) - 1;
+    let mut out = Vec::with_capacity(bits / 8);
+    let mut invalid: i16 = 0;
+    let mut acc: u32 = 0;
+    let mut acc_bits = 0;
+    for &c in body {
+        let digit = decode_digit(c);
+        invalid |= digit;
+        acc = (acc << bits_per_digit) | (u32::from(digit.to_le_bytes()[0]) & mask);
+        acc_bits += bits_per_digit;
+        if acc_bits >= 8 {
+            acc_bits -= 8;
+            out.push((acc >> acc_bits).to_le_bytes()[0]);
+        }
+        acc &= (1 << acc_bits) - 1;
+    }
+
+    if black_box(invalid) < 0 {
+        out.fill(0);
+        black_box(&out);
+        return Err(SerialiseError::new(format!("invalid {encoding} secret")));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "hex", feature = "base32", feature = "base64"))]
+mod tests {
+
+    use super::*;
+    use crate::{Base32, Base64, Hex};
+
+    #[test]
+    fn test_matches_regular_decoders() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        for len in [0, 1, 2, 3, 4, 5, 31, 256] {
+            let bytes = &bytes[..len];
+            let hex = Hex::try_to_hex(bytes).unwrap_or_default();
+            assert!(matches!(decode_secret(Encoding::Hex, &hex), Ok(b) if b == bytes));
+            let upper = hex.to_uppercase();
+            assert!(matches!(decode_secret(Encoding::Hex, &upper), Ok(b) if b == bytes));
+            let base32 = Base32::try_to_base32(bytes).unwrap_or_default();
+            assert!(matches!(decode_secret(Encoding::Base32, &base32), Ok(b) if b == bytes));
+            let base64 = Base64::try_to_base64(bytes).unwrap_or_default();
+            assert!(matches!(decode_secret(Encoding::Base64, &base64), Ok(b) if b == bytes));
+        }
+    }
+
+    #[test]
+    fn test_every_invalid_character_gives_the_same_error() {
+        let token = "c2VjcmV0LXRva2Vu";
+        for position in 0..token.len() {
+            for bad in ["-", "_", "=", " ", "\u{80}"] {
+                if bad == "=" && position == token.len() - 1 {
+                    continue;
+                }
+                let mut broken = token.as_bytes().to_vec();
+                broken[position] = bad.as_bytes()[0];
+                let broken = String::from_utf8_lossy(&broken);
+                assert!(matches!(
+                    decode_secret(Encoding::Base64, &broken),
+                    Err(e) if e.get_message() == "invalid base64 secret"
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_digit_maps() {
+        for c in 0..=u8::MAX {
+            let hex = Hex::try_from_hex(&format!("0{}", char::from(c))).ok();
+            let expected = hex.map_or(-1, |b| i16::from(b[0]));
+            assert_eq!(hex_digit(c).max(-1), expected, "{c}");
+        }
+        assert_eq!(base32_digit(b'A'), 0);
+        assert_eq!(base32_digit(b'7'), 31);
+        assert!(base32_digit(b'a') < 0);
+        assert_eq!(base64_digit(b'+'), 62);
+        assert_eq!(base64_digit(b'/'), 63);
+        assert!(base64_digit(b'-') < 0);
+    }
+}
@@ -1,4 +1,22 @@
-use std::fmt::Display;
+use std::{error::Error, fmt::Display, io};
+
+/// Category of a [`SerialiseError`], for callers that handle some failures
+/// differently from others.
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// An error without a more specific kind
+    Other,
+    /// The input contains a character outside ASCII, which no encoding uses
+    NonAsciiInput {
+        /// Byte offset of the first non-ASCII character
+        position: usize,
+    },
+    /// A checksum or check character in the input does not match its data
+    ChecksumMismatch,
+    /// The input is well-formed but no longer, or not yet, valid
+    Expired,
+}
 
 /// Error type for serialization operations.
 ///
@@ -8,6 +26,12 @@ use std::fmt::Display;
 pub struct SerialiseError {
     /// The error message describing what went wrong
     message: String,
+    /// The category of the error
+    kind: ErrorKind,
+    /// The underlying error, such as an I/O error, if there is one
+    source: Option<Box<dyn Error + Send + Sync>>,
+    /// Stages the error passed through, outermost first
+    context: Vec<String>,
 }
 
 impl SerialiseError {
@@ -17,7 +41,12 @@ impl SerialiseError {
     /// * `message` - A description of what went wrong during serialization
     #[must_use]
     pub const fn new(message: String) -> Self {
-        Self { message }
+        Self {
+            message,
+            kind: ErrorKind::Other,
+            source: None,
+            context: Vec::new(),
+        }
     }
 
     /// Returns a reference to the error message.
@@ -25,10 +54,174 @@ impl SerialiseError {
     pub const fn get_message(&self) -> &String {
         &self.message
     }
+
+    /// Returns the category of the error.
+    #[must_use = "This returns the error kind and does nothing if unused"]
+    pub const fn get_kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Sets the category of the error.
+    ///
+    /// # Arguments
+    /// * `kind` - The category of the error
+    ///
+    /// # Returns
+    /// The error with the given kind
+    #[must_use = "This returns the error with its kind but does nothing if unused"]
+    pub const fn with_kind(mut self, kind: ErrorKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Adds a description of the stage the error happened in.
+    ///
+    /// Contexts are shown before the message when the error is displayed, so
+    /// each stage of a pipeline can say where it failed while keeping the
+    /// underlying cause, e.g. `"decoding row 3: invalid base58 character"`.
+    ///
+    /// # Arguments
+    /// * `context` - A description of the stage that failed
+    ///
+    /// # Returns
+    /// The error with `context` as its outermost context
+    #[must_use = "This returns the error with context but does nothing if unused"]
+    pub fn with_context(mut self, context: impl Into<String>) -> Self {
+        self.context.insert(0, context.into());
+        self
+    }
+
+    /// Returns the contexts added by [`with_context`](Self::with_context),
+    /// outermost first.
+    #[must_use = "This returns the contexts but does nothing if unused"]
+    pub fn context(&self) -> &[String] {
+        &self.context
+    }
 }
 
 impl Display for SerialiseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for context in &self.context {
+            write!(f, "{context}: ")?;
+        }
         write!(f, "{}", self.message)
     }
 }
+
+impl Error for SerialiseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|source| source as &(dyn Error + 'static))
+    }
+}
+
+/// Wraps the error as [`io::ErrorKind::InvalidData`], since it means the data
+/// read or written was not valid for its encoding.
+impl From<SerialiseError> for io::Error {
+    fn from(error: SerialiseError) -> Self {
+        Self::new(io::ErrorKind::InvalidData, error)
+    }
+}
+
+/// Keeps the I/O error as the source, under the fixed message "I/O error" so
+/// that error-chain reporters print the cause once, or unwraps the
+/// `SerialiseError` inside an I/O error created by the conversion above so the
+/// round trip is lossless.
+impl From<io::Error> for SerialiseError {
+    fn from(error: io::Error) -> Self {
+        match error.downcast::<Self>() {
+            Ok(inner) => inner,
+            Err(error) => Self {
+                message: "I/O error".to_string(),
+                kind: ErrorKind::Other,
+                source: Some(Box::new(error)),
+                context: Vec::new(),
+            },
+        }
+    }
+}
+
+/// Rejects input containing non-ASCII characters before a decoder looks at its
+/// bytes, so a multi-byte character is reported as such rather than as a bad
+/// length or several bad digits. Decimal digits from other scripts are named
+/// as digits, since they usually come from a mobile keyboard's locale.
+///
+/// # Errors
+/// Returns `SerialiseError` of kind [`ErrorKind::NonAsciiInput`] at the byte
+/// offset of the first non-ASCII character.
+pub(crate) fn check_ascii(input: &str, name: &str) -> Result<(), SerialiseError> {
+    let Some((position, c)) = input.char_indices().find(|(_, c)| !c.is_ascii()) else {
+        return Ok(());
+    };
+    let message = crate::digits::unicode_digit_value(c).map_or_else(
+        || format!("non-ASCII character '{c}' at position {position} in {name} input"),
+        |value| {
+            format!(
+                "non-ASCII digit '{c}' (digit {value}) at position {position} in {name} input, \
+                 only ASCII digits are accepted"
+            )
+        },
+    );
+    Err(SerialiseError::new(message).with_kind(ErrorKind::NonAsciiInput { position }))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_into_io_error() {
+        let error = io::Error::from(SerialiseError::new("bad digit".to_string()));
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), "bad digit");
+    }
+
+    #[test]
+    fn test_from_io_error_keeps_source() {
+        let error = SerialiseError::from(io::Error::new(io::ErrorKind::UnexpectedEof, "eof"));
+        assert_eq!(error.get_message(), "I/O error");
+        assert!(
+            matches!(error.source(), Some(source) if source.is::<io::Error>() && source.to_string() == "eof")
+        );
+    }
+
+    #[test]
+    fn test_check_ascii() {
+        assert!(check_ascii("abc", "hex").is_ok());
+        assert!(matches!(
+            check_ascii("ab\u{43a}c", "hex"),
+            Err(e) if e.get_kind() == ErrorKind::NonAsciiInput { position: 2 }
+                && e.get_message() == "non-ASCII character '\u{43a}' at position 2 in hex input"
+        ));
+        assert_eq!(
+            SerialiseError::new(String::new()).get_kind(),
+            ErrorKind::Other
+        );
+    }
+
+    #[test]
+    fn test_with_context() {
+        let error = SerialiseError::from(io::Error::other("disk full"))
+            .with_context("encoding")
+            .with_context("writing archive");
+        assert_eq!(error.context(), ["writing archive", "encoding"]);
+        assert_eq!(error.get_message(), "I/O error");
+        assert_eq!(error.to_string(), "writing archive: encoding: I/O error");
+        assert!(error.source().is_some());
+    }
+
+    #[test]
+    fn test_round_trip_through_io_error() {
+        fn read() -> io::Result<()> {
+            Err(SerialiseError::new("bad digit".to_string()))?
+        }
+        fn decode() -> Result<(), SerialiseError> {
+            Ok(read()?)
+        }
+        assert!(
+            matches!(decode(), Err(e) if e.get_message() == "bad digit" && e.source().is_none())
+        );
+    }
+}
@@ -0,0 +1,331 @@
+use crate::{SerialiseError, alphabet};
+
+/// Alphabet of a sortable identifier, both in ASCII order so that identifiers
+/// sort as strings in the same order as their timestamps.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SortableBase {
+    /// RFC 4648 extended hex Base32 (0-9 and A-V), case-insensitive on decode
+    Base32Hex,
+    /// Base62 (0-9, A-Z and a-z), case-sensitive
+    Base62,
+}
+
+impl SortableBase {
+    const fn alphabet(self) -> &'static [u8] {
+        match self {
+            Self::Base32Hex => alphabet::BASE32_HEX,
+            Self::Base62 => alphabet::BASE62,
+        }
+    }
+
+    fn digit_value(self, c: u8) -> Option<u8> {
+        let c = match self {
+            Self::Base32Hex => c.to_ascii_uppercase(),
+            Self::Base62 => c,
+        };
+        self.alphabet()
+            .iter()
+            .position(|&d| d == c)
+            .and_then(|i| u8::try_from(i).ok())
+    }
+
+    fn radix(self) -> u32 {
+        u32::try_from(self.alphabet().len()).unwrap_or(u32::MAX)
+    }
+
+    /// Writes the big-endian number in `bytes` as exactly `width` digit values,
+    /// returning `None` if it needs more digits.
+    fn bytes_to_digits(self, bytes: &[u8], width: usize) -> Option<Vec<u8>> {
+        let radix = self.radix();
+        let mut number = bytes.to_vec();
+        let mut digits = vec![0u8; width];
+        for digit in digits.iter_mut().rev() {
+            let mut remainder: u32 = 0;
+            for byte in &mut number {
+                let value = (remainder << 8) | u32::from(*byte);
+                *byte = u8::try_from(value / radix).ok()?;
+                remainder = value % radix;
+            }
+            *digit = u8::try_from(remainder).ok()?;
+        }
+        number.iter().all(|&b| b == 0).then_some(digits)
+    }
+
+    /// Reads `digits` back into a big-endian number of exactly `len` bytes,
+    /// returning `None` if the value does not fit.
+    fn digits_to_bytes(self, digits: &[u8], len: usize) -> Option<Vec<u8>> {
+        let radix = self.radix();
+        let mut number = vec![0u8; len];
+        for &digit in digits {
+            let mut carry = u32::from(digit);
+            for byte in number.iter_mut().rev() {
+                let value = u32::from(*byte) * radix + carry;
+                *byte = value.to_le_bytes()[0];
+                carry = value >> 8;
+            }
+            if carry != 0 {
+                return None;
+            }
+        }
+        Some(number)
+    }
+
+    /// Returns the number of digits needed for any value of `len` bytes.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    fn width_for(self, len: usize) -> usize {
+        let max = vec![0xff; len];
+        let fits = |width| self.bytes_to_digits(&max, width).is_some();
+        // An estimate from the bits per digit is off by at most one either way,
+        // so two or three conversions settle it.
+        let mut width = (len as f64 * 8.0 / f64::from(self.radix()).log2()).ceil() as usize;
+        while width > 0 && fits(width - 1) {
+            width -= 1;
+        }
+        while !fits(width) {
+            width += 1;
+        }
+        width
+    }
+}
+
+/// Layout of a sortable identifier: a fixed-width timestamp followed by a
+/// fixed-width payload, usually random bytes.
+///
+/// This is the ULID layout with the base and both widths configurable. Since
+/// the timestamp comes first and every identifier has the same length,
+/// identifiers sort as strings in timestamp order. The payload is supplied by
+/// the caller, so any source of randomness (or a counter) can be used.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SortableIdFormat {
+    /// The alphabet both parts are written in
+    base: SortableBase,
+    /// The number of digits of the timestamp
+    timestamp_width: usize,
+    /// The number of payload bytes
+    payload_len: usize,
+    /// The number of digits of the payload
+    payload_width: usize,
+}
+
+impl SortableIdFormat {
+    /// Creates a format.
+    ///
+    /// # Arguments
+    /// * `base` - The alphabet to write identifiers in
+    /// * `timestamp_width` - The number of digits of the timestamp
+    /// * `payload_len` - The number of payload bytes after the timestamp
+    ///
+    /// # Returns
+    /// The format
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `timestamp_width` is zero.
+    pub fn new(
+        base: SortableBase,
+        timestamp_width: usize,
+        payload_len: usize,
+    ) -> Result<Self, SerialiseError> {
+        if timestamp_width == 0 {
+            return Err(SerialiseError::new(
+                "timestamp width must be at least one digit".to_string(),
+            ));
+        }
+        Ok(Self {
+            base,
+            timestamp_width,
+            payload_len,
+            payload_width: base.width_for(payload_len),
+        })
+    }
+
+    /// Returns the alphabet identifiers are written in.
+    #[must_use = "This returns the base but does nothing if unused"]
+    pub const fn get_base(&self) -> SortableBase {
+        self.base
+    }
+
+    /// Returns the number of digits of the timestamp.
+    #[must_use = "This returns the width but does nothing if unused"]
+    pub const fn get_timestamp_width(&self) -> usize {
+        self.timestamp_width
+    }
+
+    /// Returns the number of payload bytes.
+    #[must_use = "This returns the length but does nothing if unused"]
+    pub const fn get_payload_len(&self) -> usize {
+        self.payload_len
+    }
+
+    /// Returns the length of every identifier in this format.
+    #[must_use = "This returns the length but does nothing if unused"]
+    pub const fn get_len(&self) -> usize {
+        self.timestamp_width + self.payload_width
+    }
+
+    /// Encodes a timestamp and payload as an identifier.
+    ///
+    /// # Arguments
+    /// * `millis` - The timestamp, in milliseconds since the Unix epoch
+    /// * `payload` - The bytes to follow the timestamp, usually random
+    ///
+    /// # Returns
+    /// The identifier, always [`get_len`](Self::get_len) characters long
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `millis` needs more digits than the timestamp
+    /// width, or `payload` is not `payload_len` bytes long.
+    pub fn encode(&self, millis: u64, payload: &[u8]) -> Result<String, SerialiseError> {
+        if payload.len() != self.payload_len {
+            return Err(SerialiseError::new(format!(
+                "payload is {} bytes, expected {}",
+                payload.len(),
+                self.payload_len
+            )));
+        }
+        let Some(timestamp) = self
+            .base
+            .bytes_to_digits(&millis.to_be_bytes(), self.timestamp_width)
+        else {
+            return Err(SerialiseError::new(format!(
+                "timestamp {millis} does not fit in {} digits",
+                self.timestamp_width
+            )));
+        };
+        let payload = self
+            .base
+            .bytes_to_digits(payload, self.payload_width)
+            .unwrap_or_default();
+
+        let alphabet = self.base.alphabet();
+        Ok(timestamp
+            .iter()
+            .chain(&payload)
+            .map(|&digit| char::from(alphabet[usize::from(digit)]))
+            .collect())
+    }
+
+    /// Decodes an identifier back into its timestamp and payload.
+    ///
+    /// # Arguments
+    /// * `id` - The identifier to decode
+    ///
+    /// # Returns
+    /// The timestamp in milliseconds since the Unix epoch and the payload bytes
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `id` has the wrong length, contains characters
+    /// outside the alphabet, or either part is out of range.
+    pub fn decode(&self, id: &str) -> Result<(u64, Vec<u8>), SerialiseError> {
+        if id.len() != self.get_len() {
+            return Err(SerialiseError::new(format!(
+                "identifier is {} characters, expected {}",
+                id.len(),
+                self.get_len()
+            )));
+        }
+        let digits = id
+            .bytes()
+            .map(|c| {
+                self.base.digit_value(c).ok_or_else(|| {
+                    SerialiseError::new(format!(
+                        "invalid {:?} character '{}'",
+                        self.base,
+                        char::from(c)
+                    ))
+                })
+            })
+            .collect::<Result<Vec<u8>, SerialiseError>>()?;
+        let (timestamp, payload) = digits.split_at(self.timestamp_width);
+
+        let millis = self
+            .base
+            .digits_to_bytes(timestamp, 8)
+            .and_then(|bytes| <[u8; 8]>::try_from(bytes).ok())
+            .map(u64::from_be_bytes)
+            .ok_or_else(|| SerialiseError::new("timestamp is out of range".to_string()))?;
+        let payload = self
+            .base
+            .digits_to_bytes(payload, self.payload_len)
+            .ok_or_else(|| SerialiseError::new("payload is out of range".to_string()))?;
+        Ok((millis, payload))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn format(base: SortableBase, timestamp_width: usize, payload_len: usize) -> SortableIdFormat {
+        let format = SortableIdFormat::new(base, timestamp_width, payload_len);
+        assert!(format.is_ok());
+        format.unwrap_or(SortableIdFormat {
+            base,
+            timestamp_width,
+            payload_len,
+            payload_width: 0,
+        })
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for base in [SortableBase::Base32Hex, SortableBase::Base62] {
+            let format = format(base, 9, 10);
+            let id = format
+                .encode(1_700_000_000_000, &[0xff; 10])
+                .unwrap_or_default();
+            assert_eq!(id.len(), format.get_len());
+            assert!(matches!(
+                format.decode(&id),
+                Ok((1_700_000_000_000, payload)) if payload == [0xff; 10]
+            ));
+        }
+    }
+
+    #[test]
+    fn test_sorts_by_timestamp() {
+        let format = format(SortableBase::Base62, 8, 4);
+        let ids: Vec<String> = [
+            (5, [0xff; 4]),
+            (61, [0; 4]),
+            (62, [0x80; 4]),
+            (1_700_000_000_000, [0xff; 4]),
+            (1_700_000_000_001, [0; 4]),
+        ]
+        .iter()
+        .map(|(millis, payload)| format.encode(*millis, payload).unwrap_or_default())
+        .collect();
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(ids.iter().all(|id| id.len() == 14));
+    }
+
+    #[test]
+    fn test_invalid_is_err() {
+        assert!(SortableIdFormat::new(SortableBase::Base62, 0, 4).is_err());
+        let format = format(SortableBase::Base32Hex, 2, 1);
+        assert!(format.encode(1024, &[0]).is_err());
+        assert!(format.encode(1023, &[0, 0]).is_err());
+        assert!(matches!(format.encode(1023, &[1]).as_deref(), Ok("VV01")));
+        assert!(matches!(format.decode("vv01"), Ok((1023, payload)) if payload == [1]));
+        assert!(format.decode("VVW1").is_err());
+        assert!(format.decode("VV80").is_err());
+    }
+
+    #[test]
+    fn test_payload_width_is_minimal() {
+        for base in [SortableBase::Base32Hex, SortableBase::Base62] {
+            for len in [0, 1, 2, 5, 16, 100] {
+                let width = base.width_for(len);
+                let max = vec![0xff; len];
+                assert!(base.bytes_to_digits(&max, width).is_some());
+                assert!(width == 0 || base.bytes_to_digits(&max, width - 1).is_none());
+            }
+        }
+        assert_eq!(format(SortableBase::Base32Hex, 10, 16).get_len(), 10 + 26);
+        assert_eq!(format(SortableBase::Base62, 10, 16).get_len(), 10 + 22);
+    }
+}
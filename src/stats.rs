@@ -0,0 +1,134 @@
+use crate::{ByteVec, EncodedString};
+
+/// Byte frequency statistics for raw or encoded data.
+///
+/// Useful for deciding whether data is worth compressing before encoding (high
+/// entropy data will not shrink) and for guessing what an unknown string is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stats {
+    /// Number of occurrences of each byte value
+    histogram: [usize; 256],
+    /// Total number of bytes counted
+    len: usize,
+}
+
+impl Stats {
+    /// Counts the bytes in `bytes`.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes to analyse
+    #[must_use = "This computes statistics but does nothing if unused"]
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let mut histogram = [0usize; 256];
+        for &b in bytes {
+            histogram[usize::from(b)] += 1;
+        }
+        Self {
+            histogram,
+            len: bytes.len(),
+        }
+    }
+
+    /// Returns the number of occurrences of each byte value.
+    #[must_use = "This returns the histogram but does nothing if unused"]
+    pub const fn get_histogram(&self) -> &[usize; 256] {
+        &self.histogram
+    }
+
+    /// Returns the total number of bytes counted.
+    #[must_use = "This returns the length but does nothing if unused"]
+    pub const fn get_len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the distinct byte values that occur, in ascending order with their counts.
+    ///
+    /// For an encoded string this is the alphabet actually used.
+    #[must_use = "This returns the alphabet but does nothing if unused"]
+    pub fn alphabet(&self) -> Vec<(u8, usize)> {
+        (0..=u8::MAX)
+            .zip(self.histogram)
+            .filter(|&(_, count)| count > 0)
+            .collect()
+    }
+
+    /// Returns the Shannon entropy in bits per byte, from 0 (constant) to 8 (uniform).
+    #[must_use = "This returns the entropy but does nothing if unused"]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn entropy(&self) -> f64 {
+        if self.len == 0 {
+            return 0.0;
+        }
+
+        let len = self.len as f64;
+        self.histogram
+            .iter()
+            .filter(|&&count| count > 0)
+            .map(|&count| {
+                let p = count as f64 / len;
+                -p * p.log2()
+            })
+            .sum()
+    }
+
+    /// Returns the fraction of bytes that are printable ASCII (graphic characters,
+    /// space, tab, CR or LF), or 0 if there are no bytes.
+    #[must_use = "This returns the printable ratio but does nothing if unused"]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn printable_ratio(&self) -> f64 {
+        if self.len == 0 {
+            return 0.0;
+        }
+
+        let printable: usize = (0..=u8::MAX)
+            .zip(self.histogram)
+            .filter(|&(b, _)| b.is_ascii_graphic() || matches!(b, b' ' | b'\t' | b'\r' | b'\n'))
+            .map(|(_, count)| count)
+            .sum();
+        printable as f64 / self.len as f64
+    }
+}
+
+impl From<&ByteVec> for Stats {
+    fn from(byte_vec: &ByteVec) -> Self {
+        Self::from_bytes(byte_vec.get_bytes())
+    }
+}
+
+impl From<&EncodedString> for Stats {
+    fn from(encoded: &EncodedString) -> Self {
+        Self::from_bytes(encoded.get_string().as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn test_entropy() {
+        assert!(Stats::from_bytes(b"").entropy().abs() < f64::EPSILON);
+        assert!(Stats::from_bytes(b"aaaa").entropy().abs() < f64::EPSILON);
+        assert!((Stats::from_bytes(b"abab").entropy() - 1.0).abs() < 1e-9);
+
+        let all: Vec<u8> = (0..=u8::MAX).collect();
+        let stats = Stats::from(&ByteVec::new(Arc::new(all)));
+        assert!((stats.entropy() - 8.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_printable_ratio() {
+        assert!((Stats::from_bytes(b"ab\n\0").printable_ratio() - 0.75).abs() < 1e-9);
+        assert!(Stats::from_bytes(b"").printable_ratio().abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_alphabet() {
+        let stats = Stats::from_bytes(b"abca");
+        assert_eq!(stats.alphabet(), [(b'a', 2), (b'b', 1), (b'c', 1)]);
+        assert_eq!(stats.get_len(), 4);
+    }
+}
@@ -0,0 +1,257 @@
+use crate::{CheckScheme, Encoding, validate};
+
+/// Most suggestions returned by any function in this module.
+pub const MAX_SUGGESTIONS: usize = 16;
+
+/// Groups of characters that are easily mistaken for one another when read
+/// off a screen or a printed coupon.
+const LOOKALIKES: &[&str] = &["0Oo", "1Il|i", "2Zz", "5Ss", "6G", "8B", "9g", "UuVv"];
+
+/// Returns the edit distance between `a` and `b`, counting an insertion,
+/// deletion, substitution or swap of two adjacent characters as one edit.
+///
+/// This is the optimal string alignment form of the Damerau-Levenshtein
+/// distance, which matches the mistakes people make typing codes.
+#[must_use = "This returns the distance but does nothing if unused"]
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // Three rows of the distance table: two rows back, the last row and the
+    // row being filled.
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut last: Vec<usize> = (0..=b.len()).collect();
+    let mut row: Vec<usize> = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        row[0] = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (last[j] + 1).min(row[j - 1] + 1).min(last[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(before[j - 2] + 1);
+            }
+        }
+        std::mem::swap(&mut before, &mut last);
+        std::mem::swap(&mut last, &mut row);
+    }
+    last[b.len()]
+}
+
+/// Suggests the known codes closest to a code that was not recognised, such
+/// as a mistyped coupon code checked against the issued ones.
+///
+/// # Arguments
+/// * `input` - The code as entered
+/// * `candidates` - The valid codes
+/// * `max_distance` - The most edits a suggestion may be from `input`
+///
+/// # Returns
+/// Up to [`MAX_SUGGESTIONS`] candidates within `max_distance` edits, nearest
+/// first, ignoring an exact match
+#[must_use = "This returns the suggestions but does nothing if unused"]
+pub fn suggest_from<'a>(
+    input: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+    max_distance: usize,
+) -> Vec<&'a str> {
+    let mut near: Vec<(usize, &str)> = candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(input, candidate), candidate))
+        .filter(|&(distance, _)| distance > 0 && distance <= max_distance)
+        .collect();
+    near.sort_unstable();
+    near.dedup();
+    near.into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// Suggests corrections to a code whose check characters do not match, by
+/// trying every single substitution within `alphabet` and every swap of
+/// adjacent characters and keeping those `scheme` accepts.
+///
+/// Check schemes that catch every single error, such as [`crate::Damm`],
+/// usually leave one or two suggestions.
+///
+/// # Arguments
+/// * `code` - The code as entered, including its check characters
+/// * `alphabet` - The characters the code is written in
+/// * `scheme` - The check scheme the code uses
+///
+/// # Returns
+/// Up to [`MAX_SUGGESTIONS`] corrected codes, substitutions first, or none if
+/// `code` already passes its check
+#[must_use = "This returns the suggestions but does nothing if unused"]
+pub fn suggest_checked(code: &str, alphabet: &str, scheme: &dyn CheckScheme) -> Vec<String> {
+    if scheme.strip(code).is_ok() {
+        return Vec::new();
+    }
+    let chars: Vec<char> = code.chars().collect();
+    let substitutions = (0..chars.len()).flat_map(|i| {
+        let chars = &chars;
+        alphabet
+            .chars()
+            .filter(move |&c| c != chars[i])
+            .map(move |c| replaced(chars, &[(i, c)]))
+    });
+    let swaps = (1..chars.len())
+        .filter(|&i| chars[i - 1] != chars[i])
+        .map(|i| replaced(&chars, &[(i - 1, chars[i]), (i, chars[i - 1])]));
+    let mut suggestions: Vec<String> = Vec::new();
+    for candidate in substitutions.chain(swaps) {
+        if scheme.strip(&candidate).is_ok() && !suggestions.contains(&candidate) {
+            suggestions.push(candidate);
+            if suggestions.len() == MAX_SUGGESTIONS {
+                break;
+            }
+        }
+    }
+    suggestions
+}
+
+/// Suggests corrections to text with characters outside `encoding`'s
+/// alphabet.
+///
+/// Each such character is replaced with the alphabet characters it is easily
+/// mistaken for, such as `o` for `0` in Base58 or `0` for `O` in hex.
+///
+/// # Arguments
+/// * `text` - The text as entered
+/// * `encoding` - The encoding it should be in
+///
+/// # Returns
+/// Up to [`MAX_SUGGESTIONS`] corrected texts that [`validate`] accepts, or
+/// none if `text` is already valid or a character has no lookalike
+#[must_use = "This returns the suggestions but does nothing if unused"]
+pub fn suggest_lookalikes(text: &str, encoding: Encoding) -> Vec<String> {
+    if validate(encoding, text).is_ok() {
+        return Vec::new();
+    }
+    let alphabet = encoding.get_alphabet();
+    let in_alphabet = |c: char| {
+        alphabet.contains(c)
+            || (!encoding.is_case_sensitive()
+                && (alphabet.contains(c.to_ascii_lowercase())
+                    || alphabet.contains(c.to_ascii_uppercase())))
+    };
+    let chars: Vec<char> = text.chars().collect();
+    let mut options: Vec<(usize, Vec<char>)> = Vec::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if in_alphabet(c) || c.is_whitespace() {
+            continue;
+        }
+        let mut replacements: Vec<char> = LOOKALIKES
+            .iter()
+            .filter(|group| group.contains(c))
+            .flat_map(|group| group.chars())
+            .chain([c.to_ascii_lowercase(), c.to_ascii_uppercase()])
+            .filter(|&r| r != c && in_alphabet(r))
+            .collect();
+        replacements.dedup();
+        if replacements.is_empty() {
+            return Vec::new();
+        }
+        options.push((i, replacements));
+    }
+
+    // Every combination of replacements, stopping once there are enough.
+    let mut partial: Vec<Vec<(usize, char)>> = vec![Vec::new()];
+    for (i, replacements) in &options {
+        partial = partial
+            .iter()
+            .flat_map(|picked| {
+                replacements.iter().map(move |&r| {
+                    let mut picked = picked.clone();
+                    picked.push((*i, r));
+                    picked
+                })
+            })
+            .take(MAX_SUGGESTIONS * 4)
+            .collect();
+    }
+    let mut suggestions: Vec<String> = Vec::new();
+    for picked in &partial {
+        let candidate = replaced(&chars, picked);
+        if validate(encoding, &candidate).is_ok() && !suggestions.contains(&candidate) {
+            suggestions.push(candidate);
+            if suggestions.len() == MAX_SUGGESTIONS {
+                break;
+            }
+        }
+    }
+    suggestions
+}
+
+/// Returns `chars` as a string with the characters at the given positions
+/// replaced.
+fn replaced(chars: &[char], replacements: &[(usize, char)]) -> String {
+    chars
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| {
+            replacements
+                .iter()
+                .find(|&&(at, _)| at == i)
+                .map_or(c, |&(_, r)| r)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{Damm, Luhn};
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("abc", ""), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("SAVE20", "SAV20"), 1);
+        assert_eq!(edit_distance("SAVE20", "SAVE02"), 1);
+        assert_eq!(edit_distance("ca", "abc"), 3);
+
+        let issued = ["SAVE20", "SAVE50", "FREESHIP", "WELCOME10"];
+        assert_eq!(suggest_from("SAVE2O", issued, 1), ["SAVE20"]);
+        assert_eq!(suggest_from("SAVE30", issued, 1), ["SAVE20", "SAVE50"]);
+        assert_eq!(suggest_from("FRESHIP", issued, 2), ["FREESHIP"]);
+        assert!(suggest_from("SAVE20", issued, 0).is_empty());
+    }
+
+    #[test]
+    fn test_suggest_checked() {
+        let Ok(code) = Damm.append("572") else {
+            return;
+        };
+        assert_eq!(code, "5724");
+        assert!(suggest_checked(&code, "0123456789", &Damm).is_empty());
+        let typo = suggest_checked("5824", "0123456789", &Damm);
+        assert!(typo.contains(&code), "{typo:?}");
+        let swapped = suggest_checked("7524", "0123456789", &Damm);
+        assert!(swapped.contains(&code), "{swapped:?}");
+        assert!(
+            suggest_checked("79927398710", "0123456789", &Luhn::DECIMAL).len() <= MAX_SUGGESTIONS
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "base36", feature = "base58", feature = "hex"))]
+    fn test_suggest_lookalikes() {
+        assert_eq!(
+            suggest_lookalikes("StV1DL6CwTryKyV", Encoding::Base58),
+            Vec::<String>::new()
+        );
+        assert_eq!(suggest_lookalikes("3mJr0", Encoding::Base58), ["3mJro"]);
+        assert_eq!(
+            suggest_lookalikes("dead beeO", Encoding::Hex),
+            ["dead bee0"]
+        );
+        let both = suggest_lookalikes("Il", Encoding::Base58);
+        assert!(
+            both.contains(&"1i".to_string())
+                && both.iter().all(|s| validate(Encoding::Base58, s).is_ok())
+        );
+        assert!(suggest_lookalikes("ab#c", Encoding::Base36).is_empty());
+    }
+}
@@ -0,0 +1,244 @@
+use std::io::{Read, Write};
+
+use crate::char_stream::{block_len, streaming_decoder};
+use crate::decoder::BitDecoder;
+use crate::{Decoder, Encoding, SerialiseError};
+
+/// Bytes read from the input at a time by [`Transcoder::transcode`].
+const READ_SIZE: usize = 64 * 1024;
+
+/// Encodes `bytes`, a whole number of `encoding`'s blocks unless it is the
+/// end of the data, in one piece.
+fn encode_blocks(encoding: Encoding, bytes: &[u8]) -> Result<String, SerialiseError> {
+    match encoding {
+        #[cfg(feature = "hex")]
+        Encoding::Hex => crate::Hex::try_to_hex(bytes),
+        #[cfg(feature = "base32")]
+        Encoding::Base32 => crate::Base32::try_to_base32(bytes),
+        #[cfg(feature = "base64")]
+        Encoding::Base64 => crate::Base64::try_to_base64(bytes),
+        #[allow(unreachable_patterns)]
+        _ => Err(SerialiseError::new(format!(
+            "{encoding} cannot be encoded a block at a time"
+        ))),
+    }
+}
+
+/// Push-style converter from text in one encoding to text in another,
+/// holding at most one output block of decoded bytes between calls.
+///
+/// Input can be fed in arbitrary pieces, and each call returns the output
+/// that is ready so far. Output is a single line, the same as encoding all of
+/// the decoded bytes at once; pass it through a [`Rechunker`](crate::Rechunker)
+/// to wrap it. Only hex, Base32 and Base64, which have streaming decoders and
+/// encode block by block, are supported on either side.
+#[derive(Debug)]
+pub struct Transcoder {
+    /// The encoding of the input
+    from: Encoding,
+    /// The encoding of the output
+    to: Encoding,
+    /// Decodes the input as it arrives
+    decoder: BitDecoder,
+    /// Bytes per output block
+    block: usize,
+    /// Decoded bytes that do not yet fill an output block
+    pending: Vec<u8>,
+    /// The output returned by the last call
+    out: String,
+}
+
+impl Transcoder {
+    /// Creates a transcoder.
+    ///
+    /// # Arguments
+    /// * `from` - The encoding of the input
+    /// * `to` - The encoding of the output
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if either encoding is not hex, Base32 or Base64.
+    pub fn new(from: Encoding, to: Encoding) -> Result<Self, SerialiseError> {
+        let Some(decoder) = streaming_decoder(from) else {
+            return Err(SerialiseError::new(format!(
+                "{from} cannot be decoded as a stream"
+            )));
+        };
+        let Some(block) = block_len(to) else {
+            return Err(SerialiseError::new(format!(
+                "{to} cannot be encoded as a stream"
+            )));
+        };
+        Ok(Self {
+            from,
+            to,
+            decoder,
+            block,
+            pending: Vec::new(),
+            out: String::new(),
+        })
+    }
+
+    /// Returns the encoding of the input.
+    #[must_use = "This returns the encoding but does nothing if unused"]
+    pub const fn get_from(&self) -> Encoding {
+        self.from
+    }
+
+    /// Returns the encoding of the output.
+    #[must_use = "This returns the encoding but does nothing if unused"]
+    pub const fn get_to(&self) -> Encoding {
+        self.to
+    }
+
+    /// Feeds the next piece of input.
+    ///
+    /// # Arguments
+    /// * `input` - The next piece of text in the input encoding
+    ///
+    /// # Returns
+    /// The output ready so far, valid until the next call
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the input is not valid for its encoding.
+    pub fn feed(&mut self, input: &[u8]) -> Result<&str, SerialiseError> {
+        self.out.clear();
+        let decoded = self.decoder.feed(input)?;
+        self.pending.extend_from_slice(decoded);
+        let ready = self.pending.len() - self.pending.len() % self.block;
+        if ready > 0 {
+            self.out = encode_blocks(self.to, &self.pending[..ready])?;
+            self.pending.drain(..ready);
+        }
+        Ok(&self.out)
+    }
+
+    /// Signals the end of the input and resets the transcoder for reuse.
+    ///
+    /// # Returns
+    /// The rest of the output, including any padding
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if the input ended part way through a value.
+    pub fn finish(&mut self) -> Result<&str, SerialiseError> {
+        self.out.clear();
+        let decoded = self.decoder.finish();
+        let mut pending = std::mem::take(&mut self.pending);
+        pending.extend_from_slice(decoded?);
+        self.out = encode_blocks(self.to, &pending)?;
+        Ok(&self.out)
+    }
+
+    /// Transcodes everything `reader` produces into `writer`, reading 64 KiB
+    /// at a time, so memory use does not grow with the size of the input.
+    ///
+    /// # Arguments
+    /// * `reader` - The input, in the input encoding
+    /// * `writer` - Where to write the output
+    ///
+    /// # Returns
+    /// The number of characters written
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if reading or writing fails or the input is not
+    /// valid for its encoding. The transcoder is reset for reuse either way.
+    pub fn transcode<R: Read, W: Write>(
+        &mut self,
+        mut reader: R,
+        mut writer: W,
+    ) -> Result<u64, SerialiseError> {
+        let result = self.copy(&mut reader, &mut writer);
+        if result.is_err() {
+            let _ = self.decoder.finish();
+            self.pending.clear();
+        }
+        result
+    }
+
+    /// The body of [`transcode`](Self::transcode).
+    fn copy<R: Read, W: Write>(
+        &mut self,
+        reader: &mut R,
+        writer: &mut W,
+    ) -> Result<u64, SerialiseError> {
+        let mut buf = vec![0u8; READ_SIZE];
+        let mut written = 0u64;
+        loop {
+            let read = match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            };
+            let out = self.feed(&buf[..read])?;
+            writer.write_all(out.as_bytes())?;
+            written += out.len() as u64;
+        }
+        let out = self.finish()?;
+        writer.write_all(out.as_bytes())?;
+        writer.flush()?;
+        Ok(written + out.len() as u64)
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "base32", feature = "base64", feature = "hex"))]
+mod tests {
+
+    use super::*;
+    use crate::{Base32, Base64, Hex};
+
+    fn sample() -> Vec<u8> {
+        (0..1000u32)
+            .map(|i| i.wrapping_mul(7).to_le_bytes()[0])
+            .collect()
+    }
+
+    #[test]
+    fn test_feed_in_pieces_matches_whole_encode() {
+        let hex = Hex::try_to_hex(&sample()).unwrap_or_default();
+        let Ok(mut transcoder) = Transcoder::new(Encoding::Hex, Encoding::Base64) else {
+            return;
+        };
+        for piece in [1, 3, 7, 64] {
+            let mut out = String::new();
+            for chunk in hex.as_bytes().chunks(piece) {
+                out.push_str(transcoder.feed(chunk).unwrap_or_default());
+            }
+            out.push_str(transcoder.finish().unwrap_or_default());
+            assert_eq!(out, Base64::try_to_base64(&sample()).unwrap_or_default());
+        }
+    }
+
+    #[test]
+    fn test_transcode_reader_to_writer() {
+        let base64 = Base64::try_to_base64(&sample()).unwrap_or_default();
+        let wrapped = crate::LineEnding::CrLf.wrap(&base64, 76);
+        let mut out: Vec<u8> = Vec::new();
+        let Ok(mut transcoder) = Transcoder::new(Encoding::Base64, Encoding::Base32) else {
+            return;
+        };
+        let written = transcoder.transcode(wrapped.as_bytes(), &mut out);
+        let expected = Base32::try_to_base32(&sample()).unwrap_or_default();
+        assert!(matches!(written, Ok(n) if n == expected.len() as u64));
+        assert_eq!(String::from_utf8(out).unwrap_or_default(), expected);
+    }
+
+    #[test]
+    fn test_invalid_is_err() {
+        #[cfg(feature = "base58")]
+        assert!(Transcoder::new(Encoding::Base58, Encoding::Hex).is_err());
+        #[cfg(feature = "uuencode")]
+        assert!(Transcoder::new(Encoding::Hex, Encoding::Uuencode).is_err());
+        let Ok(mut transcoder) = Transcoder::new(Encoding::Hex, Encoding::Base64) else {
+            return;
+        };
+        assert!(transcoder.feed(b"zz").is_err());
+        let mut sink: Vec<u8> = Vec::new();
+        assert!(transcoder.transcode(&b"abc"[..], &mut sink).is_err());
+        assert!(matches!(
+            transcoder.transcode(&b"6869"[..], &mut sink),
+            Ok(4)
+        ));
+        assert_eq!(sink, b"aGk=");
+    }
+}
@@ -0,0 +1,240 @@
+use std::sync::Arc;
+
+use crate::{ByteVec, EncodedString, Encoding, SerialiseError};
+
+/// A simple reversible byte transform, as used to obscure data in CTF
+/// challenges and malware.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transform {
+    /// XOR every byte with the same key byte
+    Xor(u8),
+    /// XOR the bytes with a key repeated as often as needed; an empty key
+    /// leaves the bytes unchanged
+    XorKey(Vec<u8>),
+    /// Rotate ASCII letters by 13 places, leaving other bytes unchanged
+    Rot13,
+    /// Rotate the printable ASCII characters `!` to `~` by 47 places,
+    /// leaving other bytes unchanged
+    Rot47,
+    /// Rotate the bits of every byte left by this many places
+    RotateLeft(u32),
+    /// Rotate the bits of every byte right by this many places
+    RotateRight(u32),
+}
+
+impl Transform {
+    /// Applies the transform.
+    ///
+    /// # Arguments
+    /// * `bytes` - The bytes to transform
+    ///
+    /// # Returns
+    /// The transformed bytes
+    #[must_use = "This returns the transformed bytes but does nothing if unused"]
+    pub fn apply(&self, bytes: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Xor(key) => bytes.iter().map(|b| b ^ key).collect(),
+            Self::XorKey(key) if key.is_empty() => bytes.to_vec(),
+            Self::XorKey(key) => bytes
+                .iter()
+                .zip(key.iter().cycle())
+                .map(|(b, k)| b ^ k)
+                .collect(),
+            Self::Rot13 => bytes
+                .iter()
+                .map(|&b| match b {
+                    b'a'..=b'z' => (b - b'a' + 13) % 26 + b'a',
+                    b'A'..=b'Z' => (b - b'A' + 13) % 26 + b'A',
+                    _ => b,
+                })
+                .collect(),
+            Self::Rot47 => bytes
+                .iter()
+                .map(|&b| match b {
+                    b'!'..=b'~' => (b - b'!' + 47) % 94 + b'!',
+                    _ => b,
+                })
+                .collect(),
+            Self::RotateLeft(n) => bytes.iter().map(|b| b.rotate_left(*n)).collect(),
+            Self::RotateRight(n) => bytes.iter().map(|b| b.rotate_right(*n)).collect(),
+        }
+    }
+
+    /// Returns the transform that undoes this one.
+    ///
+    /// # Returns
+    /// The inverse, which is the same transform for everything but rotations
+    #[must_use = "This returns the inverse transform but does nothing if unused"]
+    pub fn inverse(&self) -> Self {
+        match self {
+            Self::RotateLeft(n) => Self::RotateRight(*n),
+            Self::RotateRight(n) => Self::RotateLeft(*n),
+            other => other.clone(),
+        }
+    }
+}
+
+/// One step of a [`Pipeline`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Stage {
+    /// Decode the current bytes, read as text, from an encoding
+    Decode(Encoding),
+    /// Encode the current bytes, giving the text as bytes
+    Encode(Encoding),
+    /// Apply a transform to the current bytes
+    Transform(Transform),
+}
+
+impl Stage {
+    /// Runs the stage on `bytes`.
+    fn run(&self, bytes: Vec<u8>) -> Result<Vec<u8>, SerialiseError> {
+        match self {
+            Self::Decode(encoding) => {
+                let text = String::from_utf8(bytes).map_err(|_| {
+                    SerialiseError::new(format!("input to {encoding} decoding is not text"))
+                })?;
+                let decoded = EncodedString::new(*encoding, text).try_decode()?;
+                Ok(decoded.get_bytes().to_vec())
+            }
+            Self::Encode(encoding) => Ok(ByteVec::new(Arc::new(bytes))
+                .try_encode(*encoding)?
+                .get_string()
+                .clone()
+                .into_bytes()),
+            Self::Transform(transform) => Ok(transform.apply(&bytes)),
+        }
+    }
+}
+
+/// A chain of decodings, encodings and transforms run one after another, such
+/// as "Base64 decode, XOR with 0x42, then Base32 decode".
+///
+/// Every stage works on bytes: decoding reads them as text and encoding
+/// returns the text as bytes, so stages can be combined in any order.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Pipeline {
+    /// The stages, in the order they run
+    stages: Vec<Stage>,
+}
+
+impl Pipeline {
+    /// Creates a pipeline with no stages, which returns its input unchanged.
+    #[must_use = "This returns the pipeline but does nothing if unused"]
+    pub const fn new() -> Self {
+        Self { stages: Vec::new() }
+    }
+
+    /// Adds a stage that decodes from `encoding`.
+    #[must_use = "This returns the updated pipeline but does nothing if unused"]
+    pub fn decode(mut self, encoding: Encoding) -> Self {
+        self.stages.push(Stage::Decode(encoding));
+        self
+    }
+
+    /// Adds a stage that encodes with `encoding`.
+    #[must_use = "This returns the updated pipeline but does nothing if unused"]
+    pub fn encode(mut self, encoding: Encoding) -> Self {
+        self.stages.push(Stage::Encode(encoding));
+        self
+    }
+
+    /// Adds a stage that applies `transform`.
+    #[must_use = "This returns the updated pipeline but does nothing if unused"]
+    pub fn transform(mut self, transform: Transform) -> Self {
+        self.stages.push(Stage::Transform(transform));
+        self
+    }
+
+    /// Returns the number of stages.
+    #[must_use = "This returns the number of stages but does nothing if unused"]
+    pub const fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    /// Returns true if the pipeline has no stages.
+    #[must_use = "This returns whether the pipeline is empty but does nothing if unused"]
+    pub const fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Runs every stage in order.
+    ///
+    /// # Arguments
+    /// * `input` - The bytes or text to start with
+    ///
+    /// # Returns
+    /// The output of the last stage
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` from the first stage that fails, with the
+    /// stage number as context, e.g. `"stage 2: invalid base32 character"`.
+    pub fn run(&self, input: impl AsRef<[u8]>) -> Result<Vec<u8>, SerialiseError> {
+        self.stages
+            .iter()
+            .enumerate()
+            .try_fold(input.as_ref().to_vec(), |bytes, (i, stage)| {
+                stage
+                    .run(bytes)
+                    .map_err(|e| e.with_context(format!("stage {}", i + 1)))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_transforms() {
+        assert_eq!(Transform::Rot13.apply(b"Hello, World!"), b"Uryyb, Jbeyq!");
+        assert_eq!(Transform::Rot47.apply(b"Hello, World!"), b"w6==@[ (@C=5P");
+        assert_eq!(Transform::Xor(0x20).apply(b"abc"), b"ABC");
+        assert_eq!(
+            Transform::XorKey(b"ICE".to_vec()).apply(b"Burning"),
+            [0x0b, 0x36, 0x37, 0x27, 0x2a, 0x2b, 0x2e]
+        );
+        assert_eq!(Transform::XorKey(Vec::new()).apply(b"abc"), b"abc");
+        assert_eq!(Transform::RotateLeft(1).apply(&[0x81]), [0x03]);
+    }
+
+    #[test]
+    fn test_inverse_round_trips() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        for transform in [
+            Transform::Xor(0x5a),
+            Transform::XorKey(b"key".to_vec()),
+            Transform::Rot13,
+            Transform::Rot47,
+            Transform::RotateLeft(3),
+            Transform::RotateRight(11),
+        ] {
+            let applied = transform.apply(&bytes);
+            assert_eq!(transform.inverse().apply(&applied), bytes, "{transform:?}");
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "base32", feature = "base64"))]
+    fn test_pipeline() {
+        let hidden = Pipeline::new()
+            .transform(Transform::Xor(0x42))
+            .encode(Encoding::Base32)
+            .transform(Transform::Rot13)
+            .encode(Encoding::Base64)
+            .run("flag{xor}")
+            .unwrap_or_default();
+        let solve = Pipeline::new()
+            .decode(Encoding::Base64)
+            .transform(Transform::Rot13)
+            .decode(Encoding::Base32)
+            .transform(Transform::Xor(0x42));
+        assert_eq!(solve.len(), 4);
+        assert!(matches!(solve.run(&hidden), Ok(b) if b == b"flag{xor}"));
+        assert!(matches!(
+            solve.run("!!!"),
+            Err(e) if e.to_string().starts_with("stage 1: ")
+        ));
+        assert!(matches!(Pipeline::new().run("x"), Ok(b) if b == b"x"));
+    }
+}
@@ -0,0 +1,123 @@
+use crate::{EncodedString, Encoding, SerialiseError};
+
+/// Returns the number of characters in a whole group of `encoding`, the
+/// shortest run that decodes to whole bytes, or `None` if the encoding does
+/// not map characters to a fixed number of bits.
+const fn group_chars(encoding: Encoding) -> Option<usize> {
+    match encoding {
+        #[cfg(feature = "hex")]
+        Encoding::Hex => Some(2),
+        #[cfg(feature = "base32")]
+        Encoding::Base32 => Some(8),
+        #[cfg(feature = "base64")]
+        Encoding::Base64 => Some(4),
+        #[allow(unreachable_patterns)]
+        _ => None,
+    }
+}
+
+/// Shortens an encoded string to at most `max_chars` characters, cutting at a
+/// group boundary so the shorter string still decodes.
+///
+/// Cutting anywhere else leaves characters that decode to part of a byte,
+/// which decoders reject. Only hex, Base32 and Base64 can be cut this way,
+/// since every other encoding's digits depend on the whole input. Whitespace
+/// is kept and counts towards `max_chars`, but not towards groups.
+///
+/// # Arguments
+/// * `encoded` - The encoded string to shorten
+/// * `max_chars` - The most characters the result may have
+///
+/// # Returns
+/// The longest decodable prefix within `max_chars` characters, and the
+/// number of bytes it decodes to
+///
+/// # Errors
+/// Returns `SerialiseError` if the encoding cannot be cut, or `encoded` does
+/// not decode.
+pub fn truncate_safely(
+    encoded: &EncodedString,
+    max_chars: usize,
+) -> Result<(EncodedString, usize), SerialiseError> {
+    let encoding = encoded.get_encoding();
+    let Some(group) = group_chars(encoding) else {
+        return Err(SerialiseError::new(format!(
+            "{encoding} cannot be truncated, only hex, Base32 and Base64 can"
+        )));
+    };
+    let full_len = encoded.try_decode()?.get_bytes().len();
+    let text = encoded.get_string();
+    if text.chars().count() <= max_chars {
+        return Ok((encoded.clone(), full_len));
+    }
+
+    let mut cut = 0;
+    let mut significant = 0;
+    for (at, c) in text.char_indices().take(max_chars) {
+        if !c.is_whitespace() {
+            significant += 1;
+            if significant % group == 0 {
+                cut = at + c.len_utf8();
+            }
+        }
+    }
+    let prefix = EncodedString::new(encoding, text[..cut].to_string());
+    let len = prefix.try_decode()?.get_bytes().len();
+    Ok((prefix, len))
+}
+
+#[cfg(test)]
+#[cfg(all(
+    feature = "base32",
+    feature = "base58",
+    feature = "base64",
+    feature = "hex"
+))]
+mod tests {
+
+    use super::*;
+
+    fn truncated(encoding: Encoding, text: &str, max_chars: usize) -> (String, usize) {
+        truncate_safely(&EncodedString::new(encoding, text.to_string()), max_chars)
+            .map(|(prefix, len)| (prefix.get_string().clone(), len))
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_cuts_at_group_boundary() {
+        assert_eq!(
+            truncated(Encoding::Hex, "deadbeef", 5),
+            ("dead".to_string(), 2)
+        );
+        assert_eq!(truncated(Encoding::Hex, "deadbeef", 1), (String::new(), 0));
+        assert_eq!(
+            truncated(Encoding::Base64, "aGVsbG8gd29ybGQ=", 11),
+            ("aGVsbG8g".to_string(), 6)
+        );
+        assert_eq!(
+            truncated(Encoding::Base32, "JBSWY3DPEBLW64TMMQ======", 20),
+            ("JBSWY3DPEBLW64TM".to_string(), 10)
+        );
+        assert_eq!(
+            truncated(Encoding::Base64, "aGVs\nbG8g\nd29y", 11),
+            ("aGVs\nbG8g".to_string(), 6)
+        );
+    }
+
+    #[test]
+    fn test_short_enough_is_unchanged() {
+        assert_eq!(
+            truncated(Encoding::Base64, "aGVsbG8gd29ybGQ=", 16),
+            ("aGVsbG8gd29ybGQ=".to_string(), 11)
+        );
+        assert_eq!(truncated(Encoding::Hex, "", 0), (String::new(), 0));
+    }
+
+    #[test]
+    fn test_invalid_is_err() {
+        let base58 = EncodedString::new(Encoding::Base58, "StV1DL6CwTryKyV".to_string());
+        assert!(truncate_safely(&base58, 4).is_err());
+        let bad = EncodedString::new(Encoding::Hex, "xyz".to_string());
+        assert!(truncate_safely(&bad, 2).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{Base64, Base64Config, SerialiseError, alphabet};
+
+/// The longest token [`encode`] writes or [`decode`] accepts, in characters.
+///
+/// This keeps tokens well inside the URL and header limits of common servers,
+/// proxies and object stores, and covers payloads of up to 1536 bytes.
+pub const MAX_LEN: usize = 2048;
+
+/// Encodes `bytes` as a token that can be used unescaped in a URL path
+/// segment, a query value or an HTTP header.
+///
+/// The token is URL-safe Base64 without padding (RFC 4648 section 5): only
+/// `A-Z`, `a-z`, `0-9`, `-` and `_`, none of which need percent-encoding or
+/// quoting anywhere in a URL or header value.
+///
+/// # Arguments
+/// * `bytes` - The bytes to encode, from 1 to 1536 of them
+///
+/// # Returns
+/// The token
+///
+/// # Errors
+/// Returns `SerialiseError` if `bytes` is empty or the token would be longer
+/// than [`MAX_LEN`].
+pub fn encode(bytes: &[u8]) -> Result<String, SerialiseError> {
+    if bytes.is_empty() {
+        return Err(SerialiseError::new(
+            "URL token must not be empty".to_string(),
+        ));
+    }
+    let len = (bytes.len() * 4).div_ceil(3);
+    if len > MAX_LEN {
+        return Err(SerialiseError::new(format!(
+            "URL token would be {len} characters, at most {MAX_LEN} are allowed"
+        )));
+    }
+    let standard = Base64::try_to_base64_with(bytes, Base64Config::NO_PAD)?;
+    alphabet::transcode_alphabet(
+        &standard,
+        alphabet::BASE64_STANDARD,
+        alphabet::BASE64_URL_SAFE,
+    )
+}
+
+/// Decodes a token written by [`encode`], accepting nothing else.
+///
+/// Padding, whitespace, the standard Base64 characters `+` and `/`, and
+/// tokens whose unused trailing bits are not zero are all rejected, so every
+/// byte string has exactly one token and tokens can be compared as strings.
+///
+/// # Arguments
+/// * `token` - The token
+///
+/// # Returns
+/// The decoded bytes
+///
+/// # Errors
+/// Returns `SerialiseError` if `token` is empty, longer than [`MAX_LEN`],
+/// contains a character outside the URL-safe alphabet, has a length no
+/// encoder produces or is not in canonical form.
+pub fn decode(token: &str) -> Result<Vec<u8>, SerialiseError> {
+    if token.is_empty() {
+        return Err(SerialiseError::new(
+            "URL token must not be empty".to_string(),
+        ));
+    }
+    if token.len() > MAX_LEN {
+        return Err(SerialiseError::new(format!(
+            "URL token is {} characters, at most {MAX_LEN} are allowed",
+            token.len()
+        )));
+    }
+    if let Some((position, c)) = token
+        .char_indices()
+        .find(|&(_, c)| !u8::try_from(c).is_ok_and(|b| alphabet::BASE64_URL_SAFE.contains(&b)))
+    {
+        return Err(SerialiseError::new(format!(
+            "invalid URL token character '{c}' at position {position}"
+        )));
+    }
+    if token.len() % 4 == 1 {
+        return Err(SerialiseError::new(format!(
+            "URL token length {} is not one an encoder produces",
+            token.len()
+        )));
+    }
+
+    let standard =
+        alphabet::transcode_alphabet(token, alphabet::BASE64_URL_SAFE, alphabet::BASE64_STANDARD)?;
+    let bytes = Base64::try_from_base64_with(&standard, Base64Config::NO_PAD)?;
+    if encode(&bytes)? != token {
+        return Err(SerialiseError::new(
+            "URL token is not in canonical form".to_string(),
+        ));
+    }
+    Ok(bytes)
+}
+
+/// Decodes a token that must hold exactly `len` bytes, such as a fixed-size
+/// key or nonce.
+///
+/// # Arguments
+/// * `token` - The token
+/// * `len` - The number of bytes the token must decode to
+///
+/// # Returns
+/// The decoded bytes
+///
+/// # Errors
+/// Returns `SerialiseError` if `token` is not the length that `len` bytes
+/// encode to, without decoding it, or for any reason [`decode`] fails.
+pub fn decode_exact(token: &str, len: usize) -> Result<Vec<u8>, SerialiseError> {
+    let expected = (len * 4).div_ceil(3);
+    if token.len() != expected {
+        return Err(SerialiseError::new(format!(
+            "URL token is {} characters, expected {expected} for {len} bytes",
+            token.len()
+        )));
+    }
+    decode(token)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_round_trip_is_url_safe() {
+        let bytes: Vec<u8> = (0..=255).rev().collect();
+        let token = encode(&bytes).unwrap_or_default();
+        assert!(
+            token
+                .bytes()
+                .all(|c| c.is_ascii_alphanumeric() || c == b'-' || c == b'_')
+        );
+        assert!(token.contains('-') && token.contains('_'));
+        assert!(matches!(decode(&token), Ok(b) if b == bytes));
+        assert!(matches!(encode(&[0xfb, 0xff]).as_deref(), Ok("-_8")));
+    }
+
+    #[test]
+    fn test_strict_parse() {
+        assert!(matches!(decode("-_8"), Ok(b) if b == [0xfb, 0xff]));
+        for bad in ["", "-_8=", "+/8", "-_ 8", "-_9", "a", "-_8\u{e9}"] {
+            assert!(decode(bad).is_err(), "{bad}");
+        }
+        assert!(decode(&"A".repeat(MAX_LEN + 4)).is_err());
+    }
+
+    #[test]
+    fn test_length_checks() {
+        assert!(encode(b"").is_err());
+        assert!(encode(&[0; 1536]).is_ok());
+        assert!(encode(&[0; 1537]).is_err());
+        let token = encode(&[7; 32]).unwrap_or_default();
+        assert!(matches!(decode_exact(&token, 32), Ok(b) if b == [7; 32]));
+        assert!(decode_exact(&token, 16).is_err());
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{Encoding, SerialiseError, serialise_error::check_ascii};
+
+#[cfg(any(
+    feature = "uuencode",
+    feature = "intel-hex",
+    feature = "srec",
+    feature = "binhex",
+    feature = "line-dump"
+))]
+use crate::{EncodedString, Encoder};
+
+#[cfg(feature = "base32")]
+use crate::algorithm::Base32;
+#[cfg(feature = "base36")]
+use crate::algorithm::Base36;
+#[cfg(feature = "base45")]
+use crate::algorithm::Base45;
+#[cfg(feature = "base58")]
+use crate::algorithm::Base58;
+#[cfg(feature = "base62")]
+use crate::algorithm::Base62;
+#[cfg(feature = "base64")]
+use crate::algorithm::Base64;
+#[cfg(feature = "base94")]
+use crate::algorithm::Base94;
+#[cfg(feature = "binhex")]
+use crate::algorithm::BinHex;
+#[cfg(feature = "escaped")]
+use crate::algorithm::Escaped;
+#[cfg(feature = "filename-safe")]
+use crate::algorithm::FilenameSafe;
+#[cfg(feature = "hex")]
+use crate::algorithm::Hex;
+#[cfg(feature = "intel-hex")]
+use crate::algorithm::IntelHex;
+#[cfg(feature = "line-dump")]
+use crate::algorithm::LineDump;
+#[cfg(feature = "srec")]
+use crate::algorithm::Srec;
+#[cfg(feature = "uuencode")]
+use crate::algorithm::Uuencode;
+
+/// Checks that `encoded` is a syntactically valid string in `encoding`.
+///
+/// The character-based encodings (hex, Base32, Base36, Base45, Base58, Base62
+/// and Base64) are checked in a single pass without allocating the decoded bytes,
+/// which suits form and API input validation. The line-based formats
+/// (uuencode, Intel HEX, S-record, `BinHex` and line dump) carry lengths and checksums that
+/// can only be checked by decoding, so they are decoded and the bytes discarded.
+///
+/// # Arguments
+/// * `encoding` - The encoding `encoded` should be in
+/// * `encoded` - The string to check
+///
+/// # Errors
+/// Returns `SerialiseError` describing the first problem if decoding would fail.
+pub fn validate(encoding: Encoding, encoded: &str) -> Result<(), SerialiseError> {
+    check_ascii(encoded, encoding.get_name())?;
+    match encoding {
+        #[cfg(feature = "base32")]
+        Encoding::Base32 => Base32::validate(encoded),
+        #[cfg(feature = "base36")]
+        Encoding::Base36 => Base36::validate(encoded),
+        #[cfg(feature = "base45")]
+        Encoding::Base45 => Base45::validate(encoded),
+        #[cfg(feature = "base58")]
+        Encoding::Base58 => Base58::validate(encoded),
+        #[cfg(feature = "base62")]
+        Encoding::Base62 => Base62::validate(encoded),
+        #[cfg(feature = "base64")]
+        Encoding::Base64 => Base64::validate(encoded),
+        #[cfg(feature = "hex")]
+        Encoding::Hex => Hex::validate(encoded),
+        #[cfg(feature = "filename-safe")]
+        Encoding::FilenameSafe => FilenameSafe::validate(encoded),
+        #[cfg(feature = "escaped")]
+        Encoding::Escaped => Escaped::validate(encoded),
+        #[cfg(feature = "base94")]
+        Encoding::Base94 => Base94::validate(encoded),
+        #[cfg(feature = "uuencode")]
+        Encoding::Uuencode => {
+            Uuencode::try_decode(&EncodedString::new(encoding, encoded.to_string())).map(|_| ())
+        }
+        #[cfg(feature = "intel-hex")]
+        Encoding::IntelHex => {
+            IntelHex::try_decode(&EncodedString::new(encoding, encoded.to_string())).map(|_| ())
+        }
+        #[cfg(feature = "srec")]
+        Encoding::Srec => {
+            Srec::try_decode(&EncodedString::new(encoding, encoded.to_string())).map(|_| ())
+        }
+        #[cfg(feature = "binhex")]
+        Encoding::BinHex => {
+            BinHex::try_decode(&EncodedString::new(encoding, encoded.to_string())).map(|_| ())
+        }
+        #[cfg(feature = "line-dump")]
+        Encoding::LineDump => {
+            LineDump::try_decode(&EncodedString::new(encoding, encoded.to_string())).map(|_| ())
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "base64", feature = "hex", feature = "intel-hex"))]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_validate_hex() {
+        assert!(validate(Encoding::Hex, "DEADbeef\n00").is_ok());
+        assert!(validate(Encoding::Hex, "abc").is_err());
+        assert!(validate(Encoding::Hex, "zz").is_err());
+    }
+
+    #[test]
+    fn test_validate_base64() {
+        assert!(validate(Encoding::Base64, "Zm9vYg==").is_ok());
+        assert!(validate(Encoding::Base64, "Zm9vYg=").is_err());
+        assert!(validate(Encoding::Base64, "Zm9v!").is_err());
+    }
+
+    /// Random strings mixing ASCII with lookalike and multi-byte characters must be
+    /// rejected as non-ASCII, at the first such character, by every decoder.
+    #[test]
+    fn test_non_ascii_fuzz() {
+        use crate::{ByteVec, EncodedString, ErrorKind};
+        use std::sync::Arc;
+
+        const UNICODE: [char; 6] = [
+            '\u{43a}',
+            '\u{212a}',
+            '\u{ff10}',
+            '\u{e9}',
+            '\u{1f600}',
+            '\u{a0}',
+        ];
+        let mut seed: u64 = 0x9e37_79b9_7f4a_7c15;
+        let mut next = move |bound: usize| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            usize::try_from(seed % bound as u64).unwrap_or_default()
+        };
+
+        for encoding in Encoding::iter() {
+            for _ in 0..50 {
+                let bytes: Vec<u8> = (0..next(40)).map(|_| next(256).to_le_bytes()[0]).collect();
+                let valid = ByteVec::new(Arc::new(bytes))
+                    .try_encode(encoding)
+                    .map(|encoded| encoded.get_string().clone())
+                    .unwrap_or_default();
+                let mut input: String = valid.chars().take(next(valid.len() + 1)).collect();
+                let position = input.len();
+                input.push(UNICODE[next(UNICODE.len())]);
+                input.extend(valid.chars().skip(position));
+
+                let expected = ErrorKind::NonAsciiInput { position };
+                assert!(
+                    matches!(validate(encoding, &input), Err(e) if e.get_kind() == expected),
+                    "{encoding} validate {input:?}"
+                );
+                assert!(
+                    matches!(
+                        EncodedString::new(encoding, input.clone()).try_decode(),
+                        Err(e) if e.get_kind() == expected
+                    ),
+                    "{encoding} decode {input:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_validate_intel_hex() {
+        assert!(validate(Encoding::IntelHex, ":00000001FF\n").is_ok());
+        assert!(validate(Encoding::IntelHex, ":00000001FE\n").is_err());
+    }
+}
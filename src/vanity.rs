@@ -0,0 +1,221 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread;
+
+use crate::{Base58, SerialiseError, alphabet};
+
+/// A candidate whose Base58 encoding starts with the requested prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VanityMatch {
+    /// The generated bytes
+    bytes: Vec<u8>,
+    /// The Base58 encoding of `bytes`
+    encoded: String,
+    /// Number of candidates tried across all threads when the match was found
+    attempts: u64,
+}
+
+impl VanityMatch {
+    /// Returns the generated bytes.
+    #[must_use = "This returns the bytes but does nothing if unused"]
+    pub fn get_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the Base58 encoding of the bytes.
+    #[must_use = "This returns the encoded string but does nothing if unused"]
+    pub fn get_encoded(&self) -> &str {
+        &self.encoded
+    }
+
+    /// Returns the number of candidates tried when the match was found.
+    #[must_use = "This returns the attempt count but does nothing if unused"]
+    pub const fn get_attempts(&self) -> u64 {
+        self.attempts
+    }
+}
+
+/// Multi-threaded search for inputs whose Base58 encoding starts with a prefix.
+///
+/// Candidates come from a caller-supplied generator, typically a key derivation
+/// from random seed material, so the search itself has no crypto dependencies.
+/// Each extra prefix character makes a match about 58 times rarer.
+#[derive(Debug, Clone)]
+pub struct VanitySearch {
+    /// The prefix the encoding must start with
+    prefix: String,
+    /// Number of worker threads
+    threads: usize,
+    /// Give up after this many candidates, or `None` to search until found
+    max_attempts: Option<u64>,
+    /// Report progress every this many candidates
+    report_every: u64,
+}
+
+impl VanitySearch {
+    /// Creates a search for `prefix` using one thread per available CPU.
+    ///
+    /// # Arguments
+    /// * `prefix` - The prefix the Base58 encoding must start with
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if `prefix` contains characters outside the Base58
+    /// alphabet, or starts with `1`, since no encoding could ever match it:
+    /// [`Base58::to_base58`] drops leading zero bytes rather than writing them
+    /// as `1` the way Bitcoin addresses do.
+    pub fn new(prefix: &str) -> Result<Self, SerialiseError> {
+        if let Some(c) = prefix
+            .chars()
+            .find(|&c| !u8::try_from(c).is_ok_and(|b| alphabet::BASE58_BITCOIN.contains(&b)))
+        {
+            return Err(SerialiseError::new(format!(
+                "vanity prefix contains '{c}', which is not a base58 character"
+            )));
+        }
+        if prefix.starts_with('1') {
+            return Err(SerialiseError::new(
+                "vanity prefix starts with '1', which base58 never writes first, as leading zero bytes are dropped"
+                    .to_string(),
+            ));
+        }
+
+        Ok(Self {
+            prefix: prefix.to_string(),
+            threads: thread::available_parallelism().map_or(1, usize::from),
+            max_attempts: None,
+            report_every: 100_000,
+        })
+    }
+
+    /// Sets the number of worker threads, at least one.
+    #[must_use = "This returns the updated search but does nothing until run"]
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Gives up after `max_attempts` candidates instead of searching until found.
+    #[must_use = "This returns the updated search but does nothing until run"]
+    pub const fn max_attempts(mut self, max_attempts: u64) -> Self {
+        self.max_attempts = Some(max_attempts);
+        self
+    }
+
+    /// Calls the progress callback every `report_every` candidates, at least one.
+    #[must_use = "This returns the updated search but does nothing until run"]
+    pub fn report_every(mut self, report_every: u64) -> Self {
+        self.report_every = report_every.max(1);
+        self
+    }
+
+    /// Runs the search until a candidate matches or the attempt limit is reached.
+    ///
+    /// # Arguments
+    /// * `generate` - Produces the candidate for an attempt number. Attempt numbers
+    ///   are unique across threads, so a deterministic generator never repeats work.
+    /// * `progress` - Called with the total number of candidates tried so far
+    ///
+    /// # Returns
+    /// The first match found. With several threads this is not necessarily the
+    /// match with the lowest attempt number.
+    ///
+    /// # Errors
+    /// Returns `SerialiseError` if no candidate matched within the attempt limit.
+    pub fn run<G, P>(&self, generate: G, progress: P) -> Result<VanityMatch, SerialiseError>
+    where
+        G: Fn(u64) -> Vec<u8> + Sync,
+        P: Fn(u64) + Sync,
+    {
+        let next = AtomicU64::new(0);
+        let found = AtomicBool::new(false);
+        let result: Mutex<Option<VanityMatch>> = Mutex::new(None);
+
+        thread::scope(|scope| {
+            for _ in 0..self.threads {
+                scope.spawn(|| {
+                    while !found.load(Ordering::Relaxed) {
+                        let attempt = next.fetch_add(1, Ordering::Relaxed);
+                        if self.max_attempts.is_some_and(|max| attempt >= max) {
+                            break;
+                        }
+
+                        let bytes = generate(attempt);
+                        let encoded = Base58::to_base58(&bytes);
+                        if encoded.starts_with(&self.prefix) {
+                            if !found.swap(true, Ordering::Relaxed)
+                                && let Ok(mut result) = result.lock()
+                            {
+                                *result = Some(VanityMatch {
+                                    bytes,
+                                    encoded,
+                                    attempts: attempt + 1,
+                                });
+                            }
+                            break;
+                        }
+
+                        if (attempt + 1).is_multiple_of(self.report_every) {
+                            progress(attempt + 1);
+                        }
+                    }
+                });
+            }
+        });
+
+        result.into_inner().ok().flatten().ok_or_else(|| {
+            SerialiseError::new(format!(
+                "no base58 encoding starting with '{}' found",
+                self.prefix
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::sync::atomic::AtomicUsize;
+
+    use super::*;
+
+    #[test]
+    fn test_finds_prefix() {
+        let found = VanitySearch::new("z")
+            .map(|search| search.threads(4))
+            .and_then(|search| search.run(|attempt| attempt.to_be_bytes().to_vec(), |_| {}));
+        assert!(matches!(
+            &found,
+            Ok(found) if found.get_encoded().starts_with('z')
+                && Base58::to_base58(found.get_bytes()) == found.get_encoded()
+        ));
+    }
+
+    #[test]
+    fn test_gives_up_and_reports_progress() {
+        let reports = AtomicUsize::new(0);
+        let result = VanitySearch::new("zzzzzz").map(|search| {
+            search.threads(2).max_attempts(1000).report_every(100).run(
+                |_| vec![1],
+                |_| {
+                    reports.fetch_add(1, Ordering::Relaxed);
+                },
+            )
+        });
+        assert!(matches!(result, Ok(Err(_))));
+        assert_eq!(reports.load(Ordering::Relaxed), 10);
+    }
+
+    #[test]
+    fn test_invalid_prefix_is_err() {
+        assert!(VanitySearch::new("0OIl").is_err());
+    }
+
+    #[test]
+    fn test_leading_one_is_err() {
+        assert!(matches!(
+            VanitySearch::new("1abc"),
+            Err(e) if e.get_message().contains("starts with '1'")
+        ));
+        assert!(VanitySearch::new("a1").is_ok());
+    }
+}